@@ -2,6 +2,10 @@
 // 
 // This is a minimal implementation of NDN over QUIC 
 // using the Quinn crate to demonstrate core functionality
+//
+// Packets use the simplified TLV layout that rust_ndn_transport exposes as
+// `WireFormat::Compact`; the golden vectors in the tests below are shared
+// with that crate's `ndn::compact` tests to keep the two in sync.
 
 use std::sync::Arc;
 use std::net::SocketAddr;
@@ -378,3 +382,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Same bytes as TOOL_INTEREST in rust_ndn_transport's ndn::compact tests
+    const COMPACT_INTEREST: &[u8] = &[
+        0x05, 0x00, 0x13,
+        0x07, 0x00, 0x0A, b'/', b't', b'e', b's', b't', b'/', b'd', b'a', b't', b'a',
+        0x0A, 0x04, 0x01, 0x02, 0x03, 0x04,
+    ];
+    
+    // Same bytes as TOOL_DATA in rust_ndn_transport's ndn::compact tests
+    const COMPACT_DATA: &[u8] = &[
+        0x06, 0x00, 0x15,
+        0x07, 0x00, 0x0A, b'/', b't', b'e', b's', b't', b'/', b'd', b'a', b't', b'a',
+        0x15, 0x00, 0x05, b'h', b'e', b'l', b'l', b'o',
+    ];
+    
+    #[test]
+    fn test_interest_matches_library_compact_format() {
+        let interest = Interest { name: "/test/data".to_string(), nonce: 0x01020304 };
+        assert_eq!(interest.to_bytes().as_ref(), COMPACT_INTEREST);
+        
+        // Decoding what the library encodes
+        let decoded = Interest::from_bytes(COMPACT_INTEREST).unwrap();
+        assert_eq!(decoded.name, "/test/data");
+        assert_eq!(decoded.nonce, 0x01020304);
+    }
+    
+    #[test]
+    fn test_data_matches_library_compact_format() {
+        let data = Data::new("/test/data", Bytes::from_static(b"hello"));
+        assert_eq!(data.to_bytes().as_ref(), COMPACT_DATA);
+        
+        let decoded = Data::from_bytes(COMPACT_DATA).unwrap();
+        assert_eq!(decoded.name, "/test/data");
+        assert_eq!(decoded.content.as_ref(), b"hello");
+    }
+}
@@ -0,0 +1,53 @@
+//
+// μDCN Encode Benchmark
+//
+// Compares Interest and Data encode throughput with and without the buffer
+// pool. Build without the `buffer-pool` feature so that `to_bytes` is the
+// unpooled baseline. Data encodings are memoized, so the unpooled Data case
+// encodes a fresh copy each iteration and the memoized case a warm one.
+//
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+use rust_ndn_transport::name::Name;
+use rust_ndn_transport::ndn::pool::BufferPool;
+use rust_ndn_transport::ndn::{Data, Interest};
+
+fn bench_interest_encode(c: &mut Criterion) {
+    let interest = Interest::new(Name::from_uri("/udcn/bench/video/segment/42").unwrap());
+    let pool = BufferPool::default();
+    
+    let mut group = c.benchmark_group("interest_encode");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("unpooled", |b| b.iter(|| black_box(interest.to_bytes())));
+    group.bench_function("pooled", |b| b.iter(|| black_box(interest.to_bytes_pooled(&pool))));
+    group.finish();
+    
+    let stats = pool.stats();
+    println!("interest_encode/pooled: {} encodes, {} chunk refills", stats.encodes, stats.refills);
+}
+
+fn bench_data_encode(c: &mut Criterion) {
+    let data = Data::new(Name::from_uri("/udcn/bench/video/segment/42").unwrap(), vec![0xAB; 200]);
+    let pool = BufferPool::default();
+    
+    let mut group = c.benchmark_group("data_encode");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("unpooled", |b| b.iter_batched(
+        || data.clone().with_content(vec![0xAB; 200]),
+        |data| black_box(data.to_bytes()),
+        BatchSize::SmallInput,
+    ));
+    group.bench_function("pooled", |b| b.iter(|| black_box(data.to_bytes_pooled(&pool))));
+    
+    // Warm the memoized encoding
+    data.to_bytes();
+    group.bench_function("memoized", |b| b.iter(|| black_box(data.to_bytes())));
+    group.finish();
+    
+    let stats = pool.stats();
+    println!("data_encode/pooled: {} encodes, {} chunk refills", stats.encodes, stats.refills);
+}
+
+criterion_group!(benches, bench_interest_encode, bench_data_encode);
+criterion_main!(benches);
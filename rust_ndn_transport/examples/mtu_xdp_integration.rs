@@ -460,6 +460,7 @@ async fn main() -> Result<()> {
             map_pin_path: "/sys/fs/bpf/ndn".to_string(),
             enable_metrics: true,
             metrics_interval: 5,
+            ..XdpConfig::default()
         };
         
         config.xdp_config = Some(xdp_config);
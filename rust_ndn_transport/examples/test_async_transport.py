@@ -0,0 +1,58 @@
+#!/usr/bin/env python3
+"""
+μDCN asyncio Transport Example
+
+Shows the awaitable `Transport` API: a producer callback is registered for
+a prefix, then an Interest under it is sent and its content awaited.
+
+Run with pytest after building the Python bindings:
+  cargo build --features extension-module
+  pytest examples/test_async_transport.py
+"""
+
+import asyncio
+import os
+import sys
+
+import pytest
+
+# Make sure the library is in the path
+sys.path.append(os.path.join(os.path.dirname(__file__), '..'))
+
+udcn_transport = pytest.importorskip("udcn_transport")
+
+
+def produce(name: str) -> bytes:
+    """Producer callback: receives the Interest name, returns the Data content."""
+    return f"content for {name}".encode()
+
+
+async def fetch(transport, name: str) -> bytes:
+    return await transport.send_interest(name)
+
+
+def test_send_interest_to_registered_prefix():
+    async def main():
+        transport = udcn_transport.Transport({"enable_metrics": False})
+        registration_id = await transport.register_prefix("/example/async", produce)
+
+        # Several Interests can be awaited concurrently on one event loop
+        contents = await asyncio.gather(
+            fetch(transport, "/example/async/a"),
+            fetch(transport, "/example/async/b"),
+        )
+
+        await transport.unregister_prefix(registration_id)
+        return contents
+
+    contents = asyncio.run(main())
+    assert contents == [b"content for /example/async/a", b"content for /example/async/b"]
+
+
+def test_unknown_prefix_raises():
+    async def main():
+        transport = udcn_transport.Transport({"enable_metrics": False})
+        await transport.send_interest("/example/nobody/home")
+
+    with pytest.raises(RuntimeError):
+        asyncio.run(main())
@@ -0,0 +1,247 @@
+//
+// μDCN Access Control
+//
+// This module restricts which peers may request Data under a prefix. An
+// `AclPolicy` maps name prefixes to a list of subnets that are either the
+// only ones allowed or the ones denied. The rule of the longest prefix
+// matching an Interest decides; an Interest under no rule is allowed. A
+// refused Interest is answered with a NotAuth NACK before any handler runs.
+//
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use tracing::debug;
+
+use crate::error::{Error, Result};
+use crate::name::Name;
+use crate::ndn::{Interest, Nack, NackReason};
+
+/// Access control policy shared with the QUIC engine, so it can be replaced
+/// while connections run
+pub type SharedAcl = Arc<parking_lot::RwLock<AclPolicy>>;
+
+/// Block of addresses sharing a leading run of bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subnet {
+    /// Network address
+    addr: IpAddr,
+    
+    /// Number of leading bits that must match
+    prefix_len: u8,
+}
+
+impl Subnet {
+    /// Create a subnet of the addresses sharing the first `prefix_len` bits
+    /// of `addr`
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<Self> {
+        let max_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max_len {
+            return Err(Error::InvalidArgument(format!(
+                "Prefix length {} is longer than the {} bits of {}", prefix_len, max_len, addr)));
+        }
+        
+        Ok(Self { addr, prefix_len })
+    }
+    
+    /// Check whether an address is in the subnet
+    ///
+    /// IPv4 addresses mapped into IPv6 match IPv4 subnets.
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.addr, unmap(addr)) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<IpAddr> for Subnet {
+    /// The subnet holding just this address
+    fn from(addr: IpAddr) -> Self {
+        let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        Self { addr, prefix_len }
+    }
+}
+
+impl FromStr for Subnet {
+    type Err = Error;
+    
+    /// Parse a subnet in CIDR notation, such as "10.0.0.0/8", or a single
+    /// address
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::ParsingError(format!("Invalid subnet: {}", s));
+        
+        match s.split_once('/') {
+            Some((addr, prefix_len)) => {
+                let addr = addr.parse().map_err(|_| invalid())?;
+                let prefix_len = prefix_len.parse().map_err(|_| invalid())?;
+                Self::new(addr, prefix_len)
+            }
+            None => s.parse::<IpAddr>().map(Self::from).map_err(|_| invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// Get the IPv4 address an IPv4-mapped IPv6 address stands for
+fn unmap(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(addr),
+        addr => addr,
+    }
+}
+
+/// Which peers may request Data under a prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AclRule {
+    /// Only peers in these subnets
+    Allow(Vec<Subnet>),
+    
+    /// Every peer outside these subnets
+    Deny(Vec<Subnet>),
+}
+
+impl AclRule {
+    /// Check whether the rule lets a peer in
+    pub fn permits(&self, peer: IpAddr) -> bool {
+        match self {
+            AclRule::Allow(subnets) => subnets.iter().any(|subnet| subnet.contains(peer)),
+            AclRule::Deny(subnets) => !subnets.iter().any(|subnet| subnet.contains(peer)),
+        }
+    }
+}
+
+/// Access control rules by name prefix
+#[derive(Debug, Clone, Default)]
+pub struct AclPolicy {
+    rules: HashMap<Name, AclRule>,
+}
+
+impl AclPolicy {
+    /// Create a policy without rules, allowing every peer
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Let only peers in `subnets` request Data under `prefix`, replacing
+    /// any rule for the prefix
+    pub fn allow(mut self, prefix: Name, subnets: impl IntoIterator<Item = Subnet>) -> Self {
+        self.rules.insert(prefix, AclRule::Allow(subnets.into_iter().collect()));
+        self
+    }
+    
+    /// Refuse peers in `subnets` Data under `prefix`, replacing any rule for
+    /// the prefix
+    pub fn deny(mut self, prefix: Name, subnets: impl IntoIterator<Item = Subnet>) -> Self {
+        self.rules.insert(prefix, AclRule::Deny(subnets.into_iter().collect()));
+        self
+    }
+    
+    /// Find the rule of the longest prefix of a name, with that prefix
+    pub fn rule(&self, name: &Name) -> Option<(&Name, &AclRule)> {
+        self.rules
+            .iter()
+            .filter(|(prefix, _)| name.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+    }
+    
+    /// Check whether a peer may request a name
+    pub fn is_allowed(&self, name: &Name, peer: IpAddr) -> bool {
+        match self.rule(name) {
+            Some((_, rule)) => rule.permits(peer),
+            None => true,
+        }
+    }
+    
+    /// Let an Interest from `remote` through, or refuse it with a NotAuth
+    /// NACK
+    pub fn check(&self, remote: SocketAddr, interest: &Interest) -> std::result::Result<(), Nack> {
+        match self.rule(interest.name()) {
+            Some((prefix, rule)) if !rule.permits(remote.ip()) => {
+                debug!("Refusing {} to {} under the ACL of {}", interest.name(), remote, prefix);
+                Err(Nack::new(interest.clone(), NackReason::NotAuth))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// Get the number of prefixes with a rule
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+    
+    /// Check whether the policy has no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn subnet(s: &str) -> Subnet {
+        s.parse().unwrap()
+    }
+    
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+    
+    #[test]
+    fn test_subnet_contains() {
+        let private = subnet("10.0.0.0/8");
+        assert!(private.contains(ip("10.20.30.40")));
+        assert!(!private.contains(ip("11.0.0.1")));
+        assert!(private.contains(ip("::ffff:10.1.2.3")));
+        assert!(!private.contains(ip("fd00::1")));
+        
+        assert!(subnet("0.0.0.0/0").contains(ip("192.0.2.1")));
+        assert!(subnet("192.0.2.7").contains(ip("192.0.2.7")));
+        assert!(!subnet("192.0.2.7").contains(ip("192.0.2.8")));
+        assert!(subnet("fd00::/8").contains(ip("fd12:3456::1")));
+        assert_eq!(subnet("192.0.2.7").to_string(), "192.0.2.7/32");
+        
+        for invalid in ["10.0.0.0/33", "::/129", "10.0.0.0/x", "host/8", ""] {
+            assert!(invalid.parse::<Subnet>().is_err(), "{}", invalid);
+        }
+    }
+    
+    #[test]
+    fn test_longest_prefix_rule_decides() {
+        let policy = AclPolicy::new()
+            .allow(Name::from_uri("/corp").unwrap(), [subnet("10.0.0.0/8")])
+            .deny(Name::from_uri("/corp/public").unwrap(), [subnet("10.66.0.0/16")]);
+        let corp = Name::from_uri("/corp/payroll").unwrap();
+        let public = Name::from_uri("/corp/public/news").unwrap();
+        
+        assert!(policy.is_allowed(&corp, ip("10.1.1.1")));
+        assert!(!policy.is_allowed(&corp, ip("192.0.2.1")));
+        assert!(policy.is_allowed(&public, ip("192.0.2.1")));
+        assert!(!policy.is_allowed(&public, ip("10.66.1.1")));
+        assert!(policy.is_allowed(&Name::from_uri("/other").unwrap(), ip("192.0.2.1")));
+        
+        let remote: SocketAddr = "192.0.2.1:6363".parse().unwrap();
+        let nack = policy.check(remote, &Interest::new(corp)).unwrap_err();
+        assert_eq!(nack.reason(), NackReason::NotAuth);
+        policy.check(remote, &Interest::new(public)).unwrap();
+    }
+}
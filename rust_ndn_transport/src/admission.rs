@@ -0,0 +1,398 @@
+//
+// μDCN Admission Control
+//
+// This module implements adaptive admission control for incoming Interests.
+// While the number of Interests being processed (the queue depth) or the CPU
+// load is above its threshold, the controller raises the fraction of new
+// Interests it rejects with a Congestion NACK; once load drops, the fraction
+// decays back to zero. Rejections are spread evenly rather than drawn at
+// random, so a given shed fraction always sheds the same share of arrivals.
+// The CPU load is sampled from /proc/stat by a task the QUIC engine runs.
+//
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use prometheus::{register_gauge, register_int_counter, Gauge, IntCounter};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::ndn::{Interest, Nack, NackReason};
+
+lazy_static! {
+    static ref INTEREST_RATE: Gauge = register_gauge!(
+        "udcn_interests_per_second", "Incoming Interests per second").unwrap();
+    static ref INTERESTS_SHED: IntCounter = register_int_counter!(
+        "udcn_interests_shed_total", "Interests rejected by admission control").unwrap();
+}
+
+/// Length of the window the Interest rate is measured over
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Kernel statistics holding the CPU time counters
+const PROC_STAT: &str = "/proc/stat";
+
+/// Admission control thresholds
+#[derive(Debug, Clone)]
+pub struct AdmissionConfig {
+    /// Queue depth at or above which the router is overloaded
+    pub queue_depth_threshold: usize,
+    
+    /// CPU load (0.0 to 1.0) above which the router is overloaded
+    pub cpu_threshold: f64,
+    
+    /// How often the CPU load is sampled
+    pub cpu_sample_interval: Duration,
+    
+    /// Largest fraction of new Interests that is ever shed
+    pub max_shed_fraction: f64,
+    
+    /// Increase of the shed fraction per Interest arriving while overloaded
+    pub increase_step: f64,
+    
+    /// Decrease of the shed fraction per Interest arriving while not overloaded
+    pub decrease_step: f64,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            queue_depth_threshold: 1024,
+            cpu_threshold: 0.9,
+            cpu_sample_interval: Duration::from_secs(1),
+            max_shed_fraction: 0.9,
+            increase_step: 0.05,
+            decrease_step: 0.01,
+        }
+    }
+}
+
+/// Mutable controller state
+#[derive(Debug)]
+struct ControllerState {
+    /// Current fraction of new Interests to shed
+    shed_fraction: f64,
+    
+    /// Accumulated shed credit; an Interest is shed each time it reaches 1
+    credit: f64,
+    
+    /// Start of the current rate window
+    window_start: Instant,
+    
+    /// Interests seen in the current rate window
+    window_count: u64,
+    
+    /// Rate measured over the last complete window
+    rate: f64,
+}
+
+/// Adaptive admission controller
+#[derive(Debug)]
+pub struct AdmissionController {
+    /// Thresholds
+    config: AdmissionConfig,
+    
+    /// Number of admitted Interests still being processed
+    in_flight: Arc<AtomicUsize>,
+    
+    /// Last reported CPU load, as f64 bits
+    cpu_load: AtomicU64,
+    
+    /// Number of Interests admitted
+    admitted: AtomicU64,
+    
+    /// Number of Interests shed
+    shed: AtomicU64,
+    
+    /// Shed fraction and rate window
+    state: Mutex<ControllerState>,
+}
+
+/// Marks an admitted Interest as in flight until dropped
+#[derive(Debug)]
+pub struct AdmissionPermit {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl AdmissionController {
+    /// Create a new admission controller
+    pub fn new(config: AdmissionConfig) -> Self {
+        Self {
+            config,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            cpu_load: AtomicU64::new(0f64.to_bits()),
+            admitted: AtomicU64::new(0),
+            shed: AtomicU64::new(0),
+            state: Mutex::new(ControllerState {
+                shed_fraction: 0.0,
+                credit: 0.0,
+                window_start: Instant::now(),
+                window_count: 0,
+                rate: 0.0,
+            }),
+        }
+    }
+    
+    /// Get the thresholds
+    pub fn config(&self) -> &AdmissionConfig {
+        &self.config
+    }
+    
+    /// Report the current CPU load (0.0 to 1.0)
+    pub fn record_cpu_load(&self, load: f64) {
+        self.cpu_load.store(load.to_bits(), Ordering::Relaxed);
+    }
+    
+    /// Get the last reported CPU load
+    pub fn cpu_load(&self) -> f64 {
+        f64::from_bits(self.cpu_load.load(Ordering::Relaxed))
+    }
+    
+    /// Spawn a task recording the CPU load every `cpu_sample_interval`
+    ///
+    /// The task stops once the controller is dropped, or straight away where
+    /// /proc/stat cannot be read.
+    pub fn spawn_cpu_sampler(self: &Arc<Self>) -> JoinHandle<()> {
+        let controller = Arc::downgrade(self);
+        let interval = self.config.cpu_sample_interval;
+        
+        tokio::spawn(async move {
+            let mut sampler = CpuSampler::default();
+            if sampler.sample().is_none() {
+                warn!("Cannot read {}, admission control ignores the CPU load", PROC_STAT);
+                return;
+            }
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            
+            loop {
+                ticker.tick().await;
+                
+                let controller = match controller.upgrade() {
+                    Some(controller) => controller,
+                    None => break,
+                };
+                if let Some(load) = sampler.sample() {
+                    controller.record_cpu_load(load);
+                }
+            }
+        })
+    }
+    
+    /// Admit an Interest, or reject it with a Congestion NACK
+    ///
+    /// The returned permit keeps the Interest counted in the queue depth
+    /// until it is dropped.
+    pub fn admit(&self, interest: &Interest) -> std::result::Result<AdmissionPermit, Nack> {
+        let depth = self.in_flight.load(Ordering::Acquire);
+        let cpu = f64::from_bits(self.cpu_load.load(Ordering::Relaxed));
+        let overloaded = depth >= self.config.queue_depth_threshold || cpu > self.config.cpu_threshold;
+        
+        let shed = {
+            let mut state = self.state.lock();
+            
+            // Interest rate over a fixed window
+            state.window_count += 1;
+            let elapsed = state.window_start.elapsed();
+            if elapsed >= RATE_WINDOW {
+                state.rate = state.window_count as f64 / elapsed.as_secs_f64();
+                state.window_start = Instant::now();
+                state.window_count = 0;
+                INTEREST_RATE.set(state.rate);
+            }
+            
+            // Ramp the shed fraction up under load and back down after
+            state.shed_fraction = if overloaded {
+                (state.shed_fraction + self.config.increase_step).min(self.config.max_shed_fraction)
+            } else {
+                (state.shed_fraction - self.config.decrease_step).max(0.0)
+            };
+            
+            state.credit += state.shed_fraction;
+            if state.credit >= 1.0 {
+                state.credit -= 1.0;
+                true
+            } else {
+                false
+            }
+        };
+        
+        if shed {
+            self.shed.fetch_add(1, Ordering::Relaxed);
+            INTERESTS_SHED.inc();
+            debug!("Shedding Interest for {} (queue depth {}, cpu {:.2})", interest.name(), depth, cpu);
+            return Err(Nack::new(interest.clone(), NackReason::Congestion));
+        }
+        
+        self.admitted.fetch_add(1, Ordering::Relaxed);
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        Ok(AdmissionPermit {
+            in_flight: self.in_flight.clone(),
+        })
+    }
+    
+    /// Get the number of admitted Interests still being processed
+    pub fn queue_depth(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+    
+    /// Get the current fraction of new Interests being shed
+    pub fn shed_fraction(&self) -> f64 {
+        self.state.lock().shed_fraction
+    }
+    
+    /// Get the Interest rate measured over the last complete window
+    pub fn interests_per_second(&self) -> f64 {
+        self.state.lock().rate
+    }
+    
+    /// Get the number of Interests admitted and shed
+    pub fn counts(&self) -> (u64, u64) {
+        (self.admitted.load(Ordering::Relaxed), self.shed.load(Ordering::Relaxed))
+    }
+}
+
+impl Default for AdmissionController {
+    fn default() -> Self {
+        Self::new(AdmissionConfig::default())
+    }
+}
+
+/// CPU load measured between successive reads of the CPU time counters
+#[derive(Debug, Default)]
+struct CpuSampler {
+    /// Busy and total time at the last read
+    last: Option<(u64, u64)>,
+}
+
+impl CpuSampler {
+    /// Read the counters, returning the load since the last read
+    ///
+    /// The first read only sets the baseline and returns 0.
+    fn sample(&mut self) -> Option<f64> {
+        let stat = std::fs::read_to_string(PROC_STAT).ok()?;
+        let (busy, total) = parse_cpu_times(&stat)?;
+        
+        let load = match self.last.replace((busy, total)) {
+            Some((last_busy, last_total)) if total > last_total => {
+                busy.saturating_sub(last_busy) as f64 / (total - last_total) as f64
+            }
+            _ => 0.0,
+        };
+        Some(load.clamp(0.0, 1.0))
+    }
+}
+
+/// Get the busy and total time from the aggregate line of /proc/stat
+///
+/// Idle and iowait time count as not busy.
+fn parse_cpu_times(stat: &str) -> Option<(u64, u64)> {
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+    let times = line
+        .split_whitespace()
+        .skip(1)
+        .map(|field| field.parse::<u64>().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    if times.len() < 4 {
+        return None;
+    }
+    
+    let total: u64 = times.iter().sum();
+    let idle = times[3] + times.get(4).copied().unwrap_or(0);
+    Some((total - idle, total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::Name;
+    
+    #[tokio::test]
+    async fn test_flood_sheds_a_fraction_with_congestion() {
+        let controller = Arc::new(AdmissionController::new(AdmissionConfig {
+            queue_depth_threshold: 8,
+            ..Default::default()
+        }));
+        
+        // Far more concurrent Interests than the queue depth threshold
+        let mut tasks = Vec::new();
+        for i in 0..200 {
+            let controller = controller.clone();
+            tasks.push(tokio::spawn(async move {
+                let interest = Interest::new(Name::from_uri(&format!("/flood/{}", i)).unwrap());
+                match controller.admit(&interest) {
+                    Ok(_permit) => {
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok(())
+                    }
+                    Err(nack) => Err(nack.reason()),
+                }
+            }));
+        }
+        
+        let mut succeeded = 0;
+        let mut congested = 0;
+        for task in tasks {
+            match task.await.unwrap() {
+                Ok(()) => succeeded += 1,
+                Err(NackReason::Congestion) => congested += 1,
+                Err(reason) => panic!("Unexpected NACK reason: {:?}", reason),
+            }
+        }
+        
+        assert!(congested > 0, "No Interests were shed");
+        assert!(succeeded > 0, "Every Interest was shed");
+        assert_eq!(controller.counts(), (succeeded, congested));
+        assert!(controller.shed_fraction() > 0.0);
+        
+        // Once load drops the shed fraction decays back to zero
+        assert_eq!(controller.queue_depth(), 0);
+        for i in 0..200 {
+            let interest = Interest::new(Name::from_uri(&format!("/calm/{}", i)).unwrap());
+            let _ = controller.admit(&interest);
+        }
+        assert_eq!(controller.shed_fraction(), 0.0);
+        assert!(controller.admit(&Interest::new(Name::from_uri("/calm").unwrap())).is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_cpu_load_sampled_and_sheds() {
+        // user nice system idle iowait irq softirq steal
+        let before = "cpu  100 0 50 800 50 0 0 0\ncpu0 100 0 50 800 50 0 0 0\n";
+        let after = "cpu  200 0 100 900 100 0 0 0\n";
+        let (busy, total) = parse_cpu_times(before).unwrap();
+        assert_eq!((busy, total), (150, 1000));
+        let (busy_after, total_after) = parse_cpu_times(after).unwrap();
+        assert_eq!((busy_after - busy) as f64 / (total_after - total) as f64, 0.5);
+        assert!(parse_cpu_times("intr 1 2 3\n").is_none());
+        
+        // A load above the threshold sheds Interests without any queue
+        let controller = Arc::new(AdmissionController::new(AdmissionConfig {
+            cpu_threshold: 0.5,
+            cpu_sample_interval: Duration::from_millis(10),
+            ..Default::default()
+        }));
+        controller.record_cpu_load(0.95);
+        let shed = (0..100)
+            .filter(|i| controller.admit(&Interest::new(Name::from_uri(&format!("/busy/{}", i)).unwrap())).is_err())
+            .count();
+        assert!(shed > 0);
+        
+        // The sampler overwrites the reported load with the measured one
+        if std::path::Path::new(PROC_STAT).exists() {
+            controller.record_cpu_load(2.0);
+            let sampler = controller.spawn_cpu_sampler();
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            assert!((0.0..=1.0).contains(&controller.cpu_load()));
+            sampler.abort();
+        }
+    }
+}
@@ -4,6 +4,7 @@
 // This binary implements a stand-alone NDN node with QUIC-based transport.
 //
 
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -49,6 +50,18 @@ struct Opt {
     /// Path to private key file
     #[structopt(long)]
     key: Option<PathBuf>,
+    
+    /// Answer pings from peers
+    #[structopt(long)]
+    enable_ping: bool,
+    
+    /// Ping a peer and exit instead of serving
+    #[structopt(long)]
+    ping: Option<SocketAddr>,
+    
+    /// Number of pings to send
+    #[structopt(long, default_value = "4")]
+    count: u32,
 }
 
 /// Main entry point
@@ -74,6 +87,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         bind_address: opt.address.clone(),
         enable_metrics: true,
         metrics_port: opt.metrics_port,
+        enable_ping: opt.enable_ping,
     };
     
     info!("Configuration: {:?}", config);
@@ -95,6 +109,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Start the transport layer
     transport.start().await?;
     
+    // Check a peer responds, one ping a second
+    if let Some(remote) = opt.ping {
+        for seq in 1..=opt.count {
+            match transport.ping(remote).await {
+                Ok(rtt) => println!("Reply from {}: seq={} time={:.3} ms", remote, seq, rtt.as_secs_f64() * 1000.0),
+                Err(e) => println!("No reply from {}: seq={} {}", remote, seq, e),
+            }
+            if seq < opt.count {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+        transport.shutdown().await?;
+        return Ok(());
+    }
+    
     info!("μDCN Node running on {}", opt.address);
     info!("Press Ctrl+C to exit");
     
@@ -5,19 +5,26 @@
 // It uses an LRU cache with TTL support for efficient caching.
 //
 
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
+use futures::stream::{self, Stream};
 use lru::LruCache;
 use parking_lot::Mutex;
 use prometheus::{register_counter, register_gauge, Counter, Gauge};
 use tracing::{debug, info, trace};
 
-// use crate::error::Error;
 use crate::name::Name;
-use crate::ndn::Data;
-// use crate::Result;
+use crate::ndn::{Data, Interest};
+use crate::Config;
+use crate::Result;
+
+pub mod tiered;
+#[cfg(feature = "sled")]
+pub mod persistent;
 
 /// Default content store capacity
 const DEFAULT_CAPACITY: usize = 10_000;
@@ -55,6 +62,76 @@ lazy_static! {
     static ref CACHE_EXPIRATIONS: DummyCounter = DummyCounter {};
 }
 
+/// Where the transport's content store keeps its Data
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CacheBackend {
+    /// In memory, lost on restart
+    #[default]
+    Memory,
+    
+    /// In a sled database in the given directory (requires the `sled` feature)
+    Sled(PathBuf),
+    
+    /// In memory in front of a disk tier, sized by `cache_tiers`
+    Tiered,
+}
+
+/// Storage behind the transport's content store
+pub trait ContentStoreBackend: Send + Sync {
+    /// Insert a data item under its own name with the store's default TTL
+    fn insert(&mut self, data: Data);
+    
+    /// Get a data item, honoring MustBeFresh and a trailing implicit digest
+    fn get(&self, name: &Name, must_be_fresh: bool) -> Option<Data>;
+    
+    /// Get a data item that can satisfy the Interest
+    fn get_for_interest(&self, interest: &Interest) -> Option<Data> {
+        self.get(interest.name(), interest.get_must_be_fresh())
+    }
+    
+    /// Check if the store contains an item
+    fn contains(&self, name: &Name) -> bool;
+    
+    /// Remove an item, returning whether it was stored
+    fn remove(&mut self, name: &Name) -> bool;
+    
+    /// Remove every item
+    fn clear(&mut self);
+    
+    /// Get the number of items in the store
+    fn len(&self) -> usize;
+    
+    /// Get the maximum number of items the store holds
+    fn capacity(&self) -> usize;
+    
+    /// Check if the store is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Open the content store backend selected by `cache_backend`
+pub fn open_content_store(config: &Config) -> Result<Box<dyn ContentStoreBackend>> {
+    match &config.cache_backend {
+        CacheBackend::Memory => Ok(Box::new(ContentStore::from_config(config))),
+        
+        #[cfg(feature = "sled")]
+        CacheBackend::Sled(path) => Ok(Box::new(persistent::SledContentStore::open(
+            path, config.cache_capacity, config.cache_capacity_bytes)?)),
+        
+        #[cfg(not(feature = "sled"))]
+        CacheBackend::Sled(path) => Err(crate::error::Error::ContentStore(format!(
+            "Cannot open {}: built without the sled feature", path.display()))),
+        
+        CacheBackend::Tiered => Ok(Box::new(tiered::TieredContentStore::from_config(
+            &config.cache_tiers.clone().unwrap_or_default())?)),
+    }
+}
+
+/// An entry evicted from a content store: its name, data, remaining TTL
+/// and remaining fresh period
+pub(crate) type Evicted = (Name, Data, Duration, Duration);
+
 /// A cached data entry with expiration time
 struct CacheEntry {
     /// The cached data
@@ -63,46 +140,59 @@ struct CacheEntry {
     /// When this entry was created
     created_at: Instant,
     
-    /// Time-to-live in seconds
-    ttl: u64,
+    /// Time-to-live
+    ttl: Duration,
+    
+    /// How long after creation the entry is fresh
+    fresh_for: Duration,
+    
+    /// Encoded size of the data in bytes
+    size: usize,
 }
 
 impl CacheEntry {
     /// Create a new cache entry
-    fn new(data: Data, ttl: u64) -> Self {
+    fn new(data: Data, ttl: Duration, fresh_for: Duration) -> Self {
         Self {
+            size: data.to_bytes().len(),
             data,
             created_at: Instant::now(),
             ttl,
+            fresh_for,
         }
     }
     
     /// Check if the entry has expired
     fn is_expired(&self) -> bool {
-        self.created_at.elapsed().as_secs() > self.ttl
+        self.created_at.elapsed() >= self.ttl
     }
     
-    /// Get the remaining TTL in seconds
-    fn remaining_ttl(&self) -> u64 {
-        let elapsed = self.created_at.elapsed().as_secs();
-        if elapsed >= self.ttl {
-            0
-        } else {
-            self.ttl - elapsed
-        }
+    /// Check if the entry is still within its fresh period
+    fn is_fresh(&self) -> bool {
+        self.created_at.elapsed() < self.fresh_for
+    }
+    
+    /// Get the remaining TTL
+    fn remaining_ttl(&self) -> Duration {
+        self.ttl.saturating_sub(self.created_at.elapsed())
+    }
+    
+    /// Get the remaining fresh period
+    fn remaining_fresh(&self) -> Duration {
+        self.fresh_for.saturating_sub(self.created_at.elapsed())
     }
 }
 
 /// Content store for caching NDN data
 ///
-/// This implementation uses a two-level caching strategy:
-/// 1. An LRU cache for fast access to the most recently used items
+/// This implementation keeps every item in two structures:
+/// 1. An LRU cache that tracks the recency order used for eviction
 /// 2. A DashMap for concurrent access to all cached items
 ///
-/// The LRU cache acts as a fast path for the most frequently accessed items,
-/// while the DashMap provides concurrent access to all cached items.
+/// When the store is full, the least recently inserted or retrieved item
+/// is evicted first.
 pub struct ContentStore {
-    /// LRU cache for fast access to the most recently used items
+    /// Recency order of all cached items
     lru: Mutex<LruCache<Name, Arc<CacheEntry>>>,
     
     /// Map of all cached items for concurrent access
@@ -112,15 +202,20 @@ pub struct ContentStore {
     capacity: usize,
     
     /// Default TTL for cached items
-    default_ttl: u64,
+    default_ttl: Duration,
+    
+    /// Maximum total size of the cached items in bytes (None means unbounded)
+    max_bytes: Option<usize>,
+    
+    /// Total size of the cached items in bytes
+    total_bytes: AtomicUsize,
 }
 
 impl ContentStore {
     /// Create a new content store with the given capacity
     pub fn new(capacity: usize) -> Self {
-        // Initialize the LRU cache with 1/10 of the total capacity
-        // This represents the "hot" items that are accessed most frequently
-        let lru_capacity = std::cmp::max(1, capacity / 10);
+        // The LRU cache orders every item, so it is as large as the store
+        let lru_capacity = std::cmp::max(1, capacity);
         
         // Set the Prometheus gauge for capacity
         CACHE_CAPACITY.set(capacity as f64);
@@ -131,7 +226,9 @@ impl ContentStore {
             lru: Mutex::new(LruCache::new(std::num::NonZeroUsize::new(lru_capacity).unwrap())),
             map: DashMap::with_capacity(capacity),
             capacity,
-            default_ttl: DEFAULT_TTL_SECONDS,
+            default_ttl: Duration::from_secs(DEFAULT_TTL_SECONDS),
+            max_bytes: None,
+            total_bytes: AtomicUsize::new(0),
         }
     }
     
@@ -140,34 +237,88 @@ impl ContentStore {
         Self::new(DEFAULT_CAPACITY)
     }
     
+    /// Create a content store bounded by the configured entry count and byte budget
+    pub fn from_config(config: &Config) -> Self {
+        let mut cs = Self::new(config.cache_capacity);
+        cs.set_max_bytes(config.cache_capacity_bytes);
+        cs
+    }
+    
+    /// Bound the total size of the cached items in bytes, on top of the entry count
+    ///
+    /// Items are evicted on insert until the new item fits, so lowering the
+    /// budget takes effect at the next insert.
+    pub fn set_max_bytes(&mut self, max_bytes: Option<usize>) {
+        self.max_bytes = max_bytes;
+    }
+    
+    /// Get the byte budget, if any
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+    
+    /// Get the total encoded size of the cached items in bytes
+    pub fn size_bytes(&self) -> usize {
+        self.total_bytes.load(Ordering::Relaxed)
+    }
+    
     /// Set the default TTL for cached items
     pub fn set_default_ttl(&mut self, ttl: Duration) {
-        self.default_ttl = ttl.as_secs();
+        self.default_ttl = ttl;
     }
     
     /// Get the current default TTL
     pub fn default_ttl(&self) -> Duration {
-        Duration::from_secs(self.default_ttl)
+        self.default_ttl
     }
     
-    /// Insert a data item into the cache
+    /// Insert a data item into the cache under its own name
     ///
     /// If the cache is full, the least recently used item will be evicted.
-    pub fn insert(&mut self, name: Name, data: Data) {
-        self.insert_with_ttl(name, data, self.default_ttl);
+    /// The item is kept for the default TTL, and is fresh for its Data's
+    /// fresh period.
+    pub fn insert(&mut self, data: Data) {
+        let fresh_for = data.get_fresh_period();
+        self.insert_evicting(data.name().clone(), data, self.default_ttl, fresh_for);
+    }
+    
+    /// Insert a data item under its own name, cached locally for `ttl`
+    ///
+    /// The TTL overrides the Data's fresh period for this store only; the
+    /// Data itself, including its fresh period, is stored and served unchanged.
+    pub fn insert_with_ttl(&mut self, data: Data, ttl: Duration) {
+        let fresh_for = data.get_fresh_period();
+        self.insert_evicting(data.name().clone(), data, ttl, fresh_for);
     }
     
-    /// Insert a data item with a specific TTL
-    pub fn insert_with_ttl(&mut self, name: Name, data: Data, ttl: u64) {
-        // Check if we need to evict items to make room
-        if self.map.len() >= self.capacity && !self.map.contains_key(&name) {
-            self.evict_one();
+    /// Insert a data item kept for `ttl` and fresh for `fresh_for`,
+    /// returning the entries evicted to make room along with their
+    /// remaining TTLs and fresh periods
+    pub(crate) fn insert_evicting(&self, name: Name, data: Data, ttl: Duration, fresh_for: Duration) -> Vec<Evicted> {
+        // Create the cache entry
+        let entry = Arc::new(CacheEntry::new(data, ttl, fresh_for));
+        let mut evicted = Vec::new();
+        
+        if let Some(max_bytes) = self.max_bytes.filter(|max_bytes| entry.size > *max_bytes) {
+            debug!("Not caching {}: {} bytes exceeds the {} byte budget", name, entry.size, max_bytes);
+            return evicted;
         }
         
-        // Create the cache entry
-        let entry = Arc::new(CacheEntry::new(data, ttl));
+        // An older copy doesn't count against either limit
+        self.remove_entry(&name);
+        
+        // Evict until both the entry count and the byte budget leave room
+        while self.map.len() >= self.capacity
+            || self.max_bytes.map_or(false, |max_bytes| self.size_bytes() + entry.size > max_bytes)
+        {
+            match self.evict_one() {
+                Some(item) => evicted.push(item),
+                None => break,
+            }
+        }
         
         // Insert into both caches
+        self.total_bytes.fetch_add(entry.size, Ordering::Relaxed);
         self.map.insert(name.clone(), Arc::clone(&entry));
         self.lru.lock().put(name.clone(), entry);
         
@@ -176,19 +327,46 @@ impl ContentStore {
         CACHE_INSERTS.inc();
         
         trace!("Inserted data for {}", name);
+        
+        evicted.retain(|(_, _, remaining, _)| !remaining.is_zero());
+        evicted
     }
     
     /// Get a data item from the cache
     ///
-    /// Returns None if the item is not in the cache or has expired.
-    pub fn get(&self, name: &Name) -> Option<Data> {
+    /// Returns None if the item is not in the cache or has expired, or if
+    /// `must_be_fresh` is set and the item is past its Data's fresh period;
+    /// stale items stay cached for lookups that accept them. A name ending
+    /// in an ImplicitSha256Digest only matches Data with that digest.
+    pub fn get(&self, name: &Name, must_be_fresh: bool) -> Option<Data> {
+        if name.implicit_digest().is_some() {
+            let data = self.get_exact(&name.without_implicit_digest(), must_be_fresh)?;
+            return match data.verify_implicit_digest(name) {
+                Ok(()) => Some(data),
+                Err(e) => {
+                    debug!("Cached data rejected: {}", e);
+                    None
+                }
+            };
+        }
+        
+        self.get_exact(name, must_be_fresh)
+    }
+    
+    /// Get a data item that can satisfy the Interest
+    pub fn get_for_interest(&self, interest: &Interest) -> Option<Data> {
+        self.get(interest.name(), interest.get_must_be_fresh())
+    }
+    
+    /// Get a data item stored under exactly this name
+    fn get_exact(&self, name: &Name, must_be_fresh: bool) -> Option<Data> {
         // First check the LRU cache (fast path)
         let mut lru = self.lru.lock();
         if let Some(entry) = lru.get(name) {
             if entry.is_expired() {
                 // Entry has expired, remove it from both caches
                 lru.pop(name);
-                self.map.remove(name);
+                self.remove_from_map(name);
                 CACHE_EXPIRATIONS.inc();
                 CACHE_SIZE.set(self.map.len() as f64);
                 debug!("Expired entry for {}", name);
@@ -196,6 +374,12 @@ impl ContentStore {
                 return None;
             }
             
+            if must_be_fresh && !entry.is_fresh() {
+                trace!("Stale entry for {} cannot satisfy MustBeFresh", name);
+                CACHE_MISSES.inc();
+                return None;
+            }
+            
             // Entry is valid, return a clone of the data
             trace!("LRU cache hit for {}", name);
             CACHE_HITS.inc();
@@ -205,8 +389,9 @@ impl ContentStore {
         // Check the main map
         if let Some(entry) = self.map.get(name) {
             if entry.is_expired() {
-                // Entry has expired, remove it
-                self.map.remove(name);
+                // Entry has expired; release the map guard and remove it
+                drop(entry);
+                self.remove_from_map(name);
                 CACHE_EXPIRATIONS.inc();
                 CACHE_SIZE.set(self.map.len() as f64);
                 debug!("Expired entry for {}", name);
@@ -214,6 +399,12 @@ impl ContentStore {
                 return None;
             }
             
+            if must_be_fresh && !entry.is_fresh() {
+                trace!("Stale entry for {} cannot satisfy MustBeFresh", name);
+                CACHE_MISSES.inc();
+                return None;
+            }
+            
             // Entry is valid, promote it to the LRU cache and return a clone
             lru.put(name.clone(), Arc::clone(&entry));
             trace!("Map cache hit for {}", name);
@@ -245,12 +436,17 @@ impl ContentStore {
     ///
     /// Returns true if the item was removed, false if it wasn't in the cache.
     pub fn remove(&mut self, name: &Name) -> bool {
+        self.remove_entry(name)
+    }
+    
+    /// Remove an item from both caches
+    fn remove_entry(&self, name: &Name) -> bool {
         // Remove from the LRU cache
         let mut lru = self.lru.lock();
         let in_lru = lru.pop(name).is_some();
         
         // Remove from the main map
-        let in_map = self.map.remove(name).is_some();
+        let in_map = self.remove_from_map(name).is_some();
         
         if in_lru || in_map {
             CACHE_SIZE.set(self.map.len() as f64);
@@ -264,6 +460,7 @@ impl ContentStore {
         let mut lru = self.lru.lock();
         lru.clear();
         self.map.clear();
+        self.total_bytes.store(0, Ordering::Relaxed);
         CACHE_SIZE.set(0.0);
         info!("Cleared content store");
     }
@@ -273,33 +470,56 @@ impl ContentStore {
         self.map.len()
     }
     
+    /// Get the maximum number of items in the cache; a zero capacity still
+    /// holds one
+    pub fn capacity(&self) -> usize {
+        self.capacity.max(1)
+    }
+    
     /// Check if the cache is empty
     pub fn is_empty(&self) -> bool {
         self.map.is_empty()
     }
     
+    /// Stream the names of all cached items
+    ///
+    /// The names are snapshotted up front (without cloning any Data), so the
+    /// store can be modified while the stream is consumed. Entries inserted
+    /// afterwards are not seen; removed or expired ones may still be listed.
+    pub fn names(&self) -> impl Stream<Item = Name> {
+        let names: Vec<Name> = self.map.iter().map(|entry| entry.key().clone()).collect();
+        stream::iter(names)
+    }
+    
     /// Evict one item from the cache
     ///
     /// This uses the LRU policy to decide which item to evict.
     /// If the LRU cache is empty, it evicts a random item from the main map.
-    fn evict_one(&mut self) {
+    /// Returns the evicted name, data, remaining TTL and remaining fresh period.
+    fn evict_one(&self) -> Option<Evicted> {
         // Try to evict from the LRU cache
         let mut lru = self.lru.lock();
-        if let Some((name, _)) = lru.pop_lru() {
+        if let Some((name, entry)) = lru.pop_lru() {
             // Also remove from the main map
-            self.map.remove(&name);
+            self.remove_from_map(&name);
             CACHE_EVICTIONS.inc();
             trace!("Evicted LRU entry for {}", name);
-            return;
+            return Some((name, entry.data.clone(), entry.remaining_ttl(), entry.remaining_fresh()));
         }
         
         // If the LRU cache is empty, evict a random item from the main map
-        if let Some(entry) = self.map.iter().next() {
-            let name = entry.key().clone();
-            self.map.remove(&name);
-            CACHE_EVICTIONS.inc();
-            trace!("Evicted random entry for {}", name);
-        }
+        let name = self.map.iter().next().map(|entry| entry.key().clone())?;
+        let entry = self.remove_from_map(&name)?;
+        CACHE_EVICTIONS.inc();
+        trace!("Evicted random entry for {}", name);
+        Some((name, entry.data.clone(), entry.remaining_ttl(), entry.remaining_fresh()))
+    }
+    
+    /// Remove an entry from the main map, releasing its bytes
+    fn remove_from_map(&self, name: &Name) -> Option<Arc<CacheEntry>> {
+        let (_, entry) = self.map.remove(name)?;
+        self.total_bytes.fetch_sub(entry.size, Ordering::Relaxed);
+        Some(entry)
     }
     
     /// Expire all entries that have exceeded their TTL
@@ -339,7 +559,7 @@ impl ContentStore {
             if entry.is_expired() {
                 None
             } else {
-                Some(Duration::from_secs(entry.remaining_ttl()))
+                Some(entry.remaining_ttl())
             }
         } else {
             None
@@ -347,9 +567,40 @@ impl ContentStore {
     }
 }
 
+impl ContentStoreBackend for ContentStore {
+    fn insert(&mut self, data: Data) {
+        ContentStore::insert(self, data)
+    }
+    
+    fn get(&self, name: &Name, must_be_fresh: bool) -> Option<Data> {
+        ContentStore::get(self, name, must_be_fresh)
+    }
+    
+    fn contains(&self, name: &Name) -> bool {
+        ContentStore::contains(self, name)
+    }
+    
+    fn remove(&mut self, name: &Name) -> bool {
+        ContentStore::remove(self, name)
+    }
+    
+    fn clear(&mut self) {
+        ContentStore::clear(self)
+    }
+    
+    fn len(&self) -> usize {
+        ContentStore::len(self)
+    }
+    
+    fn capacity(&self) -> usize {
+        ContentStore::capacity(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
     use crate::ndn::{Data, Interest};
     
     #[test]
@@ -361,9 +612,9 @@ mod tests {
         let data = Data::new(name.clone(), vec![1, 2, 3, 4]);
         
         // Insert and retrieve
-        cs.insert(name.clone(), data.clone());
+        cs.insert(data.clone());
         
-        let retrieved = cs.get(&name);
+        let retrieved = cs.get(&name, false);
         assert!(retrieved.is_some());
         
         // Check content equality
@@ -381,16 +632,102 @@ mod tests {
         let data = Data::new(name.clone(), vec![1, 2, 3, 4]);
         
         // Insert with a very short TTL (1 second)
-        cs.insert_with_ttl(name.clone(), data.clone(), 1);
+        cs.insert_with_ttl(data.clone(), Duration::from_secs(1));
         
         // Should be available immediately
-        assert!(cs.get(&name).is_some());
+        assert!(cs.get(&name, false).is_some());
         
         // Wait for expiration
         std::thread::sleep(Duration::from_secs(2));
         
         // Should be expired now
-        assert!(cs.get(&name).is_none());
+        assert!(cs.get(&name, false).is_none());
+    }
+    
+    #[test]
+    fn test_insert_with_ttl_overrides_freshness() {
+        let mut cs = ContentStore::new(10);
+        
+        // Fresh for an hour, but cached locally for only 50 ms
+        let name = Name::from_uri("/test/edge/policy").unwrap();
+        let data = Data::new(name.clone(), vec![1, 2, 3, 4]).fresh_period(Duration::from_secs(3600));
+        cs.insert_with_ttl(data, Duration::from_millis(50));
+        
+        // The cached Data keeps its own fresh period for downstream
+        let cached = cs.get(&name, false).unwrap();
+        assert_eq!(cached.get_fresh_period(), Duration::from_secs(3600));
+        assert!(cs.get_ttl(&name).unwrap() <= Duration::from_millis(50));
+        
+        std::thread::sleep(Duration::from_millis(60));
+        
+        // The local TTL has passed, so it is gone from this store
+        assert!(cs.get(&name, false).is_none());
+        assert!(!cs.contains(&name));
+    }
+    
+    #[test]
+    fn test_must_be_fresh_skips_stale_entries() {
+        let mut cs = ContentStore::new(10);
+        
+        // Fresh for 50 ms, but cached for the default TTL
+        let name = Name::from_uri("/test/fresh").unwrap();
+        cs.insert(Data::new(name.clone(), vec![1, 2, 3, 4]).fresh_period(Duration::from_millis(50)));
+        
+        let fresh = Interest::new(name.clone()).must_be_fresh(true);
+        let any = Interest::new(name.clone()).must_be_fresh(false);
+        assert!(cs.get_for_interest(&fresh).is_some());
+        assert!(cs.get_for_interest(&any).is_some());
+        assert!(cs.get(&name, true).is_some());
+        
+        std::thread::sleep(Duration::from_millis(60));
+        
+        // Stale now: only lookups without MustBeFresh are satisfied
+        assert!(cs.get_for_interest(&fresh).is_none());
+        assert!(cs.get(&name, true).is_none());
+        assert!(cs.get_for_interest(&any).is_some());
+        assert!(cs.get(&name, false).is_some());
+        assert!(cs.contains(&name));
+    }
+    
+    #[test]
+    fn test_byte_budget_bounds_total_size() {
+        let config = Config {
+            cache_capacity: 100,
+            cache_capacity_bytes: Some(200),
+            ..Default::default()
+        };
+        let mut cs = ContentStore::from_config(&config);
+        
+        // Mixed sizes adding up to well over the budget, far under the entry count
+        let sizes = [10, 120, 30, 60, 90, 5, 100, 40];
+        let mut inserted = 0;
+        for (i, size) in sizes.iter().enumerate() {
+            let name = Name::from_uri(&format!("/test/budget/{}", i)).unwrap();
+            let data = Data::new(name.clone(), vec![i as u8; *size]);
+            inserted += data.to_bytes().len();
+            cs.insert(data);
+            
+            // The newest item always fits, and the total stays under budget
+            assert!(cs.contains(&name));
+            assert!(cs.size_bytes() <= 200, "{} bytes cached after insert {}", cs.size_bytes(), i);
+        }
+        assert!(inserted > 200);
+        assert!(cs.len() < sizes.len());
+        
+        // The running total matches what is actually cached
+        let cached: usize = (0..sizes.len())
+            .filter_map(|i| cs.get(&Name::from_uri(&format!("/test/budget/{}", i)).unwrap(), false))
+            .map(|data| data.to_bytes().len())
+            .sum();
+        assert_eq!(cs.size_bytes(), cached);
+        
+        // Data larger than the whole budget is not cached at all
+        let huge = Name::from_uri("/test/budget/huge").unwrap();
+        cs.insert(Data::new(huge.clone(), vec![0; 250]));
+        assert!(!cs.contains(&huge));
+        
+        cs.clear();
+        assert_eq!(cs.size_bytes(), 0);
     }
     
     #[test]
@@ -408,23 +745,119 @@ mod tests {
         // Insert 3 items
         for i in 0..3 {
             let data = Data::new(names[i].clone(), vec![i as u8]);
-            cs.insert(names[i].clone(), data);
+            cs.insert(data);
         }
         
         // All 3 should be in the cache
         for i in 0..3 {
-            assert!(cs.get(&names[i]).is_some());
+            assert!(cs.get(&names[i], false).is_some());
         }
         
         // Insert a 4th item, which should evict the least recently used
         let data = Data::new(names[3].clone(), vec![3]);
-        cs.insert(names[3].clone(), data);
+        cs.insert(data);
         
         // The 4th item should be in the cache
-        assert!(cs.get(&names[3]).is_some());
+        assert!(cs.get(&names[3], false).is_some());
         
         // One of the previous items should have been evicted,
         // but we can't know which one in this test
         assert!(cs.len() == 3);
     }
+    
+    #[test]
+    fn test_content_store_eviction_order() {
+        let mut cs = ContentStore::new(3);
+        let names: Vec<Name> = (1..=4)
+            .map(|i| Name::from_uri(&format!("/test/data{}", i)).unwrap())
+            .collect();
+        
+        for (i, name) in names.iter().take(3).enumerate() {
+            cs.insert(Data::new(name.clone(), vec![i as u8]));
+        }
+        
+        // Touch data1 so data2 becomes the least recently used
+        assert!(cs.get(&names[0], false).is_some());
+        
+        cs.insert(Data::new(names[3].clone(), vec![3]));
+        assert_eq!(cs.len(), 3);
+        assert!(!cs.contains(&names[1]));
+        assert!(cs.contains(&names[0]));
+        assert!(cs.contains(&names[2]));
+        assert!(cs.contains(&names[3]));
+        
+        // The next eviction takes data3, the oldest untouched entry
+        cs.insert(Data::new(names[1].clone(), vec![1]));
+        assert!(!cs.contains(&names[2]));
+        assert!(cs.contains(&names[0]));
+    }
+    
+    #[tokio::test]
+    async fn test_content_store_names() {
+        use futures::StreamExt;
+        
+        let mut cs = ContentStore::new(10);
+        let mut expected: Vec<String> = (0..5).map(|i| format!("/test/names/{}", i)).collect();
+        for uri in &expected {
+            let name = Name::from_uri(uri).unwrap();
+            cs.insert(Data::new(name, vec![1, 2, 3, 4]));
+        }
+        
+        // Modifying the store mid-iteration doesn't disturb the stream
+        let mut names = Box::pin(cs.names());
+        let first = names.next().await.unwrap();
+        cs.remove(&first);
+        
+        let mut listed = vec![first.to_string()];
+        while let Some(name) = names.next().await {
+            listed.push(name.to_string());
+        }
+        
+        listed.sort();
+        expected.sort();
+        assert_eq!(listed, expected);
+    }
+    
+    #[test]
+    fn test_content_store_implicit_digest() {
+        let mut cs = ContentStore::new(10);
+        
+        let name = Name::from_uri("/test/data").unwrap();
+        let data = Data::new(name.clone(), vec![1, 2, 3, 4]);
+        cs.insert(data.clone());
+        
+        // The full name matches the cached Data
+        let hit = cs.get(&data.full_name(), false);
+        assert_eq!(hit.unwrap().content(), data.content());
+        
+        // A digest of different content does not
+        let other = Data::new(name.clone(), vec![5, 6, 7, 8]);
+        assert!(cs.get(&other.full_name(), false).is_none());
+        assert!(matches!(data.verify_implicit_digest(&other.full_name()), Err(Error::DigestMismatch(_))));
+    }    
+    #[test]
+    fn test_open_selects_backend() {
+        let config = Config {
+            cache_capacity: 2,
+            ..Default::default()
+        };
+        let mut cs = open_content_store(&config).unwrap();
+        
+        for i in 0..3 {
+            cs.insert(Data::new(Name::from_uri(&format!("/test/backend/{}", i)).unwrap(), vec![i as u8]));
+        }
+        assert_eq!(cs.len(), 2);
+        
+        let interest = Interest::new(Name::from_uri("/test/backend/2").unwrap());
+        assert_eq!(cs.get_for_interest(&interest).unwrap().content().as_ref(), &[2]);
+        
+        #[cfg(not(feature = "sled"))]
+        {
+            let config = Config {
+                cache_backend: CacheBackend::Sled(std::env::temp_dir().join("udcn-no-sled")),
+                ..config
+            };
+            assert!(matches!(open_content_store(&config), Err(Error::ContentStore(_))));
+        }
+    }
 }
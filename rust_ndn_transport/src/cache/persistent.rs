@@ -0,0 +1,407 @@
+//
+// μDCN Persistent Content Store
+//
+// This module implements a content store backed by a sled database, so that
+// cached Data survives restarts. Each entry is stored under its name's TLV
+// encoding along with its expiry and arrival times; a second tree records
+// the order entries were last used in, from which the LRU order is rebuilt
+// when the store is reopened.
+//
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use lru::LruCache;
+use parking_lot::Mutex;
+use tracing::{debug, info, trace, warn};
+
+use crate::cache::{ContentStoreBackend, DEFAULT_TTL_SECONDS};
+use crate::error::Error;
+use crate::name::Name;
+use crate::ndn::Data;
+use crate::Result;
+
+/// Size of the record header: expiry and arrival times in Unix milliseconds
+const RECORD_HEADER_SIZE: usize = 16;
+
+/// A sled-backed content store
+///
+/// The store is bounded by an entry count and, optionally, a byte budget,
+/// evicting the least recently used entries first. The recency order is
+/// persisted, so limits keep being enforced in the same order after a
+/// restart, including when the store is reopened with smaller limits.
+pub struct SledContentStore {
+    /// The database, flushed when the store is dropped
+    db: sled::Db,
+    
+    /// Records keyed by name TLV
+    records: sled::Tree,
+    
+    /// Last-use sequence number keyed by name TLV
+    recency: sled::Tree,
+    
+    /// Names in LRU order with their encoded sizes
+    index: Mutex<LruCache<Name, usize>>,
+    
+    /// Next last-use sequence number
+    next_seq: AtomicU64,
+    
+    /// Maximum number of entries
+    capacity: usize,
+    
+    /// Maximum total size of the stored Data in bytes (None means unbounded)
+    max_bytes: Option<usize>,
+    
+    /// Total size of the stored Data in bytes
+    total_bytes: AtomicU64,
+    
+    /// TTL for inserted entries
+    default_ttl: Duration,
+}
+
+impl SledContentStore {
+    /// Open the store in the given directory, restoring its entries
+    ///
+    /// Expired entries are dropped, and the least recently used ones are
+    /// evicted until the store is within `capacity` and `max_bytes`.
+    pub fn open(path: impl AsRef<Path>, capacity: usize, max_bytes: Option<usize>) -> Result<Self> {
+        let path = path.as_ref();
+        let db = sled::open(path)
+            .map_err(|e| Error::ContentStore(format!("Failed to open {}: {}", path.display(), e)))?;
+        let records = db.open_tree("records").map_err(store_error)?;
+        let recency = db.open_tree("recency").map_err(store_error)?;
+        
+        let store = Self {
+            db,
+            records,
+            recency,
+            index: Mutex::new(LruCache::unbounded()),
+            next_seq: AtomicU64::new(0),
+            capacity,
+            max_bytes,
+            total_bytes: AtomicU64::new(0),
+            default_ttl: Duration::from_secs(DEFAULT_TTL_SECONDS),
+        };
+        store.restore()?;
+        
+        info!("Opened persistent content store at {} with {} entries", path.display(), store.len());
+        Ok(store)
+    }
+    
+    /// Set the TTL for entries inserted from now on
+    pub fn set_default_ttl(&mut self, ttl: Duration) {
+        self.default_ttl = ttl;
+    }
+    
+    /// Get the total encoded size of the stored Data in bytes
+    pub fn size_bytes(&self) -> usize {
+        self.total_bytes.load(Ordering::Relaxed) as usize
+    }
+    
+    /// Insert a data item, stored for `ttl`
+    pub fn insert_with_ttl(&self, data: Data, ttl: Duration) -> Result<()> {
+        let name = data.name().clone();
+        let wire = data.to_bytes();
+        
+        if let Some(max_bytes) = self.max_bytes.filter(|max_bytes| wire.len() > *max_bytes) {
+            debug!("Not storing {}: {} bytes exceeds the {} byte budget", name, wire.len(), max_bytes);
+            return Ok(());
+        }
+        
+        // An older copy doesn't count against either limit
+        self.remove_entry(&name)?;
+        
+        let mut index = self.index.lock();
+        while index.len() >= self.capacity
+            || self.max_bytes.map_or(false, |max_bytes| self.size_bytes() + wire.len() > max_bytes)
+        {
+            match index.pop_lru() {
+                Some((evicted, size)) => {
+                    self.delete(&evicted, size)?;
+                    trace!("Evicted persistent entry for {}", evicted);
+                }
+                None => break,
+            }
+        }
+        
+        let now = unix_millis();
+        let mut record = BytesMut::with_capacity(RECORD_HEADER_SIZE + wire.len());
+        record.put_u64(now.saturating_add(ttl.as_millis() as u64));
+        record.put_u64(now);
+        record.put_slice(&wire);
+        
+        let key = name.to_tlv();
+        self.records.insert(&key[..], &record[..]).map_err(store_error)?;
+        self.touch(&key)?;
+        
+        self.total_bytes.fetch_add(wire.len() as u64, Ordering::Relaxed);
+        index.put(name, wire.len());
+        Ok(())
+    }
+    
+    /// Write everything inserted so far to disk
+    pub fn flush(&self) -> Result<()> {
+        self.db.flush().map_err(store_error)?;
+        Ok(())
+    }
+    
+    /// Rebuild the index from the stored entries and enforce the limits
+    fn restore(&self) -> Result<()> {
+        let now = unix_millis();
+        let mut entries = Vec::new();
+        
+        for item in self.recency.iter() {
+            let (key, seq) = item.map_err(store_error)?;
+            let name = Name::from_tlv(&mut Bytes::copy_from_slice(&key))?;
+            
+            let record = self.records.get(&key).map_err(store_error)?;
+            match record.as_deref().and_then(read_header) {
+                Some((expires_at, _)) if expires_at > now => {
+                    let size = record.map_or(0, |record| record.len() - RECORD_HEADER_SIZE);
+                    entries.push((read_seq(&seq), name, size));
+                }
+                _ => {
+                    self.records.remove(&key).map_err(store_error)?;
+                    self.recency.remove(&key).map_err(store_error)?;
+                }
+            }
+        }
+        
+        // Oldest first, so the most recently used end up at the front
+        entries.sort_by_key(|(seq, _, _)| *seq);
+        
+        let mut index = self.index.lock();
+        for (seq, name, size) in entries {
+            self.next_seq.fetch_max(seq + 1, Ordering::Relaxed);
+            self.total_bytes.fetch_add(size as u64, Ordering::Relaxed);
+            index.put(name, size);
+        }
+        
+        while index.len() > self.capacity
+            || self.max_bytes.map_or(false, |max_bytes| self.size_bytes() > max_bytes)
+        {
+            match index.pop_lru() {
+                Some((evicted, size)) => self.delete(&evicted, size)?,
+                None => break,
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Record a use of the entry with the given key
+    fn touch(&self, key: &[u8]) -> Result<()> {
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        self.recency.insert(key, &seq.to_be_bytes()[..]).map_err(store_error)?;
+        Ok(())
+    }
+    
+    /// Remove an entry from disk and from the index
+    fn remove_entry(&self, name: &Name) -> Result<bool> {
+        let size = self.index.lock().pop(name);
+        match size {
+            Some(size) => {
+                self.delete(name, size)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    
+    /// Remove an entry from disk, releasing its bytes
+    fn delete(&self, name: &Name, size: usize) -> Result<()> {
+        let key = name.to_tlv();
+        self.records.remove(&key[..]).map_err(store_error)?;
+        self.recency.remove(&key[..]).map_err(store_error)?;
+        self.total_bytes.fetch_sub(size as u64, Ordering::Relaxed);
+        Ok(())
+    }
+    
+    /// Read a stored entry, dropping it if it has expired
+    fn load(&self, name: &Name, must_be_fresh: bool) -> Result<Option<Data>> {
+        let key = name.to_tlv();
+        let record = match self.records.get(&key[..]).map_err(store_error)? {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+        
+        let (expires_at, stored_at) = read_header(&record)
+            .ok_or_else(|| Error::ContentStore(format!("Truncated record for {}", name)))?;
+        
+        let now = unix_millis();
+        if now >= expires_at {
+            debug!("Expired persistent entry for {}", name);
+            self.remove_entry(name)?;
+            return Ok(None);
+        }
+        
+        let data = Data::from_bytes(&record[RECORD_HEADER_SIZE..])?;
+        if must_be_fresh && now >= stored_at.saturating_add(data.get_fresh_period().as_millis() as u64) {
+            trace!("Stale entry for {} cannot satisfy MustBeFresh", name);
+            return Ok(None);
+        }
+        
+        self.index.lock().promote(name);
+        self.touch(&key)?;
+        Ok(Some(data))
+    }
+}
+
+impl ContentStoreBackend for SledContentStore {
+    fn insert(&mut self, data: Data) {
+        let name = data.name().clone();
+        if let Err(e) = self.insert_with_ttl(data, self.default_ttl) {
+            warn!("Failed to store {}: {}", name, e);
+        }
+    }
+    
+    fn get(&self, name: &Name, must_be_fresh: bool) -> Option<Data> {
+        // A full name only matches Data with that digest
+        let exact = name.without_implicit_digest();
+        match self.load(&exact, must_be_fresh) {
+            Ok(data) => data.filter(|data| data.verify_implicit_digest(name).is_ok()),
+            Err(e) => {
+                warn!("Failed to read {} from the content store: {}", exact, e);
+                None
+            }
+        }
+    }
+    
+    fn contains(&self, name: &Name) -> bool {
+        self.index.lock().contains(name)
+    }
+    
+    fn remove(&mut self, name: &Name) -> bool {
+        self.remove_entry(name).unwrap_or_else(|e| {
+            warn!("Failed to remove {} from the content store: {}", name, e);
+            false
+        })
+    }
+    
+    fn clear(&mut self) {
+        let cleared = self.records.clear().and_then(|_| self.recency.clear());
+        if let Err(e) = cleared {
+            warn!("Failed to clear the content store: {}", e);
+        }
+        self.index.lock().clear();
+        self.total_bytes.store(0, Ordering::Relaxed);
+    }
+    
+    fn len(&self) -> usize {
+        self.index.lock().len()
+    }
+    
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+impl Drop for SledContentStore {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Failed to flush the content store: {}", e);
+        }
+    }
+}
+
+/// Convert a sled error
+fn store_error(e: sled::Error) -> Error {
+    Error::ContentStore(format!("Persistent content store error: {}", e))
+}
+
+/// Read the expiry and arrival times from the front of a record
+fn read_header(mut record: &[u8]) -> Option<(u64, u64)> {
+    if record.len() < RECORD_HEADER_SIZE {
+        return None;
+    }
+    Some((record.get_u64(), record.get_u64()))
+}
+
+/// Read a big-endian sequence number, treating malformed ones as the oldest
+fn read_seq(value: &[u8]) -> u64 {
+    value.try_into().map(u64::from_be_bytes).unwrap_or(0)
+}
+
+/// Get the current time in Unix milliseconds
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn test_dir(label: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("udcn-sled-{}-{}", label, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+    
+    fn name(i: usize) -> Name {
+        Name::from_uri(&format!("/test/persistent/{}", i)).unwrap()
+    }
+    
+    #[test]
+    fn test_data_survives_reopen() {
+        let dir = test_dir("reopen");
+        
+        {
+            let mut cs = SledContentStore::open(&dir, 10, None).unwrap();
+            cs.insert(Data::new(name(0), vec![1, 2, 3, 4]));
+            cs.insert(Data::new(name(1), vec![5, 6, 7, 8]));
+        }
+        
+        let cs = SledContentStore::open(&dir, 10, None).unwrap();
+        assert_eq!(cs.len(), 2);
+        assert_eq!(cs.get(&name(0), false).unwrap().content().as_ref(), &[1, 2, 3, 4]);
+        assert_eq!(cs.get(&name(1), true).unwrap().content().as_ref(), &[5, 6, 7, 8]);
+        
+        // Full names are checked against the stored Data
+        let stored = Data::new(name(0), vec![1, 2, 3, 4]);
+        assert!(cs.get(&stored.full_name(), false).is_some());
+        assert!(cs.get(&Data::new(name(0), vec![0]).full_name(), false).is_none());
+        
+        drop(cs);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+    
+    #[test]
+    fn test_capacity_enforced_across_restarts() {
+        let dir = test_dir("capacity");
+        
+        {
+            let mut cs = SledContentStore::open(&dir, 3, None).unwrap();
+            for i in 0..3 {
+                cs.insert(Data::new(name(i), vec![i as u8]));
+            }
+            
+            // Touch 0 so that 1 is the least recently used
+            assert!(cs.get(&name(0), false).is_some());
+        }
+        
+        {
+            // The restored store is full, so an insert evicts the LRU entry
+            let mut cs = SledContentStore::open(&dir, 3, None).unwrap();
+            assert_eq!(cs.len(), 3);
+            cs.insert(Data::new(name(3), vec![3]));
+            assert_eq!(cs.len(), 3);
+            assert!(!cs.contains(&name(1)));
+            assert!(cs.contains(&name(0)));
+        }
+        
+        // Reopening with a smaller capacity evicts down to it, oldest first
+        let cs = SledContentStore::open(&dir, 2, None).unwrap();
+        assert_eq!(cs.len(), 2);
+        assert!(cs.contains(&name(0)));
+        assert!(cs.contains(&name(3)));
+        assert!(cs.get(&name(2), false).is_none());
+        
+        drop(cs);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,446 @@
+//
+// μDCN Tiered Content Store
+//
+// This module implements a two-tier content store: a small in-memory L1
+// (the regular `ContentStore`) in front of a larger on-disk L2. Lookups
+// check L1 then L2, promoting L2 hits back into L1; inserts go to L1, and
+// entries evicted from L1 spill into L2 instead of being dropped. It backs
+// the transport's content store when `cache_backend` is `Tiered`.
+//
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use parking_lot::{Mutex, MutexGuard};
+use sha2::{Digest, Sha256};
+use tracing::{debug, trace, warn};
+
+use crate::cache::{ContentStore, ContentStoreBackend};
+use crate::error::Error;
+use crate::name::Name;
+use crate::ndn::Data;
+use crate::Result;
+
+/// Configuration for a tiered content store
+#[derive(Debug, Clone)]
+pub struct CacheTiers {
+    /// Number of entries held in memory (L1)
+    pub l1_capacity: usize,
+    
+    /// Number of entries held on disk (L2)
+    pub l2_capacity: usize,
+    
+    /// Directory the L2 tier stores its entries in
+    pub l2_path: PathBuf,
+}
+
+impl Default for CacheTiers {
+    fn default() -> Self {
+        Self {
+            l1_capacity: 1_000,
+            l2_capacity: 100_000,
+            l2_path: std::env::temp_dir().join("udcn-cs"),
+        }
+    }
+}
+
+/// An on-disk entry
+struct DiskEntry {
+    /// File holding the encoded Data
+    path: PathBuf,
+    
+    /// When the entry expires
+    expires_at: Instant,
+    
+    /// When the entry stops being fresh
+    fresh_until: Instant,
+}
+
+/// An on-disk content store, one file per Data packet
+///
+/// Only the index is kept in memory; it is not reloaded on restart, so
+/// files left behind by a previous process are overwritten or ignored.
+pub struct DiskStore {
+    /// Directory holding the entry files
+    dir: PathBuf,
+    
+    /// Entries in LRU order
+    index: LruCache<Name, DiskEntry>,
+}
+
+impl DiskStore {
+    /// Open a disk store in the given directory, creating it if necessary
+    pub fn new(dir: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| Error::ContentStore(format!("Failed to create {}: {}", dir.display(), e)))?;
+        
+        let capacity = std::num::NonZeroUsize::new(capacity)
+            .ok_or_else(|| Error::ContentStore("L2 capacity must be non-zero".into()))?;
+        
+        Ok(Self {
+            dir,
+            index: LruCache::new(capacity),
+        })
+    }
+    
+    /// Store a data item kept for `ttl` and fresh for `fresh_for`
+    pub fn insert(&mut self, name: Name, data: &Data, ttl: Duration, fresh_for: Duration) -> Result<()> {
+        let path = self.dir.join(format!("{}.data", hex::encode(Sha256::digest(name.to_tlv()))));
+        fs::write(&path, data.to_bytes())
+            .map_err(|e| Error::ContentStore(format!("Failed to write {}: {}", path.display(), e)))?;
+        
+        let now = Instant::now();
+        let entry = DiskEntry {
+            path,
+            expires_at: now + ttl,
+            fresh_until: now + fresh_for,
+        };
+        
+        // Drop the file of the entry pushed out by this insert
+        if let Some((evicted, old)) = self.index.push(name.clone(), entry) {
+            if evicted != name {
+                let _ = fs::remove_file(&old.path);
+                trace!("Evicted L2 entry for {}", evicted);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Take a data item out of the store, with its remaining TTL and fresh
+    /// period
+    ///
+    /// With `must_be_fresh`, an item past its fresh period is left in place.
+    pub fn take(&mut self, name: &Name, must_be_fresh: bool) -> Option<(Data, Duration, Duration)> {
+        if must_be_fresh && self.index.peek(name)?.fresh_until <= Instant::now() {
+            trace!("Stale L2 entry for {} cannot satisfy MustBeFresh", name);
+            return None;
+        }
+        
+        let entry = self.index.pop(name)?;
+        let bytes = fs::read(&entry.path);
+        let _ = fs::remove_file(&entry.path);
+        
+        let now = Instant::now();
+        let remaining = entry.expires_at.saturating_duration_since(now);
+        if remaining.is_zero() {
+            debug!("Expired L2 entry for {}", name);
+            return None;
+        }
+        
+        match bytes.map_err(Error::from).and_then(|bytes| Data::from_bytes(&bytes)) {
+            Ok(data) => Some((data, remaining, entry.fresh_until.saturating_duration_since(now))),
+            Err(e) => {
+                warn!("Dropping unreadable L2 entry for {}: {}", name, e);
+                None
+            }
+        }
+    }
+    
+    /// Remove a data item
+    pub fn remove(&mut self, name: &Name) -> bool {
+        match self.index.pop(name) {
+            Some(entry) => {
+                let _ = fs::remove_file(&entry.path);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Remove every item
+    pub fn clear(&mut self) {
+        for (_, entry) in self.index.iter() {
+            let _ = fs::remove_file(&entry.path);
+        }
+        self.index.clear();
+    }
+    
+    /// Check if the store contains an item
+    pub fn contains(&self, name: &Name) -> bool {
+        self.index.contains(name)
+    }
+    
+    /// Get the number of items in the store
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+    
+    /// Get the maximum number of items in the store
+    pub fn capacity(&self) -> usize {
+        self.index.cap().get()
+    }
+    
+    /// Check if the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+}
+
+impl Drop for DiskStore {
+    fn drop(&mut self) {
+        for (_, entry) in self.index.iter() {
+            let _ = fs::remove_file(&entry.path);
+        }
+    }
+}
+
+/// A content store with an in-memory L1 and an on-disk L2
+pub struct TieredContentStore {
+    /// Fast, small tier
+    l1: ContentStore,
+    
+    /// Large, slower tier, locked so lookups can promote from it
+    l2: Mutex<DiskStore>,
+}
+
+impl TieredContentStore {
+    /// Create a tiered store from its two tiers
+    pub fn new(l1: ContentStore, l2: DiskStore) -> Self {
+        Self { l1, l2: Mutex::new(l2) }
+    }
+    
+    /// Create a tiered store from the cache tier configuration
+    pub fn from_config(tiers: &CacheTiers) -> Result<Self> {
+        Ok(Self::new(
+            ContentStore::new(tiers.l1_capacity),
+            DiskStore::new(&tiers.l2_path, tiers.l2_capacity)?,
+        ))
+    }
+    
+    /// Insert a data item under its own name with the L1 default TTL
+    pub fn insert(&mut self, data: Data) {
+        let ttl = self.l1.default_ttl();
+        self.insert_with_ttl(data, ttl);
+    }
+    
+    /// Insert a data item under its own name with a specific local TTL
+    pub fn insert_with_ttl(&mut self, data: Data, ttl: Duration) {
+        let fresh_for = data.get_fresh_period();
+        self.insert_spilling(data.name().clone(), data, ttl, fresh_for);
+    }
+    
+    /// Insert a data item into L1, spilling any L1 eviction to L2
+    fn insert_spilling(&self, name: Name, data: Data, ttl: Duration, fresh_for: Duration) {
+        // Drop any older copy spilled to disk
+        let mut l2 = self.l2.lock();
+        l2.remove(&name);
+        
+        for (evicted, evicted_data, remaining, fresh) in self.l1.insert_evicting(name, data, ttl, fresh_for) {
+            if let Err(e) = l2.insert(evicted.clone(), &evicted_data, remaining, fresh) {
+                warn!("Failed to spill {} to L2: {}", evicted, e);
+            }
+        }
+    }
+    
+    /// Get a data item, checking L1 then L2 and promoting L2 hits to L1
+    pub fn get(&self, name: &Name) -> Option<Data> {
+        self.lookup(name, false)
+    }
+    
+    /// Get a data item as `get` does, honoring MustBeFresh
+    ///
+    /// Promoted L2 hits keep their remaining TTL and fresh period.
+    fn lookup(&self, name: &Name, must_be_fresh: bool) -> Option<Data> {
+        if let Some(data) = self.l1.get(name, must_be_fresh) {
+            return Some(data);
+        }
+        
+        let exact = name.without_implicit_digest();
+        let (data, remaining, fresh) = self.l2.lock().take(&exact, must_be_fresh)?;
+        
+        trace!("L2 hit for {}, promoting to L1", exact);
+        self.insert_spilling(exact, data.clone(), remaining, fresh);
+        
+        // A full name only matches Data with that digest
+        match data.verify_implicit_digest(name) {
+            Ok(()) => Some(data),
+            Err(e) => {
+                debug!("Cached data rejected: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// Remove a data item from both tiers
+    pub fn remove(&mut self, name: &Name) -> bool {
+        let in_l1 = self.l1.remove(name);
+        let in_l2 = self.l2.get_mut().remove(name);
+        in_l1 || in_l2
+    }
+    
+    /// Remove every item from both tiers
+    pub fn clear(&mut self) {
+        self.l1.clear();
+        self.l2.get_mut().clear();
+    }
+    
+    /// Check if either tier contains an item
+    pub fn contains(&self, name: &Name) -> bool {
+        self.l1.contains(name) || self.l2.lock().contains(name)
+    }
+    
+    /// Get the total number of items in both tiers
+    pub fn len(&self) -> usize {
+        self.l1.len() + self.l2.lock().len()
+    }
+    
+    /// Get the total capacity of both tiers
+    pub fn capacity(&self) -> usize {
+        self.l1.capacity() + self.l2.lock().capacity()
+    }
+    
+    /// Check if both tiers are empty
+    pub fn is_empty(&self) -> bool {
+        self.l1.is_empty() && self.l2.lock().is_empty()
+    }
+    
+    /// Get the in-memory tier
+    pub fn l1(&self) -> &ContentStore {
+        &self.l1
+    }
+    
+    /// Get the on-disk tier, locked until the guard is dropped
+    pub fn l2(&self) -> MutexGuard<'_, DiskStore> {
+        self.l2.lock()
+    }
+}
+
+impl ContentStoreBackend for TieredContentStore {
+    fn insert(&mut self, data: Data) {
+        TieredContentStore::insert(self, data)
+    }
+    
+    fn get(&self, name: &Name, must_be_fresh: bool) -> Option<Data> {
+        self.lookup(name, must_be_fresh)
+    }
+    
+    fn contains(&self, name: &Name) -> bool {
+        TieredContentStore::contains(self, name)
+    }
+    
+    fn remove(&mut self, name: &Name) -> bool {
+        TieredContentStore::remove(self, name)
+    }
+    
+    fn clear(&mut self) {
+        TieredContentStore::clear(self)
+    }
+    
+    fn len(&self) -> usize {
+        TieredContentStore::len(self)
+    }
+    
+    fn capacity(&self) -> usize {
+        TieredContentStore::capacity(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_l1_eviction_spills_to_l2_and_promotes_back() {
+        let tiers = CacheTiers {
+            l1_capacity: 2,
+            l2_capacity: 10,
+            l2_path: std::env::temp_dir().join(format!("udcn-cs-test-{}", std::process::id())),
+        };
+        let mut cs = TieredContentStore::from_config(&tiers).unwrap();
+        
+        let names: Vec<Name> = (0..3)
+            .map(|i| Name::from_uri(&format!("/test/tiered/{}", i)).unwrap())
+            .collect();
+        for (i, name) in names.iter().enumerate() {
+            cs.insert(Data::new(name.clone(), vec![i as u8; 4]));
+        }
+        
+        // The third insert evicted one entry from L1 into L2
+        assert_eq!(cs.l1().len(), 2);
+        assert_eq!(cs.l2().len(), 1);
+        let spilled = names.iter().position(|name| cs.l2().contains(name)).unwrap();
+        assert!(!cs.l1().contains(&names[spilled]));
+        
+        // It is still retrievable, and the hit promotes it back into L1
+        let data = cs.get(&names[spilled]).unwrap();
+        assert_eq!(data.content().as_ref(), &[spilled as u8; 4]);
+        assert!(cs.l1().contains(&names[spilled]));
+        assert!(!cs.l2().contains(&names[spilled]));
+        
+        // Promotion pushed another entry down, so nothing was lost
+        assert_eq!(cs.len(), 3);
+        for name in &names {
+            assert!(cs.get(name).is_some());
+        }
+        
+        drop(cs);
+        let _ = fs::remove_dir(&tiers.l2_path);
+    }
+    
+    #[test]
+    fn test_l2_keeps_fresh_period() {
+        let tiers = CacheTiers {
+            l1_capacity: 1,
+            l2_capacity: 10,
+            l2_path: std::env::temp_dir().join(format!("udcn-cs-fresh-{}", std::process::id())),
+        };
+        let mut cs = TieredContentStore::from_config(&tiers).unwrap();
+        
+        let stale = Name::from_uri("/test/tiered/stale").unwrap();
+        let fresh = Name::from_uri("/test/tiered/fresh").unwrap();
+        cs.insert(Data::new(stale.clone(), "stale").fresh_period(Duration::ZERO));
+        cs.insert(Data::new(fresh.clone(), "fresh"));
+        cs.insert(Data::new(Name::from_uri("/test/tiered/other").unwrap(), "other"));
+        assert!(cs.l2().contains(&stale) && cs.l2().contains(&fresh));
+        
+        // A fresh L2 entry answers MustBeFresh; a stale one stays on disk
+        assert_eq!(cs.lookup(&fresh, true).unwrap().content().as_ref(), b"fresh");
+        assert!(cs.lookup(&stale, true).is_none());
+        assert!(cs.l2().contains(&stale));
+        
+        // Promotion does not make a stale entry fresh again
+        assert!(cs.lookup(&stale, false).is_some());
+        assert!(cs.l1().contains(&stale));
+        assert!(cs.lookup(&stale, true).is_none());
+        
+        cs.clear();
+        drop(cs);
+        let _ = fs::remove_dir(&tiers.l2_path);
+    }
+    
+    #[test]
+    fn test_tiered_backend_from_config() {
+        let tiers = CacheTiers {
+            l1_capacity: 1,
+            l2_capacity: 10,
+            l2_path: std::env::temp_dir().join(format!("udcn-cs-backend-{}", std::process::id())),
+        };
+        let config = crate::Config {
+            cache_backend: crate::CacheBackend::Tiered,
+            cache_tiers: Some(tiers.clone()),
+            ..Default::default()
+        };
+        let mut cs = crate::cache::open_content_store(&config).unwrap();
+        
+        let first = Name::from_uri("/test/tiered/backend/1").unwrap();
+        let second = Name::from_uri("/test/tiered/backend/2").unwrap();
+        cs.insert(Data::new(first.clone(), "first"));
+        cs.insert(Data::new(second.clone(), "second"));
+        
+        // The first Data spilled to disk but is still served, to MustBeFresh
+        // lookups too while it is fresh
+        assert_eq!(cs.len(), 2);
+        assert_eq!(cs.get(&first, true).unwrap().content().as_ref(), b"first");
+        assert_eq!(cs.get(&second, false).unwrap().content().as_ref(), b"second");
+        assert!(cs.contains(&second));
+        
+        cs.clear();
+        assert!(cs.is_empty());
+        drop(cs);
+        let _ = fs::remove_dir(&tiers.l2_path);
+    }
+}
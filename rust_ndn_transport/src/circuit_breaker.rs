@@ -0,0 +1,168 @@
+//
+// μDCN Circuit Breaker
+//
+// This module keeps the engine from hammering a peer it cannot reach. Each
+// remote has a breaker counting connection attempts that failed in a row;
+// at the threshold the breaker opens and further attempts fail straight
+// away for a cooldown. Once the cooldown is over the breaker is half-open:
+// the next attempt goes through, closing the breaker if it connects and
+// opening it for another cooldown if it fails.
+//
+
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tracing::{debug, warn};
+
+use crate::error::{Error, Result};
+
+/// State of the breaker of one remote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BreakerState {
+    /// Connection attempts go through
+    #[default]
+    Closed,
+    
+    /// Connection attempts fail straight away until the cooldown is over
+    Open,
+    
+    /// The cooldown is over and the next attempt decides
+    HalfOpen,
+}
+
+/// Failures of one remote
+#[derive(Debug, Default)]
+struct PeerFailures {
+    /// Connection attempts failed in a row
+    consecutive: u32,
+    
+    /// When the breaker last opened, if it is not closed
+    opened_at: Option<Instant>,
+}
+
+/// Circuit breakers of the remotes the engine connects to
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// Failures in a row that open a breaker; 0 never opens one
+    threshold: u32,
+    
+    /// How long an open breaker refuses attempts
+    cooldown: Duration,
+    
+    /// Failures of each remote with a failed attempt since its last success
+    peers: DashMap<SocketAddr, PeerFailures>,
+}
+
+impl CircuitBreaker {
+    /// Create breakers opening after `threshold` failures in a row for
+    /// `cooldown`
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            peers: DashMap::new(),
+        }
+    }
+    
+    /// Get the state of the breaker of a remote
+    pub fn state(&self, remote: SocketAddr) -> BreakerState {
+        match self.peers.get(&remote).and_then(|peer| peer.opened_at) {
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+            None => BreakerState::Closed,
+        }
+    }
+    
+    /// Get the number of connection attempts to a remote that failed in a row
+    pub fn consecutive_failures(&self, remote: SocketAddr) -> u32 {
+        self.peers.get(&remote).map_or(0, |peer| peer.consecutive)
+    }
+    
+    /// Let a connection attempt to a remote through, or refuse it while the
+    /// breaker is open
+    pub fn check(&self, remote: SocketAddr) -> Result<()> {
+        let opened_at = match self.peers.get(&remote).and_then(|peer| peer.opened_at) {
+            Some(opened_at) => opened_at,
+            None => return Ok(()),
+        };
+        
+        let elapsed = opened_at.elapsed();
+        if elapsed < self.cooldown {
+            return Err(Error::ConnectionError(format!(
+                "Circuit breaker open for {}, retrying in {} ms",
+                remote, (self.cooldown - elapsed).as_millis())));
+        }
+        
+        debug!("Circuit breaker for {} half-open, trying to connect", remote);
+        Ok(())
+    }
+    
+    /// Record a successful connection attempt, closing the breaker
+    pub fn record_success(&self, remote: SocketAddr) {
+        if self.peers.remove(&remote).is_some() {
+            debug!("Circuit breaker for {} closed", remote);
+        }
+    }
+    
+    /// Record a failed connection attempt, opening the breaker at the
+    /// threshold or when the attempt was the half-open trial
+    pub fn record_failure(&self, remote: SocketAddr) {
+        if self.threshold == 0 {
+            return;
+        }
+        
+        let mut peer = self.peers.entry(remote).or_default();
+        peer.consecutive = peer.consecutive.saturating_add(1);
+        if peer.opened_at.is_some() || peer.consecutive >= self.threshold {
+            warn!("Circuit breaker for {} open for {} ms after {} failed connection attempts",
+                  remote, self.cooldown.as_millis(), peer.consecutive);
+            peer.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_breaker_opens_and_half_opens() {
+        let breaker = CircuitBreaker::new(3, Duration::from_millis(50));
+        let remote: SocketAddr = "192.0.2.1:6363".parse().unwrap();
+        let other: SocketAddr = "192.0.2.2:6363".parse().unwrap();
+        
+        // Failures below the threshold leave it closed
+        for _ in 0..2 {
+            breaker.check(remote).unwrap();
+            breaker.record_failure(remote);
+        }
+        assert_eq!(breaker.state(remote), BreakerState::Closed);
+        breaker.record_failure(remote);
+        assert_eq!(breaker.state(remote), BreakerState::Open);
+        assert_eq!(breaker.consecutive_failures(remote), 3);
+        assert!(matches!(breaker.check(remote), Err(Error::ConnectionError(_))));
+        breaker.check(other).unwrap();
+        
+        // A failed trial after the cooldown opens it again at once
+        std::thread::sleep(Duration::from_millis(70));
+        assert_eq!(breaker.state(remote), BreakerState::HalfOpen);
+        breaker.check(remote).unwrap();
+        breaker.record_failure(remote);
+        assert_eq!(breaker.state(remote), BreakerState::Open);
+        
+        // A successful one closes it
+        std::thread::sleep(Duration::from_millis(70));
+        breaker.check(remote).unwrap();
+        breaker.record_success(remote);
+        assert_eq!(breaker.state(remote), BreakerState::Closed);
+        assert_eq!(breaker.consecutive_failures(remote), 0);
+        
+        // A threshold of 0 never opens
+        let disabled = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            disabled.record_failure(remote);
+        }
+        disabled.check(remote).unwrap();
+    }
+}
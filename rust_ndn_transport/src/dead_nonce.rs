@@ -0,0 +1,121 @@
+//
+// μDCN Dead Nonce List
+//
+// This module implements loop detection for forwarded Interests. The
+// forwarder remembers the (name, nonce) of every Interest it forwards for a
+// sliding time window; an Interest arriving again with the same name and
+// nonce within the window has looped back, and is answered with a Duplicate
+// NACK instead of being forwarded again.
+//
+
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tracing::debug;
+
+use crate::name::Name;
+use crate::ndn::{Interest, Nack, NackReason};
+
+/// Name and nonce identifying one Interest
+type NonceKey = (Name, u32);
+
+/// Recently forwarded Interests, oldest first
+#[derive(Debug, Default)]
+struct NonceWindow {
+    /// Interests forwarded within the window
+    seen: HashSet<NonceKey>,
+    
+    /// Forwarding times in order, for sliding the window
+    order: VecDeque<(Instant, NonceKey)>,
+}
+
+impl NonceWindow {
+    /// Forget Interests forwarded before `cutoff`
+    fn expire(&mut self, cutoff: Instant) {
+        while let Some((at, _)) = self.order.front() {
+            if *at >= cutoff {
+                break;
+            }
+            
+            let (_, key) = self.order.pop_front().unwrap();
+            self.seen.remove(&key);
+        }
+    }
+}
+
+/// Table of the (name, nonce) of recently forwarded Interests
+#[derive(Debug)]
+pub struct DeadNonceList {
+    /// How long an Interest is remembered
+    window: Duration,
+    
+    /// Interests forwarded within the window
+    entries: Mutex<NonceWindow>,
+}
+
+impl DeadNonceList {
+    /// Create a list remembering Interests for `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: Mutex::new(NonceWindow::default()),
+        }
+    }
+    
+    /// Record an Interest about to be forwarded, or reject it with a
+    /// Duplicate NACK when the same name and nonce were forwarded within
+    /// the window
+    pub fn check(&self, interest: &Interest) -> std::result::Result<(), Nack> {
+        let now = Instant::now();
+        let key = (interest.name().clone(), interest.nonce());
+        
+        let mut entries = self.entries.lock();
+        if let Some(cutoff) = now.checked_sub(self.window) {
+            entries.expire(cutoff);
+        }
+        
+        if entries.seen.contains(&key) {
+            debug!("Interest {} with nonce {:#010x} looped back", interest.name(), interest.nonce());
+            return Err(Nack::new(interest.clone(), NackReason::Duplicate));
+        }
+        
+        entries.seen.insert(key.clone());
+        entries.order.push_back((now, key));
+        Ok(())
+    }
+    
+    /// Get the number of Interests remembered
+    pub fn len(&self) -> usize {
+        self.entries.lock().seen.len()
+    }
+    
+    /// Check if no Interest is remembered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_nonce_seen_within_window() {
+        let list = DeadNonceList::new(Duration::from_millis(50));
+        let name = Name::from_uri("/loop").unwrap();
+        let interest = Interest::new(name.clone()).with_nonce(7);
+        
+        // The same name and nonce is a loop; another nonce or name is not
+        list.check(&interest).unwrap();
+        assert_eq!(list.check(&interest).unwrap_err().reason(), NackReason::Duplicate);
+        list.check(&Interest::new(name).with_nonce(8)).unwrap();
+        list.check(&Interest::new(Name::from_uri("/other").unwrap()).with_nonce(7)).unwrap();
+        assert_eq!(list.len(), 3);
+        
+        // Once the window slides past it, the Interest may be forwarded again
+        std::thread::sleep(Duration::from_millis(80));
+        list.check(&interest).unwrap();
+        assert_eq!(list.len(), 1);
+    }
+}
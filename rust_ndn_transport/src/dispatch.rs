@@ -0,0 +1,137 @@
+//
+// μDCN Handler Dispatch
+//
+// This module implements the handler table shared by the QUIC engines.
+// An Interest is dispatched to the handler registered for the longest prefix
+// of its name. A handler may instead be registered for an exact name, in
+// which case it only serves Interests for that name. When an exact-name
+// handler and a prefix handler are registered for the same name, both match
+// equally long and the exact-name handler wins.
+//
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::name::Name;
+
+/// How a registered name is matched against Interest names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchKind {
+    /// Serve the name and every name below it
+    Prefix,
+    
+    /// Serve only the name itself
+    Exact,
+}
+
+/// Handlers by registered name, for prefix and exact-name matches
+pub struct HandlerTable<H> {
+    /// Handlers registered for a prefix
+    prefixes: HashMap<Name, H>,
+    
+    /// Handlers registered for an exact name
+    exact: HashMap<Name, H>,
+}
+
+impl<H> HandlerTable<H> {
+    /// Create an empty handler table
+    pub fn new() -> Self {
+        Self {
+            prefixes: HashMap::new(),
+            exact: HashMap::new(),
+        }
+    }
+    
+    /// Register a handler, returning the one it replaces
+    pub fn insert(&mut self, name: Name, kind: MatchKind, handler: H) -> Option<H> {
+        match kind {
+            MatchKind::Prefix => self.prefixes.insert(name, handler),
+            MatchKind::Exact => self.exact.insert(name, handler),
+        }
+    }
+    
+    /// Remove a handler
+    pub fn remove(&mut self, name: &Name, kind: MatchKind) -> Option<H> {
+        match kind {
+            MatchKind::Prefix => self.prefixes.remove(name),
+            MatchKind::Exact => self.exact.remove(name),
+        }
+    }
+    
+    /// Find the handler for an Interest name
+    ///
+    /// An exact-name handler wins over a prefix handler registered for the
+    /// same name; otherwise the longest matching prefix is used.
+    pub fn lookup(&self, name: &Name) -> Option<&H> {
+        self.lookup_entry(name).map(|(_, handler)| handler)
+    }
+    
+    /// Find the handler for an Interest name along with the name it was
+    /// registered for
+    pub fn lookup_entry(&self, name: &Name) -> Option<(&Name, &H)> {
+        if let Some(entry) = self.exact.get_key_value(name) {
+            return Some(entry);
+        }
+        
+        self.prefixes
+            .iter()
+            .filter(|(prefix, _)| name.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+    }
+    
+    /// Get the number of registered handlers
+    pub fn len(&self) -> usize {
+        self.prefixes.len() + self.exact.len()
+    }
+    
+    /// Check whether no handlers are registered
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && self.exact.is_empty()
+    }
+}
+
+impl<H> fmt::Debug for HandlerTable<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Handlers are usually closures, so only the registered names are shown
+        f.debug_struct("HandlerTable")
+            .field("prefixes", &self.prefixes.keys().collect::<Vec<_>>())
+            .field("exact", &self.exact.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<H> Default for HandlerTable<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_exact_match_wins_tie() {
+        let mut table = HandlerTable::new();
+        let name = Name::from_uri("/udcn/video/intro").unwrap();
+        
+        // Register the exact handler first so insertion order can't decide
+        table.insert(name.clone(), MatchKind::Exact, "exact");
+        table.insert(name.clone(), MatchKind::Prefix, "prefix");
+        table.insert(Name::from_uri("/udcn").unwrap(), MatchKind::Prefix, "root");
+        
+        // Both match the exact name equally long; the exact handler serves it
+        assert_eq!(table.lookup(&name), Some(&"exact"));
+        
+        // Longer names only match the prefix handler
+        let segment = Name::from_uri("/udcn/video/intro/seg=0").unwrap();
+        assert_eq!(table.lookup(&segment), Some(&"prefix"));
+        
+        // Without the exact handler the prefix handler serves the name too
+        table.remove(&name, MatchKind::Exact);
+        assert_eq!(table.lookup(&name), Some(&"prefix"));
+        assert_eq!(table.lookup(&Name::from_uri("/udcn/audio").unwrap()), Some(&"root"));
+        assert_eq!(table.lookup(&Name::from_uri("/other").unwrap()), None);
+        assert_eq!(table.len(), 2);
+    }
+}
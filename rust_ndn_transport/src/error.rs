@@ -11,9 +11,15 @@ use std::net::AddrParseError;
 use std::time::Duration;
 use bytes::Bytes;
 
+use crate::name::Name;
+use crate::ndn::NackReason;
+
 /// Result type for the μDCN transport
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// An underlying error kept as the source of a transport error
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
 /// Comprehensive error types for the μDCN transport layer
 #[derive(Error, Debug)]
 pub enum Error {
@@ -23,7 +29,19 @@ pub enum Error {
     
     /// QUIC error
     #[error("QUIC error: {0}")]
-    Quic(#[from] quinn::ConnectionError),
+    Quic(#[source] quinn::ConnectionError),
+    
+    /// TLS handshake failure (e.g. certificate or ALPN mismatch)
+    #[error("TLS handshake failed: {0}")]
+    TlsHandshake(String),
+    
+    /// No QUIC version in common with the peer
+    #[error("QUIC version negotiation failed: {0}")]
+    VersionNegotiation(String),
+    
+    /// QUIC transport-level protocol error
+    #[error("QUIC transport error: {0}")]
+    TransportError(String),
     
     /// TLS error
     #[error("TLS error: {0}")]
@@ -41,6 +59,22 @@ pub enum Error {
     #[error("Connection error: {0}")]
     ConnectionError(String),
     
+    /// Connection error caused by an underlying error
+    #[error("Connection error: {context}: {source}")]
+    Connection {
+        context: String,
+        #[source]
+        source: BoxError,
+    },
+    
+    /// Stream I/O error caused by an underlying error
+    #[error("Stream error: {context}: {source}")]
+    Stream {
+        context: String,
+        #[source]
+        source: BoxError,
+    },
+    
     /// Name parsing error
     #[error("Name parsing error: {0}")]
     NameParsing(String),
@@ -69,6 +103,17 @@ pub enum Error {
     #[error("Timeout error: {0}")]
     Timeout(String),
     
+    /// Interest answered with a NACK
+    #[error("NACK ({reason:?}): {message}")]
+    Nack {
+        reason: NackReason,
+        message: String,
+    },
+    
+    /// No registered or forwarding prefix matches the name
+    #[error("No matching prefix for {0}")]
+    NoMatchingPrefix(Name),
+    
     /// Content store error
     #[error("Content store error: {0}")]
     ContentStore(String),
@@ -77,6 +122,22 @@ pub enum Error {
     #[error("Signature verification failed: {0}")]
     SignatureVerification(String),
     
+    /// No key is known under the KeyLocator name of a signed packet
+    #[error("Key not found: {0}")]
+    KeyNotFound(Name),
+    
+    /// A packet is signed by a key the trust schema does not allow for its name
+    #[error("Trust schema violation: {0}")]
+    TrustSchemaViolation(String),
+    
+    /// Data does not match the ImplicitSha256Digest it was requested by
+    #[error("Digest mismatch: {0}")]
+    DigestMismatch(String),
+    
+    /// Packet failed the CRC32 integrity self-test
+    #[error("Checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+    
     /// Fragmentation error
     #[error("Fragmentation error: {0}")]
     Fragmentation(String),
@@ -113,10 +174,6 @@ pub enum Error {
     #[error("IO error: {0}")]
     IoError(String),
     
-    /// Connection Error
-    #[error("Connection error: {0}")]
-    ConnectionError(String),
-    
     /// Reassembly error
     #[error("Reassembly error: {0}")]
     ReassemblyError(String),
@@ -130,8 +187,85 @@ pub enum Error {
     OperationError(String),
 }
 
-// Display implementation is handled by the thiserror derive macro
-// Removed duplicate implementation// std::error::Error trait is implemented by thiserror
+// Display and std::error::Error are implemented by the thiserror derive macro
+
+/// Category of an [`Error`], for matching without depending on its fields
+/// or message. New variants map onto the existing kinds where they fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    Io,
+    Quic,
+    Tls,
+    InvalidAddress,
+    Connection,
+    Stream,
+    Parsing,
+    Protocol,
+    Configuration,
+    Timeout,
+    Nack,
+    NoMatchingPrefix,
+    NotFound,
+    ContentStore,
+    Security,
+    Integrity,
+    Fragmentation,
+    MlModel,
+    Xdp,
+    InvalidState,
+    Other,
+}
+
+impl Error {
+    /// Get the category of this error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Io(_) | Error::IoError(_) => ErrorKind::Io,
+            Error::Quic(_) | Error::VersionNegotiation(_) | Error::TransportError(_) => ErrorKind::Quic,
+            Error::TlsHandshake(_) | Error::Tls(_) => ErrorKind::Tls,
+            Error::AddrParseError(_) | Error::AddrParseErrorWithMsg(_) | Error::InvalidAddress(_) => {
+                ErrorKind::InvalidAddress
+            }
+            Error::ConnectionError(_) | Error::Connection { .. } | Error::NoConnections => ErrorKind::Connection,
+            Error::Stream { .. } => ErrorKind::Stream,
+            Error::NameParsing(_) | Error::TlvParsing(_) | Error::ParsingError(_) => ErrorKind::Parsing,
+            Error::ProtocolError(_) => ErrorKind::Protocol,
+            Error::ConfigurationError(_) | Error::InvalidMtu(_) | Error::InvalidArgument(_) => {
+                ErrorKind::Configuration
+            }
+            Error::Timeout(_) => ErrorKind::Timeout,
+            Error::Nack { .. } => ErrorKind::Nack,
+            Error::NoMatchingPrefix(_) => ErrorKind::NoMatchingPrefix,
+            Error::NotFound(_) => ErrorKind::NotFound,
+            Error::ContentStore(_) => ErrorKind::ContentStore,
+            Error::SignatureVerification(_) | Error::KeyNotFound(_) | Error::TrustSchemaViolation(_) => {
+                ErrorKind::Security
+            }
+            Error::DigestMismatch(_) | Error::ChecksumMismatch(_) => ErrorKind::Integrity,
+            Error::Fragmentation(_) | Error::ReassemblyError(_) => ErrorKind::Fragmentation,
+            Error::MlModel(_) => ErrorKind::MlModel,
+            Error::XdpError(_) => ErrorKind::Xdp,
+            Error::InvalidState(_) => ErrorKind::InvalidState,
+            Error::Other(_) | Error::OperationError(_) => ErrorKind::Other,
+        }
+    }
+    
+    /// Create a NACK error from a received NACK
+    pub fn nack(nack: &crate::ndn::Nack) -> Self {
+        Error::Nack { reason: nack.reason(), message: nack.message().to_string() }
+    }
+    
+    /// Create a connection error keeping `source` as its cause
+    pub fn connection(context: impl Into<String>, source: impl Into<BoxError>) -> Self {
+        Error::Connection { context: context.into(), source: source.into() }
+    }
+    
+    /// Create a stream I/O error keeping `source` as its cause
+    pub fn stream(context: impl Into<String>, source: impl Into<BoxError>) -> Self {
+        Error::Stream { context: context.into(), source: source.into() }
+    }
+}
 
 /// Helper to create timeout error
 pub fn timeout<T>(duration: Duration, error_context: &str) -> Result<T> {
@@ -139,19 +273,55 @@ pub fn timeout<T>(duration: Duration, error_context: &str) -> Result<T> {
     Err(Error::Timeout(format!("Operation timed out after {} ms", duration.as_millis())))
 }
 
+/// Range of QUIC transport error codes carrying TLS alerts (RFC 9001 §4.8)
+const CRYPTO_ERROR_RANGE: std::ops::RangeInclusive<u64> = 0x100..=0x1ff;
+
+// Convert from Quinn connection errors, keeping handshake, version and
+// transport failures apart from timeouts and closes
+impl From<quinn::ConnectionError> for Error {
+    fn from(err: quinn::ConnectionError) -> Self {
+        match err {
+            quinn::ConnectionError::VersionMismatch => {
+                Error::VersionNegotiation(err.to_string())
+            }
+            quinn::ConnectionError::TransportError(ref e) => {
+                if CRYPTO_ERROR_RANGE.contains(&u64::from(e.code)) {
+                    Error::TlsHandshake(e.to_string())
+                } else {
+                    Error::TransportError(e.to_string())
+                }
+            }
+            // The peer detected the failure and closed the connection
+            quinn::ConnectionError::ConnectionClosed(ref close) => {
+                if CRYPTO_ERROR_RANGE.contains(&u64::from(close.error_code)) {
+                    Error::TlsHandshake(close.to_string())
+                } else {
+                    Error::TransportError(close.to_string())
+                }
+            }
+            quinn::ConnectionError::TimedOut => {
+                Error::Timeout("QUIC connection timed out".into())
+            }
+            other => Error::Quic(other),
+        }
+    }
+}
+
 // Convert from Quinn connection creation error
 impl From<quinn::ConnectError> for Error {
     fn from(err: quinn::ConnectError) -> Self {
-        Error::Quic(quinn::ConnectionError::ApplicationClosed(quinn::ApplicationClose {
-            error_code: 0u32.into(),
-            reason: bytes::Bytes::new(),
-        }))
+        match err {
+            quinn::ConnectError::UnsupportedVersion => {
+                Error::VersionNegotiation(err.to_string())
+            }
+            quinn::ConnectError::InvalidDnsName(_) | quinn::ConnectError::InvalidRemoteAddress(_) => {
+                Error::InvalidAddress(err.to_string())
+            }
+            other => Error::connection("Failed to connect", other),
+        }
     }
 }
 
-// Note: The From<quinn::ConnectionError> implementation is automatically
-// generated by the #[from] attribute in the Error enum
-
 // Convert from string
 impl From<String> for Error {
     fn from(s: String) -> Self {
@@ -165,3 +335,123 @@ impl From<&'static str> for Error {
         Error::Other(s.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{SocketAddr, UdpSocket};
+    use std::sync::Arc;
+    
+    // Build a server endpoint speaking the given ALPN protocol
+    fn server_endpoint(alpn: &[u8]) -> (quinn::Endpoint, rustls::Certificate) {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+        let cert_der = rustls::Certificate(cert.serialize_der().unwrap());
+        let key_der = rustls::PrivateKey(cert.serialize_private_key_der());
+        
+        let mut crypto = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert_der.clone()], key_der)
+            .unwrap();
+        crypto.alpn_protocols = vec![alpn.to_vec()];
+        
+        let server_config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+        let endpoint = quinn::Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        (endpoint, cert_der)
+    }
+    
+    // Build a client config trusting the given certificate
+    fn client_config(cert: &rustls::Certificate, alpn: &[u8]) -> quinn::ClientConfig {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(cert).unwrap();
+        
+        let mut crypto = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        crypto.alpn_protocols = vec![alpn.to_vec()];
+        
+        let mut transport = quinn::TransportConfig::default();
+        transport.max_idle_timeout(Some(quinn::VarInt::from_u32(500).into()));
+        
+        let mut config = quinn::ClientConfig::new(Arc::new(crypto));
+        config.transport_config(Arc::new(transport));
+        config
+    }
+    
+    #[test]
+    fn test_connection_error_mapping() {
+        assert!(matches!(Error::from(quinn::ConnectionError::VersionMismatch), Error::VersionNegotiation(_)));
+        assert!(matches!(Error::from(quinn::ConnectionError::TimedOut), Error::Timeout(_)));
+        assert!(matches!(Error::from(quinn::ConnectionError::Reset), Error::Quic(_)));
+        assert!(matches!(Error::from(quinn::ConnectError::UnsupportedVersion), Error::VersionNegotiation(_)));
+        assert!(matches!(Error::from(quinn::ConnectError::TooManyConnections), Error::Connection { .. }));
+    }
+    
+    #[test]
+    fn test_error_kind() {
+        // Usable as a boxed std error
+        fn assert_std_error<E: std::error::Error + Send + Sync + 'static>() {}
+        assert_std_error::<Error>();
+        
+        let name = Name::from_uri("/a/b").unwrap();
+        let err = Error::NoMatchingPrefix(name.clone());
+        assert_eq!(err.kind(), ErrorKind::NoMatchingPrefix);
+        assert_eq!(err.to_string(), format!("No matching prefix for {}", name));
+        
+        let err = Error::Nack { reason: NackReason::NoRoute, message: "no producer".into() };
+        assert_eq!(err.kind(), ErrorKind::Nack);
+        assert_eq!(err.to_string(), "NACK (NoRoute): no producer");
+        
+        assert_eq!(Error::from(quinn::ConnectionError::TimedOut).kind(), ErrorKind::Timeout);
+        assert_eq!(Error::ProtocolError("bad".into()).kind(), ErrorKind::Protocol);
+        assert_eq!(Error::from("message").kind(), ErrorKind::Other);
+    }
+    
+    #[test]
+    fn test_connection_error_source_chain() {
+        use std::error::Error as _;
+        
+        let err = Error::from(quinn::ConnectError::TooManyConnections);
+        let source = err.source().expect("connection error has a source");
+        assert!(matches!(source.downcast_ref::<quinn::ConnectError>(), Some(quinn::ConnectError::TooManyConnections)));
+        assert_eq!(err.to_string(), format!("Connection error: Failed to connect: {}", quinn::ConnectError::TooManyConnections));
+        
+        let err = Error::connection("Failed to open stream", quinn::ConnectionError::Reset);
+        let source = err.source().unwrap().downcast_ref::<quinn::ConnectionError>();
+        assert!(matches!(source, Some(quinn::ConnectionError::Reset)));
+        
+        // Errors built from a message alone have no source
+        assert!(Error::ConnectionError("Connection not found".into()).source().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_bad_alpn_is_handshake_error() {
+        let (server, cert) = server_endpoint(b"udcn");
+        let server_addr = server.local_addr().unwrap();
+        
+        let client = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        let connecting = client
+            .connect_with(client_config(&cert, b"not-udcn"), server_addr, "localhost")
+            .unwrap();
+        
+        let err = Error::from(connecting.await.unwrap_err());
+        assert!(matches!(err, Error::TlsHandshake(_)), "unexpected error: {:?}", err);
+    }
+    
+    #[tokio::test]
+    async fn test_unresponsive_peer_is_timeout() {
+        // A bound socket that never answers
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let silent_addr: SocketAddr = silent.local_addr().unwrap();
+        let (_, cert) = server_endpoint(b"udcn");
+        
+        let client = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        let connecting = client
+            .connect_with(client_config(&cert, b"udcn"), silent_addr, "localhost")
+            .unwrap();
+        
+        let err = Error::from(connecting.await.unwrap_err());
+        assert!(matches!(err, Error::Timeout(_)), "unexpected error: {:?}", err);
+    }
+}
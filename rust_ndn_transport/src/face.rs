@@ -0,0 +1,288 @@
+//
+// μDCN Face Abstraction
+//
+// This module defines the `NdnFace` trait, the minimal interface higher
+// layers (pipelines, producers, subscribers) need from a transport, along
+// with an in-memory `LoopbackFace` that lets those layers run without sockets.
+//
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use tokio::sync::broadcast;
+
+use crate::error::Error;
+use crate::name::Name;
+use crate::ndn::{Data, Interest};
+use crate::quic::PrefixHandler;
+use crate::quic_transport::QuicTransport;
+use crate::Result;
+
+/// Capacity of the loopback push channel
+const PUSH_CHANNEL_CAPACITY: usize = 1024;
+
+/// A face that can express Interests, serve prefixes and push Data
+#[async_trait]
+pub trait NdnFace: Send + Sync {
+    /// Send an Interest and wait for the matching Data
+    async fn send_interest(&self, interest: Interest) -> Result<Data>;
+    
+    /// Register a handler for Interests under a prefix
+    async fn register_prefix(&self, prefix: Name, handler: PrefixHandler) -> Result<u64>;
+    
+    /// Push unsolicited Data to the other side of the face
+    async fn push_data(&self, data: Data) -> Result<()>;
+}
+
+/// An in-memory face that answers Interests from locally registered handlers
+pub struct LoopbackFace {
+    /// Registered handlers by prefix
+    handlers: DashMap<Name, (u64, PrefixHandler)>,
+    
+    /// Data pushed through the face, by name
+    pushed: DashMap<Name, Data>,
+    
+    /// Subscribers to pushed Data
+    push_tx: broadcast::Sender<Data>,
+    
+    /// Next registration ID
+    next_id: AtomicU64,
+    
+    /// Artificial delay applied before answering an Interest
+    delay: Option<Duration>,
+    
+    /// Number of Interests received
+    interests_received: AtomicU64,
+}
+
+impl LoopbackFace {
+    /// Create a new loopback face
+    pub fn new() -> Self {
+        let (push_tx, _) = broadcast::channel(PUSH_CHANNEL_CAPACITY);
+        
+        Self {
+            handlers: DashMap::new(),
+            pushed: DashMap::new(),
+            push_tx,
+            next_id: AtomicU64::new(1),
+            delay: None,
+            interests_received: AtomicU64::new(0),
+        }
+    }
+    
+    /// Delay every answer by the given duration (to emulate a slow link)
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+    
+    /// Subscribe to Data pushed through the face
+    pub fn subscribe(&self) -> broadcast::Receiver<Data> {
+        self.push_tx.subscribe()
+    }
+    
+    /// Get the number of Interests received
+    pub fn interests_received(&self) -> u64 {
+        self.interests_received.load(Ordering::Relaxed)
+    }
+    
+    /// Unregister a handler by registration ID
+    pub fn unregister_prefix(&self, registration_id: u64) -> Result<()> {
+        let key = self.handlers
+            .iter()
+            .find(|entry| entry.value().0 == registration_id)
+            .map(|entry| entry.key().clone());
+        
+        match key {
+            Some(prefix) => {
+                self.handlers.remove(&prefix);
+                Ok(())
+            }
+            None => Err(Error::NotFound(format!("Registration ID {}", registration_id))),
+        }
+    }
+    
+    /// Answer an Interest from pushed Data or the longest matching handler
+    fn answer(&self, interest: Interest) -> Result<Data> {
+        if let Some(data) = self.pushed.get(interest.name()) {
+            return Ok(data.clone());
+        }
+        
+        let mut best: Option<(usize, Name)> = None;
+        for entry in self.handlers.iter() {
+            let prefix = entry.key();
+            if interest.name().starts_with(prefix)
+                && best.as_ref().map_or(true, |(len, _)| prefix.len() > *len)
+            {
+                best = Some((prefix.len(), prefix.clone()));
+            }
+        }
+        
+        match best.and_then(|(_, prefix)| self.handlers.get(&prefix)) {
+            Some(entry) => (entry.value().1)(interest),
+            None => Err(Error::NotFound(format!("Route for {}", interest.name()))),
+        }
+    }
+}
+
+impl Default for LoopbackFace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl NdnFace for LoopbackFace {
+    async fn send_interest(&self, interest: Interest) -> Result<Data> {
+        self.interests_received.fetch_add(1, Ordering::Relaxed);
+        
+        if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        }
+        
+        self.answer(interest)
+    }
+    
+    async fn register_prefix(&self, prefix: Name, handler: PrefixHandler) -> Result<u64> {
+        let registration_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.handlers.insert(prefix, (registration_id, handler));
+        Ok(registration_id)
+    }
+    
+    async fn push_data(&self, data: Data) -> Result<()> {
+        self.pushed.insert(data.name().clone(), data.clone());
+        
+        // Having no subscribers is not an error
+        let _ = self.push_tx.send(data);
+        
+        Ok(())
+    }
+}
+
+/// A face bound to a single remote peer of a `QuicTransport`
+pub struct QuicTransportFace {
+    /// The underlying transport
+    transport: Arc<QuicTransport>,
+    
+    /// The remote peer
+    remote_addr: SocketAddr,
+    
+    /// Prefixes registered through the face, by registration ID
+    registrations: DashMap<u64, Name>,
+    
+    /// Next registration ID
+    next_id: AtomicU64,
+}
+
+impl QuicTransportFace {
+    /// Create a face for the given remote peer
+    pub fn new(transport: Arc<QuicTransport>, remote_addr: SocketAddr) -> Self {
+        Self {
+            transport,
+            remote_addr,
+            registrations: DashMap::new(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+    
+    /// Get the remote peer address
+    pub fn remote_addr(&self) -> SocketAddr {
+        self.remote_addr
+    }
+    
+    /// Unregister a handler by registration ID
+    pub async fn unregister_prefix(&self, registration_id: u64) -> Result<()> {
+        match self.registrations.remove(&registration_id) {
+            Some((_, prefix)) => self.transport.unregister_handler(&prefix).await,
+            None => Err(Error::NotFound(format!("Registration ID {}", registration_id))),
+        }
+    }
+}
+
+#[async_trait]
+impl NdnFace for QuicTransportFace {
+    async fn send_interest(&self, interest: Interest) -> Result<Data> {
+        self.transport.send_interest(self.remote_addr, interest).await
+    }
+    
+    async fn register_prefix(&self, prefix: Name, handler: PrefixHandler) -> Result<u64> {
+        self.transport.register_handler(prefix.clone(), move |interest| handler(interest)).await?;
+        
+        // A new handler for the prefix replaces the old registration
+        self.registrations.retain(|_, registered| *registered != prefix);
+        let registration_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.registrations.insert(registration_id, prefix);
+        Ok(registration_id)
+    }
+    
+    /// `QuicTransport` has no push streams, so this always fails
+    async fn push_data(&self, data: Data) -> Result<()> {
+        Err(Error::OperationError(format!(
+            "QUIC transport cannot push {} to {}", data.name(), self.remote_addr)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_loopback_face_longest_prefix() {
+        let face = LoopbackFace::new();
+        
+        face.register_prefix(Name::from_uri("/a").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "short"))
+        })).await.unwrap();
+        face.register_prefix(Name::from_uri("/a/b").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "long"))
+        })).await.unwrap();
+        
+        let data = face.send_interest(Interest::new(Name::from_uri("/a/b/c").unwrap())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"long");
+        
+        let result = face.send_interest(Interest::new(Name::from_uri("/x").unwrap())).await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+        assert_eq!(face.interests_received(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_quic_transport_face_registrations() {
+        let transport = Arc::new(QuicTransport::new("127.0.0.1", 0, 30, 65535).await.unwrap());
+        let face = QuicTransportFace::new(transport.clone(), "127.0.0.1:6363".parse().unwrap());
+        let prefix = Name::from_uri("/udcn/face").unwrap();
+        let handler = || -> PrefixHandler {
+            Box::new(|interest: Interest| Ok(Data::new(interest.name().clone(), "face")))
+        };
+        
+        // Registering the prefix again retires the first ID
+        let first = face.register_prefix(prefix.clone(), handler()).await.unwrap();
+        let second = face.register_prefix(prefix.clone(), handler()).await.unwrap();
+        assert_ne!(first, second);
+        assert!(matches!(face.unregister_prefix(first).await, Err(Error::NotFound(_))));
+        
+        // The current ID removes the handler from the transport
+        face.unregister_prefix(second).await.unwrap();
+        assert!(matches!(transport.unregister_handler(&prefix).await, Err(Error::NotFound(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_loopback_face_push_data() {
+        let face = LoopbackFace::new();
+        let mut rx = face.subscribe();
+        
+        let name = Name::from_uri("/chat/msg/1").unwrap();
+        face.push_data(Data::new(name.clone(), "hello")).await.unwrap();
+        
+        // Subscribers see the pushed Data
+        let pushed = rx.recv().await.unwrap();
+        assert_eq!(pushed.name(), &name);
+        
+        // And it satisfies later Interests
+        let data = face.send_interest(Interest::new(name)).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"hello");
+    }
+}
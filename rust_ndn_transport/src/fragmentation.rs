@@ -5,26 +5,47 @@
 // over QUIC streams, allowing efficient handling of large data transfers.
 //
 
-// use std::sync::Arc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::collections::HashMap;
+use std::time::Duration;
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 use bytes::{Bytes, BytesMut, BufMut, Buf};
 use tracing::{debug, error, info};
-use prometheus::{register_counter, register_histogram, Counter, Histogram, HistogramOpts};
+use prometheus::{register_counter, register_gauge, register_histogram, Counter, Gauge, Histogram, HistogramOpts};
+use sha2::{Digest, Sha256};
 
+use crate::cache::DummyCounter;
 use crate::ndn::Data;
 use crate::name::Name;
 use crate::error::Error;
 use crate::Result;
 
-/// Fragment header size in bytes
-const FRAGMENT_HEADER_SIZE: usize = 8;
+/// Size in bytes of the fixed part of the fragment header, before the Name
+pub(crate) const FRAGMENT_HEADER_SIZE: usize = 29;
+
+/// Size in bytes of the content hash carried by every fragment
+const CONTENT_HASH_SIZE: usize = 16;
 
 /// Default MTU size in bytes
 const DEFAULT_MTU: usize = 1400;
 
+/// Default largest number of fragments a single object may be split into
+const DEFAULT_MAX_FRAGMENTS: usize = 1024;
+
 /// Fragment header magic value for identification
-const FRAGMENT_MAGIC: u16 = 0x4644; 
+pub(crate) const FRAGMENT_MAGIC: u16 = 0x4644; 
+
+/// Default time a partial reassembly is kept before it expires
+const DEFAULT_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of partial reassemblies held at once
+const DEFAULT_REASSEMBLY_CAPACITY: usize = 1024;
+
+/// Default largest number of fragment payload bytes held by partial
+/// reassemblies altogether
+pub const DEFAULT_MAX_REASSEMBLY_BYTES: usize = 64 * 1024 * 1024;
 
 // Stub for Histogram 
 pub struct DummyHistogram;
@@ -47,18 +68,42 @@ lazy_static! {
     static ref REASSEMBLY_TIME_HISTOGRAM: DummyHistogram = DummyHistogram {};
 }
 
+lazy_static! {
+    static ref REASSEMBLY_CONTEXTS: Gauge = register_gauge!(
+        "udcn_reassembly_contexts", "Partial reassemblies in progress").unwrap();
+    static ref REASSEMBLY_OLDEST_AGE: Gauge = register_gauge!(
+        "udcn_reassembly_oldest_age_seconds", "Age of the oldest partial reassembly").unwrap();
+    static ref REASSEMBLY_BUFFERED_BYTES: Gauge = register_gauge!(
+        "udcn_reassembly_buffered_bytes", "Fragment payload bytes held by partial reassemblies").unwrap();
+}
+
 /// Fragment header format
 /// 
 /// ```
 /// 0                   1                   2                   3
 /// 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |    Magic (FD)   |F|  Reserved |          Fragment ID          |
+/// |          Magic (FD)           |F|  Reserved   |  Fragment ID  |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |            Fragment ID (continued)            |   Sequence    |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  (continued)  |        Total Fragments        | Content Hash  |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-/// |        Sequence Number        |         Total Fragments       |
+/// |                  Content Hash (continued) ...                 |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  ... (128 bits in all)                        |  Name Length  |
+/// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+/// |  (continued)  |          Name (Name Length bytes) ...         |
 /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
 /// ```
-#[derive(Debug, Clone, Copy)]
+///
+/// The Name is the TLV-encoded name of the fragmented Data, and the content
+/// hash the first 128 bits of the SHA-256 of the whole encoded Data.
+/// Reassembly is keyed on the name together with the hash, so fragments of
+/// two objects are never mixed, even when they share a fragment ID (after
+/// the ID wraps, or from different senders) or a name, and a reassembled
+/// object is checked against it.
+#[derive(Debug, Clone)]
 struct FragmentHeader {
     /// Magic value for identification (FD)
     magic: u16,
@@ -69,19 +114,25 @@ struct FragmentHeader {
     /// Reserved bits (7 bits)
     reserved: u8,
     
-    /// Fragment ID to identify the data object (16 bits)
-    fragment_id: u16,
+    /// Fragment ID assigned by the sender (32 bits)
+    fragment_id: u32,
     
     /// Sequence number of this fragment (16 bits)
     sequence: u16,
     
     /// Total number of fragments for this data object (16 bits)
     total_fragments: u16,
+    
+    /// Truncated SHA-256 of the encoded data object (128 bits)
+    content_hash: ContentHash,
+    
+    /// Name of the fragmented data object
+    name: Name,
 }
 
 impl FragmentHeader {
     /// Create a new fragment header
-    fn new(fragment_id: u16, sequence: u16, total_fragments: u16, is_final: bool) -> Self {
+    fn new(fragment_id: u32, sequence: u16, total_fragments: u16, is_final: bool, content_hash: ContentHash, name: Name) -> Self {
         Self {
             magic: FRAGMENT_MAGIC,
             is_final,
@@ -89,12 +140,25 @@ impl FragmentHeader {
             fragment_id,
             sequence,
             total_fragments,
+            content_hash,
+            name,
         }
     }
     
+    /// Get the encoded size of a header carrying the given name
+    fn encoded_len(name: &Name) -> usize {
+        FRAGMENT_HEADER_SIZE + name.to_tlv().len()
+    }
+    
+    /// Key of the reassembly this fragment belongs to
+    fn reassembly_key(&self) -> ReassemblyKey {
+        (self.name.clone(), self.content_hash)
+    }
+    
     /// Encode the header to bytes
     fn to_bytes(&self) -> BytesMut {
-        let mut buf = BytesMut::with_capacity(FRAGMENT_HEADER_SIZE);
+        let name = self.name.to_tlv();
+        let mut buf = BytesMut::with_capacity(FRAGMENT_HEADER_SIZE + name.len());
         
         // Magic value
         buf.put_u16(self.magic);
@@ -103,11 +167,8 @@ impl FragmentHeader {
         let flags = if self.is_final { 0x80 } else { 0x00 } | (self.reserved & 0x7F);
         buf.put_u8(flags);
         
-        // Fragment ID (high byte)
-        buf.put_u8((self.fragment_id >> 8) as u8);
-        
-        // Fragment ID (low byte)
-        buf.put_u8(self.fragment_id as u8);
+        // Fragment ID
+        buf.put_u32(self.fragment_id);
         
         // Sequence number
         buf.put_u16(self.sequence);
@@ -115,6 +176,14 @@ impl FragmentHeader {
         // Total fragments
         buf.put_u16(self.total_fragments);
         
+        // Content hash
+        buf.put_slice(&self.content_hash);
+        
+        // Length-prefixed name; `Fragmenter::fragment` rejects names too
+        // long for the 16-bit length
+        buf.put_u16(name.len() as u16);
+        buf.extend_from_slice(&name);
+        
         buf
     }
     
@@ -136,9 +205,7 @@ impl FragmentHeader {
         let reserved = flags & 0x7F;
         
         // Fragment ID
-        let fragment_id_high = buf.get_u8() as u16;
-        let fragment_id_low = buf.get_u8() as u16;
-        let fragment_id = (fragment_id_high << 8) | fragment_id_low;
+        let fragment_id = buf.get_u32();
         
         // Sequence number
         let sequence = buf.get_u16();
@@ -146,6 +213,23 @@ impl FragmentHeader {
         // Total fragments
         let total_fragments = buf.get_u16();
         
+        // Content hash
+        let mut content_hash = [0u8; CONTENT_HASH_SIZE];
+        buf.copy_to_slice(&mut content_hash);
+        
+        // Length-prefixed name
+        let name_len = buf.get_u16() as usize;
+        if buf.len() < name_len {
+            return Err(Error::Fragmentation(format!(
+                "Buffer too short for {}-byte fragment name", name_len)));
+        }
+        let mut name_buf = buf.split_to(name_len);
+        let name = Name::from_tlv(&mut name_buf)
+            .map_err(|e| Error::Fragmentation(format!("Invalid fragment name: {}", e)))?;
+        if !name_buf.is_empty() {
+            return Err(Error::Fragmentation("Trailing bytes after fragment name".into()));
+        }
+        
         Ok(Self {
             magic,
             is_final,
@@ -153,12 +237,15 @@ impl FragmentHeader {
             fragment_id,
             sequence,
             total_fragments,
+            content_hash,
+            name,
         })
     }
 }
 
 /// A fragment of an NDN data object
-struct Fragment {
+#[derive(Debug, Clone)]
+pub struct Fragment {
     /// Fragment header
     header: FragmentHeader,
     
@@ -172,12 +259,13 @@ impl Fragment {
         Self { header, payload }
     }
     
-    /// Encode the fragment to bytes
-    fn to_bytes(&self) -> Bytes {
-        let mut buf = BytesMut::with_capacity(FRAGMENT_HEADER_SIZE + self.payload.len());
+    /// Encode the fragment for the wire
+    pub fn to_wire(&self) -> Bytes {
+        let header = self.header.to_bytes();
+        let mut buf = BytesMut::with_capacity(header.len() + self.payload.len());
         
         // Header
-        buf.extend_from_slice(&self.header.to_bytes());
+        buf.extend_from_slice(&header);
         
         // Payload
         buf.extend_from_slice(&self.payload);
@@ -185,8 +273,13 @@ impl Fragment {
         buf.freeze()
     }
     
+    /// Decode a fragment from its wire encoding
+    pub fn from_wire(wire: &[u8]) -> Result<Self> {
+        Self::from_bytes(&mut Bytes::copy_from_slice(wire))
+    }
+    
     /// Decode a fragment from bytes
-    fn from_bytes(buf: &mut Bytes) -> Result<Self> {
+    pub fn from_bytes(buf: &mut Bytes) -> Result<Self> {
         // Parse header
         let header = FragmentHeader::from_bytes(buf)?;
         
@@ -195,6 +288,57 @@ impl Fragment {
         
         Ok(Self { header, payload })
     }
+    
+    /// Get the position of this fragment in its object
+    pub fn sequence(&self) -> usize {
+        self.header.sequence as usize
+    }
+    
+    /// Get the number of fragments the object was split into
+    pub fn total_fragments(&self) -> usize {
+        self.header.total_fragments as usize
+    }
+    
+    /// Check whether this is the last fragment of its object
+    pub fn is_final(&self) -> bool {
+        self.header.is_final
+    }
+    
+    /// Get the name of the fragmented Data
+    pub fn original_name(&self) -> &Name {
+        &self.header.name
+    }
+    
+    /// Get the fragment payload
+    pub fn data(&self) -> &Bytes {
+        &self.payload
+    }
+}
+
+/// Truncated SHA-256 identifying the content of a fragmented object
+type ContentHash = [u8; CONTENT_HASH_SIZE];
+
+/// Compute the content hash of an encoded data object
+fn content_hash(data_bytes: &[u8]) -> ContentHash {
+    let mut hash = [0u8; CONTENT_HASH_SIZE];
+    hash.copy_from_slice(&Sha256::digest(data_bytes)[..CONTENT_HASH_SIZE]);
+    hash
+}
+
+/// Reassemblies are keyed by Data name and content hash
+type ReassemblyKey = (Name, ContentHash);
+
+/// Snapshot of the reassemblies currently in progress
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReassemblyStats {
+    /// Number of partial reassemblies
+    pub contexts: usize,
+    
+    /// Fragment payload bytes held by partial reassemblies
+    pub buffered_bytes: usize,
+    
+    /// Age of the oldest partial reassembly (zero if there are none)
+    pub oldest_age: Duration,
 }
 
 /// Fragment reassembly context for a single data object
@@ -203,30 +347,47 @@ struct ReassemblyContext {
     /// Name of the data object
     name: Name,
     
+    /// Fragment ID the sender gave the object
+    fragment_id: u32,
+    
     /// Total number of fragments expected
     total_fragments: u16,
     
     /// Received fragments (sequence number -> payload)
     fragments: HashMap<u16, Bytes>,
     
+    /// Payload bytes received so far
+    bytes: usize,
+    
     /// When reassembly started
     start_time: std::time::Instant,
+    
+    /// When the last fragment arrived
+    updated_at: std::time::Instant,
 }
 
 impl ReassemblyContext {
     /// Create a new reassembly context
-    fn new(name: Name, total_fragments: u16) -> Self {
+    fn new(name: Name, fragment_id: u32, total_fragments: u16) -> Self {
         Self {
             name,
+            fragment_id,
             total_fragments,
             fragments: HashMap::new(),
+            bytes: 0,
             start_time: std::time::Instant::now(),
+            updated_at: std::time::Instant::now(),
         }
     }
     
-    /// Add a fragment to the context
-    pub fn add_fragment(&mut self, sequence: u16, payload: Bytes) {
-        self.fragments.insert(sequence, payload);
+    /// Add a fragment to the context, returning the payload bytes of any
+    /// earlier copy it replaces
+    pub fn add_fragment(&mut self, sequence: u16, payload: Bytes) -> usize {
+        self.bytes += payload.len();
+        self.updated_at = std::time::Instant::now();
+        let replaced = self.fragments.insert(sequence, payload).map_or(0, |old| old.len());
+        self.bytes -= replaced;
+        replaced
     }
     
     /// Check if all fragments have been received
@@ -266,6 +427,294 @@ impl ReassemblyContext {
     }
 }
 
+/// Reassembles Data from their fragments
+///
+/// Partial reassemblies expire after a timeout, and once `capacity` of them
+/// are in progress the oldest is evicted to make room for a new one. Their
+/// payloads are held to `max_bytes` altogether: a fragment that would go
+/// over evicts the reassemblies updated least recently, and is dropped with
+/// its own reassembly when that is not enough.
+#[derive(Debug)]
+pub struct Reassembler {
+    /// Partial reassemblies
+    contexts: HashMap<ReassemblyKey, ReassemblyContext>,
+    
+    /// How long a partial reassembly is kept
+    timeout: Duration,
+    
+    /// Largest number of partial reassemblies held at once
+    capacity: usize,
+    
+    /// Largest number of payload bytes held by partial reassemblies
+    max_bytes: usize,
+    
+    /// Payload bytes held by partial reassemblies
+    buffered_bytes: usize,
+    
+    /// Number of partial reassemblies evicted to stay under `max_bytes`
+    evictions: u64,
+}
+
+impl Default for Reassembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reassembler {
+    /// Create a reassembler with the default timeout and capacity
+    pub fn new() -> Self {
+        Self {
+            contexts: HashMap::new(),
+            timeout: DEFAULT_REASSEMBLY_TIMEOUT,
+            capacity: DEFAULT_REASSEMBLY_CAPACITY,
+            max_bytes: DEFAULT_MAX_REASSEMBLY_BYTES,
+            buffered_bytes: 0,
+            evictions: 0,
+        }
+    }
+    
+    /// Create a reassembler expiring partial reassemblies after `timeout`
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            ..Self::new()
+        }
+    }
+    
+    /// Create a reassembler holding at most `capacity` partial reassemblies
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ..Self::new()
+        }
+    }
+    
+    /// Create a reassembler holding at most `max_bytes` of fragment payload
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self {
+            max_bytes: max_bytes.max(1),
+            ..Self::new()
+        }
+    }
+    
+    /// Add a fragment, returning the Data once its last fragment arrives
+    ///
+    /// Fragments that cannot be used are logged and dropped.
+    pub fn add_fragment(&mut self, fragment: Fragment) -> Option<Data> {
+        match self.try_add_fragment(fragment) {
+            Ok(data) => data,
+            Err(e) => {
+                debug!("Dropping fragment: {}", e);
+                None
+            }
+        }
+    }
+    
+    /// Add a fragment, returning the Data once its last fragment arrives
+    pub(crate) fn try_add_fragment(&mut self, fragment: Fragment) -> Result<Option<Data>> {
+        // Update metrics
+        FRAGMENTS_RECEIVED.inc();
+        
+        let header = fragment.header;
+        let key = header.reassembly_key();
+        debug!("Received fragment {}/{} of {} (id: {}, hash: {})", 
+            header.sequence, header.total_fragments, header.name, header.fragment_id, hex::encode(header.content_hash));
+        
+        if header.sequence >= header.total_fragments {
+            return Err(Error::Fragmentation(format!(
+                "Fragment {} of {} is out of range", header.sequence, header.total_fragments)));
+        }
+        
+        // Late fragments of an expired reassembly are not restarted
+        if self.expire().contains(&key) {
+            return Err(Error::Fragmentation(format!(
+                "Reassembly of {} (id {}) timed out", header.name, header.fragment_id)));
+        }
+        
+        if !self.contexts.contains_key(&key) {
+            // The same object from the same sender can only have one content
+            let conflicting = self.contexts.iter().any(|((name, _), ctx)| {
+                *name == header.name && ctx.fragment_id == header.fragment_id
+            });
+            if conflicting {
+                REASSEMBLY_ERRORS.inc();
+                return Err(Error::Fragmentation(format!(
+                    "Fragment of {} (id {}) does not match the content being reassembled",
+                    header.name, header.fragment_id)));
+            }
+            
+            if self.contexts.len() >= self.capacity {
+                self.evict_oldest();
+            }
+            self.contexts.insert(key.clone(), ReassemblyContext::new(
+                header.name.clone(), header.fragment_id, header.total_fragments));
+        }
+        let expected_fragments = self.contexts[&key].total_fragments;
+        
+        if expected_fragments != header.total_fragments {
+            return Err(Error::Fragmentation(format!(
+                "Fragment of {} claims {} fragments, expected {}",
+                header.name, header.total_fragments, expected_fragments)));
+        }
+        
+        // Stay under the memory limit, giving up on this object if the
+        // others cannot make room for it
+        if !self.make_room(&key, fragment.payload.len()) {
+            self.remove_context(&key);
+            REASSEMBLY_ERRORS.inc();
+            return Err(Error::Fragmentation(format!(
+                "Reassembly of {} (id {}) exceeds the {} byte reassembly limit",
+                header.name, header.fragment_id, self.max_bytes)));
+        }
+        
+        // Add the fragment to the context
+        let context = self.contexts.get_mut(&key).unwrap();
+        self.buffered_bytes += fragment.payload.len();
+        self.buffered_bytes -= context.add_fragment(header.sequence, fragment.payload);
+        
+        if !context.is_complete() {
+            // Still waiting for more fragments
+            return Ok(None);
+        }
+        
+        // All fragments are in, so the context is done either way
+        let context = self.remove_context(&key).unwrap();
+        debug!("Completed reassembly of {} (id {})", header.name, header.fragment_id);
+        
+        // Reassemble the data
+        let data_bytes = match context.reassemble() {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!("Failed to reassemble data: {}", e);
+                REASSEMBLY_ERRORS.inc();
+                return Err(e);
+            }
+        };
+        
+        // The reassembled object must match the content hash it was sent with
+        if content_hash(&data_bytes) != header.content_hash {
+            REASSEMBLY_ERRORS.inc();
+            return Err(Error::Fragmentation(format!(
+                "Reassembled object for {} does not match its content hash", header.name)));
+        }
+        
+        // Parse the data
+        let data = match Data::parse(data_bytes) {
+            Ok(data) => data,
+            Err(e) => {
+                error!("Failed to parse reassembled data: {}", e);
+                REASSEMBLY_ERRORS.inc();
+                return Err(e);
+            }
+        };
+        
+        // Update metrics
+        REASSEMBLY_COMPLETED.inc();
+        
+        Ok(Some(data))
+    }
+    
+    /// Get the number of partial reassemblies
+    pub fn pending_reassemblies(&self) -> usize {
+        self.contexts.len()
+    }
+    
+    /// Get the payload bytes held by partial reassemblies
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered_bytes
+    }
+    
+    /// Get the number of partial reassemblies evicted to stay under the
+    /// memory limit
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+    
+    /// Remove a partial reassembly, releasing its payload bytes
+    fn remove_context(&mut self, key: &ReassemblyKey) -> Option<ReassemblyContext> {
+        let context = self.contexts.remove(key)?;
+        self.buffered_bytes -= context.bytes;
+        Some(context)
+    }
+    
+    /// Evict the partial reassemblies updated least recently, other than
+    /// `keep`, until `size` more bytes fit under the limit
+    ///
+    /// Returns false when they do not fit even with `keep` alone left.
+    fn make_room(&mut self, keep: &ReassemblyKey, size: usize) -> bool {
+        while self.buffered_bytes + size > self.max_bytes {
+            let least_recent = self.contexts
+                .iter()
+                .filter(|(key, _)| *key != keep)
+                .min_by_key(|(_, ctx)| ctx.updated_at)
+                .map(|(key, _)| key.clone());
+            
+            match least_recent.and_then(|key| self.remove_context(&key)) {
+                Some(ctx) => {
+                    debug!("Evicted partial reassembly of {} ({} bytes) to stay under the reassembly limit",
+                        ctx.name, ctx.bytes);
+                    self.evictions += 1;
+                }
+                None => return false,
+            }
+        }
+        
+        true
+    }
+    
+    /// Drop partial reassemblies older than the timeout, returning their keys
+    fn expire(&mut self) -> Vec<ReassemblyKey> {
+        self.remove_older_than(self.timeout)
+    }
+    
+    /// Drop partial reassemblies older than `max_age`, returning their keys
+    fn remove_older_than(&mut self, max_age: Duration) -> Vec<ReassemblyKey> {
+        let now = std::time::Instant::now();
+        let stale: Vec<ReassemblyKey> = self.contexts
+            .iter()
+            .filter(|(_, ctx)| now.duration_since(ctx.start_time) > max_age)
+            .map(|(key, _)| key.clone())
+            .collect();
+        
+        for key in &stale {
+            if let Some(ctx) = self.remove_context(key) {
+                debug!("Dropped partial reassembly of {} ({}/{} fragments)",
+                    ctx.name, ctx.fragments.len(), ctx.total_fragments);
+            }
+        }
+        
+        stale
+    }
+    
+    /// Drop the partial reassembly that started first
+    fn evict_oldest(&mut self) {
+        let oldest = self.contexts
+            .iter()
+            .min_by_key(|(_, ctx)| ctx.start_time)
+            .map(|(key, _)| key.clone());
+        
+        if let Some(ctx) = oldest.and_then(|key| self.remove_context(&key)) {
+            debug!("Evicted partial reassembly of {} to make room", ctx.name);
+        }
+    }
+    
+    /// Compute statistics about the partial reassemblies
+    fn stats(&self) -> ReassemblyStats {
+        let now = std::time::Instant::now();
+        
+        ReassemblyStats {
+            contexts: self.contexts.len(),
+            buffered_bytes: self.buffered_bytes,
+            oldest_age: self.contexts
+                .values()
+                .map(|ctx| now.duration_since(ctx.start_time))
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+}
+
 /// Fragmenter for NDN data objects
 #[derive(Debug)]
 pub struct Fragmenter {
@@ -273,10 +722,16 @@ pub struct Fragmenter {
     mtu: Mutex<usize>,
     
     /// Next fragment ID to assign
-    next_fragment_id: Mutex<u16>,
+    next_fragment_id: Mutex<u32>,
     
-    /// Reassembly contexts for received fragments
-    reassembly: Mutex<HashMap<u16, ReassemblyContext>>,
+    /// Reassembly of received fragments
+    reassembly: Mutex<Reassembler>,
+    
+    /// Largest number of fragments a single object may be split into
+    max_fragments: usize,
+    
+    /// Number of partial reassemblies dropped as stale
+    reassembly_timeouts: AtomicU64,
     
     /// MTU prediction history - keeps track of recent packet sizes for adaptive MTU
     mtu_history: Mutex<Vec<usize>>,
@@ -291,7 +746,9 @@ impl Fragmenter {
         Self {
             mtu: Mutex::new(std::cmp::max(mtu, FRAGMENT_HEADER_SIZE + 1)), // Ensure minimum viable MTU
             next_fragment_id: Mutex::new(1),
-            reassembly: Mutex::new(HashMap::new()),
+            reassembly: Mutex::new(Reassembler::new()),
+            max_fragments: DEFAULT_MAX_FRAGMENTS,
+            reassembly_timeouts: AtomicU64::new(0),
             mtu_history: Mutex::new(Vec::with_capacity(100)),  // Keep track of last 100 packet sizes
             last_mtu_adjustment: Mutex::new(std::time::Instant::now()),
         }
@@ -302,6 +759,22 @@ impl Fragmenter {
         Self::new(DEFAULT_MTU)
     }
     
+    /// Create a new fragmenter splitting an object into at most `max_fragments`
+    ///
+    /// The limit is capped at 65535, the most the fragment header can count.
+    pub fn with_max_fragments(mtu: usize, max_fragments: usize) -> Self {
+        Self {
+            max_fragments: max_fragments.clamp(1, u16::MAX as usize),
+            ..Self::new(mtu)
+        }
+    }
+    
+    /// Hold at most `max_bytes` of fragment payload in partial reassemblies
+    pub fn with_max_reassembly_bytes(mut self, max_bytes: usize) -> Self {
+        self.reassembly.get_mut().max_bytes = max_bytes.max(1);
+        self
+    }
+    
     /// Update the MTU
     pub async fn update_mtu(&self, new_mtu: usize) {
         let min_mtu = FRAGMENT_HEADER_SIZE + 1;
@@ -372,11 +845,21 @@ impl Fragmenter {
     }
     
     /// Fragment a data object into multiple smaller fragments
-    pub async fn fragment(&self, data: &Data) -> Vec<Bytes> {
+    ///
+    /// Fails when the name is too long for the fragment header, when the
+    /// MTU leaves no room for payload after the header, or when the object
+    /// would need more fragments than the configured limit.
+    pub async fn fragment(&self, data: &Data) -> Result<Vec<Bytes>> {
         // Get the name and serialized data
         let name = data.name().clone();
         let data_bytes = data.to_bytes();
         
+        let name_len = name.to_tlv().len();
+        if name_len > u16::MAX as usize {
+            return Err(Error::Fragmentation(format!(
+                "Name of {} bytes is too long for a fragment header (at most {})", name_len, u16::MAX)));
+        }
+        
         // Record original packet size for MTU adaptation
         {
             let mut history = self.mtu_history.lock().await;
@@ -394,11 +877,24 @@ impl Fragmenter {
         // Get the current MTU
         let mtu = self.mtu().await;
         
-        // Calculate the maximum payload size per fragment
-        let max_payload = mtu - FRAGMENT_HEADER_SIZE;
+        // Calculate the maximum payload size per fragment, after the header and name
+        let overhead = FragmentHeader::encoded_len(&name);
+        let max_payload = mtu.saturating_sub(overhead);
+        if max_payload == 0 {
+            return Err(Error::Fragmentation(format!(
+                "MTU {} leaves no room for payload after the {}-byte header of {}", mtu, overhead, name)));
+        }
         
         // Calculate the number of fragments needed
         let total_fragments = (data_bytes.len() + max_payload - 1) / max_payload;
+        if total_fragments > self.max_fragments {
+            return Err(Error::Fragmentation(format!(
+                "{} ({} bytes) would need {} fragments at MTU {}, more than the limit of {}",
+                name, data_bytes.len(), total_fragments, mtu, self.max_fragments)));
+        }
+        
+        // Digest binding the fragments to this object
+        let object_hash = content_hash(&data_bytes);
         
         // Get the next fragment ID
         let fragment_id = {
@@ -424,7 +920,9 @@ impl Fragmenter {
                 fragment_id,
                 i as u16,
                 total_fragments as u16,
-                i == total_fragments - 1
+                i == total_fragments - 1,
+                object_hash,
+                name.clone(),
             );
             
             // Extract the payload for this fragment
@@ -437,13 +935,13 @@ impl Fragmenter {
             let fragment = Fragment::new(header, payload);
             
             // Add to the list of fragments
-            fragments.push(fragment.to_bytes());
+            fragments.push(fragment.to_wire());
             
             // Update metrics
             FRAGMENTS_SENT.inc();
         }
         
-        fragments
+        Ok(fragments)
     }
     
     /// Process a received fragment and reassemble if complete
@@ -459,91 +957,74 @@ impl Fragmenter {
             }
         };
         
-        // Update metrics
-        FRAGMENTS_RECEIVED.inc();
-        
-        let header = fragment.header;
-        debug!("Received fragment {}/{} (id: {})", 
-            header.sequence, header.total_fragments, header.fragment_id);
-        
-        // Get or create the reassembly context
+        self.add_fragment(fragment).await
+    }
+    
+    /// Add a decoded fragment and reassemble if complete
+    pub async fn add_fragment(&self, fragment: Fragment) -> Result<Option<Data>> {
         let mut reassembly = self.reassembly.lock().await;
-        
-        let context = if let Some(ctx) = reassembly.get_mut(&header.fragment_id) {
-            ctx
-        } else {
-            // Create a new context with a dummy name for now
-            // We'll update it when we reassemble the data
-            let ctx = ReassemblyContext::new(
-                Name::from("/tmp"), // Temporary name
-                header.total_fragments
-            );
-            reassembly.insert(header.fragment_id, ctx);
-            reassembly.get_mut(&header.fragment_id).unwrap()
-        };
-        
-        // Add the fragment to the context
-        context.add_fragment(header.sequence, fragment.payload);
-        
-        // Check if we have all fragments
-        if context.is_complete() {
-            debug!("Completed reassembly for fragment id {}", header.fragment_id);
-            
-            // Reassemble the data
-            let data_bytes = match context.reassemble() {
-                Ok(bytes) => bytes,
-                Err(e) => {
-                    error!("Failed to reassemble data: {}", e);
-                    REASSEMBLY_ERRORS.inc();
-                    return Err(e);
-                }
-            };
-            
-            // Parse the data
-            let data = match Data::from_bytes(&data_bytes) {
-                Ok(data) => data,
-                Err(e) => {
-                    error!("Failed to parse reassembled data: {}", e);
-                    REASSEMBLY_ERRORS.inc();
-                    return Err(e);
-                }
-            };
-            
-            // Remove the context
-            reassembly.remove(&header.fragment_id);
-            
-            // Update metrics
-            REASSEMBLY_COMPLETED.inc();
-            
-            Ok(Some(data))
-        } else {
-            // Still waiting for more fragments
-            Ok(None)
-        }
+        let result = reassembly.try_add_fragment(fragment);
+        Self::record_reassembly_stats(&reassembly.stats());
+        result
+    }
+    
+    /// Get statistics about reassemblies in progress
+    pub async fn reassembly_stats(&self) -> ReassemblyStats {
+        let stats = self.reassembly.lock().await.stats();
+        Self::record_reassembly_stats(&stats);
+        stats
+    }
+    
+    /// Publish reassembly statistics to the metrics gauges
+    fn record_reassembly_stats(stats: &ReassemblyStats) {
+        REASSEMBLY_CONTEXTS.set(stats.contexts as f64);
+        REASSEMBLY_OLDEST_AGE.set(stats.oldest_age.as_secs_f64());
+        REASSEMBLY_BUFFERED_BYTES.set(stats.buffered_bytes as f64);
     }
     
-    /// Clean up stale reassembly contexts
-    pub async fn cleanup_stale(&self, max_age_secs: u64) -> usize {
+    /// Clean up reassembly contexts older than `max_age`
+    pub async fn cleanup_stale(&self, max_age: Duration) -> usize {
         let mut reassembly = self.reassembly.lock().await;
         
-        let now = std::time::Instant::now();
-        let stale: Vec<u16> = reassembly
-            .iter()
-            .filter(|(_, ctx)| now.duration_since(ctx.start_time).as_secs() > max_age_secs)
-            .map(|(id, _)| *id)
-            .collect();
-        
-        let count = stale.len();
-        for id in stale {
-            reassembly.remove(&id);
-        }
-        
+        let count = reassembly.remove_older_than(max_age).len();
         if count > 0 {
             debug!("Cleaned up {} stale reassembly contexts", count);
+            self.reassembly_timeouts.fetch_add(count as u64, Ordering::Relaxed);
         }
         
+        Self::record_reassembly_stats(&reassembly.stats());
+        
         count
     }
+    
+    /// Get the number of partial reassemblies cleaned up as stale
+    pub fn reassembly_timeouts(&self) -> u64 {
+        self.reassembly_timeouts.load(Ordering::Relaxed)
+    }
+    
+    /// Spawn a task cleaning up reassemblies older than `max_age` every
+    /// `interval`, so a peer that disappears mid-transfer does not leak them
+    ///
+    /// The task runs until its handle is aborted.
+    pub fn spawn_sweeper(self: &Arc<Self>, max_age: Duration, interval: Duration) -> JoinHandle<()> {
+        let fragmenter = Arc::downgrade(self);
+        
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            
+            loop {
+                ticker.tick().await;
+                
+                // Stop once the fragmenter itself is gone
+                match fragmenter.upgrade() {
+                    Some(fragmenter) => {
+                        fragmenter.cleanup_stale(max_age).await;
+                    }
+                    None => break,
+                }
+            }
+        })
+    }
 }
 
 #[cfg(test)]
@@ -555,13 +1036,15 @@ mod tests {
     #[cfg_attr(not(feature = "tokio-test"), test)]
     async fn test_fragment_header() {
         // Create a header
-        let header = FragmentHeader::new(0x1234, 0x5678, 0x9abc, true);
+        let name = Name::from_uri("/test/header").unwrap();
+        let header = FragmentHeader::new(0x1234_5678, 0x5678, 0x9abc, true, [0xab; CONTENT_HASH_SIZE], name.clone());
         
         // Encode to bytes
         let bytes = header.to_bytes();
         
         // Check size
-        assert_eq!(bytes.len(), FRAGMENT_HEADER_SIZE);
+        assert_eq!(bytes.len(), FRAGMENT_HEADER_SIZE + name.to_tlv().len());
+        assert_eq!(bytes.len(), FragmentHeader::encoded_len(&name));
         
         // Decode back
         let mut buf = bytes.freeze();
@@ -570,9 +1053,16 @@ mod tests {
         // Check values
         assert_eq!(decoded.magic, FRAGMENT_MAGIC);
         assert_eq!(decoded.is_final, true);
-        assert_eq!(decoded.fragment_id, 0x1234);
+        assert_eq!(decoded.fragment_id, 0x1234_5678);
         assert_eq!(decoded.sequence, 0x5678);
         assert_eq!(decoded.total_fragments, 0x9abc);
+        assert_eq!(decoded.content_hash, [0xab; CONTENT_HASH_SIZE]);
+        assert_eq!(decoded.name, name);
+        assert!(buf.is_empty());
+        
+        // A name length running past the buffer is rejected
+        let mut truncated = header.to_bytes().freeze().slice(..FRAGMENT_HEADER_SIZE + 2);
+        assert!(FragmentHeader::from_bytes(&mut truncated).is_err());
     }
     
     #[cfg_attr(feature = "tokio-test", tokio::test)]
@@ -587,10 +1077,11 @@ mod tests {
         let data = Data::new(name, content);
         
         // Fragment the data
-        let fragments = fragmenter.fragment(&data).await;
+        let fragments = fragmenter.fragment(&data).await.unwrap();
         
-        // Should be at least 3 fragments (250 / (100 - 8) = ~3)
-        assert!(fragments.len() >= 3);
+        // Each fragment holds 100 bytes less the 29-byte header and 14-byte
+        // name, so the 250+ byte Data needs at least 5 (250 / 57 = ~4.4)
+        assert!(fragments.len() >= 5);
         
         // Process the fragments in order
         let mut reassembled_data = None;
@@ -609,22 +1100,295 @@ mod tests {
         assert_eq!(reassembled.name(), data.name());
         assert_eq!(reassembled.content(), data.content());
     }
-}
-
-// Add implementation of methods needed for fragment reassembly
-impl Fragmenter {
-    /// Create a new reassembly context for receiving fragments
-    pub fn new_reassembly_context(&self, fragment_id: u16, total_fragments: u16) -> ReassemblyContext {
-        // Create a temporary name for the reassembly context
-        // Start with an empty name
-        let mut name = Name::new();
-        // Add components as needed to identify the fragment
-        let fragment_name = format!("/fragment/{}", fragment_id);
-        
-        // Create the context
-        let context = ReassemblyContext::new(name, total_fragments);
-        
-        // Clone and return the context
-        context
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_fragment_limits() {
+        let data = Data::new(Name::from_uri("/test/limits").unwrap(), vec![8u8; 100_000]);
+        
+        // A tiny MTU would need far too many fragments
+        assert!(Fragmenter::new(100).fragment(&data).await.is_err());
+        
+        // A normal MTU stays well within the limit
+        let fragments = Fragmenter::new(1400).fragment(&data).await.unwrap();
+        assert!(fragments.len() < 100);
+        
+        // The limit is configurable
+        assert!(Fragmenter::with_max_fragments(1400, 50).fragment(&data).await.is_err());
+        
+        // An MTU that cannot hold the header and name leaves no payload at all
+        let long_name = Name::from_uri(&format!("/test/{}", "n".repeat(64))).unwrap();
+        let data = Data::new(long_name, vec![8u8; 10]);
+        assert!(Fragmenter::new(60).fragment(&data).await.is_err());
+        
+        // A name longer than the header's 16-bit length is rejected, not truncated
+        let huge_name = Name::from_uri(&format!("/test/{}", "n".repeat(70_000))).unwrap();
+        let data = Data::new(huge_name, vec![8u8; 10]);
+        assert!(matches!(Fragmenter::new(200_000).fragment(&data).await, Err(Error::Fragmentation(_))));
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_reassembly_stats() {
+        let fragmenter = Fragmenter::new(50);
+        
+        // No reassemblies yet
+        let stats = fragmenter.reassembly_stats().await;
+        assert_eq!(stats, ReassemblyStats::default());
+        
+        // Start three reassemblies, holding back the last fragment of each
+        let mut expected_bytes = 0;
+        for i in 0..3 {
+            let name = Name::from_uri(&format!("/test/partial/{}", i)).unwrap();
+            let data = Data::new(name, vec![i as u8; 100]);
+            
+            let mut fragments = fragmenter.fragment(&data).await.unwrap();
+            assert!(fragments.len() > 1);
+            fragments.pop();
+            
+            for fragment in fragments {
+                // Count only the payload behind the header and name
+                expected_bytes += Fragment::from_wire(&fragment).unwrap().data().len();
+                
+                assert!(fragmenter.process_fragment(fragment).await.unwrap().is_none());
+            }
+        }
+        
+        let stats = fragmenter.reassembly_stats().await;
+        assert_eq!(stats.contexts, 3);
+        assert_eq!(stats.buffered_bytes, expected_bytes);
+        
+        // The oldest context keeps ageing
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(fragmenter.reassembly_stats().await.oldest_age >= Duration::from_millis(10));
+        
+        // The statistics are exported
+        let exported: Vec<String> = prometheus::gather().iter().map(|family| family.get_name().to_string()).collect();
+        for metric in ["udcn_reassembly_contexts", "udcn_reassembly_oldest_age_seconds", "udcn_reassembly_buffered_bytes"] {
+            assert!(exported.iter().any(|name| name == metric), "{} not exported", metric);
+        }
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_fragment_id_wraparound() {
+        let sender = Fragmenter::new(80);
+        let receiver = Fragmenter::new(80);
+        
+        let first = Data::new(Name::from_uri("/test/wrap/first").unwrap(), vec![1u8; 60]);
+        let second = Data::new(Name::from_uri("/test/wrap/second").unwrap(), vec![2u8; 60]);
+        let third = Data::new(Name::from_uri("/test/wrap/third").unwrap(), vec![3u8; 60]);
+        
+        // The ID wraps from u32::MAX to 0
+        *sender.next_fragment_id.lock().await = u32::MAX;
+        let first_fragments = sender.fragment(&first).await.unwrap();
+        let second_fragments = sender.fragment(&second).await.unwrap();
+        
+        // After a full cycle the ID is reused while `first` is still in flight
+        *sender.next_fragment_id.lock().await = u32::MAX;
+        let third_fragments = sender.fragment(&third).await.unwrap();
+        
+        let id_of = |fragment: &Bytes| FragmentHeader::from_bytes(&mut fragment.clone()).unwrap().fragment_id;
+        assert_eq!(id_of(&first_fragments[0]), u32::MAX);
+        assert_eq!(id_of(&second_fragments[0]), 0);
+        assert_eq!(id_of(&third_fragments[0]), u32::MAX);
+        
+        // Interleave the two objects sharing an ID
+        let mut reassembled = Vec::new();
+        for (a, b) in first_fragments.into_iter().zip(third_fragments) {
+            reassembled.extend(receiver.process_fragment(a).await.unwrap());
+            reassembled.extend(receiver.process_fragment(b).await.unwrap());
+        }
+        for fragment in second_fragments {
+            reassembled.extend(receiver.process_fragment(fragment).await.unwrap());
+        }
+        
+        // Each object is reassembled intact from its own fragments
+        assert_eq!(reassembled.len(), 3);
+        for expected in [&first, &third, &second] {
+            let data = reassembled.iter().find(|data| data.name() == expected.name()).unwrap();
+            assert_eq!(data.content(), expected.content());
+        }
+        assert_eq!(receiver.reassembly_stats().await.contexts, 0);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_reassembler_capacity_and_timeout() {
+        let fragmenter = Fragmenter::new(60);
+        let mut objects = Vec::new();
+        for i in 0..3 {
+            let data = Data::new(Name::from_uri(&format!("/test/reassembler/{}", i)).unwrap(), vec![i as u8; 80]);
+            let fragments: Vec<Fragment> = fragmenter.fragment(&data).await.unwrap()
+                .iter()
+                .map(|wire| Fragment::from_wire(wire).unwrap())
+                .collect();
+            assert!(fragments.len() > 2);
+            assert_eq!(fragments[0].original_name(), data.name());
+            objects.push((data, fragments));
+        }
+        
+        // Starting a third reassembly evicts the first
+        let mut reassembler = Reassembler::with_capacity(2);
+        for (_, fragments) in &objects {
+            assert!(reassembler.add_fragment(fragments[0].clone()).is_none());
+        }
+        assert_eq!(reassembler.pending_reassemblies(), 2);
+        let (data, fragments) = &objects[2];
+        let mut reassembled = None;
+        for fragment in &fragments[1..] {
+            reassembled = reassembler.add_fragment(fragment.clone());
+        }
+        assert_eq!(reassembled.unwrap().content(), data.content());
+        assert_eq!(reassembler.pending_reassemblies(), 1);
+        
+        // Fragments arriving after the timeout do not restart the reassembly
+        let mut reassembler = Reassembler::with_timeout(Duration::from_millis(20));
+        let (_, fragments) = &objects[0];
+        let (last, rest) = fragments.split_last().unwrap();
+        for fragment in rest {
+            assert!(reassembler.add_fragment(fragment.clone()).is_none());
+        }
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(reassembler.add_fragment(last.clone()).is_none());
+        assert_eq!(reassembler.pending_reassemblies(), 0);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_mismatched_content_hash_is_dropped() {
+        let name = Name::from_uri("/test/hash/object").unwrap();
+        let original = Data::new(name.clone(), vec![1u8; 120]);
+        let tampered = Data::new(name, vec![2u8; 120]);
+        
+        // Two senders starting from the same ID give both objects ID 1
+        let to_fragments = |wires: Vec<Bytes>| -> Vec<Fragment> {
+            wires.iter().map(|wire| Fragment::from_wire(wire).unwrap()).collect()
+        };
+        let original_fragments = to_fragments(Fragmenter::new(60).fragment(&original).await.unwrap());
+        let tampered_fragments = to_fragments(Fragmenter::new(60).fragment(&tampered).await.unwrap());
+        assert_eq!(original_fragments.len(), tampered_fragments.len());
+        
+        let mut reassembler = Reassembler::new();
+        let (last, rest) = original_fragments.split_last().unwrap();
+        for fragment in rest {
+            assert!(reassembler.add_fragment(fragment.clone()).is_none());
+        }
+        
+        // A fragment of the same object with a different hash is dropped
+        let forged = tampered_fragments.last().unwrap().clone();
+        assert!(reassembler.try_add_fragment(forged).is_err());
+        assert_eq!(reassembler.pending_reassemblies(), 1);
+        
+        // The genuine fragment still completes the object
+        let data = reassembler.add_fragment(last.clone()).unwrap();
+        assert_eq!(data.content(), original.content());
+        assert_eq!(reassembler.pending_reassemblies(), 0);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_same_name_objects_reassemble_independently() {
+        let fragmenter = Fragmenter::new(60);
+        let name = Name::from_uri("/test/hash/versions").unwrap();
+        let first = Data::new(name.clone(), vec![1u8; 120]);
+        let second = Data::new(name, vec![2u8; 120]);
+        
+        let first_fragments = fragmenter.fragment(&first).await.unwrap();
+        let second_fragments = fragmenter.fragment(&second).await.unwrap();
+        assert!(first_fragments.len() > 1);
+        
+        // Interleave the fragments of both objects
+        let mut reassembler = Reassembler::new();
+        let mut reassembled = Vec::new();
+        for (a, b) in first_fragments.iter().zip(&second_fragments) {
+            reassembled.extend(reassembler.add_fragment(Fragment::from_wire(a).unwrap()));
+            reassembled.extend(reassembler.add_fragment(Fragment::from_wire(b).unwrap()));
+        }
+        
+        // Each object is reassembled from its own fragments only
+        assert_eq!(reassembled.len(), 2);
+        assert_eq!(reassembled[0].content(), first.content());
+        assert_eq!(reassembled[1].content(), second.content());
+        assert_eq!(reassembler.pending_reassemblies(), 0);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_sweeper_reaps_partial_transfer() {
+        let sender = Fragmenter::new(60);
+        let receiver = Arc::new(Fragmenter::new(60));
+        
+        // A peer sends all but the last fragment, then disappears
+        let data = Data::new(Name::from_uri("/test/sweeper").unwrap(), vec![5u8; 120]);
+        let mut fragments = sender.fragment(&data).await.unwrap();
+        fragments.pop();
+        for fragment in fragments {
+            assert!(receiver.process_fragment(fragment).await.unwrap().is_none());
+        }
+        assert_eq!(receiver.reassembly_stats().await.contexts, 1);
+        
+        let sweeper = receiver.spawn_sweeper(Duration::from_millis(30), Duration::from_millis(10));
+        
+        // Still within its age, the reassembly is kept
+        assert_eq!(receiver.reassembly_stats().await.contexts, 1);
+        assert_eq!(receiver.reassembly_timeouts(), 0);
+        
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(receiver.reassembly_stats().await.contexts, 0);
+        assert_eq!(receiver.reassembly_timeouts(), 1);
+        
+        sweeper.abort();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    #[cfg_attr(not(feature = "tokio-test"), test)]
+    async fn test_reassembly_memory_cap_evicts() {
+        let sender = Fragmenter::new(60);
+        let mut transfers = Vec::new();
+        for i in 0..10 {
+            let data = Data::new(Name::from_uri(&format!("/test/cap/{}", i)).unwrap(), vec![i as u8; 120]);
+            let fragments: Vec<Fragment> = sender.fragment(&data).await.unwrap()
+                .iter()
+                .map(|wire| Fragment::from_wire(wire).unwrap())
+                .collect();
+            transfers.push((data, fragments));
+        }
+        
+        // Room for the partial payloads of three transfers
+        let partial_bytes: usize = transfers[0].1[1..].iter().map(|f| f.data().len()).sum();
+        let cap = 3 * partial_bytes;
+        let receiver = Fragmenter::new(60).with_max_reassembly_bytes(cap);
+        
+        // Ten concurrent transfers, each missing its first fragment
+        for (_, fragments) in &transfers {
+            for fragment in &fragments[1..] {
+                assert!(receiver.add_fragment(fragment.clone()).await.unwrap().is_none());
+                assert!(receiver.reassembly_stats().await.buffered_bytes <= cap);
+            }
+        }
+        let stats = receiver.reassembly_stats().await;
+        assert_eq!(stats.contexts, 3);
+        assert_eq!(stats.buffered_bytes, cap);
+        assert_eq!(receiver.reassembly.lock().await.evictions(), 7);
+        
+        // The transfers updated most recently were kept and still complete,
+        // though at the limit, so the next least recent one makes room
+        let (data, fragments) = &transfers[9];
+        let reassembled = receiver.add_fragment(fragments[0].clone()).await.unwrap().unwrap();
+        assert_eq!(reassembled.content(), data.content());
+        assert_eq!(receiver.reassembly_stats().await.buffered_bytes, partial_bytes);
+        assert_eq!(receiver.reassembly.lock().await.evictions(), 8);
+        
+        // An object larger than the cap on its own is dropped, not buffered
+        let mut reassembler = Reassembler::with_max_bytes(partial_bytes);
+        let fragments = &transfers[0].1;
+        for fragment in &fragments[..fragments.len() - 2] {
+            assert!(reassembler.try_add_fragment(fragment.clone()).unwrap().is_none());
+        }
+        let too_large = reassembler.try_add_fragment(fragments[fragments.len() - 2].clone());
+        assert!(matches!(too_large, Err(Error::Fragmentation(_))));
+        assert_eq!(reassembler.pending_reassemblies(), 0);
+        assert_eq!(reassembler.buffered_bytes(), 0);
     }
 }
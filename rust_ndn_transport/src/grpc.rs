@@ -21,6 +21,7 @@ use udcn::{
     MtuRequest, MtuResponse,
     PrefixRegistrationRequest, PrefixRegistrationResponse,
     PrefixUnregistrationRequest, PrefixUnregistrationResponse,
+    ListPrefixesRequest, ListPrefixesResponse, PrefixEntry,
     MetricsRequest, MetricsResponse, MetricValue,
     NetworkInterfacesRequest, NetworkInterfacesResponse, NetworkInterface,
     TransportControlRequest, TransportControlResponse,
@@ -138,6 +139,36 @@ impl UdcnControlService {
             .unwrap()
             .as_millis() as u64
     }
+
+    // Helper method to build the response for an Interest that got no Data
+    fn interest_error_response(name: String, nonce: u32, error_message: String) -> DataPacketResponse {
+        DataPacketResponse {
+            success: false,
+            error_message,
+            name,
+            content: vec![],
+            content_type: 0,
+            freshness_period: 0,
+            signature: vec![],
+            signature_type: String::new(),
+            timestamp_ms: Self::current_timestamp(),
+            nonce,
+        }
+    }
+
+    // Helper method to convert a metrics histogram; the infinite bucket has
+    // no bound, and no sum is recorded
+    fn histogram(buckets: &[(f64, u64)]) -> Histogram {
+        Histogram {
+            count: buckets.iter().map(|(_, count)| count).sum(),
+            sum: 0.0,
+            bucket_bounds: buckets.iter()
+                .filter(|(bound, _)| bound.is_finite())
+                .map(|(bound, _)| *bound as u64)
+                .collect(),
+            bucket_counts: buckets.iter().map(|(_, count)| *count).collect(),
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -156,14 +187,7 @@ impl UdcnControl for UdcnControlService {
             req.mtu, req.confidence
         );
         
-        // Validate MTU value
-        if req.mtu < 576 || req.mtu > 9000 {
-            return Err(Status::invalid_argument(
-                format!("Invalid MTU value: {}. Must be between 576 and 9000", req.mtu)
-            ));
-        }
-        
-        // Update MTU in the transport layer
+        // Update MTU in the transport layer (validated against the configured bounds)
         match self.transport.update_mtu(req.mtu as usize).await {
             Ok(_) => {
                 let response = MtuResponse {
@@ -174,6 +198,9 @@ impl UdcnControl for UdcnControlService {
                 };
                 Ok(Response::new(response))
             },
+            Err(crate::error::Error::InvalidArgument(msg)) => {
+                Err(Status::invalid_argument(msg))
+            },
             Err(e) => {
                 let error_message = format!("Failed to update MTU: {}", e);
                 tracing::error!("{}", error_message);
@@ -231,8 +258,11 @@ impl UdcnControl for UdcnControlService {
             },
             udcn::prefix_registration_request::PrefixType::Forwarder => {
                 // For forwarder prefixes, register a forwarding rule
-                // This is a simplified implementation
-                match self.transport.register_forwarding_prefix(prefix, req.priority as usize).await {
+                let next_hop = req.next_hop.parse().map_err(|e| {
+                    Status::invalid_argument(format!("Invalid next hop {:?}: {}", req.next_hop, e))
+                })?;
+                
+                match self.transport.register_forwarding_prefix(prefix, req.priority as usize, next_hop).await {
                     Ok(id) => id,
                     Err(e) => {
                         return Err(Status::internal(
@@ -283,6 +313,30 @@ impl UdcnControl for UdcnControlService {
         }
     }
 
+    // List the forwarding prefixes currently in the FIB
+    async fn list_prefixes(
+        &self,
+        _request: Request<ListPrefixesRequest>,
+    ) -> Result<Response<ListPrefixesResponse>, Status> {
+        let prefixes = self.transport.forwarding_entries()
+            .into_iter()
+            .map(|entry| PrefixEntry {
+                prefix: entry.prefix.to_string(),
+                priority: entry.priority as u32,
+                next_hop: entry.next_hop.to_string(),
+                registration_id: entry.registration_id,
+            })
+            .collect();
+        
+        let response = ListPrefixesResponse {
+            success: true,
+            error_message: String::new(),
+            prefixes,
+        };
+        
+        Ok(Response::new(response))
+    }
+
     // Get transport statistics and metrics
     async fn get_metrics(
         &self,
@@ -310,14 +364,9 @@ impl UdcnControl for UdcnControlService {
                         value: Some(metric_value::Value::GaugeValue(v)),
                         timestamp: Self::current_timestamp(),
                     },
-                    crate::metrics::MetricValue::Histogram(_h) => {
+                    crate::metrics::MetricValue::Histogram(h) => {
                         // Convert histogram to gRPC histogram
-                        let hist = Histogram {
-                            bucket_counts: vec![0; 8], // Initialized with zeros
-                            bucket_bounds: vec![100, 500, 1000, 1400, 2000, 4000, 8000], // Default buckets
-                            sum: 0.0, // Default sum
-                            count: 0, // Default count
-                        };
+                        let hist = Self::histogram(&h);
                         
                         MetricValue {
                             value: Some(metric_value::Value::HistogramValue(hist)),
@@ -355,20 +404,23 @@ impl UdcnControl for UdcnControlService {
                 // Convert internal interface representation to gRPC representation
                 let grpc_interfaces: Vec<NetworkInterface> = interfaces
                     .into_iter()
-                    .map(|iface| NetworkInterface {
-                        name: iface.clone(),
-                        mac_address: "00:00:00:00:00:00".to_string(), // Default value
-                        ip_addresses: vec!["0.0.0.0".to_string()], // Default value
-                        mtu: 1500, // Default MTU
-                        is_up: true, // Default value
-                        rx_bytes: 0,
-                        tx_bytes: 0,
-                        rx_packets: 0,
-                        tx_packets: 0,
-                        rx_errors: 0,
-                        tx_errors: 0,
-                        rx_dropped: 0,
-                        tx_dropped: 0,
+                    .map(|iface| {
+                        let stats = iface.stats.unwrap_or_default();
+                        NetworkInterface {
+                            name: iface.name,
+                            mac_address: iface.mac_address.unwrap_or_default(),
+                            ip_addresses: iface.addresses.iter().map(|addr| addr.to_string()).collect(),
+                            mtu: iface.mtu,
+                            is_up: iface.is_up,
+                            rx_bytes: stats.rx_bytes,
+                            tx_bytes: stats.tx_bytes,
+                            rx_packets: stats.rx_packets,
+                            tx_packets: stats.tx_packets,
+                            rx_errors: stats.rx_errors,
+                            tx_errors: stats.tx_errors,
+                            rx_dropped: stats.rx_dropped,
+                            tx_dropped: stats.tx_dropped,
+                        }
                     })
                     .collect();
                 
@@ -521,14 +573,9 @@ impl UdcnControl for UdcnControlService {
                                 value: Some(metric_value::Value::GaugeValue(v)),
                                 timestamp: UdcnControlService::current_timestamp(),
                             },
-                            crate::metrics::MetricValue::Histogram(_h) => {
+                            crate::metrics::MetricValue::Histogram(h) => {
                                 // Convert histogram to gRPC histogram
-                                let hist = Histogram {
-                                    bucket_counts: vec![0; 7], // Initialized with zeros
-                                    bucket_bounds: vec![1, 5, 10, 50, 100, 500, 1000], // Default buckets (milliseconds)
-                                    sum: 0.0, // Default sum
-                                    count: 0, // Default count
-                                };
+                                let hist = UdcnControlService::histogram(&h);
                                 
                                 MetricValue {
                                     value: Some(metric_value::Value::HistogramValue(hist)),
@@ -830,6 +877,7 @@ impl UdcnControl for UdcnControlService {
                     signature: data.signature().unwrap_or_default().clone(),
                     signature_type: data.signature_type().unwrap_or_default() as u32,
                     timestamp_ms: Self::current_timestamp(),
+                    nonce: 0,
                 };
                 
                 Ok(Response::new(response))
@@ -848,6 +896,7 @@ impl UdcnControl for UdcnControlService {
                     signature: vec![],
                     signature_type: String::new(),
                     timestamp_ms: Self::current_timestamp(),
+                    nonce: 0,
                 };
                 
                 Ok(Response::new(response))
@@ -900,6 +949,7 @@ impl UdcnControl for UdcnControlService {
                                         .duration_since(UNIX_EPOCH)
                                         .unwrap_or_default()
                                         .as_millis() as u64,
+                                    nonce: 0,
                                 };
                                 
                                 // Send data through channel
@@ -923,6 +973,7 @@ impl UdcnControl for UdcnControlService {
                                         .duration_since(UNIX_EPOCH)
                                         .unwrap_or_default()
                                         .as_millis() as u64,
+                                    nonce: 0,
                                 };
                                 
                                 if tx.send(Ok(error_response)).await.is_err() {
@@ -944,7 +995,83 @@ impl UdcnControl for UdcnControlService {
         // Return the receiver as a stream
         Ok(Response::new(ReceiverStream::new(rx)))
     }
-    
+
+    // Define stream type for StreamInterests
+    type StreamInterestsStream = ReceiverStream<Result<DataPacketResponse, Status>>;
+
+    // Exchange Interests for Data over one bidirectional stream
+    async fn stream_interests(
+        &self,
+        request: Request<tonic::Streaming<InterestPacketRequest>>,
+    ) -> Result<Response<Self::StreamInterestsStream>, Status> {
+        let mut inbound = request.into_inner();
+        let (tx, rx) = mpsc::channel(128);
+        let transport = Arc::clone(&self.transport);
+
+        tokio::spawn(async move {
+            while let Some(message) = inbound.next().await {
+                let req = match message {
+                    Ok(req) => req,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+
+                let name = match Name::from_uri(&req.name) {
+                    Ok(name) => name,
+                    Err(e) => {
+                        let response = Self::interest_error_response(
+                            req.name, req.nonce, format!("Invalid Interest name: {}", e));
+                        if tx.send(Ok(response)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                let mut interest = crate::ndn::Interest::new(name)
+                    .can_be_prefix(req.can_be_prefix)
+                    .must_be_fresh(req.must_be_fresh);
+                if req.lifetime_ms > 0 {
+                    interest = interest.lifetime(Duration::from_millis(req.lifetime_ms as u64));
+                }
+                if req.nonce != 0 {
+                    interest = interest.with_nonce(req.nonce);
+                }
+
+                // Satisfy each Interest in its own task so that Data is
+                // streamed back as soon as it arrives, not in request order
+                let transport = Arc::clone(&transport);
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let nonce = interest.nonce();
+                    let response = match transport.send_interest(interest).await {
+                        Ok(data) => DataPacketResponse {
+                            success: true,
+                            error_message: String::new(),
+                            name: data.name().to_string(),
+                            content: data.content().to_vec(),
+                            content_type: data.get_content_type().as_u8() as u32,
+                            freshness_period: data.get_fresh_period().as_millis() as u64,
+                            signature: data.signature_value().to_vec(),
+                            signature_type: String::new(),
+                            timestamp_ms: Self::current_timestamp(),
+                            nonce,
+                        },
+                        Err(e) => Self::interest_error_response(
+                            req.name, nonce, format!("Failed to send Interest: {}", e)),
+                    };
+
+                    // The client may have closed the stream already
+                    let _ = tx.send(Ok(response)).await;
+                });
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
     // XDP integration methods
     
     // Configure and load the XDP program on a network interface
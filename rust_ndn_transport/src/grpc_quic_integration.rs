@@ -74,7 +74,7 @@ impl GrpcQuicAdapter {
         };
         
         // Store connection mapping
-        let remote_addr = format!("{}:{}", peer_address, port).parse()?;
+        let remote_addr = crate::parse_bind_addr(&peer_address, port)?;
         {
             let mut connections = self.connections.write().await;
             connections.insert(conn_id.clone(), remote_addr);
@@ -163,6 +163,7 @@ impl GrpcQuicAdapter {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_millis() as u64,
+            nonce: 0,
         };
         
         Ok(response)
@@ -111,7 +111,7 @@ impl EnhancedGrpcQuicAdapter {
         };
         
         // Store connection mapping
-        let remote_addr = format!("{}:{}", peer_address, port).parse()?;
+        let remote_addr = crate::parse_bind_addr(&peer_address, port)?;
         {
             let mut connections = self.connections.write().await;
             connections.insert(conn_id.clone(), remote_addr);
@@ -214,6 +214,7 @@ impl EnhancedGrpcQuicAdapter {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_millis() as u64,
+            nonce: 0,
         };
         
         Ok(response)
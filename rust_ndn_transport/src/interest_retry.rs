@@ -3,93 +3,131 @@
 // This module implements retry strategies for Interest packet transmission
 // with exponential backoff and configurable policies.
 
+use std::future::Future;
 use std::time::Duration;
 use rand::Rng;
+use tracing::debug;
 
 use crate::error::{Error, Result};
+use crate::ndn::NackReason;
+use crate::Config;
 
 /// RetryPolicy defines parameters for Interest retransmission attempts
+///
+/// This replaces the earlier `max_attempts`, `base_delay_ms`, `max_delay_ms`,
+/// `backoff_factor` and `with_jitter` fields, which is a breaking change for
+/// code building a policy field by field. `max_retries` does not count the
+/// first send, so it is one less than the old `max_attempts`; delays always
+/// double, and `jitter` sets how far they vary rather than switching a fixed
+/// 25% on or off. `from_attempts` converts the old parameters.
 #[derive(Debug, Clone)]
 pub struct RetryPolicy {
-    /// Maximum number of retry attempts
-    pub max_attempts: u32,
+    /// Number of times an Interest is re-sent after the first attempt
+    pub max_retries: u32,
     
-    /// Base delay between retries in milliseconds
-    pub base_delay_ms: u64,
+    /// Delay before the first retry; each further retry doubles it
+    pub base_interval: Duration,
     
-    /// Maximum delay between retries in milliseconds
-    pub max_delay_ms: u64,
+    /// Maximum delay between retries
+    pub max_interval: Duration,
     
-    /// Backoff multiplier (delay grows by this factor each attempt)
-    pub backoff_factor: f64,
-    
-    /// Whether to add jitter to retry delays
-    pub with_jitter: bool,
+    /// Largest fraction (0.0 to 1.0) randomly added to or taken off a delay
+    pub jitter: f64,
 }
 
 impl Default for RetryPolicy {
     fn default() -> Self {
         Self {
-            max_attempts: 3,
-            base_delay_ms: 100,
-            max_delay_ms: 5000,
-            backoff_factor: 2.0,
-            with_jitter: true,
+            max_retries: 3,
+            base_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(5),
+            jitter: 0.25,
         }
     }
 }
 
 impl RetryPolicy {
+    /// Creates a policy from the transport's `retries` and `retry_interval`
+    pub fn from_config(config: &Config) -> Self {
+        let base_interval = Duration::from_millis(config.retry_interval);
+        let default = Self::default();
+        
+        Self {
+            max_retries: config.retries,
+            base_interval,
+            max_interval: default.max_interval.max(base_interval),
+            ..default
+        }
+    }
+    
+    /// Creates a policy from the parameters of the earlier fields
+    ///
+    /// `max_attempts` counts the first send. The old `backoff_factor` has no
+    /// counterpart, as delays now always double.
+    #[deprecated(note = "set `max_retries`, `base_interval`, `max_interval` and `jitter` instead")]
+    pub fn from_attempts(max_attempts: u32, base_delay_ms: u64, max_delay_ms: u64, with_jitter: bool) -> Self {
+        Self {
+            max_retries: max_attempts.saturating_sub(1),
+            base_interval: Duration::from_millis(base_delay_ms),
+            max_interval: Duration::from_millis(max_delay_ms),
+            jitter: if with_jitter { 0.25 } else { 0.0 },
+        }
+    }
+    
+    /// Get the number of sends allowed, counting the first
+    #[deprecated(note = "use `max_retries`, which does not count the first send")]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_retries.saturating_add(1)
+    }
+    
     /// Creates a policy for quick retries with short intervals
     pub fn quick_retries() -> Self {
         Self {
-            max_attempts: 5,
-            base_delay_ms: 50,
-            max_delay_ms: 1000,
-            backoff_factor: 1.5,
-            with_jitter: true,
+            max_retries: 5,
+            base_interval: Duration::from_millis(50),
+            max_interval: Duration::from_secs(1),
+            jitter: 0.25,
         }
     }
     
     /// Creates a policy for slow but persistent retries
     pub fn persistent_retries() -> Self {
         Self {
-            max_attempts: 10,
-            base_delay_ms: 500,
-            max_delay_ms: 30000,
-            backoff_factor: 2.0,
-            with_jitter: true,
+            max_retries: 10,
+            base_interval: Duration::from_millis(500),
+            max_interval: Duration::from_secs(30),
+            jitter: 0.25,
         }
     }
     
-    /// Calculate the delay for a specific retry attempt
-    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
-        if attempt == 0 {
-            return Duration::from_millis(0);
+    /// Calculate the delay before a retry (the first retry is 1)
+    pub fn delay_for_retry(&self, retry: u32) -> Duration {
+        if retry == 0 {
+            return Duration::ZERO;
         }
         
-        // Calculate base exponential backoff
-        let exponential_delay = (self.base_delay_ms as f64 * self.backoff_factor.powf((attempt - 1) as f64)) as u64;
+        // Double the base interval for every retry after the first, up to the cap
+        let factor = 2u32.saturating_pow(retry - 1);
+        let delay = self.base_interval.saturating_mul(factor).min(self.max_interval);
         
-        // Apply maximum delay cap
-        let capped_delay = std::cmp::min(exponential_delay, self.max_delay_ms);
-        
-        // Add jitter if configured
-        let final_delay = if self.with_jitter {
-            // Add random jitter of up to 25%
-            let jitter = rand::thread_rng().gen_range(0.75..1.25);
-            (capped_delay as f64 * jitter) as u64
-        } else {
-            capped_delay
-        };
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
         
-        Duration::from_millis(final_delay)
+        delay.mul_f64(rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter))
+    }
+    
+    /// Calculate the delay before an attempt (the first retry is 1)
+    #[deprecated(note = "use `delay_for_retry`")]
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        self.delay_for_retry(attempt)
     }
     
-    /// Should we retry based on the error and attempt number
+    /// Should we retry based on the error and the number of attempts made
     pub fn should_retry(&self, error: &Error, attempt: u32) -> bool {
-        // Don't retry if we've reached max attempts
-        if attempt >= self.max_attempts {
+        // Don't retry once every retry has been used
+        if attempt > self.max_retries {
             return false;
         }
         
@@ -98,7 +136,10 @@ impl RetryPolicy {
             // Network errors are generally retryable
             Error::IoError(_) => true,
             Error::ConnectionError(_) => true,
+            Error::Connection { .. } => true,
+            Error::Stream { .. } => true,
             Error::Timeout(_) => true,
+            Error::Nack { reason: NackReason::Congestion, .. } => true,
             
             // Protocol errors are not retryable
             Error::ParsingError(_) => false,
@@ -107,8 +148,8 @@ impl RetryPolicy {
             // Other errors may be retryable
             Error::Other(msg) => {
                 // Check for specific error messages that might be retryable
-                msg.contains("temporary") || 
-                msg.contains("timeout") || 
+                msg.contains("temporary") ||
+                msg.contains("timeout") ||
                 msg.contains("reset") ||
                 msg.contains("connection")
             }
@@ -119,18 +160,49 @@ impl RetryPolicy {
     }
 }
 
+/// Check whether an Interest failure may succeed if the Interest is re-sent
+///
+/// Only timeouts and Congestion NACKs are retried; any other NACK means the
+/// network cannot satisfy the Interest, so re-sending it would not help.
+pub fn is_retryable(error: &Error) -> bool {
+    matches!(error, Error::Timeout(_) | Error::Nack { reason: NackReason::Congestion, .. })
+}
+
+/// Send an Interest with `send`, re-sending it on timeouts and Congestion
+/// NACKs with exponential backoff until it succeeds or the retries run out
+pub async fn retry_interest<T, F, Fut>(policy: &RetryPolicy, mut send: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retries = 0;
+    
+    loop {
+        match send().await {
+            Err(error) if is_retryable(&error) && retries < policy.max_retries => {
+                retries += 1;
+                let delay = policy.delay_for_retry(retries);
+                
+                debug!("Interest failed ({}), retry {}/{} after {:?}",
+                       error, retries, policy.max_retries, delay);
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Execute a function with retry according to the provided policy
 pub async fn with_retry<T, F, Fut>(
-    operation: F, 
+    operation: F,
     policy: &RetryPolicy,
     operation_name: &str
-) -> Result<T> 
+) -> Result<T>
 where
     F: Fn() -> Fut,
-    Fut: std::future::Future<Output = Result<T>>,
+    Fut: Future<Output = Result<T>>,
 {
     let mut attempt = 0;
-    let mut last_error = None;
     
     loop {
         attempt += 1;
@@ -144,20 +216,19 @@ where
                 // Determine if we should retry
                 if policy.should_retry(&error, attempt) {
                     // Calculate retry delay
-                    let delay = policy.delay_for_attempt(attempt);
+                    let delay = policy.delay_for_retry(attempt);
                     
                     tracing::warn!(
                         "Operation '{}' failed (attempt {}/{}): {}. Retrying after {:?}",
                         operation_name,
                         attempt,
-                        policy.max_attempts,
+                        policy.max_retries + 1,
                         error,
                         delay
                     );
                     
                     // Wait before retrying
                     tokio::time::sleep(delay).await;
-                    last_error = Some(error);
                 } else {
                     // No more retries, return the error
                     return Err(error);
@@ -165,7 +236,86 @@ where
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
     
-    // This should be unreachable, but if we get here, return the last error
-    Err(last_error.unwrap_or_else(|| Error::Other("Unknown error during retry".to_string())))
+    fn test_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base_interval: Duration::from_millis(1),
+            max_interval: Duration::from_millis(4),
+            jitter: 0.0,
+        }
+    }
+    
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let policy = test_policy();
+        let delays: Vec<u64> = (0..5).map(|retry| policy.delay_for_retry(retry).as_millis() as u64).collect();
+        assert_eq!(delays, vec![0, 1, 2, 4, 4]);
+        
+        let jittered = RetryPolicy {
+            base_interval: Duration::from_millis(100),
+            max_interval: Duration::from_millis(100),
+            jitter: 0.5,
+            ..test_policy()
+        };
+        for _ in 0..20 {
+            let delay = jittered.delay_for_retry(1);
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(150), "{:?}", delay);
+        }
+    }
+    
+    #[test]
+    #[allow(deprecated)]
+    fn test_from_attempts_excludes_first_send() {
+        let policy = RetryPolicy::from_attempts(4, 1, 4, false);
+        assert_eq!(policy.max_retries, 3);
+        assert_eq!(policy.max_attempts(), 4);
+        assert_eq!(policy.jitter, 0.0);
+        assert_eq!(policy.delay_for_attempt(3), test_policy().delay_for_retry(3));
+        
+        assert_eq!(RetryPolicy::from_attempts(0, 1, 4, true).max_retries, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_retries_timeouts_and_congestion() {
+        let policy = test_policy();
+        
+        // A timeout every time uses up every retry
+        let attempts = AtomicU32::new(0);
+        let result: Result<()> = retry_interest(&policy, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(Error::Timeout("no Data".into()))
+        }).await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), policy.max_retries + 1);
+        
+        // Congestion is retried until the Interest gets through
+        let attempts = AtomicU32::new(0);
+        let result = retry_interest(&policy, || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(Error::Nack { reason: NackReason::Congestion, message: String::new() }),
+                _ => Ok("data"),
+            }
+        }).await;
+        assert_eq!(result.unwrap(), "data");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+    
+    #[tokio::test]
+    async fn test_no_route_fails_fast() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<()> = retry_interest(&test_policy(), || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(Error::Nack { reason: NackReason::NoRoute, message: String::new() })
+        }).await;
+        
+        assert!(matches!(result, Err(Error::Nack { reason: NackReason::NoRoute, .. })));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
 }
@@ -6,7 +6,10 @@
 //
 
 use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fs;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -26,6 +29,15 @@ pub enum Protocol {
     Ethernet,
 }
 
+/// Directory the kernel lists network interfaces in
+const SYS_CLASS_NET: &str = "/sys/class/net";
+
+/// Interface flag: administratively up
+const IFF_UP: u32 = 0x1;
+
+/// Interface flag: supports multicast
+const IFF_MULTICAST: u32 = 0x1000;
+
 /// Network interface information
 #[derive(Debug, Clone)]
 pub struct InterfaceInfo {
@@ -38,6 +50,9 @@ pub struct InterfaceInfo {
     /// Interface MTU
     pub mtu: u32,
     
+    /// Hardware (MAC) address, if the interface has one
+    pub mac_address: Option<String>,
+    
     /// Interface addresses
     pub addresses: Vec<IpAddr>,
     
@@ -46,6 +61,142 @@ pub struct InterfaceInfo {
     
     /// Interface supports multicast
     pub is_multicast: bool,
+    
+    /// Traffic counters, if requested
+    pub stats: Option<InterfaceStats>,
+}
+
+/// Traffic counters of a network interface
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfaceStats {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+/// List the host's network interfaces, ordered by index
+///
+/// Interfaces are read from `/sys/class/net` and their addresses from
+/// `getifaddrs`; with `include_stats` their traffic counters are read too.
+pub fn list_interfaces(include_stats: bool) -> Result<Vec<InterfaceInfo>> {
+    let entries = fs::read_dir(SYS_CLASS_NET)
+        .map_err(|e| Error::IoError(format!("Failed to list {}: {}", SYS_CLASS_NET, e)))?;
+    let mut addresses = interface_addresses()?;
+    
+    let mut interfaces = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let dir = entry.path();
+        
+        let flags = read_sys_value(&dir, "flags")
+            .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok())
+            .unwrap_or(0);
+        let mac_address = read_sys_value(&dir, "address")
+            .filter(|mac| !mac.is_empty() && mac != "00:00:00:00:00:00");
+        
+        interfaces.push(InterfaceInfo {
+            index: read_sys_number(&dir, "ifindex") as u32,
+            mtu: read_sys_number(&dir, "mtu") as u32,
+            mac_address,
+            addresses: addresses.remove(&name).unwrap_or_default(),
+            is_up: flags & IFF_UP != 0,
+            is_multicast: flags & IFF_MULTICAST != 0,
+            stats: include_stats.then(|| read_stats(&dir)),
+            name,
+        });
+    }
+    
+    interfaces.sort_by_key(|iface| iface.index);
+    Ok(interfaces)
+}
+
+/// Get the MTU of the named interface
+pub fn interface_mtu(name: &str) -> Result<u32> {
+    let dir = Path::new(SYS_CLASS_NET).join(name);
+    read_sys_value(&dir, "mtu")
+        .and_then(|mtu| mtu.parse().ok())
+        .ok_or_else(|| Error::NotFound(format!("Interface {}", name)))
+}
+
+/// Read a sysfs attribute of an interface, without the trailing newline
+fn read_sys_value(dir: &Path, attribute: &str) -> Option<String> {
+    fs::read_to_string(dir.join(attribute))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+/// Read a numeric sysfs attribute of an interface, 0 if it is missing
+fn read_sys_number(dir: &Path, attribute: &str) -> u64 {
+    read_sys_value(dir, attribute)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Read the traffic counters of an interface
+fn read_stats(dir: &Path) -> InterfaceStats {
+    let stats = dir.join("statistics");
+    InterfaceStats {
+        rx_bytes: read_sys_number(&stats, "rx_bytes"),
+        tx_bytes: read_sys_number(&stats, "tx_bytes"),
+        rx_packets: read_sys_number(&stats, "rx_packets"),
+        tx_packets: read_sys_number(&stats, "tx_packets"),
+        rx_errors: read_sys_number(&stats, "rx_errors"),
+        tx_errors: read_sys_number(&stats, "tx_errors"),
+        rx_dropped: read_sys_number(&stats, "rx_dropped"),
+        tx_dropped: read_sys_number(&stats, "tx_dropped"),
+    }
+}
+
+/// Get the IPv4 and IPv6 addresses of every interface, by interface name
+fn interface_addresses() -> Result<HashMap<String, Vec<IpAddr>>> {
+    let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+    
+    // SAFETY: getifaddrs fills in a list we free below with freeifaddrs
+    if unsafe { libc::getifaddrs(&mut ifap) } != 0 {
+        return Err(Error::IoError(format!(
+            "getifaddrs failed: {}", std::io::Error::last_os_error())));
+    }
+    
+    let mut addresses: HashMap<String, Vec<IpAddr>> = HashMap::new();
+    let mut cursor = ifap;
+    while !cursor.is_null() {
+        // SAFETY: every node, name and address in the list stays valid
+        // until freeifaddrs, and addresses match their sa_family
+        let (name, address) = unsafe {
+            let ifa = &*cursor;
+            cursor = ifa.ifa_next;
+            if ifa.ifa_addr.is_null() {
+                continue;
+            }
+            
+            let address = match (*ifa.ifa_addr).sa_family as i32 {
+                libc::AF_INET => {
+                    let sin = &*(ifa.ifa_addr as *const libc::sockaddr_in);
+                    IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr)))
+                }
+                libc::AF_INET6 => {
+                    let sin6 = &*(ifa.ifa_addr as *const libc::sockaddr_in6);
+                    IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr))
+                }
+                _ => continue,
+            };
+            
+            (CStr::from_ptr(ifa.ifa_name).to_string_lossy().into_owned(), address)
+        };
+        
+        addresses.entry(name).or_default().push(address);
+    }
+    
+    // SAFETY: ifap came from a successful getifaddrs and is freed once
+    unsafe { libc::freeifaddrs(ifap) };
+    
+    Ok(addresses)
 }
 
 /// Face for communicating with other nodes
@@ -253,35 +404,13 @@ impl InterfaceManager {
     pub async fn discover_interfaces(&self) -> Result<()> {
         info!("Discovering network interfaces");
         
-        // For this prototype, we'll just add some dummy interfaces
-        // In a real implementation, this would discover actual interfaces
-        let mut interfaces = self.interfaces.write().await;
-        
-        // Add loopback interface
-        interfaces.insert("lo".to_string(), InterfaceInfo {
-            name: "lo".to_string(),
-            index: 1,
-            mtu: 65535,
-            addresses: vec![
-                IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
-                IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)),
-            ],
-            is_up: true,
-            is_multicast: false,
-        });
+        let discovered = list_interfaces(false)?;
         
-        // Add eth0 interface
-        interfaces.insert("eth0".to_string(), InterfaceInfo {
-            name: "eth0".to_string(),
-            index: 2,
-            mtu: 1500,
-            addresses: vec![
-                IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)),
-                IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
-            ],
-            is_up: true,
-            is_multicast: true,
-        });
+        let mut interfaces = self.interfaces.write().await;
+        interfaces.clear();
+        for iface in discovered {
+            interfaces.insert(iface.name.clone(), iface);
+        }
         
         info!("Discovered {} interfaces", interfaces.len());
         
@@ -393,6 +522,23 @@ impl InterfaceManager {
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_list_interfaces_finds_loopback() {
+        let interfaces = list_interfaces(true).unwrap();
+        
+        let lo = interfaces.iter().find(|iface| iface.name == "lo").expect("no loopback interface");
+        assert!(lo.is_up);
+        assert!(lo.mtu >= 1280 && lo.mtu <= 65536, "loopback MTU {}", lo.mtu);
+        assert!(lo.index > 0);
+        assert!(lo.stats.is_some());
+        
+        // Without stats, no counters are read
+        assert!(list_interfaces(false).unwrap().iter().all(|iface| iface.stats.is_none()));
+        
+        assert_eq!(interface_mtu("lo").unwrap(), lo.mtu);
+        assert!(matches!(interface_mtu("udcn-missing0"), Err(Error::NotFound(_))));
+    }
+    
     #[tokio::test]
     async fn test_interface_manager() {
         // Create a manager with a short idle timeout
@@ -426,4 +572,4 @@ mod tests {
         
         assert!(retrieved_face.is_some());
     }
-}
+}
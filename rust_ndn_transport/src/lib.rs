@@ -20,6 +20,18 @@ pub mod python;         // Python bindings for control plane integration
 pub mod ml;             // ML-based MTU prediction
 pub mod interest_retry; // Interest retry logic
 pub mod pipeline;       // Pipeline processing
+pub mod face;           // Face abstraction and in-memory loopback face
+pub mod strategy;       // Forwarding strategies
+pub mod dispatch;       // Handler dispatch for the QUIC engines
+pub mod admission;      // Adaptive admission control for incoming Interests
+pub mod rate_limit;     // Per-peer rate limiting of incoming Interests
+pub mod dead_nonce;     // Loop detection for forwarded Interests
+pub mod acl;            // Prefix-based access control for remote peers
+pub mod circuit_breaker; // Backing off from unreachable peers
+pub mod psync;          // Publish/subscribe sync groups
+pub mod trace;          // Packet tracing and pcapng capture
+#[cfg(feature = "otel")]
+pub mod otel;           // Trace-context propagation across hops
 
 // Conditionally compile gRPC module
 #[cfg(feature = "grpc")]
@@ -43,25 +55,41 @@ fn udcn_transport(py: Python, m: &PyModule) -> PyResult<()> {
 }
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use tokio::sync::RwLock;
 use std::time::Duration;
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use dashmap::DashMap;
+use futures::stream::{self, StreamExt};
 
-use crate::metrics::MetricsCollector;
+use crate::acl::SharedAcl;
+use crate::cache::ContentStoreBackend;
+use crate::dead_nonce::DeadNonceList;
+use crate::interest_retry::{retry_interest, RetryPolicy};
+use crate::metrics::{histogram_quantile, MetricsCollector, INTEREST_RTT_METRIC};
+use crate::security::SharedCertStore;
+use crate::strategy::{ForwardingCounters, ForwardingStats};
+use crate::trace::TraceHook;
 
 // Export core types from modules
-pub use crate::ndn::{Interest, Data, Nack};
+pub use crate::ndn::{Interest, Data, Nack, WireFormat};
 pub use crate::name::Name;
-pub use crate::error::{Error, Result};
+pub use crate::error::{Error, ErrorKind, Result};
 pub use crate::fragmentation::Fragmenter;
+pub use crate::face::{NdnFace, LoopbackFace};
 pub use crate::quic::QuicEngine;
 pub use crate::quic::PrefixHandler;
 pub use crate::metrics::MetricValue;
 pub use crate::xdp::XdpManager;
 pub use crate::xdp::XdpConfig;
+pub use crate::cache::tiered::CacheTiers;
+pub use crate::cache::CacheBackend;
+pub use crate::admission::AdmissionConfig;
+pub use crate::security::TlsConfig;
+pub use crate::trace::{PcapTracer, Tracer};
+pub use crate::acl::{AclPolicy, Subnet};
 
 /// Configuration for the μDCN transport
 #[derive(Debug, Clone)]
@@ -78,6 +106,12 @@ pub struct Config {
     /// Content store capacity
     pub cache_capacity: usize,
     
+    /// Maximum total size of cached Data in bytes (None means unbounded)
+    pub cache_capacity_bytes: Option<usize>,
+    
+    /// Where cached Data is kept (memory, or on disk to survive restarts)
+    pub cache_backend: CacheBackend,
+    
     /// Idle timeout in seconds
     pub idle_timeout: u64,
     
@@ -90,6 +124,14 @@ pub struct Config {
     /// Maximum packet size for fragmentation (in bytes)
     pub max_packet_size: usize,
     
+    /// Fragment Data from a handler that is larger than `max_packet_size`;
+    /// when false it is refused with a NoResource NACK
+    pub fragment_oversize_data: bool,
+    
+    /// Encoding of Interests and Data sent one per stream by QuicTransport;
+    /// Compact talks to the standalone quic_ndn_test tool
+    pub wire_format: WireFormat,
+    
     /// Logging level
     pub log_level: String,
     
@@ -108,14 +150,119 @@ pub struct Config {
     /// ML prediction interval in seconds
     pub ml_prediction_interval: u64,
     
-    /// ML model type ("rule-based" or "python")
+    /// ML model type ("rule-based", "linear", "onnx" or "python")
     pub ml_model_type: String,
     
+    /// Path of the model file for the "onnx" model type
+    pub ml_model_path: Option<String>,
+    
+    /// File the ML model's learned state is saved to on stop and restored from on start
+    pub ml_state_path: Option<String>,
+    
     /// Minimum MTU for ML prediction
     pub min_mtu: usize,
     
     /// Maximum MTU for ML prediction
     pub max_mtu: usize,
+    
+    /// Network interface whose link MTU caps the transport MTU, detected on start
+    pub mtu_interface: Option<String>,
+    
+    /// QUIC per-stream receive window in bytes
+    pub stream_receive_window: u64,
+    
+    /// QUIC per-connection receive window in bytes
+    pub receive_window: u64,
+    
+    /// QUIC per-connection send window in bytes
+    pub send_window: u64,
+    
+    /// Append and verify a CRC32 trailer on every packet (diagnostic only)
+    pub integrity_check: bool,
+    
+    /// Sizes of the tiered (memory + disk) content store, used when
+    /// `cache_backend` is `Tiered`; None uses the default sizes
+    pub cache_tiers: Option<CacheTiers>,
+    
+    /// Thresholds for shedding incoming Interests under load
+    pub admission: AdmissionConfig,
+    
+    /// Interests per second accepted from each peer, or None for no limit
+    pub max_interests_per_sec: Option<u32>,
+    
+    /// Interests a peer may send at once; defaults to one second's worth
+    pub interest_burst: Option<u32>,
+    
+    /// Interval between health-check pings on outgoing connections, in
+    /// milliseconds; 0 disables them
+    pub health_check_interval: u64,
+    
+    /// Health-check pings missed in a row before a connection is failed
+    pub max_missed_pings: u32,
+    
+    /// Connection attempts to a peer failing in a row before further ones
+    /// fail straight away for `breaker_cooldown`; 0 disables the breaker
+    pub breaker_failure_threshold: u32,
+    
+    /// How long connection attempts to an unreachable peer are refused, in
+    /// milliseconds
+    pub breaker_cooldown: u64,
+    
+    /// Answer timestamped pings under /localhop/ping, so peers can measure
+    /// the round-trip time with `UdcnTransport::ping`
+    pub enable_ping: bool,
+    
+    /// Prefix of control commands, such as prefix registration
+    pub command_prefix: String,
+    
+    /// Reject command Interests not signed by a key in the certificate store
+    /// with a NotAuth NACK
+    pub require_signed_commands: bool,
+    
+    /// How far the SignatureTime of a signed command may be from the local
+    /// clock, in milliseconds; older commands and replayed ones are rejected
+    pub command_grace_period: u64,
+    
+    /// Timeout for opening a stream and writing a packet, in milliseconds
+    pub send_timeout: u64,
+    
+    /// Timeout for reading a packet from a stream, in milliseconds
+    pub recv_timeout: u64,
+    
+    /// Longest an Interest's Data is waited for, whatever its lifetime, in
+    /// milliseconds
+    pub max_interest_lifetime: u64,
+    
+    /// Time shutdown waits for in-flight Interests to finish, in milliseconds
+    pub drain_timeout: u64,
+    
+    /// Age at which a partial fragment reassembly is dropped, in milliseconds
+    pub reassembly_timeout: u64,
+    
+    /// Interval between sweeps for stale reassemblies, in milliseconds
+    pub reassembly_sweep_interval: u64,
+    
+    /// Largest number of fragment payload bytes held by partial reassemblies
+    /// altogether; the least recently updated are evicted to stay under it
+    pub max_reassembly_bytes: usize,
+    
+    /// How long the name and nonce of a forwarded Interest are remembered to
+    /// detect it looping back, in milliseconds
+    pub dead_nonce_window: u64,
+    
+    /// How outgoing connections verify the server certificate
+    pub tls: TlsConfig,
+    
+    /// Name outgoing connections expect the server certificate to be issued
+    /// for; generated node certificates are issued for "localhost"
+    pub tls_server_name: String,
+    
+    /// PEM file with the certificate chain this node presents; without it a
+    /// self-signed certificate is generated on every start
+    pub tls_cert_path: Option<String>,
+    
+    /// PEM file with the private key of the certificate in `tls_cert_path`
+    pub tls_key_path: Option<String>,
 }
 
 impl Default for Config {
@@ -125,10 +272,14 @@ impl Default for Config {
             port: 6363,
             mtu: 1400,
             cache_capacity: 10000,
+            cache_capacity_bytes: None,
+            cache_backend: CacheBackend::Memory,
             idle_timeout: 60,
             enable_metrics: true,
             metrics_port: 9090,
             max_packet_size: 65535,
+            fragment_oversize_data: true,
+            wire_format: WireFormat::Ndn,
             log_level: "info".to_string(),
             retries: 3,
             retry_interval: 1000,
@@ -136,12 +287,83 @@ impl Default for Config {
             enable_ml_mtu_prediction: false,
             ml_prediction_interval: 30,
             ml_model_type: "rule-based".to_string(),
+            ml_model_path: None,
+            ml_state_path: None,
             min_mtu: 576,    // IPv4 minimum MTU
             max_mtu: 9000,   // Jumbo frame size
+            mtu_interface: None,
+            stream_receive_window: 1_250_000,
+            receive_window: 8_000_000,
+            send_window: 8_000_000,
+            integrity_check: false,
+            cache_tiers: None,
+            admission: AdmissionConfig::default(),
+            max_interests_per_sec: None,
+            interest_burst: None,
+            health_check_interval: 30000,
+            max_missed_pings: 3,
+            breaker_failure_threshold: 5,
+            breaker_cooldown: 10000,
+            enable_ping: false,
+            command_prefix: "/localhost/nfd".to_string(),
+            require_signed_commands: false,
+            command_grace_period: 60_000,
+            send_timeout: 5000,
+            recv_timeout: 30000,
+            max_interest_lifetime: 30000,
+            drain_timeout: 5000,
+            reassembly_timeout: 30000,
+            reassembly_sweep_interval: 5000,
+            max_reassembly_bytes: 64 * 1024 * 1024,
+            dead_nonce_window: 6000,
+            tls: TlsConfig::Insecure,
+            tls_server_name: "localhost".to_string(),
+            tls_cert_path: None,
+            tls_key_path: None,
         }
     }
 }
 
+impl Config {
+    /// Address the QUIC endpoint binds to
+    pub fn bind_addr(&self) -> Result<SocketAddr> {
+        parse_bind_addr(&self.bind_address, self.port)
+    }
+    
+    /// Address the metrics HTTP server listens on: the bind host, on the
+    /// metrics port
+    pub fn metrics_addr(&self) -> Result<SocketAddr> {
+        let mut addr = parse_bind_addr(&self.bind_address, 0)?;
+        addr.set_port(self.metrics_port);
+        Ok(addr)
+    }
+    
+    /// Address the gRPC server listens on: the bind host, on the port after
+    /// the metrics port
+    pub fn grpc_addr(&self) -> Result<SocketAddr> {
+        let mut addr = parse_bind_addr(&self.bind_address, 0)?;
+        addr.set_port(self.metrics_port + 1);
+        Ok(addr)
+    }
+}
+
+/// Parse a host and port into a socket address
+///
+/// The host is an IPv4 or IPv6 literal, with or without brackets (`::1`,
+/// `[::1]`). A host that carries its own port (`127.0.0.1:6363`,
+/// `[::1]:6363`) keeps it instead of `port`.
+pub fn parse_bind_addr(host: &str, port: u16) -> Result<SocketAddr> {
+    let host = host.trim();
+    if let Ok(addr) = host.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    
+    let literal = host.strip_prefix('[').and_then(|h| h.strip_suffix(']')).unwrap_or(host);
+    literal.parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, port))
+        .map_err(|_| Error::InvalidAddress(format!("{} is not an IP address", host)))
+}
+
 // Statistics struct
 #[derive(Clone, Debug)]
 pub struct TransportStatistics {
@@ -151,6 +373,30 @@ pub struct TransportStatistics {
     pub cache_hits: u64,
     pub cache_misses: u64,
     pub cache_hit_ratio: f64,
+    pub rtt_p50_ms: f64,
+    pub rtt_p95_ms: f64,
+    pub rtt_p99_ms: f64,
+}
+
+// Health report for liveness/readiness probes
+#[derive(Clone, Debug, PartialEq)]
+pub struct HealthReport {
+    pub state: TransportState,
+    pub ready: bool,
+    pub quic_up: bool,
+    pub grpc_up: bool,
+    pub xdp_status: xdp::XdpStatus,
+    pub cache_ok: bool,
+    pub last_error: Option<String>,
+}
+
+// A forwarding prefix in the FIB
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForwardingEntry {
+    pub prefix: Name,
+    pub priority: usize,
+    pub next_hop: SocketAddr,
+    pub registration_id: u64,
 }
 
 // Transport state enum
@@ -164,10 +410,34 @@ pub enum TransportState {
     Stopping,
 }
 
+// Bytes of a link MTU taken by the IPv6 (40) and UDP (8) headers, a QUIC
+// short header with a 20-byte connection ID and 4-byte packet number (25),
+// and the AEAD tag (16)
+pub const LINK_MTU_OVERHEAD: usize = 89;
+
+// Number of prefixes, busiest first, listed by `get_detailed_statistics`
+pub const TOP_PREFIXES: usize = 10;
+
+// How long a health check waits for the content store before reporting it
+// as unhealthy
+const CACHE_PROBE_TIMEOUT: Duration = Duration::from_millis(100);
+
 // Type aliases
 type PrefixHandler = Box<dyn Fn(Interest) -> Result<Data> + Send + Sync>;
 type PrefixTable = Arc<DashMap<Name, (u64, PrefixHandler)>>;
-type ForwardingTable = Arc<DashMap<Name, (u64, usize)>>;
+type ForwardingTable = Arc<DashMap<Name, Vec<NextHop>>>;
+type RegistrationIndex = Arc<DashMap<u64, Registration>>;
+type SharedContentStore = Arc<RwLock<Box<dyn ContentStoreBackend>>>;
+
+// Registration ID, priority and address of one next hop of a forwarding prefix
+type NextHop = (u64, usize, SocketAddr);
+
+// Table entry a registration ID refers to
+#[derive(Clone, Debug)]
+enum Registration {
+    Producer(Name),
+    Forwarding(Name),
+}
 
 /// The main QUIC-based NDN transport layer
 // Custom Debug implementation to skip fields that don't implement Debug
@@ -195,15 +465,32 @@ pub struct UdcnTransport {
     prefix_table: PrefixTable,
     forwarding_table: ForwardingTable,
     next_registration_id: Arc<RwLock<u64>>,
+    registrations: RegistrationIndex,
+    last_error: Arc<RwLock<Option<String>>>,
     grpc_server_handle: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
     ml_prediction: Arc<RwLock<Option<ml::MtuPredictionService>>>,
+    content_store: SharedContentStore,
+    quic_engine: Arc<RwLock<Option<QuicEngine>>>,
+    xdp_manager: Arc<RwLock<Option<XdpManager>>>,
+    // Largest MTU the link can carry after overhead; 0 until detected
+    link_mtu: Arc<AtomicUsize>,
+    // Tracer shown every frame the QUIC engine sends or receives
+    trace: TraceHook,
+    // Keys trusted to sign commands
+    cert_store: SharedCertStore,
+    // Peers allowed to request each prefix
+    acl: SharedAcl,
+    // Interests forwarded recently, to detect them looping back
+    dead_nonces: Arc<DeadNonceList>,
+    // Outcomes of Interests forwarded to next hops, by forwarding prefix
+    forwarding_stats: Arc<ForwardingCounters>,
 }
 
 impl UdcnTransport {
     // Create a new transport instance
     pub async fn new(config: Config) -> Result<Self> {
-        let metrics = Arc::new(MetricsCollector::new(
-            config.metrics_port,
+        let metrics = Arc::new(MetricsCollector::with_addr(
+            config.metrics_addr()?,
             config.enable_metrics,
         ));
         
@@ -226,16 +513,38 @@ impl UdcnTransport {
                     log::warn!("Python ML model requested but extension-module feature not enabled, using rule-based model");
                     Box::new(ml::SimpleRuleBasedModel::new(config.mtu, config.min_mtu, config.max_mtu))
                 }
+            } else if config.ml_model_type == "linear" {
+                Box::new(ml::LinearRegressionModel::new(config.mtu, config.min_mtu, config.max_mtu))
+            } else if config.ml_model_type == "onnx" {
+                #[cfg(feature = "onnx")]
+                {
+                    let path = config.ml_model_path.as_deref().ok_or_else(|| {
+                        Error::InvalidArgument("The onnx ML model type requires ml_model_path".to_string())
+                    })?;
+                    Box::new(ml::OnnxMtuModel::load(path, config.min_mtu, config.max_mtu)?)
+                }
+                #[cfg(not(feature = "onnx"))]
+                {
+                    log::warn!("ONNX ML model requested but onnx feature not enabled, using rule-based model");
+                    Box::new(ml::SimpleRuleBasedModel::new(config.mtu, config.min_mtu, config.max_mtu))
+                }
             } else {
                 // Default to rule-based model
                 Box::new(ml::SimpleRuleBasedModel::new(config.mtu, config.min_mtu, config.max_mtu))
             };
             
-            Some(ml::MtuPredictionService::new(model, config.ml_prediction_interval))
+            let service = ml::MtuPredictionService::new(model, config.ml_prediction_interval);
+            Some(match &config.ml_state_path {
+                Some(path) => service.with_state_path(path),
+                None => service,
+            })
         } else {
             None
         };
         
+        let content_store = cache::open_content_store(&config)?;
+        let dead_nonces = Arc::new(DeadNonceList::new(Duration::from_millis(config.dead_nonce_window)));
+        
         let transport = Self {
             config: Arc::new(RwLock::new(config)),
             state: Arc::new(RwLock::new(TransportState::Stopped)),
@@ -244,10 +553,25 @@ impl UdcnTransport {
             prefix_table: Arc::new(DashMap::new()),
             forwarding_table: Arc::new(DashMap::new()),
             next_registration_id: Arc::new(RwLock::new(1)),
+            registrations: Arc::new(DashMap::new()),
+            last_error: Arc::new(RwLock::new(None)),
             grpc_server_handle: Arc::new(RwLock::new(None)),
             ml_prediction: Arc::new(RwLock::new(ml_prediction)),
+            content_store: Arc::new(RwLock::new(content_store)),
+            quic_engine: Arc::new(RwLock::new(None)),
+            xdp_manager: Arc::new(RwLock::new(None)),
+            link_mtu: Arc::new(AtomicUsize::new(0)),
+            trace: TraceHook::new(),
+            cert_store: SharedCertStore::default(),
+            acl: SharedAcl::default(),
+            dead_nonces,
+            forwarding_stats: Arc::new(ForwardingCounters::default()),
         };
         
+        if transport.config.read().await.enable_ping {
+            transport.register_prefix(Name::from_uri(quic::PING_PREFIX)?, Box::new(echo_ping)).await?;
+        }
+        
         Ok(transport)
     }
     
@@ -264,19 +588,93 @@ impl UdcnTransport {
         let mut start_time = self.start_time.write().await;
         *start_time = Instant::now();
         
-        // Initialize QUIC engine and other components here...
+        // Start the QUIC engine serving the registered prefixes
+        if let Err(e) = self.start_quic_engine().await {
+            *self.last_error.write().await = Some(e.to_string());
+            *state = TransportState::Error;
+            return Err(e);
+        }
+        
+        // Cap the MTU at what the configured interface can carry
+        let mtu_interface = self.config.read().await.mtu_interface.clone();
+        if let Some(interface) = mtu_interface {
+            match self.detect_link_mtu(&interface).await {
+                Ok(link_mtu) => log::info!("MTU capped at {} by {}", link_mtu, interface),
+                Err(e) => log::warn!("Failed to detect the MTU of {}: {}", interface, e),
+            }
+        }
         
         // Start ML-based MTU prediction if enabled
-        self.start_ml_prediction().await?;
+        if let Err(e) = self.start_ml_prediction().await {
+            *self.last_error.write().await = Some(e.to_string());
+            *state = TransportState::Error;
+            return Err(e);
+        }
+        
+        // Attach the XDP program if configured, feeding its metrics to the predictor
+        if let Err(e) = self.start_xdp().await {
+            *self.last_error.write().await = Some(e.to_string());
+            *state = TransportState::Error;
+            return Err(e);
+        }
+        
+        // Serve metrics to Prometheus scrapers if enabled
+        if let Err(e) = self.metrics.start().await {
+            let e = Error::IoError(format!("Failed to start metrics server: {}", e));
+            *self.last_error.write().await = Some(e.to_string());
+            *state = TransportState::Error;
+            return Err(e);
+        }
         
         // Start gRPC server if feature is enabled
         #[cfg(feature = "grpc")]
-        self.start_grpc_server().await?;
+        {
+            if let Err(e) = self.start_grpc_server().await {
+                *self.last_error.write().await = Some(e.to_string());
+                *state = TransportState::Error;
+                return Err(e);
+            }
+        }
         
         *state = TransportState::Running;
         Ok(())
     }
     
+    // Report transport and subsystem health. Readiness is false unless the
+    // transport is Running with QUIC up.
+    pub async fn health(&self) -> HealthReport {
+        let state = self.state.read().await.clone();
+        
+        let quic_up = self.quic_engine.read().await.is_some();
+        
+        let grpc_up = self.grpc_server_handle
+            .read()
+            .await
+            .as_ref()
+            .map_or(false, |handle| !handle.is_finished());
+        
+        // The content store must answer and hold no more than it can
+        let cache_ok = match tokio::time::timeout(CACHE_PROBE_TIMEOUT, self.content_store.read()).await {
+            Ok(store) => store.len() <= store.capacity(),
+            Err(_) => false,
+        };
+        
+        let xdp_status = match self.xdp_manager.read().await.as_ref() {
+            Some(manager) => manager.status().await,
+            None => xdp::XdpStatus::NotLoaded,
+        };
+        
+        HealthReport {
+            ready: quic_up && state == TransportState::Running,
+            state,
+            quic_up,
+            grpc_up,
+            xdp_status,
+            cache_ok,
+            last_error: self.last_error.read().await.clone(),
+        }
+    }
+    
     // Stop the transport
     pub async fn stop(&self) -> Result<()> {
         let mut state = self.state.write().await;
@@ -293,7 +691,11 @@ impl UdcnTransport {
         // Stop ML prediction service if running
         self.stop_ml_prediction().await?;
         
-        // Shutdown QUIC engine and other components here...
+        self.stop_xdp().await?;
+        
+        self.metrics.stop().await;
+        
+        self.stop_quic_engine().await?;
         
         *state = TransportState::Stopped;
         Ok(())
@@ -325,9 +727,30 @@ impl UdcnTransport {
         Ok(())
     }
     
-    // Graceful shutdown
+    // Graceful shutdown, draining in-flight Interests for the configured drain_timeout
     pub async fn shutdown(&self) -> Result<()> {
-        // Implement clean shutdown logic here...
+        let drain_timeout = Duration::from_millis(self.config.read().await.drain_timeout);
+        self.shutdown_with_timeout(drain_timeout).await
+    }
+    
+    // Graceful shutdown: stop taking new connections and streams, wait up to
+    // drain_timeout for in-flight Interests to be answered, then close
+    // connections with the shutdown application error code
+    pub async fn shutdown_with_timeout(&self, drain_timeout: Duration) -> Result<()> {
+        {
+            let mut state = self.state.write().await;
+            if *state == TransportState::Stopped {
+                return Ok(());
+            }
+            *state = TransportState::Stopping;
+        }
+        
+        if let Some(engine) = self.quic_engine.read().await.as_ref() {
+            if !engine.drain(drain_timeout).await {
+                log::warn!("Shutting down with Interests still in flight");
+            }
+        }
+        
         self.stop().await
     }
     
@@ -341,50 +764,56 @@ impl UdcnTransport {
         let registration_id = *next_id;
         *next_id += 1;
         
-        self.prefix_table.insert(prefix, (registration_id, handler));
+        self.registrations.insert(registration_id, Registration::Producer(prefix.clone()));
+        if let Some((replaced_id, _)) = self.prefix_table.insert(prefix.clone(), (registration_id, handler)) {
+            self.registrations.remove(&replaced_id);
+        }
+        
+        // Serve the prefix over QUIC too if the engine is already running
+        if let Some(engine) = self.quic_engine.read().await.as_ref() {
+            engine.register_prefix(prefix.clone(), self.engine_handler(prefix)).await?;
+        }
         
         Ok(registration_id)
     }
     
-    // Register a prefix for forwarding
+    // Register a next hop for forwarding Interests under a prefix
+    //
+    // A prefix may have several next hops; registering one it already has
+    // replaces that registration with the new priority.
     pub async fn register_forwarding_prefix(
         &self,
         prefix: Name,
         priority: usize,
+        next_hop: SocketAddr,
     ) -> Result<u64> {
         let mut next_id = self.next_registration_id.write().await;
         let registration_id = *next_id;
         *next_id += 1;
         
-        self.forwarding_table.insert(prefix, (registration_id, priority));
+        self.registrations.insert(registration_id, Registration::Forwarding(prefix.clone()));
+        let mut next_hops = self.forwarding_table.entry(prefix).or_default();
+        if let Some(replaced) = next_hops.iter_mut().find(|(_, _, addr)| *addr == next_hop) {
+            self.registrations.remove(&replaced.0);
+            *replaced = (registration_id, priority, next_hop);
+        } else {
+            next_hops.push((registration_id, priority, next_hop));
+        }
         
         Ok(registration_id)
     }
     
     // Unregister a prefix
     pub async fn unregister_prefix(&self, registration_id: u64) -> Result<()> {
-        // Try to remove from prefix table
-        let mut removed = false;
-        for entry in self.prefix_table.iter() {
-            let (id, _) = entry.value();
-            if *id == registration_id {
-                self.prefix_table.remove(&entry.key().clone());
-                removed = true;
-                break;
-            }
-        }
-        
-        // Try forwarding table if not found in prefix table
-        if !removed {
-            for entry in self.forwarding_table.iter() {
-                let (id, _) = entry.value();
-                if *id == registration_id {
-                    self.forwarding_table.remove(&entry.key().clone());
-                    removed = true;
-                    break;
-                }
-            }
-        }
+        // Look up the table entry through the reverse index; the ID check
+        // leaves a newer registration of the same prefix in place
+        let removed = match self.registrations.remove(&registration_id) {
+            Some((_, Registration::Producer(prefix))) => self.prefix_table
+                .remove_if(&prefix, |_, (id, _)| *id == registration_id)
+                .is_some(),
+            Some((_, Registration::Forwarding(prefix))) => self.remove_next_hop(&prefix, registration_id),
+            None => false,
+        };
         
         if removed {
             Ok(())
@@ -393,22 +822,125 @@ impl UdcnTransport {
         }
     }
     
-    // Update MTU
+    // Remove one next hop of a forwarding prefix, dropping the prefix with
+    // its last next hop
+    fn remove_next_hop(&self, prefix: &Name, registration_id: u64) -> bool {
+        let removed = match self.forwarding_table.get_mut(prefix) {
+            Some(mut next_hops) => {
+                let before = next_hops.len();
+                next_hops.retain(|(id, _, _)| *id != registration_id);
+                next_hops.len() < before
+            }
+            None => false,
+        };
+        self.forwarding_table.remove_if(prefix, |_, next_hops| next_hops.is_empty());
+        removed
+    }
+    
+    // Unregister the producer prefix equal to `prefix`, or failing that the
+    // forwarding prefix with all its next hops, returning the ID of the
+    // removed producer registration or of the first next hop registered
+    pub async fn unregister_prefix_by_name(&self, prefix: &Name) -> Result<u64> {
+        let registration_ids = if let Some((_, (id, _))) = self.prefix_table.remove(prefix) {
+            vec![id]
+        } else if let Some((_, next_hops)) = self.forwarding_table.remove(prefix) {
+            next_hops.into_iter().map(|(id, _, _)| id).collect()
+        } else {
+            return Err(Error::NotFound(format!("Prefix {}", prefix)));
+        };
+        
+        for id in &registration_ids {
+            self.registrations.remove(id);
+        }
+        Ok(registration_ids[0])
+    }
+    
+    // Get the outcomes of Interests forwarded under a forwarding prefix
+    pub fn forwarding_stats(&self, prefix: &Name) -> ForwardingStats {
+        self.forwarding_stats.get(prefix)
+    }
+    
+    // List the next hops of the forwarding prefixes in registration order
+    pub fn forwarding_entries(&self) -> Vec<ForwardingEntry> {
+        let mut entries: Vec<ForwardingEntry> = self.forwarding_table
+            .iter()
+            .flat_map(|entry| {
+                let prefix = entry.key().clone();
+                entry.value().iter().map(move |&(registration_id, priority, next_hop)| ForwardingEntry {
+                    prefix: prefix.clone(),
+                    priority,
+                    next_hop,
+                    registration_id,
+                }).collect::<Vec<_>>()
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.registration_id);
+        entries
+    }
+    
+    // Update MTU, rejecting values outside the configured min_mtu/max_mtu.
+    // A running QUIC engine fragments Data at the new MTU.
     pub async fn update_mtu(&self, mtu: usize) -> Result<()> {
-        if mtu < 576 || mtu > 9000 {
-            return Err(Error::InvalidArgument(
-                format!("Invalid MTU: {}. Must be between 576 and 9000", mtu)
-            ));
+        let mut config = self.config.write().await;
+        if mtu < config.min_mtu || mtu > config.max_mtu {
+            let suggested = mtu.clamp(config.min_mtu, config.max_mtu);
+            return Err(Error::InvalidArgument(format!(
+                "Invalid MTU: {}. Must be between {} and {} (nearest valid value: {})",
+                mtu, config.min_mtu, config.max_mtu, suggested
+            )));
         }
         
+        config.mtu = cap_to_link_mtu(mtu, self.link_mtu.load(Ordering::Relaxed));
+        let mtu = config.mtu;
+        drop(config);
+        
+        set_engine_mtu(&self.quic_engine, mtu).await;
+        Ok(())
+    }
+    
+    // Update MTU, clamping out-of-range values to the configured bounds.
+    // Returns the MTU actually applied.
+    pub async fn set_mtu_clamped(&self, mtu: usize) -> usize {
         let mut config = self.config.write().await;
-        let _old_mtu = config.mtu;
-        config.mtu = mtu;
+        let clamped = cap_to_link_mtu(
+            mtu.clamp(config.min_mtu, config.max_mtu),
+            self.link_mtu.load(Ordering::Relaxed),
+        );
+        if clamped != mtu {
+            log::warn!("MTU {} out of range, clamped to {}", mtu, clamped);
+        }
         
-        // Update QUIC endpoints with new MTU
-        // ...
+        config.mtu = clamped;
+        drop(config);
         
-        Ok(())
+        set_engine_mtu(&self.quic_engine, clamped).await;
+        clamped
+    }
+    
+    // Read the MTU of a network interface and cap the transport MTU at it
+    // minus the QUIC/UDP/IP overhead. Returns the cap.
+    pub async fn detect_link_mtu(&self, interface: &str) -> Result<usize> {
+        let name = interface.to_string();
+        let link_mtu = tokio::task::spawn_blocking(move || interface::interface_mtu(&name))
+            .await
+            .map_err(|e| Error::Other(format!("MTU detection task failed: {}", e)))??;
+        
+        Ok(self.set_link_mtu(link_mtu as usize).await)
+    }
+    
+    // Cap the transport MTU at a link MTU minus the QUIC/UDP/IP overhead,
+    // lowering the current MTU if it is above the cap. Returns the cap.
+    pub async fn set_link_mtu(&self, link_mtu: usize) -> usize {
+        let cap = link_mtu.saturating_sub(LINK_MTU_OVERHEAD);
+        self.link_mtu.store(cap, Ordering::Relaxed);
+        
+        let mut config = self.config.write().await;
+        config.mtu = cap_to_link_mtu(config.mtu, cap);
+        let mtu = config.mtu;
+        drop(config);
+        
+        set_engine_mtu(&self.quic_engine, mtu).await;
+        cap
     }
     
     // Start ML-based MTU prediction
@@ -424,19 +956,18 @@ impl UdcnTransport {
             // Create a closure that will update the MTU when the prediction service
             // determines a new optimal value
             let transport_config = self.config.clone();
+            let link_mtu = self.link_mtu.clone();
+            let quic_engine = self.quic_engine.clone();
             let update_callback = move |predicted_mtu: usize| {
                 let mut config = match transport_config.try_write() {
                     Ok(guard) => guard,
                     Err(_) => return Err(Error::LockError("Failed to acquire config lock".to_string())),
                 };
                 
-                // Only update if the prediction is significantly different
-                if (predicted_mtu as i64 - config.mtu as i64).abs() > 100 {
-                    log::info!("ML model suggests MTU change: {} -> {}", config.mtu, predicted_mtu);
-                    config.mtu = predicted_mtu;
-                    // The actual QUIC engine update would happen in a separate method
+                if let Some(mtu) = apply_mtu_prediction(&mut config, predicted_mtu, link_mtu.load(Ordering::Relaxed)) {
+                    let quic_engine = quic_engine.clone();
+                    tokio::spawn(async move { set_engine_mtu(&quic_engine, mtu).await });
                 }
-                
                 Ok(())
             };
             
@@ -459,11 +990,46 @@ impl UdcnTransport {
         Ok(())
     }
     
-    // Update ML prediction features with connection statistics
-    pub async fn update_ml_features(&self, connection_stats: &quic::ConnectionStats) -> Result<()> {
+    // Update ML prediction features with a connection's statistics and
+    // congestion window
+    pub async fn update_ml_features(&self, connection: &quic::ConnectionTracker) -> Result<()> {
         let ml_service = self.ml_prediction.read().await;
         if let Some(service) = ml_service.as_ref() {
-            service.update_features_from_stats(connection_stats).await?;
+            let stats = connection.stats().await;
+            service.update_features_from_stats(&stats, connection.congestion_window().await).await?;
+        }
+        
+        Ok(())
+    }
+    
+    // Load the configured XDP program. When ML prediction is running, the
+    // predictor follows the data-plane metrics instead of only QUIC stats.
+    async fn start_xdp(&self) -> Result<()> {
+        let mut xdp_manager = self.xdp_manager.write().await;
+        if xdp_manager.is_some() {
+            return Ok(());
+        }
+        
+        let xdp_config = match self.config.read().await.xdp_config.clone() {
+            Some(xdp_config) => xdp_config,
+            None => return Ok(()),
+        };
+        
+        let manager = XdpManager::new(xdp_config);
+        manager.load().await?;
+        
+        if let Some(service) = self.ml_prediction.read().await.as_ref() {
+            service.follow_xdp_metrics(manager.metrics_stream()).await;
+        }
+        
+        *xdp_manager = Some(manager);
+        Ok(())
+    }
+    
+    // Detach the XDP program if it is loaded
+    async fn stop_xdp(&self) -> Result<()> {
+        if let Some(manager) = self.xdp_manager.write().await.take() {
+            manager.unload().await?;
         }
         
         Ok(())
@@ -478,24 +1044,249 @@ impl UdcnTransport {
         config.mtu
     }
     
-    // Send an interest and get data
+    // Send an interest and get data, retrying timeouts and Congestion NACKs
+    // as configured by `retries` and `retry_interval`
     pub async fn send_interest(&self, interest: Interest) -> Result<Data> {
-        // Check if we have a prefix registered that matches this interest
-        for entry in self.prefix_table.iter() {
-            let prefix = entry.key();
-            let (_, handler) = entry.value();
+        let policy = RetryPolicy::from_config(&*self.config.read().await);
+        
+        // Retries carry a fresh nonce, so they are not taken for a loop
+        let mut attempts = 0;
+        retry_interest(&policy, || {
+            attempts += 1;
+            let interest = match attempts {
+                1 => interest.clone(),
+                _ => interest.clone().with_nonce(rand::random()),
+            };
+            self.send_interest_once(interest)
+        }).await
+    }
+    
+    // Send several Interests with up to `max_concurrency` of them in flight,
+    // returning their results in input order. Interests forwarded to the same
+    // next hop share its pooled connection.
+    pub async fn send_interests(&self, interests: Vec<Interest>, max_concurrency: usize) -> Vec<Result<Data>> {
+        stream::iter(interests)
+            .map(|interest| self.send_interest(interest))
+            .buffered(max_concurrency.max(1))
+            .collect()
+            .await
+    }
+    
+    // Send an interest once and get data
+    async fn send_interest_once(&self, interest: Interest) -> Result<Data> {
+        // Answer from the content store when it holds matching Data
+        let cached = self.content_store.read().await.get_for_interest(&interest);
+        if let Some(data) = cached {
+            self.metrics.increment_counter("cache_hits", 1).await;
+            return Ok(data);
+        }
+        self.metrics.increment_counter("cache_misses", 1).await;
+        let start_time = Instant::now();
+        
+        // Serve from the handler of the longest registered prefix matching
+        // the Interest, so overlapping registrations resolve deterministically
+        let served = self.prefix_table
+            .iter()
+            .filter(|entry| interest.name().starts_with(entry.key()))
+            .max_by_key(|entry| entry.key().len())
+            .map(|entry| (entry.key().to_string(), (entry.value().1)(interest.clone())));
+        
+        // A trailing implicit digest names exactly one Data, so what the
+        // producer or next hop returns must hash to it
+        if let Some((prefix, result)) = served {
+            self.record_prefix(&prefix, &result, start_time).await;
+            let data = result?;
+            data.verify_implicit_digest(interest.name())?;
+            self.content_store.write().await.insert(data.clone());
+            return Ok(data);
+        }
+        
+        // Forward via QUIC to the next hop of the longest matching prefix,
+        // falling back to the forwarding hint when the name has no route
+        let next_hop = self.next_hop(interest.name())
+            .or_else(|| interest.forwarding_hint().and_then(|hint| self.next_hop(hint)));
+        if let Some((prefix, next_hop)) = next_hop {
+            // An Interest forwarded within the window has looped back
+            self.dead_nonces.check(&interest).map_err(|nack| Error::nack(&nack))?;
+            
+            let engine = self.quic_engine.read().await;
+            let engine = engine.as_ref()
+                .ok_or_else(|| Error::InvalidState("Transport is not started".to_string()))?;
             
-            // Temporary fix: we'd normally use interest.matches(prefix)
-            // For now, let's use a simple prefix check to avoid compilation errors
-            if prefix.has_prefix(interest.name()) {
-                return handler(interest);
+            // Whatever is still outstanding when the lifetime ends has timed out
+            let requested = interest.name().clone();
+            let deadline = Some(Instant::now() + interest.get_lifetime());
+            self.forwarding_stats.record_forwarded(&prefix);
+            #[cfg(feature = "otel")]
+            let result = {
+                use tracing::Instrument;
+                let (interest, span) = otel::forward_span(interest);
+                engine.send_interest_with_deadline(next_hop, interest, deadline).instrument(span).await
+            };
+            #[cfg(not(feature = "otel"))]
+            let result = engine.send_interest_with_deadline(next_hop, interest, deadline).await;
+            self.forwarding_stats.record_result(&prefix, &result);
+            self.record_prefix(&prefix.to_string(), &result, start_time).await;
+            let data = result?;
+            data.verify_implicit_digest(&requested)?;
+            self.content_store.write().await.insert(data.clone());
+            return Ok(data);
+        }
+        
+        Err(Error::NoMatchingPrefix(interest.name().clone()))
+    }
+    
+    // Find the longest forwarding prefix matching a name and its
+    // highest-priority next hop, the earliest registered on a tie
+    fn next_hop(&self, name: &Name) -> Option<(Name, SocketAddr)> {
+        let entry = self.forwarding_table
+            .iter()
+            .filter(|entry| name.starts_with(entry.key()))
+            .max_by_key(|entry| entry.key().len())?;
+        entry.value()
+            .iter()
+            .min_by_key(|(id, priority, _)| (std::cmp::Reverse(*priority), *id))
+            .map(|&(_, _, next_hop)| (entry.key().clone(), next_hop))
+    }
+    
+    // Count an Interest dispatched to a registered prefix, recording its RTT
+    // when Data came back and counting the NACK when one did
+    async fn record_prefix(&self, prefix: &str, result: &Result<Data>, start_time: Instant) {
+        self.metrics.record_prefix_interest(prefix).await;
+        match result {
+            Ok(_) => {
+                let rtt_ms = start_time.elapsed().as_secs_f64() * 1000.0;
+                self.metrics.record_prefix_rtt(prefix, rtt_ms).await;
+            }
+            Err(e) if e.kind() == ErrorKind::Nack => {
+                self.metrics.record_prefix_nack(prefix).await;
             }
+            Err(_) => {}
+        }
+    }
+    
+    // Get the content store
+    pub fn content_store(&self) -> &SharedContentStore {
+        &self.content_store
+    }
+    
+    // Get the QUIC engine, present while the transport is started
+    pub fn quic_engine(&self) -> &Arc<RwLock<Option<QuicEngine>>> {
+        &self.quic_engine
+    }
+    
+    // Get the address the QUIC engine is bound to
+    pub async fn local_addr(&self) -> Result<SocketAddr> {
+        match self.quic_engine.read().await.as_ref() {
+            Some(engine) => engine.local_addr().await,
+            None => Err(Error::InvalidState("Transport is not started".to_string())),
+        }
+    }
+    
+    // Show every frame the QUIC engine sends or receives to a tracer,
+    // replacing any tracer set before; it applies whether or not the
+    // transport is started
+    pub fn set_tracer(&self, tracer: Box<dyn Tracer>) {
+        self.trace.set(Some(tracer));
+    }
+    
+    // Stop tracing frames
+    pub fn clear_tracer(&self) {
+        self.trace.set(None);
+    }
+    
+    // Get the store of keys trusted to sign commands; keys added to it are
+    // trusted straight away, even while the transport runs
+    pub fn cert_store(&self) -> &SharedCertStore {
+        &self.cert_store
+    }
+    
+    // Measure the round-trip time to a peer that answers pings, checking the
+    // Data echoes the timestamp of the ping
+    pub async fn ping(&self, remote: SocketAddr) -> Result<Duration> {
+        let engine = self.quic_engine.read().await;
+        let engine = engine.as_ref()
+            .ok_or_else(|| Error::InvalidState("Transport is not started".to_string()))?;
+        
+        // A fresh timestamp names each ping, so no cache can answer it
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros())
+            .unwrap_or_default()
+            .to_string();
+        let mut name = Name::from_uri(quic::PING_PREFIX)?;
+        name.push_str(&timestamp);
+        
+        let start_time = Instant::now();
+        let data = engine.send_interest(remote, Interest::new(name).must_be_fresh(true)).await?;
+        let rtt = start_time.elapsed();
+        
+        if data.content().as_ref() != timestamp.as_bytes() {
+            return Err(Error::ProtocolError(format!("Ping response from {} does not echo its timestamp", remote)));
+        }
+        Ok(rtt)
+    }
+    
+    // Restrict which peers may request Data under each prefix, replacing the
+    // policy set before; Interests refused by it are answered with a NotAuth
+    // NACK. It applies whether or not the transport is started
+    pub fn set_acl(&self, policy: AclPolicy) {
+        *self.acl.write() = policy;
+    }
+    
+    // Create and start the QUIC engine, registering every prefix served so far
+    async fn start_quic_engine(&self) -> Result<()> {
+        let mut quic_engine = self.quic_engine.write().await;
+        if quic_engine.is_some() {
+            return Ok(());
         }
         
-        // Forward via QUIC to another node (simplified for now)
-        // ...
+        let config = self.config.read().await.clone();
+        let mut engine = QuicEngine::new(&config).await?;
+        engine.set_metrics(self.metrics.clone());
+        engine.set_trace_hook(self.trace.clone());
+        engine.set_cert_store(self.cert_store.clone());
+        engine.set_acl(self.acl.clone());
+        engine.start().await?;
         
-        Err(Error::NotFound("No matching prefix".to_string()))
+        let prefixes: Vec<Name> = self.prefix_table.iter().map(|entry| entry.key().clone()).collect();
+        for prefix in prefixes {
+            engine.register_prefix(prefix.clone(), self.engine_handler(prefix)).await?;
+        }
+        
+        *quic_engine = Some(engine);
+        Ok(())
+    }
+    
+    // Stop the QUIC engine if it is running
+    async fn stop_quic_engine(&self) -> Result<()> {
+        if let Some(mut engine) = self.quic_engine.write().await.take() {
+            engine.stop().await?;
+        }
+        
+        Ok(())
+    }
+    
+    // Handler the QUIC engine runs for a prefix. It dispatches to whatever is
+    // registered in the prefix table when the Interest arrives, so replacing
+    // or unregistering the prefix takes effect without touching the engine.
+    fn engine_handler(&self, prefix: Name) -> PrefixHandler {
+        let prefix_table = self.prefix_table.clone();
+        Box::new(move |interest: Interest| match prefix_table.get(&prefix) {
+            Some(entry) => (entry.value().1)(interest),
+            None => Err(Error::NoMatchingPrefix(interest.name().clone())),
+        })
+    }
+    
+    // Push unsolicited Data to the next hop of the longest forwarding prefix
+    // matching its name, which caches it to answer later Interests
+    pub async fn push_data(&self, data: Data) -> Result<()> {
+        let (_, next_hop) = self.next_hop(data.name())
+            .ok_or_else(|| Error::NoMatchingPrefix(data.name().clone()))?;
+        
+        let engine = self.quic_engine.read().await;
+        let engine = engine.as_ref()
+            .ok_or_else(|| Error::InvalidState("Transport is not started".to_string()))?;
+        engine.push_data(next_hop, data).await
     }
     
     // Get metrics
@@ -503,11 +1294,17 @@ impl UdcnTransport {
         self.metrics.get_all_metrics().await
     }
     
+    // Get the address the Prometheus endpoint listens on, if it is serving
+    pub async fn metrics_addr(&self) -> Option<SocketAddr> {
+        self.metrics.local_addr().await
+    }
+    
     // Get network interfaces
-    pub async fn get_network_interfaces(&self, _include_stats: bool) -> Result<Vec<String>> {
-        // Placeholder implementation instead of interface::get_network_interfaces
-        // Replace with actual implementation when available
-        Ok(vec!["eth0".to_string(), "lo".to_string()])
+    // List the host's network interfaces, with traffic counters if requested
+    pub async fn get_network_interfaces(&self, include_stats: bool) -> Result<Vec<interface::InterfaceInfo>> {
+        tokio::task::spawn_blocking(move || interface::list_interfaces(include_stats))
+            .await
+            .map_err(|e| Error::Other(format!("Interface listing task failed: {}", e)))?
     }
     
     // Get current state
@@ -520,6 +1317,8 @@ impl UdcnTransport {
     pub fn new_mock() -> Self {
         let metrics = Arc::new(MetricsCollector::new(0, false));
         let config = Config::default();
+        let content_store: Box<dyn ContentStoreBackend> = Box::new(cache::ContentStore::from_config(&config));
+        let dead_nonces = Arc::new(DeadNonceList::new(Duration::from_millis(config.dead_nonce_window)));
         
         Self {
             config: Arc::new(RwLock::new(config)),
@@ -529,8 +1328,19 @@ impl UdcnTransport {
             prefix_table: Arc::new(DashMap::new()),
             forwarding_table: Arc::new(DashMap::new()),
             next_registration_id: Arc::new(RwLock::new(1)),
+            registrations: Arc::new(DashMap::new()),
+            last_error: Arc::new(RwLock::new(None)),
             grpc_server_handle: Arc::new(RwLock::new(None)),
             ml_prediction: Arc::new(RwLock::new(None)),
+            content_store: Arc::new(RwLock::new(content_store)),
+            quic_engine: Arc::new(RwLock::new(None)),
+            xdp_manager: Arc::new(RwLock::new(None)),
+            link_mtu: Arc::new(AtomicUsize::new(0)),
+            trace: TraceHook::new(),
+            cert_store: SharedCertStore::default(),
+            acl: SharedAcl::default(),
+            dead_nonces,
+            forwarding_stats: Arc::new(ForwardingCounters::default()),
         }
     }
     
@@ -572,6 +1382,13 @@ impl UdcnTransport {
             0.0
         };
         
+        // Interest RTT percentiles, 0 until an Interest has been satisfied
+        let rtt_buckets = match self.metrics.get_metric(INTEREST_RTT_METRIC).await {
+            Some(crate::metrics::MetricValue::Histogram(buckets)) => buckets,
+            _ => Vec::new(),
+        };
+        let rtt_percentile = |quantile| histogram_quantile(&rtt_buckets, quantile).unwrap_or(0.0);
+        
         TransportStatistics {
             uptime_seconds: uptime.as_secs(),
             interests_processed: match self.metrics.get_metric("interests_processed").await {
@@ -585,6 +1402,9 @@ impl UdcnTransport {
             cache_hits,
             cache_misses,
             cache_hit_ratio,
+            rtt_p50_ms: rtt_percentile(0.5),
+            rtt_p95_ms: rtt_percentile(0.95),
+            rtt_p99_ms: rtt_percentile(0.99),
         }
     }
     
@@ -598,6 +1418,15 @@ impl UdcnTransport {
         stats.insert("interests_processed".to_string(), basic_stats.interests_processed.to_string());
         stats.insert("data_packets_sent".to_string(), basic_stats.data_packets_sent.to_string());
         stats.insert("cache_hit_ratio".to_string(), format!("{:.2}", basic_stats.cache_hit_ratio));
+        stats.insert("rtt_p50_ms".to_string(), format!("{:.2}", basic_stats.rtt_p50_ms));
+        stats.insert("rtt_p95_ms".to_string(), format!("{:.2}", basic_stats.rtt_p95_ms));
+        stats.insert("rtt_p99_ms".to_string(), format!("{:.2}", basic_stats.rtt_p99_ms));
+        
+        // Add partial reassemblies dropped as stale
+        if let Some(engine) = self.quic_engine.read().await.as_ref() {
+            stats.insert("reassembly_timeouts".to_string(), engine.reassembly_timeouts().to_string());
+            stats.insert("rate_limited".to_string(), engine.rate_limiter().rate_limited().to_string());
+        }
         
         // Add current state
         let state = self.state.read().await;
@@ -607,6 +1436,18 @@ impl UdcnTransport {
         stats.insert("registered_prefixes".to_string(), self.prefix_table.len().to_string());
         stats.insert("forwarding_prefixes".to_string(), self.forwarding_table.len().to_string());
         
+        // Add the traffic of the busiest prefixes
+        let top_prefixes = self.metrics.top_prefixes(TOP_PREFIXES).await;
+        let ranking: Vec<String> = top_prefixes.iter()
+            .map(|(prefix, metrics)| format!("{}={}", prefix, metrics.interests))
+            .collect();
+        stats.insert("top_prefixes".to_string(), ranking.join(","));
+        for (prefix, metrics) in &top_prefixes {
+            stats.insert(format!("prefix_interests:{}", prefix), metrics.interests.to_string());
+            stats.insert(format!("prefix_nacks:{}", prefix), metrics.nacks.to_string());
+            stats.insert(format!("prefix_rtt_avg_ms:{}", prefix), format!("{:.2}", metrics.avg_rtt_ms()));
+        }
+        
         // Add metrics
         let metrics = self.metrics.get_all_metrics().await;
         for (key, value) in metrics {
@@ -626,15 +1467,8 @@ impl UdcnTransport {
             return Ok(());
         }
         
-        // Parse bind address for gRPC from config
-        let config = self.config.read().await;
-        let grpc_address = format!("{}:{}", 
-            config.bind_address.split(':').next().unwrap_or("127.0.0.1"),
-            config.metrics_port + 1 // Use metrics_port + 1 for gRPC
-        );
-        
-        let addr: SocketAddr = grpc_address.parse()
-            .map_err(|e| Error::InvalidArgument(format!("Invalid gRPC address: {}", e)))?;
+        // Serve gRPC on the bind host, next to the metrics port
+        let addr = self.config.read().await.grpc_addr()?;
         
         // Create Arc reference to self for the server
         let transport = Arc::new(self.clone());
@@ -663,6 +1497,48 @@ impl UdcnTransport {
     }
 }
 
+// Cap an MTU at the link MTU cap, if one has been detected
+fn cap_to_link_mtu(mtu: usize, link_mtu: usize) -> usize {
+    if link_mtu > 0 && mtu > link_mtu {
+        log::warn!("MTU {} exceeds the link, capped at {}", mtu, link_mtu);
+        link_mtu
+    } else {
+        mtu
+    }
+}
+
+// Answer a ping with Data echoing the timestamp ending its name. Its zero
+// FreshnessPeriod makes it stale on arrival, so a MustBeFresh ping is never
+// answered from a cache
+fn echo_ping(interest: Interest) -> Result<Data> {
+    let timestamp = interest.name().components().last()
+        .map(|component| component.value().clone())
+        .unwrap_or_default();
+    Ok(Data::new(interest.name().clone(), timestamp).fresh_period(Duration::ZERO))
+}
+
+// Apply an ML MTU prediction capped at the link MTU, ignoring small changes.
+// Returns the new MTU if it changed.
+fn apply_mtu_prediction(config: &mut Config, predicted_mtu: usize, link_mtu: usize) -> Option<usize> {
+    let predicted_mtu = cap_to_link_mtu(predicted_mtu, link_mtu);
+    
+    // Only update if the prediction is significantly different
+    if (predicted_mtu as i64 - config.mtu as i64).abs() > 100 {
+        log::info!("ML model suggests MTU change: {} -> {}", config.mtu, predicted_mtu);
+        config.mtu = predicted_mtu;
+        return Some(predicted_mtu);
+    }
+    None
+}
+
+// Fragment Data at a new MTU on the running QUIC engine, if any; a stopped
+// transport picks the MTU up from its configuration when it starts
+async fn set_engine_mtu(quic_engine: &RwLock<Option<QuicEngine>>, mtu: usize) {
+    if let Some(engine) = quic_engine.read().await.as_ref() {
+        engine.set_mtu(mtu).await;
+    }
+}
+
 // Clone implementation for UdcnTransport
 impl Clone for UdcnTransport {
     fn clone(&self) -> Self {
@@ -674,12 +1550,38 @@ impl Clone for UdcnTransport {
             prefix_table: self.prefix_table.clone(),
             forwarding_table: self.forwarding_table.clone(),
             next_registration_id: self.next_registration_id.clone(),
+            registrations: self.registrations.clone(),
+            last_error: self.last_error.clone(),
             grpc_server_handle: self.grpc_server_handle.clone(),
             ml_prediction: self.ml_prediction.clone(),
+            content_store: self.content_store.clone(),
+            quic_engine: self.quic_engine.clone(),
+            xdp_manager: self.xdp_manager.clone(),
+            link_mtu: self.link_mtu.clone(),
+            trace: self.trace.clone(),
+            cert_store: self.cert_store.clone(),
+            acl: self.acl.clone(),
+            dead_nonces: self.dead_nonces.clone(),
+            forwarding_stats: self.forwarding_stats.clone(),
         }
     }
 }
 
+#[async_trait::async_trait]
+impl NdnFace for UdcnTransport {
+    async fn send_interest(&self, interest: Interest) -> Result<Data> {
+        UdcnTransport::send_interest(self, interest).await
+    }
+    
+    async fn register_prefix(&self, prefix: Name, handler: PrefixHandler) -> Result<u64> {
+        UdcnTransport::register_prefix(self, prefix, handler).await
+    }
+    
+    async fn push_data(&self, data: Data) -> Result<()> {
+        UdcnTransport::push_data(self, data).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -691,10 +1593,14 @@ mod tests {
             port: 6363,
             mtu: 1400,
             cache_capacity: 1000,
+            cache_capacity_bytes: None,
+            cache_backend: CacheBackend::Memory,
             idle_timeout: 30,
             enable_metrics: false,
             metrics_port: 0,
             max_packet_size: 65535,
+            fragment_oversize_data: true,
+            wire_format: WireFormat::Ndn,
             log_level: "info".to_string(),
             retries: 3,
             retry_interval: 1000,
@@ -702,12 +1608,1055 @@ mod tests {
             enable_ml_mtu_prediction: false,
             ml_prediction_interval: 30,
             ml_model_type: "rule-based".to_string(),
+            ml_model_path: None,
+            ml_state_path: None,
             min_mtu: 576,
             max_mtu: 9000,
+            mtu_interface: None,
+            stream_receive_window: 1_250_000,
+            receive_window: 8_000_000,
+            send_window: 8_000_000,
+            integrity_check: false,
+            cache_tiers: None,
+            admission: AdmissionConfig::default(),
+            max_interests_per_sec: None,
+            interest_burst: None,
+            health_check_interval: 30000,
+            max_missed_pings: 3,
+            breaker_failure_threshold: 5,
+            breaker_cooldown: 10000,
+            enable_ping: false,
+            command_prefix: "/localhost/nfd".to_string(),
+            require_signed_commands: false,
+            command_grace_period: 60_000,
+            send_timeout: 5000,
+            recv_timeout: 30000,
+            max_interest_lifetime: 30000,
+            drain_timeout: 5000,
+            reassembly_timeout: 30000,
+            reassembly_sweep_interval: 5000,
+            max_reassembly_bytes: 64 * 1024 * 1024,
+            dead_nonce_window: 6000,
+            tls: TlsConfig::Insecure,
+            tls_server_name: "localhost".to_string(),
+            tls_cert_path: None,
+            tls_key_path: None,
         };
         
         let transport = UdcnTransport::new(config).await;
         assert!(transport.is_ok());
     }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread", worker_threads = 4))]
+    async fn test_concurrent_register_unregister() {
+        let transport = Arc::new(UdcnTransport::new(Config::default()).await.unwrap());
+        
+        let tasks: Vec<_> = (0..8).map(|task| {
+            let transport = transport.clone();
+            tokio::spawn(async move {
+                for i in 0..200 {
+                    // Tasks share prefixes so registrations replace each other
+                    let prefix = Name::from_uri(&format!("/concurrent/{}", i % 50)).unwrap();
+                    let id = if task % 2 == 0 {
+                        transport.register_prefix(prefix, Box::new(|interest: Interest| {
+                            Ok(Data::new(interest.name().clone(), "data"))
+                        })).await.unwrap()
+                    } else {
+                        let next_hop = "127.0.0.1:6363".parse().unwrap();
+                        transport.register_forwarding_prefix(prefix, i, next_hop).await.unwrap()
+                    };
+                    
+                    // A replaced registration may already be gone
+                    let _ = transport.unregister_prefix(id).await;
+                }
+            })
+        }).collect();
+        
+        let all = futures::future::join_all(tasks);
+        let results = tokio::time::timeout(Duration::from_secs(10), all).await
+            .expect("register/unregister deadlocked");
+        assert!(results.into_iter().all(|r| r.is_ok()));
+        
+        // Every registration was removed exactly once
+        assert!(transport.prefix_table.is_empty());
+        assert!(transport.forwarding_table.is_empty());
+        assert!(transport.registrations.is_empty());
+        assert!(matches!(transport.unregister_prefix(1).await, Err(Error::NotFound(_))));
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_unregister_prefix_by_name() {
+        let transport = UdcnTransport::new(Config::default()).await.unwrap();
+        let handler = || -> PrefixHandler {
+            Box::new(|interest: Interest| Ok(Data::new(interest.name().clone(), "data")))
+        };
+        let next_hop: SocketAddr = "127.0.0.1:6363".parse().unwrap();
+        let a = Name::from_uri("/a").unwrap();
+        let b = Name::from_uri("/b").unwrap();
+        let remote = Name::from_uri("/remote").unwrap();
+        
+        let a_id = transport.register_prefix(a.clone(), handler()).await.unwrap();
+        let b_id = transport.register_prefix(b.clone(), handler()).await.unwrap();
+        let remote_id = transport.register_forwarding_prefix(remote.clone(), 1, next_hop).await.unwrap();
+        
+        // By name, the freed ID is returned and its index entry dropped
+        assert_eq!(transport.unregister_prefix_by_name(&a).await.unwrap(), a_id);
+        assert!(matches!(transport.unregister_prefix(a_id).await, Err(Error::NotFound(_))));
+        assert!(matches!(transport.unregister_prefix_by_name(&a).await, Err(Error::NotFound(_))));
+        assert_eq!(transport.unregister_prefix_by_name(&remote).await.unwrap(), remote_id);
+        
+        // Only exact matches are removed
+        assert!(matches!(
+            transport.unregister_prefix_by_name(&Name::from_uri("/b/c").unwrap()).await,
+            Err(Error::NotFound(_))
+        ));
+        
+        // By ID, the name is gone too
+        transport.unregister_prefix(b_id).await.unwrap();
+        assert!(matches!(transport.unregister_prefix(b_id).await, Err(Error::NotFound(_))));
+        assert!(matches!(transport.unregister_prefix_by_name(&b).await, Err(Error::NotFound(_))));
+        
+        assert!(transport.prefix_table.is_empty());
+        assert!(transport.forwarding_table.is_empty());
+        assert!(transport.registrations.is_empty());
+    }
+    
+    #[test]
+    fn test_parse_bind_addr() {
+        let v6: SocketAddr = "[::1]:6363".parse().unwrap();
+        assert_eq!(parse_bind_addr("::1", 6363).unwrap(), v6);
+        assert_eq!(parse_bind_addr("[::1]", 6363).unwrap(), v6);
+        assert_eq!(parse_bind_addr("[::1]:6363", 9).unwrap(), v6);
+        assert_eq!(parse_bind_addr("127.0.0.1", 6363).unwrap(), "127.0.0.1:6363".parse().unwrap());
+        assert_eq!(parse_bind_addr("127.0.0.1:0", 6363).unwrap(), "127.0.0.1:0".parse().unwrap());
+        assert!(matches!(parse_bind_addr("not-an-ip", 6363), Err(Error::InvalidAddress(_))));
+        
+        // Metrics and gRPC listen on the bind host, on IPv6 hosts too
+        let config = Config { bind_address: "::1".to_string(), metrics_port: 9090, ..Default::default() };
+        assert_eq!(config.metrics_addr().unwrap(), "[::1]:9090".parse().unwrap());
+        assert_eq!(config.grpc_addr().unwrap(), "[::1]:9091".parse().unwrap());
+        assert_eq!(config.bind_addr().unwrap(), "[::1]:6363".parse().unwrap());
+        let config = Config { bind_address: "[fd00::2]:7000".to_string(), ..Default::default() };
+        assert_eq!(config.metrics_addr().unwrap(), "[fd00::2]:9090".parse().unwrap());
+        assert_eq!(config.grpc_addr().unwrap(), "[fd00::2]:9091".parse().unwrap());
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_bind_ipv6_loopback() {
+        let transport = UdcnTransport::new(Config {
+            bind_address: "::1".to_string(),
+            ..local_config()
+        }).await.unwrap();
+        transport.start().await.unwrap();
+        
+        let addr = transport.local_addr().await.unwrap();
+        assert!(addr.is_ipv6() && addr.ip().is_loopback(), "bound to {}", addr);
+        
+        transport.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_health_after_start() {
+        let transport = UdcnTransport::new(local_config()).await.unwrap();
+        
+        let health = transport.health().await;
+        assert_eq!(health.state, TransportState::Stopped);
+        assert!(!health.ready);
+        assert!(!health.quic_up);
+        
+        transport.start().await.unwrap();
+        
+        let health = transport.health().await;
+        assert_eq!(health.state, TransportState::Running);
+        assert!(health.quic_up);
+        assert!(health.ready);
+        assert!(health.cache_ok);
+        assert_eq!(health.last_error, None);
+        
+        // A content store stuck behind its lock is unhealthy
+        {
+            let _store = transport.content_store().write().await;
+            assert!(!transport.health().await.cache_ok);
+        }
+        assert!(transport.health().await.cache_ok);
+        
+        // Not ready while stopping
+        *transport.state.write().await = TransportState::Stopping;
+        assert!(!transport.health().await.ready);
+    }
+    
+    // Config binding the QUIC engine to an ephemeral loopback port
+    fn local_config() -> Config {
+        Config {
+            bind_address: "127.0.0.1".to_string(),
+            port: 0,
+            enable_metrics: false,
+            ..Default::default()
+        }
+    }
+    
+    fn bounded_mtu_config() -> Config {
+        Config {
+            mtu: 1200,
+            min_mtu: 1000,
+            max_mtu: 1500,
+            ..Default::default()
+        }
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_update_mtu_below_min() {
+        let transport = UdcnTransport::new(bounded_mtu_config()).await.unwrap();
+        
+        match transport.update_mtu(800).await {
+            Err(Error::InvalidArgument(msg)) => {
+                assert!(msg.contains("between 1000 and 1500"), "{}", msg);
+                assert!(msg.contains("nearest valid value: 1000"), "{}", msg);
+            }
+            other => panic!("Expected an invalid MTU error, got {:?}", other),
+        }
+        assert_eq!(transport.mtu(), 1200);
+        
+        assert_eq!(transport.set_mtu_clamped(800).await, 1000);
+        assert_eq!(transport.mtu(), 1000);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_update_mtu_above_max() {
+        let transport = UdcnTransport::new(bounded_mtu_config()).await.unwrap();
+        
+        // Within the old hardcoded range but above the configured maximum
+        match transport.update_mtu(9000).await {
+            Err(Error::InvalidArgument(msg)) => {
+                assert!(msg.contains("nearest valid value: 1500"), "{}", msg);
+            }
+            other => panic!("Expected an invalid MTU error, got {:?}", other),
+        }
+        assert_eq!(transport.mtu(), 1200);
+        
+        assert_eq!(transport.set_mtu_clamped(9000).await, 1500);
+        assert_eq!(transport.mtu(), 1500);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_mtu_capped_at_link_mtu() {
+        let transport = UdcnTransport::new(bounded_mtu_config()).await.unwrap();
+        
+        // A 1300-byte link leaves room for 1211-byte packets
+        let cap = transport.set_link_mtu(1300).await;
+        assert_eq!(cap, 1300 - LINK_MTU_OVERHEAD);
+        assert_eq!(transport.mtu(), 1200);
+        
+        // In range for the configuration, but too large for the link
+        transport.update_mtu(1450).await.unwrap();
+        assert_eq!(transport.mtu(), cap);
+        assert_eq!(transport.set_mtu_clamped(9000).await, cap);
+        
+        // So is an over-large ML prediction
+        let mut config = bounded_mtu_config();
+        assert_eq!(apply_mtu_prediction(&mut config, 9000, cap), None);
+        assert_eq!(config.mtu, 1200);
+        assert_eq!(apply_mtu_prediction(&mut config, 9000, 1500 - LINK_MTU_OVERHEAD), Some(1500 - LINK_MTU_OVERHEAD));
+        assert_eq!(config.mtu, 1500 - LINK_MTU_OVERHEAD);
+        
+        // A lower link MTU lowers the current MTU with it
+        transport.set_link_mtu(1200).await;
+        assert_eq!(transport.mtu(), 1200 - LINK_MTU_OVERHEAD);
+        
+        assert!(matches!(transport.detect_link_mtu("udcn-missing0").await, Err(Error::NotFound(_))));
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_update_mtu_in_range() {
+        let transport = UdcnTransport::new(bounded_mtu_config()).await.unwrap();
+        
+        transport.update_mtu(1400).await.unwrap();
+        assert_eq!(transport.mtu(), 1400);
+        
+        assert_eq!(transport.set_mtu_clamped(1100).await, 1100);
+        assert_eq!(transport.mtu(), 1100);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_update_mtu_reaches_engine() {
+        let transport = UdcnTransport::new(Config {
+            mtu: 1200,
+            min_mtu: 1000,
+            max_mtu: 1500,
+            ..local_config()
+        }).await.unwrap();
+        transport.start().await.unwrap();
+        
+        let engine_mtu = || async { transport.quic_engine.read().await.as_ref().unwrap().mtu().await };
+        assert_eq!(engine_mtu().await, 1200);
+        
+        transport.update_mtu(1400).await.unwrap();
+        assert_eq!(engine_mtu().await, 1400);
+        
+        assert_eq!(transport.set_mtu_clamped(900).await, 1000);
+        assert_eq!(engine_mtu().await, 1000);
+        
+        transport.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_send_interest_served_from_cache() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        
+        let transport = UdcnTransport::new(Config::default()).await.unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = calls.clone();
+        transport.register_prefix(Name::from_uri("/cached").unwrap(), Box::new(move |interest: Interest| {
+            handler_calls.fetch_add(1, Ordering::SeqCst);
+            let fresh_period = if interest.name().to_string().ends_with("stale") { 0 } else { 60 };
+            Ok(Data::new(interest.name().clone(), "payload").fresh_period(Duration::from_secs(fresh_period)))
+        })).await.unwrap();
+        
+        // The second Interest is answered from the content store
+        let name = Name::from_uri("/cached/object").unwrap();
+        transport.send_interest(Interest::new(name.clone())).await.unwrap();
+        let data = transport.send_interest(Interest::new(name.clone())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"payload");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        
+        let stats = transport.get_statistics().await;
+        assert_eq!((stats.cache_hits, stats.cache_misses), (1, 1));
+        
+        // Stale Data only satisfies Interests without MustBeFresh
+        let stale = Name::from_uri("/cached/stale").unwrap();
+        transport.send_interest(Interest::new(stale.clone())).await.unwrap();
+        transport.send_interest(Interest::new(stale.clone()).must_be_fresh(true)).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        transport.send_interest(Interest::new(stale).must_be_fresh(false)).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_longest_registered_prefix_wins() {
+        let transport = UdcnTransport::new(Config::default()).await.unwrap();
+        transport.register_prefix(Name::from_uri("/a").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "a"))
+        })).await.unwrap();
+        transport.register_prefix(Name::from_uri("/a/b").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "a/b"))
+        })).await.unwrap();
+        
+        let data = transport.send_interest(Interest::new(Name::from_uri("/a/b/c").unwrap())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"a/b");
+        let data = transport.send_interest(Interest::new(Name::from_uri("/a/c").unwrap())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"a");
+        
+        // A component prefix of a name is not a name prefix
+        assert!(matches!(
+            transport.send_interest(Interest::new(Name::from_uri("/ab").unwrap())).await,
+            Err(Error::NoMatchingPrefix(_))
+        ));
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_implicit_digest_disambiguates_data() {
+        let transport = UdcnTransport::new(Config::default()).await.unwrap();
+        transport.register_prefix(Name::from_uri("/versioned").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().without_implicit_digest(), "v2"))
+        })).await.unwrap();
+        
+        // Two Data share a name but not a digest
+        let name = Name::from_uri("/versioned/object").unwrap();
+        let v1 = Data::new(name.clone(), "v1");
+        let v2 = Data::new(name.clone(), "v2");
+        assert_ne!(v1.full_name(), v2.full_name());
+        transport.content_store().write().await.insert(v1.clone());
+        
+        // Each full name gets its own Data, from the cache or the producer
+        let data = transport.send_interest(Interest::new(v1.full_name())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"v1");
+        let data = transport.send_interest(Interest::new(v2.full_name())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"v2");
+        
+        // The producer's Data no longer hashes to v1's digest
+        let mut v1_name = name;
+        v1_name.append_implicit_digest(v1.implicit_digest());
+        assert!(matches!(
+            transport.send_interest(Interest::new(v1_name)).await,
+            Err(Error::DigestMismatch(_))
+        ));
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_transports_exchange_data_over_quic() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        let consumer = UdcnTransport::new(Config {
+            enable_metrics: true,
+            metrics_port: 0,
+            ..local_config()
+        }).await.unwrap();
+        
+        // Registered before start, and while running
+        producer.register_prefix(Name::from_uri("/producer/early").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "early"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        consumer.start().await.unwrap();
+        producer.register_prefix(Name::from_uri("/producer/late").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "late"))
+        })).await.unwrap();
+        
+        let addr = producer.local_addr().await.unwrap();
+        {
+            let engine = consumer.quic_engine().read().await;
+            let engine = engine.as_ref().expect("QUIC engine not started");
+            
+            for (name, content) in [("/producer/early/1", "early"), ("/producer/late/1", "late")] {
+                let interest = Interest::new(Name::from_uri(name).unwrap());
+                let data = engine.send_interest(addr, interest).await.unwrap();
+                assert_eq!(data.content().as_ref(), content.as_bytes());
+            }
+        }
+        
+        // Both round trips were recorded in the RTT histogram
+        match consumer.get_metrics().await.get(INTEREST_RTT_METRIC) {
+            Some(MetricValue::Histogram(buckets)) => {
+                assert_eq!(buckets.iter().map(|(_, count)| count).sum::<u64>(), 2);
+            }
+            other => panic!("no RTT histogram: {:?}", other),
+        }
+        let stats = consumer.get_statistics().await;
+        assert!(stats.rtt_p50_ms <= stats.rtt_p95_ms && stats.rtt_p95_ms <= stats.rtt_p99_ms);
+        
+        consumer.stop().await.unwrap();
+        producer.stop().await.unwrap();
+        assert!(!producer.health().await.quic_up);
+        assert!(producer.local_addr().await.is_err());
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_shutdown_drains_in_flight_interest() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        let finished = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let handler_finished = finished.clone();
+        producer.register_prefix(Name::from_uri("/slow").unwrap(), Box::new(move |interest: Interest| {
+            std::thread::sleep(Duration::from_millis(500));
+            handler_finished.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(Data::new(interest.name().clone(), "finally"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let addr = producer.local_addr().await.unwrap();
+        
+        let consumer = UdcnTransport::new(local_config()).await.unwrap();
+        consumer.start().await.unwrap();
+        let request = {
+            let consumer = consumer.clone();
+            tokio::spawn(async move {
+                let engine = consumer.quic_engine().read().await;
+                let interest = Interest::new(Name::from_uri("/slow/object").unwrap());
+                engine.as_ref().unwrap().send_interest(addr, interest).await
+            })
+        };
+        
+        // Shut down while the handler is still running
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        producer.shutdown_with_timeout(Duration::from_secs(5)).await.unwrap();
+        
+        // The handler finished and its Data went out before the endpoint closed
+        assert!(finished.load(std::sync::atomic::Ordering::SeqCst));
+        let data = request.await.unwrap().unwrap();
+        assert_eq!(data.content().as_ref(), b"finally");
+        assert_eq!(producer.state().await, TransportState::Stopped);
+        
+        consumer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_forward_to_next_hop() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/remote").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "from producer"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        // The longer prefix wins over the route to an unreachable next hop
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/").unwrap(), 10, "127.0.0.1:9".parse().unwrap())
+            .await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/remote").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        forwarder.start().await.unwrap();
+        
+        let name = Name::from_uri("/remote/object").unwrap();
+        let data = forwarder.send_interest(Interest::new(name.clone())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"from producer");
+        assert!(forwarder.content_store().read().await.contains(&name));
+        
+        // Names no prefix matches are still not found
+        let forwarder_only = UdcnTransport::new(local_config()).await.unwrap();
+        let err = forwarder_only.send_interest(Interest::new(name.clone())).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NoMatchingPrefix);
+        assert!(matches!(err, Error::NoMatchingPrefix(unmatched) if unmatched == name));
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_highest_priority_next_hop() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/multi").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "from producer"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        // Both next hops stay registered on the one prefix
+        let prefix = Name::from_uri("/multi").unwrap();
+        let unreachable: SocketAddr = "127.0.0.1:9".parse().unwrap();
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        let low_id = forwarder.register_forwarding_prefix(prefix.clone(), 1, unreachable).await.unwrap();
+        let high_id = forwarder.register_forwarding_prefix(prefix.clone(), 5, producer_addr).await.unwrap();
+        forwarder.start().await.unwrap();
+        let hops: Vec<_> = forwarder.forwarding_entries().into_iter()
+            .map(|entry| (entry.registration_id, entry.priority, entry.next_hop))
+            .collect();
+        assert_eq!(hops, vec![(low_id, 1, unreachable), (high_id, 5, producer_addr)]);
+        
+        // The higher priority wins, whatever the registration order
+        let name = Name::from_uri("/multi/object").unwrap();
+        assert_eq!(forwarder.next_hop(&name), Some((prefix.clone(), producer_addr)));
+        let data = forwarder.send_interest(Interest::new(name.clone())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"from producer");
+        
+        // Re-registering a next hop updates its priority in place
+        let raised_id = forwarder.register_forwarding_prefix(prefix.clone(), 9, unreachable).await.unwrap();
+        assert_eq!(forwarder.next_hop(&name), Some((prefix.clone(), unreachable)));
+        assert_eq!(forwarder.forwarding_entries().len(), 2);
+        assert!(matches!(forwarder.unregister_prefix(low_id).await, Err(Error::NotFound(_))));
+        
+        // Removing one next hop falls back to the other
+        forwarder.unregister_prefix(raised_id).await.unwrap();
+        assert_eq!(forwarder.next_hop(&name), Some((prefix.clone(), producer_addr)));
+        forwarder.unregister_prefix(high_id).await.unwrap();
+        assert_eq!(forwarder.next_hop(&name), None);
+        assert!(forwarder.forwarding_table.is_empty());
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_forward_by_forwarding_hint() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/app").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "via hint"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        // Only the delegation name has a route
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/site/b").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        forwarder.start().await.unwrap();
+        
+        let name = Name::from_uri("/app/video").unwrap();
+        assert!(matches!(
+            forwarder.send_interest(Interest::new(name.clone())).await,
+            Err(Error::NoMatchingPrefix(_))
+        ));
+        
+        let hinted = Interest::new(name).with_forwarding_hint(Name::from_uri("/site/b").unwrap());
+        let data = forwarder.send_interest(hinted).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"via hint");
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_producer_failure_is_nack() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/broken").unwrap(), Box::new(|_interest: Interest| {
+            Err(Error::Other("producer failed".to_string()))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/broken").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        forwarder.start().await.unwrap();
+        
+        // The producer's NACK comes back with its reason and message
+        let err = forwarder.send_interest(Interest::new(Name::from_uri("/broken/object").unwrap()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Nack);
+        match err {
+            Error::Nack { reason, message } => {
+                assert_eq!(reason, crate::ndn::NackReason::NoRoute);
+                assert_eq!(message, "producer failed");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_per_prefix_metrics() {
+        let config = Config {
+            enable_metrics: true,
+            metrics_port: 0,
+            ..local_config()
+        };
+        let producer = UdcnTransport::new(config.clone()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/hot").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "hot"))
+        })).await.unwrap();
+        producer.register_prefix(Name::from_uri("/cold").unwrap(), Box::new(|interest: Interest| {
+            if interest.name().to_string().ends_with("/broken") {
+                return Err(Error::Other("producer failed".to_string()));
+            }
+            Ok(Data::new(interest.name().clone(), "cold"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        let forwarder = UdcnTransport::new(config).await.unwrap();
+        for prefix in ["/hot", "/cold"] {
+            forwarder.register_forwarding_prefix(Name::from_uri(prefix).unwrap(), 1, producer_addr)
+                .await.unwrap();
+        }
+        forwarder.start().await.unwrap();
+        
+        for i in 0..5 {
+            let name = Name::from_uri(&format!("/hot/{}", i)).unwrap();
+            forwarder.send_interest(Interest::new(name)).await.unwrap();
+        }
+        forwarder.send_interest(Interest::new(Name::from_uri("/cold/0").unwrap())).await.unwrap();
+        forwarder.send_interest(Interest::new(Name::from_uri("/cold/broken").unwrap())).await.unwrap_err();
+        
+        // The forwarder counts by forwarding prefix, the producer by the
+        // prefix whose handler served the Interest
+        for transport in [&forwarder, &producer] {
+            let stats = transport.get_detailed_statistics().await;
+            assert_eq!(stats["top_prefixes"], "/hot=5,/cold=2");
+            assert_eq!(stats["prefix_interests:/hot"], "5");
+            assert_eq!(stats["prefix_nacks:/hot"], "0");
+            assert_eq!(stats["prefix_interests:/cold"], "2");
+            assert_eq!(stats["prefix_nacks:/cold"], "1");
+            assert!(stats["prefix_rtt_avg_ms:/hot"].parse::<f64>().unwrap() > 0.0);
+        }
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg(feature = "otel")]
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_forwarded_interest_keeps_trace() {
+        let received = Arc::new(std::sync::Mutex::new(None));
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        let seen = received.clone();
+        producer.register_prefix(Name::from_uri("/traced").unwrap(), Box::new(move |interest: Interest| {
+            *seen.lock().unwrap() = interest.traceparent().map(str::to_string);
+            Ok(Data::new(interest.name().clone(), "data"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/traced").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        forwarder.start().await.unwrap();
+        
+        let incoming = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let interest = Interest::new(Name::from_uri("/traced/object").unwrap()).with_traceparent(incoming);
+        forwarder.send_interest(interest).await.unwrap();
+        
+        // The producer sees the same trace, under the forwarder's hop span
+        let incoming = otel::TraceContext::parse(incoming).unwrap();
+        let received = received.lock().unwrap().clone().expect("Interest arrived without a traceparent");
+        let received = otel::TraceContext::parse(&received).unwrap();
+        assert_eq!(received.trace_id, incoming.trace_id);
+        assert_ne!(received.span_id, incoming.span_id);
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread", worker_threads = 4))]
+    async fn test_looping_interest_is_duplicate() {
+        // The producer holds the first Interest until released, so it is
+        // still pending when its duplicate arrives
+        let (arrived_tx, mut arrived) = tokio::sync::mpsc::unbounded_channel();
+        let (release, release_rx) = std::sync::mpsc::channel::<()>();
+        let gate = std::sync::Mutex::new(Some(release_rx));
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/loop").unwrap(), Box::new(move |interest: Interest| {
+            if let Some(release_rx) = gate.lock().unwrap().take() {
+                arrived_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            }
+            Ok(Data::new(interest.name().clone(), "data").fresh_period(Duration::ZERO))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/loop").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        forwarder.start().await.unwrap();
+        
+        let interest = Interest::new(Name::from_uri("/loop/a").unwrap()).must_be_fresh(true);
+        let first = {
+            let forwarder = forwarder.clone();
+            let interest = interest.clone();
+            tokio::spawn(async move { forwarder.send_interest(interest).await })
+        };
+        arrived.recv().await.unwrap();
+        
+        // Nothing is cached yet, so the same name and nonce reach the
+        // forwarding path and are recognised as a loop
+        match forwarder.send_interest(interest.clone()).await {
+            Err(Error::Nack { reason, .. }) => assert_eq!(reason, crate::ndn::NackReason::Duplicate),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        release.send(()).unwrap();
+        first.await.unwrap().unwrap();
+        
+        // The Data has a zero FreshnessPeriod, so a MustBeFresh Interest with
+        // a new nonce is forwarded again rather than answered from the cache
+        forwarder.send_interest(interest.with_nonce(rand::random())).await.unwrap();
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_acl_allows_and_denies_peers() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        for prefix in ["/allowed", "/denied", "/open"] {
+            producer.register_prefix(Name::from_uri(prefix).unwrap(), Box::new(|interest: Interest| {
+                Ok(Data::new(interest.name().clone(), "data"))
+            })).await.unwrap();
+        }
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        // The policy applies to a running transport
+        let loopback: Subnet = "127.0.0.0/8".parse().unwrap();
+        producer.set_acl(AclPolicy::new()
+            .allow(Name::from_uri("/allowed").unwrap(), [loopback])
+            .deny(Name::from_uri("/denied").unwrap(), [loopback]));
+        
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        for prefix in ["/allowed", "/denied", "/open"] {
+            forwarder.register_forwarding_prefix(Name::from_uri(prefix).unwrap(), 1, producer_addr)
+                .await.unwrap();
+        }
+        forwarder.start().await.unwrap();
+        
+        // A peer in an allowed subnet gets the Data, as does any peer for a
+        // prefix without an ACL
+        forwarder.send_interest(Interest::new(Name::from_uri("/allowed/a").unwrap())).await.unwrap();
+        forwarder.send_interest(Interest::new(Name::from_uri("/open/a").unwrap())).await.unwrap();
+        
+        match forwarder.send_interest(Interest::new(Name::from_uri("/denied/a").unwrap())).await {
+            Err(Error::Nack { reason, .. }) => assert_eq!(reason, crate::ndn::NackReason::NotAuth),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_ping_measures_rtt() {
+        let config = Config { enable_ping: true, ..local_config() };
+        let alice = UdcnTransport::new(config.clone()).await.unwrap();
+        let bob = UdcnTransport::new(config).await.unwrap();
+        let silent = UdcnTransport::new(local_config()).await.unwrap();
+        for transport in [&alice, &bob, &silent] {
+            transport.start().await.unwrap();
+        }
+        let alice_addr = alice.local_addr().await.unwrap();
+        let bob_addr = bob.local_addr().await.unwrap();
+        
+        // A started transport answers its own pings
+        assert!(alice.ping(alice_addr).await.unwrap() > Duration::ZERO);
+        
+        // Peers ping each other, repeatedly
+        for _ in 0..3 {
+            assert!(alice.ping(bob_addr).await.unwrap() > Duration::ZERO);
+            assert!(bob.ping(alice_addr).await.unwrap() > Duration::ZERO);
+        }
+        
+        // The echo carries a zero FreshnessPeriod across the wire
+        let ping = Interest::new(Name::from_uri(&format!("{}/42", quic::PING_PREFIX)).unwrap());
+        let echo = Data::parse(echo_ping(ping).unwrap().to_bytes()).unwrap();
+        assert_eq!(echo.get_fresh_period(), Duration::ZERO);
+        
+        // A transport without pings enabled does not answer them
+        assert!(alice.ping(silent.local_addr().await.unwrap()).await.is_err());
+        assert!(silent.ping(alice_addr).await.is_ok());
+        
+        for transport in [&alice, &bob, &silent] {
+            transport.stop().await.unwrap();
+        }
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_interest_burst_above_rate_limit_is_congested() {
+        let producer = UdcnTransport::new(Config {
+            max_interests_per_sec: Some(1),
+            interest_burst: Some(5),
+            ..local_config()
+        }).await.unwrap();
+        producer.register_prefix(Name::from_uri("/limited").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "data"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        // Congestion NACKs are normally retried; count them instead
+        let forwarder = UdcnTransport::new(Config { retries: 0, ..local_config() }).await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/limited").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        forwarder.start().await.unwrap();
+        
+        let mut satisfied = 0;
+        let mut congested = 0;
+        for i in 0..20 {
+            let name = Name::from_uri(&format!("/limited/{}", i)).unwrap();
+            match forwarder.send_interest(Interest::new(name)).await {
+                Ok(_) => satisfied += 1,
+                Err(Error::Nack { reason: crate::ndn::NackReason::Congestion, .. }) => congested += 1,
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+        
+        // The burst gets through, at most a token or two refills meanwhile
+        assert!((5..=7).contains(&satisfied), "{} Interests satisfied", satisfied);
+        assert_eq!(satisfied + congested, 20);
+        assert_eq!(producer.get_detailed_statistics().await["rate_limited"], congested.to_string());
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread", worker_threads = 8))]
+    async fn test_send_interests_bounded_and_ordered() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        let (handler_in_flight, handler_peak) = (in_flight.clone(), peak.clone());
+        producer.register_prefix(Name::from_uri("/batch").unwrap(), Box::new(move |interest: Interest| {
+            let now = handler_in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            handler_peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(20));
+            handler_in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(Data::new(interest.name().clone(), interest.name().to_string()))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        let forwarder = UdcnTransport::new(local_config()).await.unwrap();
+        forwarder.register_forwarding_prefix(Name::from_uri("/batch").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        forwarder.start().await.unwrap();
+        
+        let names: Vec<Name> = (0..50).map(|i| Name::from_uri(&format!("/batch/{}", i)).unwrap()).collect();
+        let interests = names.iter().cloned().map(Interest::new).collect();
+        let results = forwarder.send_interests(interests, 4).await;
+        
+        // Each result lines up with its Interest
+        assert_eq!(results.len(), names.len());
+        for (name, result) in names.iter().zip(results) {
+            let data = result.unwrap();
+            assert_eq!(data.name(), name);
+            assert_eq!(data.content().as_ref(), name.to_string().as_bytes());
+        }
+        
+        // Never more than the limit in flight, over one connection
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak > 1 && peak <= 4, "peak concurrency {}", peak);
+        let engine = forwarder.quic_engine().read().await;
+        assert_eq!(engine.as_ref().unwrap().connections_opened(), 1);
+        drop(engine);
+        
+        forwarder.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_signed_commands() {
+        let operator = crate::security::KeyPair::generate("/udcn/operator/KEY/1").unwrap();
+        let router = UdcnTransport::new(Config {
+            require_signed_commands: true,
+            ..local_config()
+        }).await.unwrap();
+        router.cert_store().write().insert_cert(Name::from_uri("/udcn/operator/KEY/1").unwrap(), operator.public_key());
+        router.register_prefix(Name::from_uri("/localhost/nfd/rib/register").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "registered"))
+        })).await.unwrap();
+        router.start().await.unwrap();
+        let router_addr = router.local_addr().await.unwrap();
+        
+        let operator_face = UdcnTransport::new(local_config()).await.unwrap();
+        operator_face.register_forwarding_prefix(Name::from_uri("/localhost/nfd").unwrap(), 1, router_addr)
+            .await.unwrap();
+        operator_face.start().await.unwrap();
+        
+        // A command signed by a trusted key reaches the handler
+        let signed = Interest::new(Name::from_uri("/localhost/nfd/rib/register/udcn").unwrap()).sign(&operator);
+        let data = operator_face.send_interest(signed).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"registered");
+        
+        // An unsigned one is refused before it does
+        let unsigned = Interest::new(Name::from_uri("/localhost/nfd/rib/register/other").unwrap());
+        match operator_face.send_interest(unsigned).await {
+            Err(Error::Nack { reason, .. }) => assert_eq!(reason, crate::ndn::NackReason::NotAuth),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        let engine = router.quic_engine().read().await;
+        assert_eq!(engine.as_ref().unwrap().command_validator().rejected(), 1);
+        drop(engine);
+        
+        operator_face.stop().await.unwrap();
+        router.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_push_data_reaches_next_hop() {
+        let router = UdcnTransport::new(local_config()).await.unwrap();
+        router.start().await.unwrap();
+        let router_addr = router.local_addr().await.unwrap();
+        
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        let data = Data::new(Name::from_uri("/chat/msg/1").unwrap(), "hello");
+        
+        // Without a route or a running engine there is nowhere to push
+        assert!(matches!(producer.push_data(data.clone()).await, Err(Error::NoMatchingPrefix(_))));
+        producer.register_forwarding_prefix(Name::from_uri("/chat").unwrap(), 1, router_addr).await.unwrap();
+        assert!(matches!(producer.push_data(data.clone()).await, Err(Error::InvalidState(_))));
+        
+        // Once started, the Data lands in the next hop's content store
+        producer.start().await.unwrap();
+        producer.push_data(data.clone()).await.unwrap();
+        let mut cached = None;
+        for _ in 0..50 {
+            let engine = router.quic_engine().read().await;
+            cached = engine.as_ref().unwrap().content_store().read().await.get(data.name(), false);
+            if cached.is_some() {
+                break;
+            }
+            drop(engine);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(cached.expect("Pushed Data not cached").content().as_ref(), b"hello");
+        
+        producer.stop().await.unwrap();
+        router.stop().await.unwrap();
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_forwarding_counters_per_prefix() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/fwd/video").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "frame"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        // A next hop that never answers the handshake
+        let dead_hop = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        
+        let consumer = UdcnTransport::new(Config { retries: 0, ..local_config() }).await.unwrap();
+        let live = Name::from_uri("/fwd").unwrap();
+        let dead = Name::from_uri("/dead").unwrap();
+        consumer.register_forwarding_prefix(live.clone(), 1, producer_addr).await.unwrap();
+        consumer.register_forwarding_prefix(dead.clone(), 1, dead_hop.local_addr().unwrap()).await.unwrap();
+        consumer.start().await.unwrap();
+        
+        for i in 0..3 {
+            let name = Name::from_uri(&format!("/fwd/video/{}", i)).unwrap();
+            consumer.send_interest(Interest::new(name)).await.unwrap();
+        }
+        
+        // The producer has no handler for this one and NACKs it
+        let err = consumer.send_interest(Interest::new(Name::from_uri("/fwd/audio/1").unwrap())).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Nack);
+        
+        let expiring = Interest::new(Name::from_uri("/dead/1").unwrap()).lifetime(Duration::from_millis(300));
+        let err = consumer.send_interest(expiring).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Timeout);
+        
+        assert_eq!(consumer.forwarding_stats(&live), ForwardingStats {
+            forwarded: 4,
+            satisfied: 3,
+            nacked: 1,
+            timed_out: 0,
+            failed: 0,
+        });
+        assert_eq!(consumer.forwarding_stats(&dead), ForwardingStats {
+            forwarded: 1,
+            satisfied: 0,
+            nacked: 0,
+            timed_out: 1,
+            failed: 0,
+        });
+        
+        consumer.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
+    
+    // Tracer keeping each frame with its direction and peer
+    #[derive(Default, Clone)]
+    struct CapturingTracer {
+        frames: Arc<std::sync::Mutex<Vec<(&'static str, Vec<u8>, SocketAddr)>>>,
+    }
+    
+    impl Tracer for CapturingTracer {
+        fn on_send(&self, frame: &[u8], peer: SocketAddr) {
+            self.frames.lock().unwrap().push(("send", frame.to_vec(), peer));
+        }
+        
+        fn on_recv(&self, frame: &[u8], peer: SocketAddr) {
+            self.frames.lock().unwrap().push(("recv", frame.to_vec(), peer));
+        }
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_tracer_sees_one_exchange() {
+        let producer = UdcnTransport::new(local_config()).await.unwrap();
+        producer.register_prefix(Name::from_uri("/traced").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "traced"))
+        })).await.unwrap();
+        producer.start().await.unwrap();
+        let producer_addr = producer.local_addr().await.unwrap();
+        
+        let consumer = UdcnTransport::new(local_config()).await.unwrap();
+        consumer.register_forwarding_prefix(Name::from_uri("/traced").unwrap(), 1, producer_addr)
+            .await.unwrap();
+        let tracer = CapturingTracer::default();
+        consumer.set_tracer(Box::new(tracer.clone()));
+        consumer.start().await.unwrap();
+        
+        consumer.send_interest(Interest::new(Name::from_uri("/traced/a").unwrap())).await.unwrap();
+        
+        // The Interest went out and the Data came back, both to the producer
+        let frames = tracer.frames.lock().unwrap().clone();
+        assert_eq!(frames.len(), 2, "traced {:?}", frames);
+        assert_eq!((frames[0].0, frames[0].1[0], frames[0].2), ("send", crate::ndn::tlv_type::INTEREST, producer_addr));
+        assert_eq!((frames[1].0, frames[1].1[0], frames[1].2), ("recv", crate::ndn::tlv_type::DATA, producer_addr));
+        
+        // Nothing is traced once the tracer is cleared
+        consumer.clear_tracer();
+        consumer.send_interest(Interest::new(Name::from_uri("/traced/b").unwrap())).await.unwrap();
+        assert_eq!(tracer.frames.lock().unwrap().len(), 2);
+        
+        consumer.stop().await.unwrap();
+        producer.stop().await.unwrap();
+    }
 }
-
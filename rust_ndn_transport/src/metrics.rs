@@ -1,7 +1,9 @@
 //
 // μDCN Metrics Collector Module
 //
-// This module implements collection of metrics for the μDCN transport layer.
+// This module implements collection of metrics for the μDCN transport layer,
+// and a small HTTP server exposing them at `/metrics` in the Prometheus text
+// exposition format.
 //
 
 use prometheus::{
@@ -12,8 +14,23 @@ use prometheus::{
 
 // Simplified HTTP server implementation without direct hyper dependency
 use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Largest request head read from a scraper
+const MAX_REQUEST_HEAD: usize = 8192;
+
+/// Histogram of the round-trip time of satisfied Interests, in milliseconds
+pub const INTEREST_RTT_METRIC: &str = "interest_rtt_ms";
+
+/// Bucket upper bounds used by histograms without configured buckets
+pub const DEFAULT_BUCKETS: [f64; 12] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0];
 
 /// Metric value type
 #[derive(Debug, Clone)]
@@ -24,25 +41,65 @@ pub enum MetricValue {
     /// Gauge metric
     Gauge(f64),
     
-    /// Histogram metric
-    Histogram(Vec<u64>),
+    /// Histogram metric, as (upper bound, count) per bucket
+    ///
+    /// Counts are per bucket rather than cumulative; the last bucket's upper
+    /// bound is infinite.
+    Histogram(Vec<(f64, u64)>),
     
     /// Text metric
     Text(String),
 }
 
+/// Traffic of one registered prefix
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrefixMetrics {
+    /// Interests dispatched to the prefix
+    pub interests: u64,
+    
+    /// Interests answered with a NACK
+    pub nacks: u64,
+    
+    /// Interests satisfied with Data
+    pub satisfied: u64,
+    
+    /// Total round-trip time of the satisfied Interests, in milliseconds
+    pub rtt_sum_ms: f64,
+}
+
+impl PrefixMetrics {
+    /// Get the average round-trip time of the satisfied Interests, or 0 when
+    /// none was satisfied
+    pub fn avg_rtt_ms(&self) -> f64 {
+        if self.satisfied == 0 {
+            0.0
+        } else {
+            self.rtt_sum_ms / self.satisfied as f64
+        }
+    }
+}
+
 /// Metrics collector
 #[derive(Debug)]
 pub struct MetricsCollector {
     /// Whether metrics are enabled
     enabled: bool,
     
-    /// Port for metrics HTTP server
-    port: u16,
+    /// Address the metrics HTTP server binds to
+    addr: SocketAddr,
     
     /// Metrics storage
     metrics: RwLock<HashMap<String, MetricValue>>,
     
+    /// Bucket upper bounds configured per histogram
+    buckets: RwLock<HashMap<String, Vec<f64>>>,
+    
+    /// Traffic by registered prefix
+    prefixes: RwLock<HashMap<String, PrefixMetrics>>,
+    
+    /// Address and task of the running HTTP server
+    server: Mutex<Option<(SocketAddr, JoinHandle<()>)>>,
+    
     /// Prometheus registry
     // registry: Registry,
 }
@@ -54,33 +111,167 @@ impl Default for MetricsCollector {
 }
 
 impl MetricsCollector {
-    /// Create a new metrics collector
+    /// Create a new metrics collector serving on `port` of the loopback
+    /// interface
     pub fn new(port: u16, enabled: bool) -> Self {
+        Self::with_addr(SocketAddr::from(([127, 0, 0, 1], port)), enabled)
+    }
+    
+    /// Create a new metrics collector serving on `addr`
+    pub fn with_addr(addr: SocketAddr, enabled: bool) -> Self {
         Self {
             enabled,
-            port,
+            addr,
             metrics: RwLock::new(HashMap::new()),
+            buckets: RwLock::new(HashMap::new()),
+            prefixes: RwLock::new(HashMap::new()),
+            server: Mutex::new(None),
             // registry: Registry::new(),
         }
     }
     
-    /// Start the metrics server
-    pub async fn start(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// Start the HTTP server serving the metrics on `/metrics`
+    ///
+    /// Port 0 binds an ephemeral port; see `local_addr`.
+    pub async fn start(self: &Arc<Self>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         if !self.enabled {
             return Ok(());
         }
         
-        // In a real implementation, this would start an HTTP server
-        // For this simplified version, we just log that metrics are enabled
-        println!("Metrics collection enabled on port {}", self.port);
+        let mut server = self.server.lock().await;
+        if server.is_some() {
+            return Ok(());
+        }
+        
+        let listener = TcpListener::bind(self.addr).await?;
+        let addr = listener.local_addr()?;
+        info!("Serving metrics on http://{}/metrics", addr);
         
-        // This is a placeholder for an actual HTTP server setup
-        // In the real implementation, we would use a crate like warp or axum
-        // to serve metrics in Prometheus format
+        let collector = self.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let collector = collector.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = collector.serve_scrape(stream).await {
+                                debug!("Metrics request from {} failed: {}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Failed to accept metrics connection: {}", e),
+                }
+            }
+        });
         
+        *server = Some((addr, handle));
         Ok(())
     }
     
+    /// Stop the HTTP server
+    pub async fn stop(&self) {
+        if let Some((_, handle)) = self.server.lock().await.take() {
+            handle.abort();
+        }
+    }
+    
+    /// Get the address the HTTP server listens on, if it is running
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.lock().await.as_ref().map(|(addr, _)| *addr)
+    }
+    
+    /// Answer one HTTP request: the metrics for `GET /metrics`, 404 otherwise
+    async fn serve_scrape(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        // Read the request head; scrapes carry no body
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_HEAD {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        
+        let request = String::from_utf8_lossy(&request);
+        let mut request_line = request.split_whitespace();
+        let response = match (request_line.next(), request_line.next()) {
+            (Some("GET"), Some(path)) if path == "/metrics" || path.starts_with("/metrics?") => {
+                let body = self.render_prometheus().await;
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(), body)
+            }
+            _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+        };
+        
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+    
+    /// Render every metric in the Prometheus text exposition format
+    ///
+    /// Counters and gauges map directly; a text metric becomes a gauge of 1
+    /// carrying the text in a `value` label.
+    pub async fn render_prometheus(&self) -> String {
+        let metrics = self.metrics.read().await;
+        let mut names: Vec<&String> = metrics.keys().collect();
+        names.sort();
+        
+        let mut out = String::new();
+        for name in names {
+            let metric = prometheus_name(name);
+            match &metrics[name] {
+                MetricValue::Counter(value) => {
+                    let _ = writeln!(out, "# TYPE {} counter", metric);
+                    let _ = writeln!(out, "{} {}", metric, value);
+                }
+                MetricValue::Gauge(value) => {
+                    let _ = writeln!(out, "# TYPE {} gauge", metric);
+                    let _ = writeln!(out, "{} {}", metric, prometheus_float(*value));
+                }
+                MetricValue::Text(text) => {
+                    let _ = writeln!(out, "# TYPE {} gauge", metric);
+                    let _ = writeln!(out, "{}{{value=\"{}\"}} 1", metric, escape_label(text));
+                }
+                // No sum is kept, so only the buckets and count are exposed
+                MetricValue::Histogram(buckets) => {
+                    let _ = writeln!(out, "# TYPE {} histogram", metric);
+                    let mut cumulative = 0;
+                    for (bound, count) in buckets {
+                        cumulative += count;
+                        let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", metric, prometheus_float(*bound), cumulative);
+                    }
+                    let _ = writeln!(out, "{}_count {}", metric, cumulative);
+                }
+            }
+        }
+        
+        // Per-prefix traffic is labeled with the prefix
+        let prefixes = self.prefixes.read().await;
+        let mut names: Vec<&String> = prefixes.keys().collect();
+        names.sort();
+        if !names.is_empty() {
+            let _ = writeln!(out, "# TYPE prefix_interests_total counter");
+            for name in &names {
+                let _ = writeln!(out, "prefix_interests_total{{prefix=\"{}\"}} {}", escape_label(name), prefixes[*name].interests);
+            }
+            let _ = writeln!(out, "# TYPE prefix_nacks_total counter");
+            for name in &names {
+                let _ = writeln!(out, "prefix_nacks_total{{prefix=\"{}\"}} {}", escape_label(name), prefixes[*name].nacks);
+            }
+            
+            // No quantiles are kept, so the RTT summary has only its sum and count
+            let _ = writeln!(out, "# TYPE prefix_rtt_ms summary");
+            for name in &names {
+                let metrics = &prefixes[*name];
+                let _ = writeln!(out, "prefix_rtt_ms_sum{{prefix=\"{}\"}} {}", escape_label(name), prometheus_float(metrics.rtt_sum_ms));
+                let _ = writeln!(out, "prefix_rtt_ms_count{{prefix=\"{}\"}} {}", escape_label(name), metrics.satisfied);
+            }
+        }
+        out
+    }
+    
     /// Set a gauge metric
     pub fn set_gauge(&self, _name: &str, _value: f64) {
         if !self.enabled {
@@ -103,14 +294,92 @@ impl MetricsCollector {
             .or_insert(MetricValue::Counter(value));
     }
     
-    /// Record a histogram observation
-    pub fn observe_histogram(&self, _name: &str, _value: f64) {
+    /// Set the bucket upper bounds of a histogram, clearing its observations
+    ///
+    /// An infinite bucket is added after the largest bound.
+    pub async fn set_buckets(&self, name: &str, mut bounds: Vec<f64>) {
+        bounds.retain(|bound| bound.is_finite());
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        
+        self.buckets.write().await.insert(name.to_string(), bounds);
+        self.metrics.write().await.remove(name);
+    }
+    
+    /// Record an observation in a histogram
+    pub async fn observe(&self, name: &str, value: f64) {
         if !self.enabled {
             return;
         }
         
-        // Implement actual prometheus histogram observation here
-        // This is a placeholder for now
+        let mut metrics = self.metrics.write().await;
+        if !matches!(metrics.get(name), Some(MetricValue::Histogram(_))) {
+            let bounds = match self.buckets.read().await.get(name) {
+                Some(bounds) => bounds.clone(),
+                None => DEFAULT_BUCKETS.to_vec(),
+            };
+            let buckets = bounds.into_iter()
+                .chain(std::iter::once(f64::INFINITY))
+                .map(|bound| (bound, 0))
+                .collect();
+            metrics.insert(name.to_string(), MetricValue::Histogram(buckets));
+        }
+        
+        if let Some(MetricValue::Histogram(buckets)) = metrics.get_mut(name) {
+            // NaN falls through to the infinite bucket
+            let bucket = buckets.iter()
+                .position(|(bound, _)| value <= *bound)
+                .unwrap_or(buckets.len() - 1);
+            buckets[bucket].1 += 1;
+        }
+    }
+    
+    /// Count an Interest dispatched to a registered prefix
+    pub async fn record_prefix_interest(&self, prefix: &str) {
+        if !self.enabled {
+            return;
+        }
+        
+        self.prefixes.write().await.entry(prefix.to_string()).or_default().interests += 1;
+    }
+    
+    /// Count an Interest for a registered prefix answered with a NACK
+    pub async fn record_prefix_nack(&self, prefix: &str) {
+        if !self.enabled {
+            return;
+        }
+        
+        self.prefixes.write().await.entry(prefix.to_string()).or_default().nacks += 1;
+    }
+    
+    /// Record the round-trip time of an Interest for a registered prefix
+    /// satisfied with Data
+    pub async fn record_prefix_rtt(&self, prefix: &str, rtt_ms: f64) {
+        if !self.enabled {
+            return;
+        }
+        
+        let mut prefixes = self.prefixes.write().await;
+        let metrics = prefixes.entry(prefix.to_string()).or_default();
+        metrics.satisfied += 1;
+        metrics.rtt_sum_ms += rtt_ms;
+    }
+    
+    /// Get the traffic of a registered prefix
+    pub async fn prefix_metrics(&self, prefix: &str) -> Option<PrefixMetrics> {
+        self.prefixes.read().await.get(prefix).cloned()
+    }
+    
+    /// Get the `n` prefixes with the most Interests, busiest first; ties are
+    /// ordered by prefix
+    pub async fn top_prefixes(&self, n: usize) -> Vec<(String, PrefixMetrics)> {
+        let mut prefixes: Vec<(String, PrefixMetrics)> = self.prefixes.read().await
+            .iter()
+            .map(|(prefix, metrics)| (prefix.clone(), metrics.clone()))
+            .collect();
+        prefixes.sort_by(|a, b| b.1.interests.cmp(&a.1.interests).then_with(|| a.0.cmp(&b.0)));
+        prefixes.truncate(n);
+        prefixes
     }
     
     /// Get all metrics
@@ -122,4 +391,195 @@ impl MetricsCollector {
     pub async fn get_metric(&self, name: &str) -> Option<MetricValue> {
         self.metrics.read().await.get(name).cloned()
     }
-}
+}
+
+/// Estimate a quantile (0.0 to 1.0) of a histogram's observations
+///
+/// Observations are assumed to be spread evenly within a bucket, as
+/// Prometheus' `histogram_quantile` does; a quantile landing in the infinite
+/// bucket is reported as the largest finite bound. Returns `None` for an
+/// empty histogram.
+pub fn histogram_quantile(buckets: &[(f64, u64)], quantile: f64) -> Option<f64> {
+    let total: u64 = buckets.iter().map(|(_, count)| count).sum();
+    if total == 0 {
+        return None;
+    }
+    
+    let rank = quantile.clamp(0.0, 1.0) * total as f64;
+    let mut lower = 0.0;
+    let mut below = 0;
+    for &(upper, count) in buckets {
+        if count > 0 && (below + count) as f64 >= rank {
+            if upper.is_infinite() {
+                return Some(lower);
+            }
+            return Some(lower + (upper - lower) * (rank - below as f64) / count as f64);
+        }
+        below += count;
+        if upper.is_finite() {
+            lower = upper;
+        }
+    }
+    
+    Some(lower)
+}
+
+/// Turn a metric name into a valid Prometheus name
+fn prometheus_name(name: &str) -> String {
+    let mut metric: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == ':' { c } else { '_' })
+        .collect();
+    if metric.starts_with(|c: char| c.is_ascii_digit()) || metric.is_empty() {
+        metric.insert(0, '_');
+    }
+    metric
+}
+
+/// Format a sample value, spelling infinities and NaN the Prometheus way
+fn prometheus_float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape a label value
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_scrape_metrics_endpoint() {
+        let collector = Arc::new(MetricsCollector::new(0, true));
+        collector.increment_counter("cache_hits", 3).await;
+        collector.metrics.write().await.insert("rtt.avg".into(), MetricValue::Gauge(12.5));
+        collector.metrics.write().await.insert("mode".into(), MetricValue::Text("say \"hi\"".into()));
+        collector.start().await.unwrap();
+        
+        let port = collector.local_addr().await.unwrap().port();
+        let scrape = |path: &'static str| async move {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            stream.write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        
+        let response = scrape("/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+        assert!(response.contains("# TYPE cache_hits counter\ncache_hits 3\n"), "{}", response);
+        assert!(response.contains("# TYPE rtt_avg gauge\nrtt_avg 12.5\n"), "{}", response);
+        assert!(response.contains("mode{value=\"say \\\"hi\\\"\"} 1\n"), "{}", response);
+        
+        assert!(scrape("/other").await.starts_with("HTTP/1.1 404"));
+        
+        collector.set_buckets("rtt", vec![10.0, 100.0]).await;
+        for value in [5.0, 50.0, 500.0] {
+            collector.observe("rtt", value).await;
+        }
+        let response = scrape("/metrics").await;
+        assert!(response.contains(concat!(
+            "# TYPE rtt histogram\n",
+            "rtt_bucket{le=\"10\"} 1\n",
+            "rtt_bucket{le=\"100\"} 2\n",
+            "rtt_bucket{le=\"+Inf\"} 3\n",
+            "rtt_count 3\n")), "{}", response);
+        
+        // Nothing listens once stopped
+        collector.stop().await;
+        assert!(collector.local_addr().await.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_serves_on_configured_address() {
+        // Without an address the server only listens on loopback
+        let collector = Arc::new(MetricsCollector::new(0, true));
+        collector.start().await.unwrap();
+        assert!(collector.local_addr().await.unwrap().ip().is_loopback());
+        collector.stop().await;
+        
+        let collector = Arc::new(MetricsCollector::with_addr("127.0.0.1:0".parse().unwrap(), true));
+        collector.start().await.unwrap();
+        let addr = collector.local_addr().await.unwrap();
+        assert_eq!(addr.ip(), "127.0.0.1".parse::<std::net::IpAddr>().unwrap());
+        assert_ne!(addr.port(), 0);
+        collector.stop().await;
+    }
+    
+    #[tokio::test]
+    async fn test_histogram_buckets_and_quantiles() {
+        let collector = MetricsCollector::new(0, true);
+        collector.set_buckets(INTEREST_RTT_METRIC, vec![100.0, 10.0, 50.0, 10.0]).await;
+        
+        // 1..=100 ms, ten of them slower than a second
+        for rtt in 1..=100 {
+            collector.observe(INTEREST_RTT_METRIC, rtt as f64).await;
+        }
+        for _ in 0..10 {
+            collector.observe(INTEREST_RTT_METRIC, 1500.0).await;
+        }
+        
+        let buckets = match collector.get_metric(INTEREST_RTT_METRIC).await {
+            Some(MetricValue::Histogram(buckets)) => buckets,
+            other => panic!("not a histogram: {:?}", other),
+        };
+        assert_eq!(buckets, vec![(10.0, 10), (50.0, 40), (100.0, 50), (f64::INFINITY, 10)]);
+        
+        let p50 = histogram_quantile(&buckets, 0.5).unwrap();
+        assert!((p50 - 55.0).abs() < 1e-9, "{}", p50);
+        let p05 = histogram_quantile(&buckets, 0.05).unwrap();
+        assert!((p05 - 5.5).abs() < 1e-9, "{}", p05);
+        assert_eq!(histogram_quantile(&buckets, 0.99), Some(100.0));
+        assert_eq!(histogram_quantile(&[(10.0, 0), (f64::INFINITY, 0)], 0.5), None);
+        
+        // Default buckets are used when none are configured
+        collector.observe("other", 3.0).await;
+        match collector.get_metric("other").await {
+            Some(MetricValue::Histogram(buckets)) => {
+                assert_eq!(buckets.len(), DEFAULT_BUCKETS.len() + 1);
+                assert_eq!(buckets[2], (5.0, 1));
+            }
+            other => panic!("not a histogram: {:?}", other),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_prefix_metrics_ranked_and_labeled() {
+        let collector = MetricsCollector::new(0, true);
+        for _ in 0..3 {
+            collector.record_prefix_interest("/hot").await;
+        }
+        collector.record_prefix_rtt("/hot", 10.0).await;
+        collector.record_prefix_rtt("/hot", 20.0).await;
+        collector.record_prefix_nack("/hot").await;
+        collector.record_prefix_interest("/cold").await;
+        collector.record_prefix_interest("/also\"cold").await;
+        
+        let hot = collector.prefix_metrics("/hot").await.unwrap();
+        assert_eq!((hot.interests, hot.nacks, hot.satisfied), (3, 1, 2));
+        assert_eq!(hot.avg_rtt_ms(), 15.0);
+        assert!(collector.prefix_metrics("/none").await.is_none());
+        
+        // Busiest first, ties by prefix
+        let top: Vec<String> = collector.top_prefixes(2).await.into_iter().map(|(prefix, _)| prefix).collect();
+        assert_eq!(top, vec!["/hot".to_string(), "/also\"cold".to_string()]);
+        
+        let body = collector.render_prometheus().await;
+        assert!(body.contains("prefix_interests_total{prefix=\"/hot\"} 3\n"), "{}", body);
+        assert!(body.contains("prefix_interests_total{prefix=\"/also\\\"cold\"} 1\n"), "{}", body);
+        assert!(body.contains("prefix_nacks_total{prefix=\"/cold\"} 0\n"), "{}", body);
+        assert!(body.contains("prefix_rtt_ms_sum{prefix=\"/hot\"} 30\nprefix_rtt_ms_count{prefix=\"/hot\"} 2\n"), "{}", body);
+        
+        // Nothing is recorded while disabled
+        let disabled = MetricsCollector::new(0, false);
+        disabled.record_prefix_interest("/hot").await;
+        assert!(disabled.top_prefixes(10).await.is_empty());
+    }
+}
@@ -6,16 +6,19 @@
 // based on network conditions and traffic patterns.
 //
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::{watch, RwLock};
 use tokio::task::JoinHandle;
 use tokio::time::{Duration, interval};
 use std::collections::VecDeque;
 use log::{debug, info, warn, error};
 
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::quic::{ConnectionStats, ConnectionState};
 use crate::metrics::MetricValue;
+use crate::xdp::XdpMetrics;
 
 /// ML feature set for MTU prediction
 #[derive(Debug, Clone)]
@@ -60,6 +63,22 @@ impl Default for MtuFeatures {
     }
 }
 
+impl MtuFeatures {
+    /// The features in declaration order, as fed to exported models
+    pub fn to_array(&self) -> [f64; 8] {
+        [
+            self.avg_rtt_ms,
+            self.avg_throughput_bps,
+            self.packet_loss_rate,
+            self.congestion_window as f64,
+            self.avg_packet_size as f64,
+            self.packet_size_stddev,
+            self.network_type as f64,
+            self.time_of_day,
+        ]
+    }
+}
+
 /// ML-based MTU prediction model interface
 pub trait MtuPredictionModel: Send + Sync {
     /// Predict optimal MTU based on network features
@@ -70,9 +89,36 @@ pub trait MtuPredictionModel: Send + Sync {
     
     /// Get model type name
     fn model_type(&self) -> &'static str;
+    
+    /// Save the model's learned state to a file
+    fn save(&self, _path: &Path) -> Result<()> {
+        Err(Error::MlModel(format!("{} model state cannot be saved", self.model_type())))
+    }
+    
+    /// Replace the model's learned state with one written by `save`
+    fn restore(&mut self, _path: &Path) -> Result<()> {
+        Err(Error::MlModel(format!("{} model state cannot be restored", self.model_type())))
+    }
+}
+
+/// Write a model's state to a file as JSON
+fn save_state<T: Serialize>(state: &T, path: &Path) -> Result<()> {
+    let json = serde_json::to_vec(state)
+        .map_err(|e| Error::MlModel(format!("Failed to serialize ML model: {}", e)))?;
+    std::fs::write(path, json)
+        .map_err(|e| Error::MlModel(format!("Failed to save ML model to {}: {}", path.display(), e)))
+}
+
+/// Read a model's state from a JSON file written by `save_state`
+fn load_state<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let json = std::fs::read(path)
+        .map_err(|e| Error::MlModel(format!("Failed to read ML model from {}: {}", path.display(), e)))?;
+    serde_json::from_slice(&json)
+        .map_err(|e| Error::MlModel(format!("Failed to parse ML model from {}: {}", path.display(), e)))
 }
 
 /// Simple rule-based MTU prediction model
+#[derive(Serialize, Deserialize)]
 pub struct SimpleRuleBasedModel {
     /// Base MTU
     base_mtu: usize,
@@ -97,6 +143,11 @@ impl SimpleRuleBasedModel {
             prediction_history: VecDeque::with_capacity(10),
         }
     }
+    
+    /// Load a model saved with `save`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        load_state(path.as_ref())
+    }
 }
 
 impl MtuPredictionModel for SimpleRuleBasedModel {
@@ -164,6 +215,198 @@ impl MtuPredictionModel for SimpleRuleBasedModel {
     fn model_type(&self) -> &'static str {
         "SimpleRuleBased"
     }
+    
+    fn save(&self, path: &Path) -> Result<()> {
+        save_state(self, path)
+    }
+    
+    fn restore(&mut self, path: &Path) -> Result<()> {
+        *self = Self::load(path)?;
+        Ok(())
+    }
+}
+
+/// Number of features the linear model is weighted over
+const LINEAR_FEATURES: usize = 8;
+
+/// MTU in bytes represented by 1.0 in the linear model's output
+const LINEAR_MTU_SCALE: f64 = 1000.0;
+
+/// Linear-regression MTU prediction model trained online
+///
+/// Features are scaled to roughly unit range before weighting so a single
+/// learning rate works for all of them.
+#[derive(Serialize, Deserialize)]
+pub struct LinearRegressionModel {
+    /// Weight of each scaled feature
+    weights: [f64; LINEAR_FEATURES],
+    
+    /// Intercept, in units of `LINEAR_MTU_SCALE`
+    bias: f64,
+    
+    /// Gradient descent step size
+    learning_rate: f64,
+    
+    /// Min MTU
+    min_mtu: usize,
+    
+    /// Max MTU
+    max_mtu: usize,
+}
+
+impl LinearRegressionModel {
+    /// Create a model that predicts `base_mtu` until it has been trained
+    pub fn new(base_mtu: usize, min_mtu: usize, max_mtu: usize) -> Self {
+        Self {
+            weights: [0.0; LINEAR_FEATURES],
+            bias: base_mtu as f64 / LINEAR_MTU_SCALE,
+            learning_rate: 0.05,
+            min_mtu,
+            max_mtu,
+        }
+    }
+    
+    /// Set the gradient descent step size
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+    
+    /// Load a model saved with `save`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        load_state(path.as_ref())
+    }
+    
+    /// Get the learned weights and intercept
+    pub fn weights(&self) -> (&[f64; LINEAR_FEATURES], f64) {
+        (&self.weights, self.bias)
+    }
+    
+    /// Scale the features to roughly unit range
+    fn inputs(features: &MtuFeatures) -> [f64; LINEAR_FEATURES] {
+        [
+            features.avg_rtt_ms / 100.0,
+            features.avg_throughput_bps / 10_000_000.0,
+            features.packet_loss_rate,
+            features.congestion_window as f64 / 100.0,
+            features.avg_packet_size as f64 / LINEAR_MTU_SCALE,
+            features.packet_size_stddev / LINEAR_MTU_SCALE,
+            features.network_type as f64,
+            features.time_of_day / 24.0,
+        ]
+    }
+    
+    /// Unclamped output for the scaled inputs
+    fn output(&self, inputs: &[f64; LINEAR_FEATURES]) -> f64 {
+        self.bias + self.weights.iter().zip(inputs).map(|(w, x)| w * x).sum::<f64>()
+    }
+}
+
+impl MtuPredictionModel for LinearRegressionModel {
+    fn predict(&self, features: &MtuFeatures) -> Result<usize> {
+        let mtu = self.output(&Self::inputs(features)) * LINEAR_MTU_SCALE;
+        
+        // A NaN output (from NaN features) falls back to the smallest MTU
+        let mtu = if mtu.is_nan() { 0 } else { mtu as usize };
+        Ok(mtu.clamp(self.min_mtu, self.max_mtu))
+    }
+    
+    fn update(&mut self, features: &MtuFeatures, actual_optimal_mtu: usize) -> Result<()> {
+        let inputs = Self::inputs(features);
+        if inputs.iter().any(|x| !x.is_finite()) {
+            warn!("Skipping linear model update with non-finite features");
+            return Ok(());
+        }
+        
+        // One step of gradient descent on the squared error
+        let error = self.output(&inputs) - actual_optimal_mtu as f64 / LINEAR_MTU_SCALE;
+        for (weight, input) in self.weights.iter_mut().zip(&inputs) {
+            *weight -= self.learning_rate * error * input;
+        }
+        self.bias -= self.learning_rate * error;
+        
+        Ok(())
+    }
+    
+    fn model_type(&self) -> &'static str {
+        "LinearRegression"
+    }
+    
+    fn save(&self, path: &Path) -> Result<()> {
+        save_state(self, path)
+    }
+    
+    fn restore(&mut self, path: &Path) -> Result<()> {
+        *self = Self::load(path)?;
+        Ok(())
+    }
+}
+
+/// MTU prediction model trained offline and exported to ONNX
+///
+/// The model takes a `[1, 8]` float tensor of the features in declaration
+/// order and returns the predicted MTU as its first output value. It is
+/// not retrained online, so `update` does nothing.
+#[cfg(feature = "onnx")]
+pub struct OnnxMtuModel {
+    /// Optimized, runnable model
+    model: tract_onnx::prelude::TypedRunnableModel<tract_onnx::prelude::TypedModel>,
+    
+    /// Min MTU
+    min_mtu: usize,
+    
+    /// Max MTU
+    max_mtu: usize,
+}
+
+#[cfg(feature = "onnx")]
+impl OnnxMtuModel {
+    /// Load a model from an ONNX file
+    pub fn load(path: impl AsRef<std::path::Path>, min_mtu: usize, max_mtu: usize) -> Result<Self> {
+        use tract_onnx::prelude::*;
+        
+        let path = path.as_ref();
+        let input_fact = InferenceFact::dt_shape(f32::datum_type(), tvec!(1, 8));
+        
+        let model = tract_onnx::onnx()
+            .model_for_path(path)
+            .and_then(|model| model.with_input_fact(0, input_fact))
+            .and_then(|model| model.into_optimized())
+            .and_then(|model| model.into_runnable())
+            .map_err(|e| crate::error::Error::MlModel(format!("Failed to load ONNX model {}: {}", path.display(), e)))?;
+        
+        Ok(Self { model, min_mtu, max_mtu })
+    }
+}
+
+#[cfg(feature = "onnx")]
+impl MtuPredictionModel for OnnxMtuModel {
+    fn predict(&self, features: &MtuFeatures) -> Result<usize> {
+        use tract_onnx::prelude::*;
+        
+        let values: Vec<f32> = features.to_array().iter().map(|&value| value as f32).collect();
+        let input = Tensor::from_shape(&[1, 8], &values)
+            .map_err(|e| crate::error::Error::MlModel(format!("Failed to build ONNX input: {}", e)))?;
+        
+        let outputs = self.model.run(tvec!(input.into()))
+            .map_err(|e| crate::error::Error::MlModel(format!("ONNX inference failed: {}", e)))?;
+        
+        let mtu = outputs.first()
+            .and_then(|output| output.as_slice::<f32>().ok()?.first().copied())
+            .ok_or_else(|| crate::error::Error::MlModel("ONNX model returned no MTU".to_string()))?;
+        
+        // A NaN prediction falls back to the smallest MTU
+        let mtu = if mtu.is_nan() { 0 } else { mtu as usize };
+        Ok(mtu.clamp(self.min_mtu, self.max_mtu))
+    }
+    
+    fn update(&mut self, _features: &MtuFeatures, _actual_optimal_mtu: usize) -> Result<()> {
+        Ok(())
+    }
+    
+    fn model_type(&self) -> &'static str {
+        "Onnx"
+    }
 }
 
 /// Python-based ML MTU prediction model
@@ -259,6 +502,33 @@ impl MtuPredictionModel for PythonMlModel {
     }
 }
 
+/// Loss rate implied by Interests sent and Data received, clamped to [0, 1]
+///
+/// More Data than Interests (pushed or forwarded Data) means no loss rather
+/// than a negative rate; a non-finite result is treated as no loss.
+fn packet_loss_rate(interests_sent: u64, data_received: u64) -> f64 {
+    let rate = 1.0 - (data_received as f64 / interests_sent as f64);
+    if rate.is_finite() {
+        rate.clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Fold data-plane counters from the XDP program into the features
+///
+/// The counters are cumulative, so these are averages over the program's
+/// lifetime; a signal whose counters are still zero keeps its previous value.
+fn apply_xdp_metrics(features: &mut MtuFeatures, metrics: &XdpMetrics) {
+    if metrics.packets_processed > 0 && metrics.bytes_processed > 0 {
+        features.avg_packet_size = (metrics.bytes_processed / metrics.packets_processed) as usize;
+    }
+    
+    if metrics.interests > 0 {
+        features.packet_loss_rate = packet_loss_rate(metrics.interests, metrics.data_packets);
+    }
+}
+
 /// ML-based MTU prediction service
 pub struct MtuPredictionService {
     /// The ML model used for prediction
@@ -278,6 +548,12 @@ pub struct MtuPredictionService {
     
     /// Callback for MTU updates
     update_callback: Arc<RwLock<Option<Box<dyn Fn(usize) -> Result<()> + Send + Sync>>>>,
+    
+    /// Task feeding XDP metrics into the features
+    xdp_task: RwLock<Option<JoinHandle<()>>>,
+    
+    /// File the model state is restored from on start and saved to on stop
+    state_path: Option<PathBuf>,
 }
 
 impl MtuPredictionService {
@@ -290,9 +566,17 @@ impl MtuPredictionService {
             running: Arc::new(RwLock::new(false)),
             prediction_task: RwLock::new(None),
             update_callback: Arc::new(RwLock::new(None)),
+            xdp_task: RwLock::new(None),
+            state_path: None,
         }
     }
     
+    /// Restore the model from `path` on start and save it there on stop
+    pub fn with_state_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_path = Some(path.into());
+        self
+    }
+    
     /// Create a new service with a rule-based model
     pub fn with_rule_based_model(base_mtu: usize, min_mtu: usize, max_mtu: usize, prediction_interval: u64) -> Self {
         let model = Box::new(SimpleRuleBasedModel::new(base_mtu, min_mtu, max_mtu));
@@ -307,6 +591,14 @@ impl MtuPredictionService {
         // Store the callback
         *self.update_callback.write().await = Some(Box::new(update_callback));
         
+        // Pick up where the model left off before the last stop
+        if let Some(path) = self.state_path.as_deref().filter(|path| path.exists()) {
+            match self.model.write().await.restore(path) {
+                Ok(()) => info!("Restored ML model state from {}", path.display()),
+                Err(e) => warn!("Failed to restore ML model state: {}", e),
+            }
+        }
+        
         // Set running flag
         *self.running.write().await = true;
         
@@ -376,30 +668,75 @@ impl MtuPredictionService {
             info!("MTU prediction service stopped");
         }
         
+        if let Some(task) = self.xdp_task.write().await.take() {
+            task.abort();
+        }
+        
+        if let Some(path) = self.state_path.as_deref() {
+            if let Err(e) = self.model.read().await.save(path) {
+                warn!("Failed to save ML model state: {}", e);
+            }
+        }
+        
         Ok(())
     }
     
-    /// Update network features from connection statistics
-    pub async fn update_features_from_stats(&self, stats: &ConnectionStats) -> Result<()> {
+    /// Update network features from connection statistics and the
+    /// connection's current congestion window
+    pub async fn update_features_from_stats(&self, stats: &ConnectionStats, congestion_window: usize) -> Result<()> {
         let mut features = self.features.write().await;
         
         // Use the avg_rtt_ms field directly from the updated ConnectionStats struct
-        features.avg_rtt_ms = stats.avg_rtt_ms;
+        // (a NaN or infinite average keeps the previous value)
+        if stats.avg_rtt_ms.is_finite() {
+            features.avg_rtt_ms = stats.avg_rtt_ms;
+        }
+        
+        // Throughput over the span the received bytes accumulated in
+        // (an empty span keeps the previous value)
+        let span = stats.last_activity.saturating_duration_since(stats.started_at).as_secs_f64();
+        if span > 0.0 {
+            features.avg_throughput_bps = stats.bytes_received as f64 * 8.0 / span;
+        }
         
-        // Calculate throughput based on data received and time (if available)
-        // For now, just use a placeholder calculation
-        features.avg_throughput_bps = (stats.data_received * 8) as f64; // Simple conversion from bytes to bits
+        if stats.data_received > 0 {
+            features.avg_packet_size = (stats.bytes_received / stats.data_received) as usize;
+        }
         
-        features.congestion_window = 0; // Will be filled in by caller
+        features.congestion_window = congestion_window;
         
         // Calculate packet loss rate based on interests sent vs data received
         if stats.interests_sent > 0 {
-            features.packet_loss_rate = 1.0 - (stats.data_received as f64 / stats.interests_sent as f64);
+            features.packet_loss_rate = packet_loss_rate(stats.interests_sent, stats.data_received);
         }
         
         Ok(())
     }
     
+    /// Update network features from XDP data-plane metrics
+    pub async fn update_features_from_xdp(&self, metrics: &XdpMetrics) -> Result<()> {
+        apply_xdp_metrics(&mut *self.features.write().await, metrics);
+        Ok(())
+    }
+    
+    /// Update the features from every XDP metrics snapshot published on
+    /// `metrics` until the service is stopped or the sender is dropped
+    pub async fn follow_xdp_metrics(&self, mut metrics: watch::Receiver<XdpMetrics>) {
+        let features = Arc::clone(&self.features);
+        
+        let task = tokio::spawn(async move {
+            while metrics.changed().await.is_ok() {
+                let snapshot = metrics.borrow_and_update().clone();
+                apply_xdp_metrics(&mut *features.write().await, &snapshot);
+            }
+            debug!("XDP metrics stream closed");
+        });
+        
+        if let Some(previous) = self.xdp_task.write().await.replace(task) {
+            previous.abort();
+        }
+    }
+    
     /// Set network type hint
     pub async fn set_network_type(&self, network_type: u8) -> Result<()> {
         let mut features = self.features.write().await;
@@ -432,3 +769,172 @@ impl MtuPredictionService {
         metrics
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test]
+    async fn test_packet_loss_rate_is_clamped() {
+        let service = MtuPredictionService::with_rule_based_model(1400, 576, 9000, 30);
+        
+        // More Data received than Interests sent (e.g. pushed Data)
+        let stats = ConnectionStats {
+            interests_sent: 4,
+            data_received: 10,
+            avg_rtt_ms: f64::NAN,
+            ..Default::default()
+        };
+        service.update_features_from_stats(&stats, 10).await.unwrap();
+        
+        let features = service.get_features().await;
+        assert_eq!(features.packet_loss_rate, 0.0);
+        assert_eq!(features.avg_rtt_ms, MtuFeatures::default().avg_rtt_ms);
+        
+        // Ordinary loss is unaffected
+        let stats = ConnectionStats {
+            interests_sent: 10,
+            data_received: 4,
+            ..Default::default()
+        };
+        service.update_features_from_stats(&stats, 10).await.unwrap();
+        assert!((service.get_features().await.packet_loss_rate - 0.6).abs() < 1e-9);
+    }
+    
+    #[tokio::test]
+    async fn test_throughput_uses_transfer_duration() {
+        let service = MtuPredictionService::with_rule_based_model(1400, 576, 9000, 30);
+        
+        // 2.5 MB in 2 seconds is 10 Mbps
+        let started_at = std::time::Instant::now();
+        let stats = ConnectionStats {
+            interests_sent: 2_000,
+            data_received: 2_000,
+            bytes_received: 2_500_000,
+            started_at,
+            last_activity: started_at + Duration::from_secs(2),
+            ..Default::default()
+        };
+        service.update_features_from_stats(&stats, 42).await.unwrap();
+        
+        let features = service.get_features().await;
+        assert!((features.avg_throughput_bps - 10_000_000.0).abs() < 1_000.0, "{}", features.avg_throughput_bps);
+        assert_eq!(features.avg_packet_size, 1_250);
+        assert_eq!(features.congestion_window, 42);
+        
+        // Nothing received over no time leaves the throughput alone
+        let stats = ConnectionStats {
+            started_at,
+            last_activity: started_at,
+            ..Default::default()
+        };
+        service.update_features_from_stats(&stats, 42).await.unwrap();
+        assert_eq!(service.get_features().await.avg_throughput_bps, features.avg_throughput_bps);
+    }
+    
+    #[test]
+    fn test_linear_model_learns_rtt_trend() {
+        let mut model = LinearRegressionModel::new(1400, 576, 9000);
+        let at_rtt = |avg_rtt_ms: f64| MtuFeatures { avg_rtt_ms, ..Default::default() };
+        
+        // Higher RTT calls for a lower MTU
+        for _ in 0..200 {
+            for rtt in (0..=300).step_by(30) {
+                let optimal = 1500 - 3 * rtt;
+                model.update(&at_rtt(rtt as f64), optimal).unwrap();
+            }
+        }
+        
+        let predictions: Vec<usize> = [10.0, 100.0, 200.0, 290.0]
+            .iter()
+            .map(|&rtt| model.predict(&at_rtt(rtt)).unwrap())
+            .collect();
+        assert!(predictions.windows(2).all(|pair| pair[0] > pair[1]), "{:?}", predictions);
+        assert!(model.weights().0[0] < 0.0);
+        
+        // Predictions stay within the MTU bounds
+        assert_eq!(model.predict(&at_rtt(10_000.0)).unwrap(), 576);
+    }
+    
+    #[cfg(feature = "onnx")]
+    #[test]
+    fn test_onnx_model_predicts_from_fixture() {
+        // The fixture computes 1400 - avg_rtt_ms
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/linear_rtt_mtu.onnx");
+        let model = OnnxMtuModel::load(path, 576, 9000).unwrap();
+        
+        let features = MtuFeatures { avg_rtt_ms: 100.0, ..Default::default() };
+        assert_eq!(model.predict(&features).unwrap(), 1300);
+        
+        let features = MtuFeatures { avg_rtt_ms: 2000.0, ..Default::default() };
+        assert_eq!(model.predict(&features).unwrap(), 576);
+        
+        assert!(OnnxMtuModel::load("/nonexistent.onnx", 576, 9000).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_linear_model_state_round_trip() {
+        let mut model = LinearRegressionModel::new(1400, 576, 9000);
+        for rtt in (0..=300).step_by(10) {
+            let features = MtuFeatures { avg_rtt_ms: rtt as f64, ..Default::default() };
+            model.update(&features, 1500 - 3 * rtt).unwrap();
+        }
+        
+        let path = std::env::temp_dir().join(format!("udcn-ml-state-{}.json", std::process::id()));
+        model.save(&path).unwrap();
+        let loaded = LinearRegressionModel::load(&path).unwrap();
+        
+        for rtt in [0.0, 75.0, 150.0, 280.0] {
+            let features = MtuFeatures { avg_rtt_ms: rtt, ..Default::default() };
+            assert_eq!(loaded.predict(&features).unwrap(), model.predict(&features).unwrap());
+        }
+        assert_eq!(loaded.weights(), model.weights());
+        
+        // A service restores the saved state when it starts
+        let service = MtuPredictionService::new(Box::new(LinearRegressionModel::new(1400, 576, 9000)), 3600)
+            .with_state_path(&path);
+        service.start(|_| Ok(())).await.unwrap();
+        let features = MtuFeatures { avg_rtt_ms: 280.0, ..Default::default() };
+        assert_eq!(service.model.read().await.predict(&features).unwrap(), model.predict(&features).unwrap());
+        service.stop().await.unwrap();
+        
+        let _ = std::fs::remove_file(&path);
+    }
+    
+    #[tokio::test]
+    async fn test_features_follow_xdp_metrics() {
+        let service = MtuPredictionService::with_rule_based_model(1400, 576, 9000, 30);
+        let (tx, rx) = watch::channel(XdpMetrics::default());
+        service.follow_xdp_metrics(rx).await;
+        
+        tx.send(XdpMetrics {
+            packets_processed: 100,
+            bytes_processed: 80_000,
+            interests: 50,
+            data_packets: 40,
+            ..Default::default()
+        }).unwrap();
+        
+        // Wait for the feed task to pick up the snapshot
+        let mut features = service.get_features().await;
+        for _ in 0..100 {
+            if features.avg_packet_size == 800 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            features = service.get_features().await;
+        }
+        assert_eq!(features.avg_packet_size, 800);
+        assert!((features.packet_loss_rate - 0.2).abs() < 1e-9);
+        
+        // Stopping the service stops following the stream
+        service.stop().await.unwrap();
+        let _ = tx.send(XdpMetrics {
+            packets_processed: 100,
+            bytes_processed: 20_000,
+            ..Default::default()
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(service.get_features().await.avg_packet_size, 800);
+    }
+}
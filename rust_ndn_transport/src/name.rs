@@ -5,6 +5,7 @@
 // Names in NDN are hierarchical and consist of components.
 //
 
+use std::cmp::Ordering;
 use std::fmt;
 use std::str::FromStr;
 use std::hash::{Hash, Hasher};
@@ -14,11 +15,111 @@ use bytes::{Buf, BufMut, Bytes, BytesMut};
 use sha2::{Sha256, Digest};
 
 use crate::error::Error;
+use crate::ndn::tlv;
 use crate::Result;
 
+/// TLV type of a generic name component
+pub const GENERIC_COMPONENT: u8 = 0x08;
+
+/// TLV type of an ImplicitSha256Digest name component
+pub const IMPLICIT_SHA256_DIGEST_COMPONENT: u8 = 0x01;
+
+/// TLV type of a segment number name component
+pub const SEGMENT_COMPONENT: u8 = 0x32;
+
+/// TLV type of a version name component
+pub const VERSION_COMPONENT: u8 = 0x36;
+
+/// URI prefix of an ImplicitSha256Digest component
+const IMPLICIT_DIGEST_URI_PREFIX: &str = "sha256digest=";
+
+/// URI prefix of a segment number component
+const SEGMENT_URI_PREFIX: &str = "seg=";
+
+/// URI prefix of a version component
+const VERSION_URI_PREFIX: &str = "v=";
+
+/// Encode a number as an NDN NonNegativeInteger (1, 2, 4 or 8 bytes)
+fn encode_non_negative_integer(number: u64) -> Bytes {
+    if number <= u8::MAX as u64 {
+        Bytes::copy_from_slice(&[number as u8])
+    } else if number <= u16::MAX as u64 {
+        Bytes::copy_from_slice(&(number as u16).to_be_bytes())
+    } else if number <= u32::MAX as u64 {
+        Bytes::copy_from_slice(&(number as u32).to_be_bytes())
+    } else {
+        Bytes::copy_from_slice(&number.to_be_bytes())
+    }
+}
+
+/// Decode an NDN NonNegativeInteger
+fn decode_non_negative_integer(mut value: &[u8]) -> Option<u64> {
+    match value.len() {
+        1 | 2 | 4 | 8 => Some(value.get_uint(value.len())),
+        _ => None,
+    }
+}
+
+/// Check if a byte is left unescaped in an NDN URI
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Write a component value percent-encoded
+///
+/// Values made only of periods get three more, as `.` and `..` are not valid
+/// components and `...` stands for the empty value.
+fn write_escaped(f: &mut fmt::Formatter<'_>, value: &[u8]) -> fmt::Result {
+    if value.iter().all(|&b| b == b'.') {
+        f.write_str("...")?;
+    }
+    
+    for &b in value {
+        if is_unreserved(b) {
+            write!(f, "{}", b as char)?;
+        } else {
+            write!(f, "%{:02X}", b)?;
+        }
+    }
+    Ok(())
+}
+
+/// Decode a percent-encoded component value
+fn unescape(s: &str) -> Result<Vec<u8>> {
+    let bytes = s.as_bytes();
+    let mut value = Vec::with_capacity(bytes.len());
+    
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)
+                .ok_or_else(|| Error::NameParsing(format!("Truncated escape in {}", s)))?;
+            let b = u8::from_str_radix(hex, 16)
+                .map_err(|_| Error::NameParsing(format!("Invalid escape %{} in {}", hex, s)))?;
+            value.push(b);
+            i += 3;
+        } else {
+            value.push(bytes[i]);
+            i += 1;
+        }
+    }
+    
+    if value.iter().all(|&b| b == b'.') {
+        if value.len() < 3 {
+            return Err(Error::NameParsing(format!("Invalid component: {}", s)));
+        }
+        value.truncate(value.len() - 3);
+    }
+    
+    Ok(value)
+}
+
 /// A component in an NDN name
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Component {
+    /// The TLV type of the component
+    typ: u8,
+    
     /// The value of the component
     value: Bytes,
 }
@@ -26,7 +127,51 @@ pub struct Component {
 impl Component {
     /// Create a new component from bytes
     pub fn new(value: impl Into<Bytes>) -> Self {
-        Self { value: value.into() }
+        Self { typ: GENERIC_COMPONENT, value: value.into() }
+    }
+    
+    /// Create an ImplicitSha256Digest component
+    pub fn implicit_digest(digest: [u8; 32]) -> Self {
+        Self {
+            typ: IMPLICIT_SHA256_DIGEST_COMPONENT,
+            value: Bytes::copy_from_slice(&digest),
+        }
+    }
+    
+    /// Get the TLV type of the component
+    pub fn typ(&self) -> u8 {
+        self.typ
+    }
+    
+    /// Create a segment number component
+    pub fn segment(segment: u64) -> Self {
+        Self { typ: SEGMENT_COMPONENT, value: encode_non_negative_integer(segment) }
+    }
+    
+    /// Create a version component
+    pub fn version(version: u64) -> Self {
+        Self { typ: VERSION_COMPONENT, value: encode_non_negative_integer(version) }
+    }
+    
+    /// Check if the component is an ImplicitSha256Digest
+    pub fn is_implicit_digest(&self) -> bool {
+        self.typ == IMPLICIT_SHA256_DIGEST_COMPONENT
+    }
+    
+    /// Get the segment number if this is a segment component
+    pub fn to_segment(&self) -> Option<u64> {
+        match self.typ {
+            SEGMENT_COMPONENT => decode_non_negative_integer(&self.value),
+            _ => None,
+        }
+    }
+    
+    /// Get the version if this is a version component
+    pub fn to_version(&self) -> Option<u64> {
+        match self.typ {
+            VERSION_COMPONENT => decode_non_negative_integer(&self.value),
+            _ => None,
+        }
     }
     
     /// Create a new component from a string
@@ -51,84 +196,77 @@ impl Component {
     
     /// Encode the component as TLV
     pub fn to_tlv(&self) -> BytesMut {
-        let mut buf = BytesMut::with_capacity(2 + self.len());
-        
-        // Type (8 = NameComponent)
-        buf.put_u8(8);
-        
-        // Length
-        buf.put_u8(self.len() as u8);
-        
-        // Value
-        buf.extend_from_slice(&self.value);
-        
+        let mut buf = BytesMut::with_capacity(tlv::tlv_size(self.len()));
+        tlv::write_tlv(&mut buf, self.typ, &self.value);
         buf
     }
     
     /// Decode a component from TLV
     pub fn from_tlv(buf: &mut Bytes) -> Result<Self> {
-        // Check if we have at least 2 bytes (type + length)
-        if buf.len() < 2 {
-            return Err(Error::TlvParsing("Buffer too short for component TLV".into()));
-        }
-        
-        // Type
-        let typ = buf.get_u8();
-        if typ != 8 {
-            return Err(Error::TlvParsing(format!("Unexpected component type: {}", typ)));
-        }
+        let (typ, value) = tlv::read_tlv(buf)?;
         
-        // Length
-        let len = buf.get_u8() as usize;
+        let typ = match u8::try_from(typ) {
+            Ok(typ @ (GENERIC_COMPONENT | IMPLICIT_SHA256_DIGEST_COMPONENT
+                      | SEGMENT_COMPONENT | VERSION_COMPONENT)) => typ,
+            _ => return Err(Error::TlvParsing(format!("Unexpected component type: {}", typ))),
+        };
         
-        // Check if we have enough bytes for the value
-        if buf.len() < len {
-            return Err(Error::TlvParsing("Buffer too short for component value".into()));
+        if typ == IMPLICIT_SHA256_DIGEST_COMPONENT && value.len() != 32 {
+            return Err(Error::TlvParsing(format!("Invalid implicit digest length: {}", value.len())));
         }
         
-        // Value
-        let value = buf.split_to(len);
-        
-        Ok(Self::new(value))
+        Ok(Self { typ, value })
     }
 }
 
 impl fmt::Debug for Component {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Try to display as UTF-8 if possible
-        match std::str::from_utf8(&self.value) {
-            Ok(s) => write!(f, "{}", s),
-            Err(_) => {
-                // Otherwise, display as hex
-                write!(f, "0x")?;
-                for b in &self.value {
-                    write!(f, "{:02x}", b)?;
-                }
-                Ok(())
-            }
-        }
+        fmt::Display::fmt(self, f)
     }
 }
 
+/// Components display in NDN URI form: typed components by their convention
+/// (`seg=5`, `v=12345`), generic ones percent-encoded
 impl fmt::Display for Component {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Try to display as UTF-8 if possible
-        match std::str::from_utf8(&self.value) {
-            Ok(s) => write!(f, "{}", s),
-            Err(_) => {
-                // Otherwise, display as hex
-                write!(f, "0x")?;
-                for b in &self.value {
-                    write!(f, "{:02x}", b)?;
-                }
-                Ok(())
-            }
+        if self.is_implicit_digest() {
+            return write!(f, "{}{}", IMPLICIT_DIGEST_URI_PREFIX, hex::encode(&self.value));
+        }
+        if let Some(segment) = self.to_segment() {
+            return write!(f, "{}{}", SEGMENT_URI_PREFIX, segment);
         }
+        if let Some(version) = self.to_version() {
+            return write!(f, "{}{}", VERSION_URI_PREFIX, version);
+        }
+        
+        if self.typ != GENERIC_COMPONENT {
+            write!(f, "{}=", self.typ)?;
+        }
+        write_escaped(f, &self.value)
+    }
+}
+
+/// Components follow NDN canonical order: by TLV type, then shorter values
+/// first, then byte by byte
+impl Ord for Component {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.typ.cmp(&other.typ)
+            .then_with(|| self.value.len().cmp(&other.value.len()))
+            .then_with(|| self.value.cmp(&other.value))
+    }
+}
+
+impl PartialOrd for Component {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 /// An NDN name is a sequence of components
-#[derive(Clone, PartialEq, Eq)]
+///
+/// Names compare in NDN canonical order, component by component, so a name
+/// sorts right before the names it is a prefix of: `/a` < `/a/b` < `/a/c`.
+#[derive(Clone)]
 pub struct Name {
     /// The components of the name
     components: Vec<Component>,
@@ -165,15 +303,32 @@ impl Name {
         // Split the URI into components
         let components: Vec<Component> = uri.split('/')
             .filter(|s| !s.is_empty()) // Skip empty components
-            .map(Component::from_str)
-            .collect();
+            .map(Self::parse_component)
+            .collect::<Result<_>>()?;
         
-        let mut name = Self {
-            components,
-            cached_string: String::new(),
-        };
-        name.cached_string = uri.to_string();
-        Ok(name)
+        Ok(Self::from_components(components))
+    }
+    
+    /// Parse a single URI component, percent-decoding generic ones
+    fn parse_component(s: &str) -> Result<Component> {
+        if let Some(digest) = s.strip_prefix(IMPLICIT_DIGEST_URI_PREFIX) {
+            let bytes = hex::decode(digest)
+                .map_err(|e| Error::NameParsing(format!("Invalid implicit digest {}: {}", s, e)))?;
+            let digest: [u8; 32] = bytes.try_into()
+                .map_err(|_| Error::NameParsing(format!("Implicit digest must be 32 bytes: {}", s)))?;
+            return Ok(Component::implicit_digest(digest));
+        }
+        
+        let number = |value: &str| value.parse::<u64>()
+            .map_err(|e| Error::NameParsing(format!("Invalid number in {}: {}", s, e)));
+        if let Some(segment) = s.strip_prefix(SEGMENT_URI_PREFIX) {
+            return Ok(Component::segment(number(segment)?));
+        }
+        if let Some(version) = s.strip_prefix(VERSION_URI_PREFIX) {
+            return Ok(Component::version(number(version)?));
+        }
+        
+        Ok(Component::new(unescape(s)?))
     }
     
     /// Update the cached string representation
@@ -200,6 +355,26 @@ impl Name {
         self.push(Component::from_str(s));
     }
     
+    /// Add a segment number component to the name
+    pub fn append_segment(&mut self, segment: u64) {
+        self.push(Component::segment(segment));
+    }
+    
+    /// Add a version component to the name
+    pub fn append_version(&mut self, version: u64) {
+        self.push(Component::version(version));
+    }
+    
+    /// Add an implicit SHA-256 digest component to the name
+    pub fn append_implicit_digest(&mut self, digest: [u8; 32]) {
+        self.push(Component::implicit_digest(digest));
+    }
+    
+    /// Get the name in percent-encoded NDN URI form
+    pub fn to_uri(&self) -> String {
+        self.cached_string.clone()
+    }
+    
     /// Get the components of the name
     pub fn components(&self) -> &[Component] {
         &self.components
@@ -220,6 +395,32 @@ impl Name {
         self.components.get(index)
     }
     
+    /// Get the component at an index
+    pub fn at(&self, index: usize) -> Option<&Component> {
+        self.get(index)
+    }
+    
+    /// Get the name made of the first `n` components (the whole name if it is shorter)
+    pub fn get_prefix(&self, n: usize) -> Name {
+        Name::from_components(self.components[..n.min(self.len())].to_vec())
+    }
+    
+    /// Get the ImplicitSha256Digest if it is the last component of the name
+    pub fn implicit_digest(&self) -> Option<&[u8]> {
+        self.components
+            .last()
+            .filter(|comp| comp.is_implicit_digest())
+            .map(|comp| comp.value.as_ref())
+    }
+    
+    /// Get the name without a trailing ImplicitSha256Digest
+    pub fn without_implicit_digest(&self) -> Name {
+        match self.implicit_digest() {
+            Some(_) => Name::from_components(self.components[..self.len() - 1].to_vec()),
+            None => self.clone(),
+        }
+    }
+    
     /// Compute a hash of the name
     pub fn digest(&self) -> [u8; 32] {
         let mut hasher = Sha256::new();
@@ -251,19 +452,16 @@ impl Name {
     
     /// Encode the name as TLV
     pub fn to_tlv(&self) -> BytesMut {
-        let mut buf = BytesMut::new();
-        
         // Compute the total length of the components
         let mut components_len = 0;
         for comp in &self.components {
-            components_len += 2 + comp.len(); // type + length + value
+            components_len += tlv::tlv_size(comp.len());
         }
         
-        // Type (7 = Name)
-        buf.put_u8(7);
+        let mut buf = BytesMut::with_capacity(tlv::tlv_size(components_len));
         
-        // Length
-        buf.put_u8(components_len as u8);
+        // Type (7 = Name) and length
+        tlv::write_tlv_header(&mut buf, 7, components_len);
         
         // Components
         for comp in &self.components {
@@ -275,27 +473,12 @@ impl Name {
     
     /// Decode a name from TLV
     pub fn from_tlv(buf: &mut Bytes) -> Result<Self> {
-        // Check if we have at least 2 bytes (type + length)
-        if buf.len() < 2 {
-            return Err(Error::TlvParsing("Buffer too short for name TLV".into()));
-        }
-        
-        // Type
-        let typ = buf.get_u8();
+        // Value (components)
+        let (typ, mut components_buf) = tlv::read_tlv(buf)?;
         if typ != 7 {
             return Err(Error::TlvParsing(format!("Unexpected name type: {}", typ)));
         }
         
-        // Length
-        let len = buf.get_u8() as usize;
-        
-        // Check if we have enough bytes for the value
-        if buf.len() < len {
-            return Err(Error::TlvParsing("Buffer too short for name value".into()));
-        }
-        
-        // Value (components)
-        let mut components_buf = buf.split_to(len);
         let mut components = Vec::new();
         
         while components_buf.has_remaining() {
@@ -337,6 +520,28 @@ impl FromStr for Name {
     }
 }
 
+// Equality and ordering ignore the cached string, which depends on how the
+// name was built
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        self.components == other.components
+    }
+}
+
+impl Eq for Name {}
+
+impl Ord for Name {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.components.cmp(&other.components)
+    }
+}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Hash for Name {
     fn hash<H: Hasher>(&self, state: &mut H) {
         for comp in &self.components {
@@ -358,3 +563,95 @@ impl Deref for Name {
         &self.components
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn name(uri: &str) -> Name {
+        Name::from_uri(uri).unwrap()
+    }
+    
+    #[test]
+    fn test_canonical_order() {
+        // A name sorts before the longer names it is a prefix of
+        assert!(name("/") < name("/a"));
+        assert!(name("/a") < name("/a/b"));
+        assert!(name("/a/b") < name("/a/c"));
+        assert!(name("/a/c") < name("/b"));
+        
+        // Shorter components sort first, equal-length ones by byte content
+        assert!(name("/b") < name("/aa"));
+        assert!(name("/ab") < name("/ba"));
+        assert!(Component::new(vec![0x01, 0xff]) < Component::new(vec![0x02, 0x00]));
+        
+        // A digest component has a lower TLV type than a generic one
+        assert!(Component::implicit_digest([0xff; 32]) < Component::new("a"));
+        
+        // Sorting is stable across differently built but equal names
+        let mut names = vec![name("/a/c"), name("/b"), name("/a"), name("/a/b")];
+        names.sort();
+        assert_eq!(names, vec![name("/a"), name("/a/b"), name("/a/c"), name("/b")]);
+        assert_eq!(name("/a/b/"), name("/a/b").get_prefix(5));
+        assert_eq!(name("/a/b/").cmp(&name("/a/b")), Ordering::Equal);
+    }
+    
+    #[test]
+    fn test_prefix_and_component_access() {
+        let full = name("/udcn/video/seg=1");
+        
+        assert_eq!(full.get_prefix(0), name("/"));
+        assert_eq!(full.get_prefix(2), name("/udcn/video"));
+        assert_eq!(full.get_prefix(10), full);
+        assert!(full.starts_with(&full.get_prefix(1)));
+        
+        assert_eq!(full.at(1), Some(&Component::from_str("video")));
+        assert_eq!(full.at(3), None);
+    }
+    
+    #[test]
+    fn test_uri_percent_encoding_round_trip() {
+        // An escaped slash stays inside its component
+        let slash = name("/a%2Fb/c");
+        assert_eq!(slash.len(), 2);
+        assert_eq!(slash.at(0).unwrap().value().as_ref(), b"a/b");
+        assert_eq!(slash.to_uri(), "/a%2Fb/c");
+        
+        // Binary and reserved bytes are escaped as uppercase hex
+        let binary = Name::from_components(vec![
+            Component::new(vec![0x00, 0xff, b' ', b'~']),
+            Component::new(""),
+            Component::new(".."),
+        ]);
+        assert_eq!(binary.to_string(), "/%00%FF%20~/.../.....");
+        assert_eq!(name(&binary.to_uri()), binary);
+        assert_eq!(name("/%e2%82%AC").at(0).unwrap().value().as_ref(), "€".as_bytes());
+        
+        // Malformed escapes and bare dot components are rejected
+        assert!(Name::from_uri("/a%2").is_err());
+        assert!(Name::from_uri("/a%zz").is_err());
+        assert!(Name::from_uri("/a/..").is_err());
+    }
+    
+    #[test]
+    fn test_segment_and_version_components() {
+        let mut versioned = name("/udcn/video");
+        versioned.append_version(1_700_000_000_000);
+        versioned.append_segment(0);
+        
+        // Typed components are encoded as NonNegativeIntegers
+        let version = versioned.at(2).unwrap();
+        assert_eq!(version.typ(), VERSION_COMPONENT);
+        assert_eq!(version.value().len(), 8);
+        assert_eq!(version.to_version(), Some(1_700_000_000_000));
+        assert_eq!(version.to_segment(), None);
+        assert_eq!(Component::segment(256).value().as_ref(), &[0x01, 0x00]);
+        
+        // They print in URI convention and parse back to the same TLVs
+        assert_eq!(versioned.to_uri(), "/udcn/video/v=1700000000000/seg=0");
+        assert_eq!(name(&versioned.to_uri()), versioned);
+        assert_eq!(Name::from_tlv(&mut versioned.to_tlv().freeze()).unwrap(), versioned);
+        assert_ne!(name("/udcn/seg=1"), name("/udcn/seg%3D1"));
+        assert!(Name::from_uri("/udcn/seg=x").is_err());
+    }
+}
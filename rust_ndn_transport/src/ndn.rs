@@ -8,11 +8,23 @@ use std::fmt;
 use std::time::Duration;
 
 use bytes::{Buf, BufMut, Bytes, BytesMut};
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
 
 use crate::error::Error;
-use crate::name::Name;
+use crate::fragmentation::{Fragment, FRAGMENT_MAGIC};
+use crate::name::{Component, Name};
+use crate::security::{decode_signature_info, encode_interest_signature_info, encode_signature_info, now_ms, KeyPair, PublicKey, SIGNATURE_ED25519};
 use crate::Result;
 
+pub mod prefix_ann;
+pub mod compact;
+pub mod pool;
+pub mod integrity;
+pub mod tlv;
+
+use self::pool::BufferPool;
+
 /// NDN TLV types
 pub mod tlv_type {
     pub const INTEREST: u8 = 0x05;
@@ -21,13 +33,46 @@ pub mod tlv_type {
     pub const NAME: u8 = 0x07;
     pub const NAME_COMPONENT: u8 = 0x08;
     pub const SELECTORS: u8 = 0x09;
+    pub const CAN_BE_PREFIX: u8 = 0x21;
+    pub const MUST_BE_FRESH: u8 = 0x12;
     pub const NONCE: u8 = 0x0A;
+    pub const FORWARDING_HINT: u8 = 0x1E;
     pub const INTEREST_LIFETIME: u8 = 0x0C;
     pub const META_INFO: u8 = 0x14;
+    pub const FRESHNESS_PERIOD: u8 = 0x19;
+    pub const FINAL_BLOCK_ID: u8 = 0x1A;
     pub const CONTENT: u8 = 0x15;
     pub const SIGNATURE_INFO: u8 = 0x16;
     pub const SIGNATURE_VALUE: u8 = 0x17;
+    pub const INTEREST_SIGNATURE_INFO: u8 = 0x2C;
+    pub const INTEREST_SIGNATURE_VALUE: u8 = 0x2E;
     pub const NACK_REASON: u8 = 0x0F;
+    /// Application-defined, non-critical (even and at least 32): peers that
+    /// don't know it skip it
+    pub const INTEREST_CONTEXT: u8 = 0x80;
+    /// Application-defined, non-critical: W3C traceparent of the span that sent the Interest
+    pub const TRACE_PARENT: u8 = 0x85;
+    /// Trailing CRC32 appended by the integrity self-test mode
+    pub const INTEGRITY_CHECK: u8 = 0x83;
+}
+
+/// NDNLPv2 link protocol TLV types
+pub mod lp_type {
+    pub const LP_PACKET: u64 = 0x64;
+    pub const FRAGMENT: u64 = 0x50;
+    pub const NACK: u64 = 0x0320;
+    pub const NACK_REASON: u64 = 0x0321;
+}
+
+/// Packet encodings understood by the transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    /// NDN TLV encoding used by the transport
+    #[default]
+    Ndn,
+    
+    /// Simplified TLV used by the standalone quic_ndn_test tool
+    Compact,
 }
 
 /// An NDN Interest packet
@@ -42,11 +87,26 @@ pub struct Interest {
     /// Random nonce for loop detection
     nonce: u32,
     
-    /// Whether the interest can be satisfied from cache
+    /// Whether Data with a longer name can satisfy the Interest
     can_be_prefix: bool,
     
-    /// Whether the interest must be forwarded
+    /// Whether only fresh (not stale) cached Data can satisfy the Interest
     must_be_fresh: bool,
+    
+    /// Delegation name to route by when no FIB entry matches the name
+    forwarding_hint: Option<Name>,
+    
+    /// Opaque application context (e.g. a trace context) carried end to end
+    context: Option<Bytes>,
+    
+    /// W3C traceparent of the span that sent the Interest, replaced at each hop
+    traceparent: Option<String>,
+    
+    /// InterestSignatureInfo value, empty when unsigned
+    signature_info: Vec<u8>,
+    
+    /// InterestSignatureValue, empty when unsigned
+    signature_value: Vec<u8>,
 }
 
 impl Interest {
@@ -58,6 +118,11 @@ impl Interest {
             nonce: rand::random(),
             can_be_prefix: false,
             must_be_fresh: true,
+            forwarding_hint: None,
+            context: None,
+            traceparent: None,
+            signature_info: Vec::new(),
+            signature_value: Vec::new(),
         }
     }
     
@@ -79,6 +144,40 @@ impl Interest {
         self
     }
     
+    /// Set the nonce
+    pub fn with_nonce(mut self, nonce: u32) -> Self {
+        self.nonce = nonce;
+        self
+    }
+    
+    /// Set the forwarding hint (delegation name)
+    pub fn with_forwarding_hint(mut self, hint: Name) -> Self {
+        self.forwarding_hint = Some(hint);
+        self
+    }
+    
+    /// Attach an opaque context that is carried with the Interest
+    pub fn with_context(mut self, context: impl Into<Bytes>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+    
+    /// Get the context attached to the Interest, if any
+    pub fn context(&self) -> Option<&Bytes> {
+        self.context.as_ref()
+    }
+    
+    /// Set the W3C traceparent of the span sending the Interest
+    pub fn with_traceparent(mut self, traceparent: impl Into<String>) -> Self {
+        self.traceparent = Some(traceparent.into());
+        self
+    }
+    
+    /// Get the W3C traceparent carried by the Interest, if any
+    pub fn traceparent(&self) -> Option<&str> {
+        self.traceparent.as_deref()
+    }
+    
     /// Get the name of the Interest
     pub fn name(&self) -> &Name {
         &self.name
@@ -94,27 +193,159 @@ impl Interest {
         self.nonce
     }
     
+    /// Get the can_be_prefix flag
+    pub fn get_can_be_prefix(&self) -> bool {
+        self.can_be_prefix
+    }
+    
+    /// Check whether a Data packet satisfies this Interest: its name is the
+    /// Interest name, or starts with it when CanBePrefix is set
+    pub fn matches_data(&self, data: &Data) -> bool {
+        if self.can_be_prefix {
+            data.name().starts_with(&self.name)
+        } else {
+            data.name() == &self.name
+        }
+    }
+    
+    /// Get the must_be_fresh flag
+    pub fn get_must_be_fresh(&self) -> bool {
+        self.must_be_fresh
+    }
+    
+    /// Get the forwarding hint, if any
+    pub fn forwarding_hint(&self) -> Option<&Name> {
+        self.forwarding_hint.as_ref()
+    }
+    
+    /// Get the raw InterestSignatureInfo value, empty when unsigned
+    pub fn signature_info(&self) -> &[u8] {
+        &self.signature_info
+    }
+    
+    /// Get the raw InterestSignatureValue, empty when unsigned
+    pub fn signature_value(&self) -> &[u8] {
+        &self.signature_value
+    }
+    
+    /// Check whether the Interest carries a signature
+    pub fn is_signed(&self) -> bool {
+        !self.signature_value.is_empty()
+    }
+    
+    /// Get the portion of the Interest covered by the signature
+    /// (Name and InterestSignatureInfo TLVs)
+    ///
+    /// The nonce and lifetime are left out, as forwarders may change them;
+    /// the SignatureTime and SignatureNonce in the InterestSignatureInfo
+    /// stand in for them against replays.
+    pub fn signed_portion(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(&self.name.to_tlv());
+        tlv::write_tlv(&mut buf, tlv_type::INTEREST_SIGNATURE_INFO, &self.signature_info);
+        buf.freeze()
+    }
+    
+    /// Sign the Interest with an Ed25519 key
+    ///
+    /// The InterestSignatureInfo carries the signature type, the key name
+    /// as its KeyLocator, the current time and a random SignatureNonce, and
+    /// is itself covered by the signature.
+    pub fn sign(mut self, key: &KeyPair) -> Self {
+        let nonce: [u8; 8] = rand::random();
+        self.signature_info = encode_interest_signature_info(key.name(), now_ms(), &nonce);
+        self.signature_value = key.sign(&self.signed_portion());
+        self
+    }
+    
+    /// Verify the Interest's signature with a public key
+    pub fn verify(&self, key: &PublicKey) -> Result<()> {
+        match decode_signature_info(&self.signature_info) {
+            Some((SIGNATURE_ED25519, _)) => {}
+            Some((signature_type, _)) => {
+                return Err(Error::SignatureVerification(format!(
+                    "Unsupported signature type: {}", signature_type)));
+            }
+            None => return Err(Error::SignatureVerification("Missing InterestSignatureInfo".into())),
+        }
+        
+        key.verify(&self.signed_portion(), &self.signature_value)
+    }
+    
     /// Encode the Interest as TLV
     pub fn to_bytes(&self) -> Bytes {
-        let mut buf = BytesMut::new();
+        #[cfg(feature = "buffer-pool")]
+        return pool::with_local_pool(|pool| self.to_bytes_pooled(pool));
         
+        #[cfg(not(feature = "buffer-pool"))]
+        {
+            let mut buf = BytesMut::new();
+            self.encode_into(&mut buf);
+            buf.freeze()
+        }
+    }
+    
+    /// Encode the Interest as TLV into a buffer taken from the pool
+    pub fn to_bytes_pooled(&self, pool: &BufferPool) -> Bytes {
+        pool.encode(|buf| self.encode_into(buf))
+    }
+    
+    /// Append the TLV encoding of the Interest to the buffer
+    pub(crate) fn encode_into(&self, buf: &mut BytesMut) {
         // Compute the size of the Interest
         let name_tlv = self.name.to_tlv();
         let name_size = name_tlv.len();
         
+        // selectors (empty TLVs, present only when set)
+        let selectors_size = [self.can_be_prefix, self.must_be_fresh]
+            .iter()
+            .filter(|set| **set)
+            .count() * tlv::tlv_size(0);
+        
+        // forwarding hint (optional, wraps the delegation Name)
+        let hint_tlv = self.forwarding_hint.as_ref().map(|hint| hint.to_tlv());
+        let hint_size = hint_tlv.as_ref().map_or(0, |hint| tlv::tlv_size(hint.len()));
+        
         // nonce (4 bytes)
-        let nonce_size = 2 + 4; // type + length + value
+        let nonce_size = tlv::tlv_size(4);
         
         // lifetime (variable, but we'll use 2 bytes)
-        let lifetime_size = 2 + 2; // type + length + value
+        let lifetime_size = tlv::tlv_size(2);
+        
+        // signature (present only when signed)
+        let signature_size = if self.is_signed() {
+            tlv::tlv_size(self.signature_info.len()) + tlv::tlv_size(self.signature_value.len())
+        } else {
+            0
+        };
+        
+        // context (optional)
+        let context_size = self.context.as_ref().map_or(0, |ctx| tlv::tlv_size(ctx.len()));
+        
+        // traceparent (optional)
+        let traceparent_size = self.traceparent.as_ref().map_or(0, |tp| tlv::tlv_size(tp.len()));
         
         // Interest TLV
-        buf.put_u8(tlv_type::INTEREST);
-        buf.put_u8((name_size + nonce_size + lifetime_size) as u8);
+        tlv::write_tlv_header(buf, tlv_type::INTEREST,
+            name_size + selectors_size + hint_size + nonce_size + lifetime_size + signature_size
+                + context_size + traceparent_size);
         
         // Name
         buf.extend_from_slice(&name_tlv);
         
+        // Selectors
+        if self.can_be_prefix {
+            tlv::write_tlv_header(buf, tlv_type::CAN_BE_PREFIX, 0);
+        }
+        if self.must_be_fresh {
+            tlv::write_tlv_header(buf, tlv_type::MUST_BE_FRESH, 0);
+        }
+        
+        // Forwarding hint
+        if let Some(hint) = &hint_tlv {
+            tlv::write_tlv(buf, tlv_type::FORWARDING_HINT, hint);
+        }
+        
         // Nonce
         buf.put_u8(tlv_type::NONCE);
         buf.put_u8(4); // 4 bytes
@@ -125,34 +356,53 @@ impl Interest {
         buf.put_u8(2); // 2 bytes
         buf.put_u16(self.lifetime_ms as u16);
         
-        buf.freeze()
-    }
-    
-    /// Decode an Interest from TLV
-    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
-        let mut bytes = Bytes::copy_from_slice(buf);
-        
-        // Check if we have at least 2 bytes (type + length)
-        if bytes.len() < 2 {
-            return Err(Error::TlvParsing("Buffer too short for Interest TLV".into()));
+        // Signature
+        if self.is_signed() {
+            tlv::write_tlv(buf, tlv_type::INTEREST_SIGNATURE_INFO, &self.signature_info);
+            tlv::write_tlv(buf, tlv_type::INTEREST_SIGNATURE_VALUE, &self.signature_value);
         }
         
-        // Type
-        let typ = bytes.get_u8();
-        if typ != tlv_type::INTEREST {
-            return Err(Error::TlvParsing(format!("Unexpected TLV type: {}", typ)));
+        // Context
+        if let Some(context) = &self.context {
+            tlv::write_tlv(buf, tlv_type::INTEREST_CONTEXT, context);
         }
         
-        // Length
-        let len = bytes.get_u8() as usize;
-        
-        // Check if we have enough bytes for the value
-        if bytes.len() < len {
-            return Err(Error::TlvParsing("Buffer too short for Interest value".into()));
+        // Trace context
+        if let Some(traceparent) = &self.traceparent {
+            tlv::write_tlv(buf, tlv_type::TRACE_PARENT, traceparent.as_bytes());
         }
-        
+    }
+    
+    /// Encode the Interest in the given wire format
+    pub fn encode(&self, format: WireFormat) -> Result<Bytes> {
+        match format {
+            WireFormat::Ndn => Ok(self.to_bytes()),
+            WireFormat::Compact => compact::encode_interest(self),
+        }
+    }
+    
+    /// Decode an Interest from the given wire format
+    pub fn decode(buf: &[u8], format: WireFormat) -> Result<Self> {
+        match format {
+            WireFormat::Ndn => Self::from_bytes(buf),
+            WireFormat::Compact => compact::decode_interest(buf),
+        }
+    }
+    
+    /// Decode an Interest from TLV
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        Self::parse(Bytes::copy_from_slice(buf))
+    }
+    
+    /// Decode an Interest from TLV held in `bytes`, without copying it
+    ///
+    /// The context is a slice of the same allocation.
+    pub fn parse(mut bytes: Bytes) -> Result<Self> {
         // Value (Name + Nonce + Lifetime)
-        let mut value = bytes.split_to(len);
+        let (typ, mut value) = tlv::read_tlv(&mut bytes)?;
+        if typ != tlv_type::INTEREST as u64 {
+            return Err(Error::TlvParsing(format!("Unexpected TLV type: {}", typ)));
+        }
         
         // Parse name
         let name = Name::from_tlv(&mut value)?;
@@ -160,43 +410,49 @@ impl Interest {
         // Default values
         let mut lifetime_ms = 4000;
         let mut nonce = 0;
-        let can_be_prefix = false;
-        let must_be_fresh = true;
+        let mut can_be_prefix = false;
+        let mut must_be_fresh = false;
+        let mut forwarding_hint = None;
+        let mut context = None;
+        let mut traceparent = None;
+        let mut signature_info = Vec::new();
+        let mut signature_value = Vec::new();
         
-        // Parse remaining TLVs
+        // Parse remaining TLVs, skipping unknown ones
         while value.has_remaining() {
-            // Check if we have at least 2 bytes (type + length)
-            if value.len() < 2 {
-                break;
-            }
-            
-            let typ = value.get_u8();
-            let len = value.get_u8() as usize;
-            
-            // Check if we have enough bytes for the value
-            if value.len() < len {
-                break;
-            }
+            let (typ, mut field) = tlv::read_tlv(&mut value)?;
             
-            match typ {
-                tlv_type::NONCE => {
-                    if len == 4 {
-                        nonce = value.get_u32();
-                    } else {
-                        value.advance(len);
-                    }
+            match u8::try_from(typ) {
+                Ok(tlv_type::CAN_BE_PREFIX) => {
+                    can_be_prefix = true;
                 }
-                tlv_type::INTEREST_LIFETIME => {
-                    if len == 2 {
-                        lifetime_ms = value.get_u16() as u64;
-                    } else {
-                        value.advance(len);
-                    }
+                Ok(tlv_type::MUST_BE_FRESH) => {
+                    must_be_fresh = true;
+                }
+                Ok(tlv_type::FORWARDING_HINT) if field.has_remaining() => {
+                    // Only the first delegation is used
+                    forwarding_hint = Some(Name::from_tlv(&mut field)?);
+                }
+                Ok(tlv_type::NONCE) if field.len() == 4 => {
+                    nonce = field.get_u32();
+                }
+                Ok(tlv_type::INTEREST_LIFETIME) if field.len() == 2 => {
+                    lifetime_ms = field.get_u16() as u64;
+                }
+                Ok(tlv_type::INTEREST_SIGNATURE_INFO) => {
+                    signature_info = field.to_vec();
                 }
-                _ => {
-                    // Skip unknown TLV
-                    value.advance(len);
+                Ok(tlv_type::INTEREST_SIGNATURE_VALUE) => {
+                    signature_value = field.to_vec();
                 }
+                Ok(tlv_type::INTEREST_CONTEXT) => {
+                    context = Some(field);
+                }
+                Ok(tlv_type::TRACE_PARENT) => {
+                    // A traceparent that is not text is dropped rather than rejected
+                    traceparent = String::from_utf8(field.to_vec()).ok();
+                }
+                _ => {}
             }
         }
         
@@ -206,6 +462,11 @@ impl Interest {
             nonce,
             can_be_prefix,
             must_be_fresh,
+            forwarding_hint,
+            context,
+            traceparent,
+            signature_info,
+            signature_value,
         })
     }
 }
@@ -218,6 +479,10 @@ impl fmt::Debug for Interest {
             .field("nonce", &format!("{:08x}", self.nonce))
             .field("can_be_prefix", &self.can_be_prefix)
             .field("must_be_fresh", &self.must_be_fresh)
+            .field("forwarding_hint", &self.forwarding_hint)
+            .field("context", &self.context)
+            .field("traceparent", &self.traceparent)
+            .field("signed", &self.is_signed())
             .finish()
     }
 }
@@ -255,6 +520,21 @@ impl From<u8> for ContentType {
     }
 }
 
+impl ContentType {
+    /// Get the wire value of the content type
+    pub fn as_u8(&self) -> u8 {
+        match *self {
+            ContentType::Blob => 0,
+            ContentType::Link => 1,
+            ContentType::Key => 2,
+            ContentType::Cert => 3,
+            ContentType::Manifest => 4,
+            ContentType::PrefixAnn => 5,
+            ContentType::Custom(n) => n,
+        }
+    }
+}
+
 /// An NDN Data packet
 #[derive(Clone)]
 pub struct Data {
@@ -270,13 +550,20 @@ pub struct Data {
     /// Fresh period in milliseconds
     fresh_period_ms: u64,
     
-    /// Signature info placeholder
-    // In a real implementation, this would be more complex
+    /// Name component of the last segment of the object this Data belongs to
+    final_block_id: Option<Component>,
+    
+    /// SignatureInfo value (SignatureType and KeyLocator)
     signature_info: Vec<u8>,
     
-    /// Signature value placeholder
-    // In a real implementation, this would use proper crypto
+    /// SignatureValue
     signature_value: Vec<u8>,
+    
+    /// Wire encoding, computed on first use and reset by every setter
+    encoded: OnceCell<Bytes>,
+    
+    /// Signed portion exactly as received, reset by every setter
+    signed_wire: Option<Bytes>,
 }
 
 impl Data {
@@ -287,20 +574,39 @@ impl Data {
             content_type: ContentType::Blob,
             content: content.into(),
             fresh_period_ms: 3600000, // Default 1 hour
+            final_block_id: None,
             signature_info: vec![0], // Placeholder
             signature_value: vec![0], // Placeholder
+            encoded: OnceCell::new(),
+            signed_wire: None,
         }
     }
     
     /// Set the content type
     pub fn content_type(mut self, content_type: ContentType) -> Self {
         self.content_type = content_type;
+        self.invalidate();
         self
     }
     
     /// Set the fresh period
     pub fn fresh_period(mut self, fresh_period: Duration) -> Self {
         self.fresh_period_ms = fresh_period.as_millis() as u64;
+        self.invalidate();
+        self
+    }
+    
+    /// Set the FinalBlockId (the name component of the last segment)
+    pub fn final_block_id(mut self, final_block_id: Component) -> Self {
+        self.final_block_id = Some(final_block_id);
+        self.invalidate();
+        self
+    }
+    
+    /// Replace the content
+    pub fn with_content(mut self, content: impl Into<Bytes>) -> Self {
+        self.content = content.into();
+        self.invalidate();
         self
     }
     
@@ -324,90 +630,236 @@ impl Data {
         Duration::from_millis(self.fresh_period_ms)
     }
     
-    /// Sign the Data packet (placeholder)
-    /// In a real implementation, this would use proper crypto
-    pub fn sign(mut self, _key: &[u8]) -> Self {
-        // Placeholder for signature logic
-        self.signature_info = vec![1]; // Dummy value
-        self.signature_value = vec![2]; // Dummy value
+    /// Get the FinalBlockId, if any
+    pub fn get_final_block_id(&self) -> Option<&Component> {
+        self.final_block_id.as_ref()
+    }
+    
+    /// Get the raw SignatureInfo value
+    pub fn signature_info(&self) -> &[u8] {
+        &self.signature_info
+    }
+    
+    /// Get the raw SignatureValue
+    pub fn signature_value(&self) -> &[u8] {
+        &self.signature_value
+    }
+    
+    /// Replace the signature fields with externally computed values
+    pub fn with_signature(mut self, signature_info: Vec<u8>, signature_value: Vec<u8>) -> Self {
+        self.signature_info = signature_info;
+        self.signature_value = signature_value;
+        self.invalidate();
+        self
+    }
+    
+    /// Drop the cached wire encoding and received signed portion after a change
+    fn invalidate(&mut self) {
+        self.encoded = OnceCell::new();
+        self.signed_wire = None;
+    }
+    
+    /// Get the portion of the packet covered by the signature
+    /// (Name, MetaInfo, Content and SignatureInfo TLVs)
+    ///
+    /// For a decoded packet these are the bytes as received, so verification
+    /// does not depend on re-encoding producing the same bytes.
+    pub fn signed_portion(&self) -> Bytes {
+        if let Some(signed) = &self.signed_wire {
+            return signed.clone();
+        }
+        
+        let mut buf = BytesMut::new();
+        
+        buf.extend_from_slice(&self.name.to_tlv());
+        buf.extend_from_slice(&self.meta_info_tlv());
+        
+        tlv::write_tlv(&mut buf, tlv_type::CONTENT, &self.content);
+        tlv::write_tlv(&mut buf, tlv_type::SIGNATURE_INFO, &self.signature_info);
+        
+        buf.freeze()
+    }
+    
+    /// Encode the MetaInfo TLV: the content type, then the FinalBlockId if set
+    fn meta_info_tlv(&self) -> BytesMut {
+        // A zero FreshnessPeriod is the same as none, so it is left out
+        let freshness_size = match self.fresh_period_ms {
+            0 => 0,
+            period => tlv::tlv_size(tlv::non_neg_int_size(period)),
+        };
+        let final_block_id = self.final_block_id.as_ref().map(|component| component.to_tlv());
+        let final_block_id_size = final_block_id.as_ref().map_or(0, |component| tlv::tlv_size(component.len()));
+        
+        let meta_info_size = 1 + freshness_size + final_block_id_size;
+        let mut buf = BytesMut::with_capacity(tlv::tlv_size(meta_info_size));
+        tlv::write_tlv_header(&mut buf, tlv_type::META_INFO, meta_info_size);
+        buf.put_u8(self.content_type.as_u8());
+        
+        if self.fresh_period_ms > 0 {
+            tlv::write_tlv_header(&mut buf, tlv_type::FRESHNESS_PERIOD, tlv::non_neg_int_size(self.fresh_period_ms));
+            tlv::write_non_neg_int(&mut buf, self.fresh_period_ms);
+        }
+        
+        if let Some(component) = final_block_id {
+            tlv::write_tlv(&mut buf, tlv_type::FINAL_BLOCK_ID, &component);
+        }
+        
+        buf
+    }
+    
+    /// Compute the implicit SHA-256 digest of the Data's wire encoding
+    pub fn implicit_digest(&self) -> [u8; 32] {
+        Sha256::digest(self.to_bytes()).into()
+    }
+    
+    /// Get the full name of the Data (its name plus implicit digest)
+    pub fn full_name(&self) -> Name {
+        let mut name = self.name.clone();
+        name.append_implicit_digest(self.implicit_digest());
+        name
+    }
+    
+    /// Check the Data against an ImplicitSha256Digest ending the requested name
+    ///
+    /// Names without an implicit digest are accepted unchanged.
+    pub fn verify_implicit_digest(&self, requested: &Name) -> Result<()> {
+        let expected = match requested.implicit_digest() {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+        
+        let actual = self.implicit_digest();
+        if actual.as_slice() != expected {
+            return Err(Error::DigestMismatch(format!(
+                "{} expected {} but Data hashes to {}",
+                requested.without_implicit_digest(), hex::encode(expected), hex::encode(actual))));
+        }
+        
+        Ok(())
+    }
+    
+    /// Sign the Data packet with an Ed25519 key
+    ///
+    /// The SignatureInfo carries the signature type and the key name as its
+    /// KeyLocator, and is itself covered by the signature.
+    pub fn sign(mut self, key: &KeyPair) -> Self {
+        self.signature_info = encode_signature_info(key.name());
+        self.invalidate();
+        self.signature_value = key.sign(&self.signed_portion());
         self
     }
     
+    /// Verify the Data's signature with a public key
+    pub fn verify(&self, key: &PublicKey) -> Result<()> {
+        match decode_signature_info(&self.signature_info) {
+            Some((SIGNATURE_ED25519, _)) => {}
+            Some((signature_type, _)) => {
+                return Err(Error::SignatureVerification(format!(
+                    "Unsupported signature type: {}", signature_type)));
+            }
+            None => return Err(Error::SignatureVerification("Missing SignatureInfo".into())),
+        }
+        
+        key.verify(&self.signed_portion(), &self.signature_value)
+    }
+    
     /// Encode the Data as TLV
+    ///
+    /// The encoding is computed once and shared by later calls (and clones),
+    /// so Data served repeatedly from a cache is only serialized once.
     pub fn to_bytes(&self) -> Bytes {
-        let mut buf = BytesMut::new();
+        self.encoded.get_or_init(|| self.encode_uncached()).clone()
+    }
+    
+    /// Encode the Data as TLV, bypassing the memoized encoding
+    fn encode_uncached(&self) -> Bytes {
+        #[cfg(feature = "buffer-pool")]
+        return pool::with_local_pool(|pool| self.to_bytes_pooled(pool));
         
+        #[cfg(not(feature = "buffer-pool"))]
+        {
+            let mut buf = BytesMut::new();
+            self.encode_into(&mut buf);
+            buf.freeze()
+        }
+    }
+    
+    /// Encode the Data as TLV into a buffer taken from the pool
+    pub fn to_bytes_pooled(&self, pool: &BufferPool) -> Bytes {
+        pool.encode(|buf| self.encode_into(buf))
+    }
+    
+    /// Append the TLV encoding of the Data to the buffer
+    pub(crate) fn encode_into(&self, buf: &mut BytesMut) {
         // Compute the size of the Data
         let name_tlv = self.name.to_tlv();
         let name_size = name_tlv.len();
         
-        // MetaInfo (content type + fresh period)
-        let meta_info_size = 2 + 3; // type + length + value
+        // MetaInfo (content type and FinalBlockId)
+        let meta_info_tlv = self.meta_info_tlv();
+        let meta_info_size = meta_info_tlv.len();
         
         // Content
-        let content_size = 2 + self.content.len(); // type + length + value
+        let content_size = tlv::tlv_size(self.content.len());
         
         // Signature info
-        let sig_info_size = 2 + self.signature_info.len(); // type + length + value
+        let sig_info_size = tlv::tlv_size(self.signature_info.len());
         
         // Signature value
-        let sig_value_size = 2 + self.signature_value.len(); // type + length + value
+        let sig_value_size = tlv::tlv_size(self.signature_value.len());
         
         // Data TLV
-        buf.put_u8(tlv_type::DATA);
-        buf.put_u8((name_size + meta_info_size + content_size + sig_info_size + sig_value_size) as u8);
+        tlv::write_tlv_header(buf, tlv_type::DATA,
+            name_size + meta_info_size + content_size + sig_info_size + sig_value_size);
         
         // Name
         buf.extend_from_slice(&name_tlv);
         
         // MetaInfo
-        buf.put_u8(tlv_type::META_INFO);
-        buf.put_u8(1); // 1 byte
-        // Convert content type to u8 safely\n        let content_type_value = match self.content_type {\n            ContentType::Blob => 0,\n            ContentType::Link => 1,\n            ContentType::Key => 2,\n            ContentType::Cert => 3,\n            ContentType::Manifest => 4,\n            ContentType::PrefixAnn => 5,\n            ContentType::Custom(n) => n,\n        };\n        buf.put_u8(content_type_value);
+        buf.extend_from_slice(&meta_info_tlv);
         
         // Content
-        buf.put_u8(tlv_type::CONTENT);
-        buf.put_u8(self.content.len() as u8);
-        buf.extend_from_slice(&self.content);
+        tlv::write_tlv(buf, tlv_type::CONTENT, &self.content);
         
         // Signature info
-        buf.put_u8(tlv_type::SIGNATURE_INFO);
-        buf.put_u8(self.signature_info.len() as u8);
-        buf.extend_from_slice(&self.signature_info);
+        tlv::write_tlv(buf, tlv_type::SIGNATURE_INFO, &self.signature_info);
         
         // Signature value
-        buf.put_u8(tlv_type::SIGNATURE_VALUE);
-        buf.put_u8(self.signature_value.len() as u8);
-        buf.extend_from_slice(&self.signature_value);
-        
-        buf.freeze()
+        tlv::write_tlv(buf, tlv_type::SIGNATURE_VALUE, &self.signature_value);
+    }
+    
+    /// Encode the Data in the given wire format
+    pub fn encode(&self, format: WireFormat) -> Result<Bytes> {
+        match format {
+            WireFormat::Ndn => Ok(self.to_bytes()),
+            WireFormat::Compact => compact::encode_data(self),
+        }
+    }
+    
+    /// Decode a Data packet from the given wire format
+    pub fn decode(buf: &[u8], format: WireFormat) -> Result<Self> {
+        match format {
+            WireFormat::Ndn => Self::from_bytes(buf),
+            WireFormat::Compact => compact::decode_data(buf),
+        }
     }
     
     /// Decode a Data packet from TLV
     pub fn from_bytes(buf: &[u8]) -> Result<Self> {
-        let mut bytes = Bytes::copy_from_slice(buf);
-        
-        // Check if we have at least 2 bytes (type + length)
-        if bytes.len() < 2 {
-            return Err(Error::TlvParsing("Buffer too short for Data TLV".into()));
-        }
-        
-        // Type
-        let typ = bytes.get_u8();
-        if typ != tlv_type::DATA {
+        Self::parse(Bytes::copy_from_slice(buf))
+    }
+    
+    /// Decode a Data packet from TLV held in `bytes`, without copying it
+    ///
+    /// The content and signed portion are slices of the same allocation, so
+    /// large Data received off the wire is never duplicated.
+    pub fn parse(mut bytes: Bytes) -> Result<Self> {
+        // Value (Name + MetaInfo + Content + Signature)
+        let (typ, mut value) = tlv::read_tlv(&mut bytes)?;
+        if typ != tlv_type::DATA as u64 {
             return Err(Error::TlvParsing(format!("Unexpected TLV type: {}", typ)));
         }
-        
-        // Length
-        let len = bytes.get_u8() as usize;
-        
-        // Check if we have enough bytes for the value
-        if bytes.len() < len {
-            return Err(Error::TlvParsing("Buffer too short for Data value".into()));
-        }
-        
-        // Value (Name + MetaInfo + Content + Signature)
-        let mut value = bytes.split_to(len);
+        let whole = value.clone();
         
         // Parse name
         let name = Name::from_tlv(&mut value)?;
@@ -415,45 +867,52 @@ impl Data {
         // Default values
         let mut content_type = ContentType::Blob;
         let mut content = Bytes::new();
-        let fresh_period_ms = 3600000; // 1 hour
+        // Data without a FreshnessPeriod is never fresh
+        let mut fresh_period_ms = 0;
+        let mut final_block_id = None;
         let mut signature_info = vec![];
         let mut signature_value = vec![];
+        let mut signed_wire = None;
         
-        // Parse remaining TLVs
+        // Parse remaining TLVs, skipping unknown ones
         while value.has_remaining() {
-            // Check if we have at least 2 bytes (type + length)
-            if value.len() < 2 {
-                break;
-            }
-            
-            let typ = value.get_u8();
-            let len = value.get_u8() as usize;
-            
-            // Check if we have enough bytes for the value
-            if value.len() < len {
-                break;
-            }
+            let (typ, field) = tlv::read_tlv(&mut value)?;
             
-            match typ {
-                tlv_type::META_INFO => {
-                    if len > 0 {
-                        content_type = ContentType::from(value.get_u8());
-                        value.advance(len - 1);
+            match u8::try_from(typ) {
+                Ok(tlv_type::META_INFO) => {
+                    let mut meta_info = field;
+                    if meta_info.has_remaining() {
+                        content_type = ContentType::from(meta_info.get_u8());
+                    }
+                    
+                    // Fields following the content type
+                    while meta_info.has_remaining() {
+                        let (typ, mut field) = tlv::read_tlv(&mut meta_info)?;
+                        match u8::try_from(typ) {
+                            Ok(tlv_type::FRESHNESS_PERIOD) => {
+                                fresh_period_ms = tlv::read_non_neg_int(&field).ok_or_else(|| Error::TlvParsing(
+                                    format!("Invalid FreshnessPeriod of {} bytes", field.len())))?;
+                            }
+                            Ok(tlv_type::FINAL_BLOCK_ID) => {
+                                final_block_id = Some(Component::from_tlv(&mut field)?);
+                            }
+                            _ => {}
+                        }
                     }
                 }
-                tlv_type::CONTENT => {
-                    content = value.split_to(len);
-                }
-                tlv_type::SIGNATURE_INFO => {
-                    signature_info = value.split_to(len).to_vec();
+                Ok(tlv_type::CONTENT) => {
+                    content = field;
                 }
-                tlv_type::SIGNATURE_VALUE => {
-                    signature_value = value.split_to(len).to_vec();
+                Ok(tlv_type::SIGNATURE_INFO) => {
+                    signature_info = field.to_vec();
+                    
+                    // Everything up to and including the SignatureInfo is signed
+                    signed_wire = Some(whole.slice(..whole.len() - value.len()));
                 }
-                _ => {
-                    // Skip unknown TLV
-                    value.advance(len);
+                Ok(tlv_type::SIGNATURE_VALUE) => {
+                    signature_value = field.to_vec();
                 }
+                _ => {}
             }
         }
         
@@ -462,8 +921,11 @@ impl Data {
             content_type,
             content,
             fresh_period_ms,
+            final_block_id,
             signature_info,
             signature_value,
+            encoded: OnceCell::new(),
+            signed_wire,
         })
     }
 }
@@ -516,6 +978,28 @@ impl From<u16> for NackReason {
     }
 }
 
+impl NackReason {
+    /// Get the NDNLPv2 reason code; reasons NDNLPv2 has no code for are sent as None (0)
+    pub fn lp_code(&self) -> u8 {
+        match self {
+            NackReason::Congestion => 50,
+            NackReason::Duplicate => 100,
+            NackReason::NoRoute => 150,
+            _ => 0,
+        }
+    }
+    
+    /// Get the reason for an NDNLPv2 reason code
+    pub fn from_lp_code(code: u64) -> Self {
+        match code {
+            50 => NackReason::Congestion,
+            100 => NackReason::Duplicate,
+            150 => NackReason::NoRoute,
+            _ => NackReason::Other,
+        }
+    }
+}
+
 /// An NDN Negative Acknowledgment (NACK) packet
 #[derive(Clone)]
 pub struct Nack {
@@ -571,18 +1055,17 @@ impl Nack {
         let interest_tlv = self.interest.to_bytes();
         
         // Reason TLV
-        let reason_size = 2 + 2; // type + length + value
+        let reason_size = tlv::tlv_size(2);
         
         // Message TLV (if non-empty)
         let message_size = if self.message.is_empty() {
             0
         } else {
-            2 + self.message.len() // type + length + value
+            tlv::tlv_size(self.message.len())
         };
         
         // NACK TLV
-        buf.put_u8(tlv_type::NACK);
-        buf.put_u8((interest_tlv.len() + reason_size + message_size) as u8);
+        tlv::write_tlv_header(&mut buf, tlv_type::NACK, interest_tlv.len() + reason_size + message_size);
         
         // Interest
         buf.extend_from_slice(&interest_tlv);
@@ -594,9 +1077,7 @@ impl Nack {
         
         // Message (if non-empty)
         if !self.message.is_empty() {
-            buf.put_u8(0x10); // Custom TLV for message
-            buf.put_u8(self.message.len() as u8);
-            buf.extend_from_slice(self.message.as_bytes());
+            tlv::write_tlv(&mut buf, 0x10, self.message.as_bytes()); // Custom TLV for message
         }
         
         buf.freeze()
@@ -608,78 +1089,117 @@ impl Nack {
         
         let mut bytes = Bytes::copy_from_slice(buf);
         
-        // Check if we have at least 2 bytes (type + length)
-        if bytes.len() < 2 {
-            return Err(Error::TlvParsing("Buffer too short for NACK TLV".into()));
-        }
-        
-        // Type
-        let typ = bytes.get_u8();
-        if typ != tlv_type::NACK {
+        // Value (Interest + Reason + Message)
+        let (typ, mut value) = tlv::read_tlv(&mut bytes)?;
+        if typ != tlv_type::NACK as u64 {
             return Err(Error::TlvParsing(format!("Unexpected TLV type: {}", typ)));
         }
         
-        // Length
-        let len = bytes.get_u8() as usize;
-        
-        // Check if we have enough bytes for the value
-        if bytes.len() < len {
-            return Err(Error::TlvParsing("Buffer too short for NACK value".into()));
-        }
-        
-        // Value (Interest + Reason + Message)
-        let mut value = bytes.split_to(len);
-        
         // Parse interest (assuming first TLV is the Interest)
-        let interest = Interest::from_bytes(&value)?;
-        
-        // Advance past the Interest
-        let interest_size = 2 + value[1] as usize; // type + length + Interest TLV size
-        value.advance(interest_size);
+        let interest = Interest::from_bytes(&tlv::split_tlv(&mut value)?)?;
         
         // Default values
         let mut reason = NackReason::NoRoute;
         let mut message = String::new();
         
-        // Parse remaining TLVs
+        // Parse remaining TLVs, skipping unknown ones
         while value.has_remaining() {
-            // Check if we have at least 2 bytes (type + length)
-            if value.len() < 2 {
-                break;
-            }
+            let (typ, mut field) = tlv::read_tlv(&mut value)?;
             
-            let typ = value.get_u8();
-            let len = value.get_u8() as usize;
-            
-            // Check if we have enough bytes for the value
-            if value.len() < len {
-                break;
+            match u8::try_from(typ) {
+                Ok(tlv_type::NACK_REASON) if field.len() == 2 => {
+                    reason = NackReason::from(field.get_u16());
+                }
+                Ok(0x10) => {
+                    // Custom TLV for message
+                    message = String::from_utf8_lossy(&field).to_string();
+                }
+                _ => {}
             }
+        }
+        
+        Ok(Self {
+            interest,
+            reason,
+            message,
+        })
+    }
+    
+    /// Encode the NACK as an NDNLPv2 LpPacket: a Nack header carrying the
+    /// reason, and the Interest as the fragment
+    ///
+    /// NDNLPv2 has no field for the message, so it is not sent.
+    pub fn to_lp_bytes(&self) -> Bytes {
+        let interest_tlv = self.interest.to_bytes();
+        
+        // NackReason holds a one-byte NonNegativeInteger
+        let reason_size = tlv::var_number_size(lp_type::NACK_REASON) + 2;
+        let nack_size = tlv::var_number_size(lp_type::NACK)
+            + tlv::var_number_size(reason_size as u64) + reason_size;
+        let fragment_size = tlv::var_number_size(lp_type::FRAGMENT)
+            + tlv::var_number_size(interest_tlv.len() as u64) + interest_tlv.len();
+        
+        let mut buf = BytesMut::new();
+        tlv::write_var_number(&mut buf, lp_type::LP_PACKET);
+        tlv::write_var_number(&mut buf, (nack_size + fragment_size) as u64);
+        
+        // Nack header
+        tlv::write_var_number(&mut buf, lp_type::NACK);
+        tlv::write_var_number(&mut buf, reason_size as u64);
+        tlv::write_var_number(&mut buf, lp_type::NACK_REASON);
+        tlv::write_var_number(&mut buf, 1);
+        buf.put_u8(self.reason.lp_code());
+        
+        // Interest
+        tlv::write_var_number(&mut buf, lp_type::FRAGMENT);
+        tlv::write_var_number(&mut buf, interest_tlv.len() as u64);
+        buf.extend_from_slice(&interest_tlv);
+        
+        buf.freeze()
+    }
+    
+    /// Decode a NACK from an NDNLPv2 LpPacket
+    pub fn from_lp_bytes(buf: &[u8]) -> Result<Self> {
+        let mut bytes = Bytes::copy_from_slice(buf);
+        
+        let (typ, mut value) = tlv::read_tlv(&mut bytes)?;
+        if typ != lp_type::LP_PACKET {
+            return Err(Error::TlvParsing(format!("Unexpected TLV type: {}", typ)));
+        }
+        
+        let mut reason = None;
+        let mut interest = None;
+        
+        // Parse the header fields and the fragment, skipping unknown headers
+        while value.has_remaining() {
+            let (typ, mut field) = tlv::read_tlv(&mut value)?;
             
             match typ {
-                tlv_type::NACK_REASON => {
-                    if len == 2 {
-                        reason = NackReason::from(value.get_u16());
-                    } else {
-                        value.advance(len);
+                lp_type::NACK => {
+                    // A Nack header without a reason is unspecified
+                    let mut code = 0;
+                    while field.has_remaining() {
+                        let (typ, reason_value) = tlv::read_tlv(&mut field)?;
+                        if typ == lp_type::NACK_REASON && matches!(reason_value.len(), 1 | 2 | 4 | 8) {
+                            code = reason_value.as_ref().get_uint(reason_value.len());
+                        }
                     }
+                    reason = Some(NackReason::from_lp_code(code));
                 }
-                0x10 => {
-                    // Custom TLV for message
-                    let msg_bytes = value.split_to(len);
-                    message = String::from_utf8_lossy(&msg_bytes).to_string();
-                }
-                _ => {
-                    // Skip unknown TLV
-                    value.advance(len);
+                lp_type::FRAGMENT => {
+                    interest = Some(Interest::from_bytes(&field)?);
                 }
+                _ => {}
             }
         }
         
+        let reason = reason.ok_or_else(|| Error::TlvParsing("LpPacket has no Nack header".into()))?;
+        let interest = interest.ok_or_else(|| Error::TlvParsing("LpPacket has no fragment".into()))?;
+        
         Ok(Self {
             interest,
             reason,
-            message,
+            message: String::new(),
         })
     }
 }
@@ -699,3 +1219,381 @@ impl fmt::Display for Nack {
         write!(f, "Nack({}, {:?})", self.interest.name(), self.reason)
     }
 }
+
+/// Any packet carried on a transport stream
+#[derive(Debug, Clone)]
+pub enum Packet {
+    /// Interest packet
+    Interest(Interest),
+    
+    /// Data packet
+    Data(Data),
+    
+    /// Negative acknowledgement
+    Nack(Nack),
+    
+    /// Fragment of a Data packet larger than the MTU
+    Fragment(Fragment),
+}
+
+impl Packet {
+    /// Decode a packet, dispatching on its leading TLV type
+    ///
+    /// Fragments are recognized by their magic value, which does not clash
+    /// with any TLV type the transport sends.
+    pub fn decode(buf: &[u8]) -> Result<Packet> {
+        Self::parse(Bytes::copy_from_slice(buf))
+    }
+    
+    /// Decode a packet held in `bytes`, sharing its allocation with the
+    /// decoded Interest or Data rather than copying it
+    pub fn parse(mut bytes: Bytes) -> Result<Packet> {
+        if bytes.starts_with(&FRAGMENT_MAGIC.to_be_bytes()) {
+            return Fragment::from_bytes(&mut bytes).map(Packet::Fragment);
+        }
+        
+        match bytes.first() {
+            Some(&tlv_type::INTEREST) => Interest::parse(bytes).map(Packet::Interest),
+            Some(&tlv_type::DATA) => Data::parse(bytes).map(Packet::Data),
+            Some(&tlv_type::NACK) => Nack::from_bytes(&bytes).map(Packet::Nack),
+            Some(&typ) if typ as u64 == lp_type::LP_PACKET => Nack::from_lp_bytes(&bytes).map(Packet::Nack),
+            Some(typ) => Err(Error::TlvParsing(format!("Unknown packet type: {}", typ))),
+            None => Err(Error::TlvParsing("Empty packet".into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_data_encoding_is_memoized() {
+        let data = Data::new(Name::from_uri("/udcn/hot/object").unwrap(), "cached content");
+        
+        // The second encode (and a clone's) reuses the first one's bytes
+        let first = data.to_bytes();
+        let second = data.to_bytes();
+        assert_eq!(first.as_ptr(), second.as_ptr());
+        assert_eq!(data.clone().to_bytes().as_ptr(), first.as_ptr());
+        
+        // Changing the content invalidates the cached encoding
+        let changed = data.clone().with_content("new content");
+        let encoded = changed.to_bytes();
+        assert_ne!(encoded, first);
+        assert_eq!(Data::from_bytes(&encoded).unwrap().content().as_ref(), b"new content");
+        
+        // So does every other setter
+        let key = KeyPair::generate("/udcn/KEY/1").unwrap();
+        let signed = data.sign(&key);
+        assert_ne!(signed.to_bytes(), first);
+        assert_eq!(Data::from_bytes(&signed.to_bytes()).unwrap().signature_value(), signed.signature_value());
+    }
+    
+    #[test]
+    fn test_sign_and_verify() {
+        let key = KeyPair::generate("/udcn/producer/KEY/1").unwrap();
+        let other = KeyPair::generate("/udcn/other/KEY/1").unwrap();
+        let data = Data::new(Name::from_uri("/udcn/signed").unwrap(), "signed content").sign(&key);
+        
+        let wire = data.to_bytes();
+        let decoded = Data::from_bytes(&wire).unwrap();
+        assert!(decoded.verify(&key.public_key()).is_ok());
+        assert!(matches!(decoded.verify(&other.public_key()), Err(Error::SignatureVerification(_))));
+        
+        // Flipping a content byte on the wire breaks the signature
+        let mut tampered = wire.to_vec();
+        let offset = tampered.windows(7).position(|w| w == b"content").unwrap();
+        tampered[offset] ^= 0x01;
+        let tampered = Data::from_bytes(&tampered).unwrap();
+        assert!(tampered.verify(&key.public_key()).is_err());
+        
+        // Unsigned Data has no SignatureInfo to verify against
+        let unsigned = Data::new(Name::from_uri("/udcn/unsigned").unwrap(), "content");
+        assert!(unsigned.verify(&key.public_key()).is_err());
+    }
+    
+    #[test]
+    fn test_verify_uses_received_signed_bytes() {
+        let key = KeyPair::generate("/udcn/producer/KEY/1").unwrap();
+        let name = Name::from_uri("/udcn/extended").unwrap();
+        let data = Data::new(name.clone(), "content");
+        
+        // A producer that adds a TLV this implementation does not know about
+        let mut signed = BytesMut::new();
+        signed.extend_from_slice(&name.to_tlv());
+        signed.extend_from_slice(&data.meta_info_tlv());
+        tlv::write_tlv(&mut signed, tlv_type::CONTENT, b"content");
+        tlv::write_tlv(&mut signed, 0x30, b"unknown");
+        tlv::write_tlv(&mut signed, tlv_type::SIGNATURE_INFO, &encode_signature_info(key.name()));
+        let signature = key.sign(&signed);
+        
+        let mut value = signed.clone();
+        tlv::write_tlv(&mut value, tlv_type::SIGNATURE_VALUE, &signature);
+        let mut wire = BytesMut::new();
+        tlv::write_tlv(&mut wire, tlv_type::DATA, &value);
+        
+        // Re-encoding drops the unknown TLV, but verification uses the received bytes
+        let decoded = Data::from_bytes(&wire).unwrap();
+        assert_ne!(decoded.clone().with_content("content").signed_portion(), signed.freeze());
+        assert!(decoded.verify(&key.public_key()).is_ok());
+    }
+    
+    #[test]
+    fn test_large_content_round_trips() {
+        let name = Name::from_uri("/udcn/large/object").unwrap();
+        
+        // One-byte, three-byte and five-byte TLV-LENGTH forms
+        for size in [100, 300, 70000, 100000] {
+            let content: Vec<u8> = (0..size).map(|i| i as u8).collect();
+            let data = Data::new(name.clone(), content.clone())
+                .final_block_id(Component::from_str("seg=0"));
+            
+            let encoded = data.to_bytes();
+            let decoded = Data::from_bytes(&encoded).unwrap();
+            assert_eq!(decoded.name(), &name, "size {}", size);
+            assert_eq!(decoded.content().as_ref(), content.as_slice(), "size {}", size);
+            assert_eq!(decoded.get_final_block_id(), Some(&Component::from_str("seg=0")));
+            
+            let interest = Interest::new(name.clone()).with_context(content.clone());
+            let decoded = Interest::from_bytes(&interest.to_bytes()).unwrap();
+            assert_eq!(decoded.context().map(|ctx| ctx.as_ref()), Some(content.as_slice()));
+            
+            let nack = Nack::from_interest(interest, "x".repeat(size));
+            let decoded = Nack::from_bytes(&nack.to_bytes()).unwrap();
+            assert_eq!(decoded.reason(), NackReason::NoRoute);
+            assert_eq!(decoded.message().len(), size);
+            assert_eq!(decoded.interest().context().map(|ctx| ctx.len()), Some(size));
+        }
+    }
+    
+    #[test]
+    fn test_interest_selectors_round_trip() {
+        let name = Name::from_uri("/udcn/selectors").unwrap();
+        
+        for can_be_prefix in [false, true] {
+            for must_be_fresh in [false, true] {
+                let interest = Interest::new(name.clone())
+                    .can_be_prefix(can_be_prefix)
+                    .must_be_fresh(must_be_fresh);
+                
+                let decoded = Interest::from_bytes(&interest.to_bytes()).unwrap();
+                assert_eq!(decoded.get_can_be_prefix(), can_be_prefix);
+                assert_eq!(decoded.get_must_be_fresh(), must_be_fresh);
+                assert_eq!(decoded.nonce(), interest.nonce());
+            }
+        }
+        
+        // The selectors are empty TLVs following the Name
+        let wire = Interest::new(name.clone()).can_be_prefix(true).must_be_fresh(true).to_bytes();
+        let name_end = 2 + name.to_tlv().len();
+        assert_eq!(&wire[name_end..name_end + 4], &[tlv_type::CAN_BE_PREFIX, 0, tlv_type::MUST_BE_FRESH, 0]);
+    }
+    
+    #[test]
+    fn test_nack_round_trips_selector_interest() {
+        // Selectors, a hint and a context long enough for a three-byte length
+        let interest = Interest::new(Name::from_uri("/udcn/nacked").unwrap())
+            .can_be_prefix(true)
+            .must_be_fresh(true)
+            .with_forwarding_hint(Name::from_uri("/site/b").unwrap())
+            .with_context(vec![7u8; 300]);
+        let nack = Nack::from_interest(interest.clone(), "no next hop".to_string());
+        
+        let decoded = Nack::from_bytes(&nack.to_bytes()).unwrap();
+        assert_eq!(decoded.reason(), NackReason::NoRoute);
+        assert_eq!(decoded.message(), "no next hop");
+        assert!(decoded.interest().get_can_be_prefix());
+        assert_eq!(decoded.interest().nonce(), interest.nonce());
+        assert_eq!(decoded.interest().context().map(|ctx| ctx.len()), Some(300));
+        
+        // The context travels as an even, application-range TLV peers can skip
+        let wire = Interest::new(Name::from_uri("/udcn/context").unwrap()).with_context(vec![9u8]).to_bytes();
+        assert!(wire.windows(3).any(|tlv| tlv == [0x80, 1, 9]));
+        
+        // The LpPacket form keeps the reason and the Interest
+        for reason in [NackReason::Congestion, NackReason::Duplicate, NackReason::NoRoute] {
+            let wire = Nack::new(interest.clone(), reason).to_lp_bytes();
+            assert_eq!(wire[0], 0x64);
+            
+            let decoded = match Packet::decode(&wire).unwrap() {
+                Packet::Nack(nack) => nack,
+                other => panic!("Expected a Nack, got {:?}", other),
+            };
+            assert_eq!(decoded.reason(), reason);
+            assert_eq!(decoded.interest().name(), interest.name());
+            assert_eq!(decoded.interest().forwarding_hint(), interest.forwarding_hint());
+            assert!(decoded.interest().get_must_be_fresh());
+        }
+    }
+    
+    #[test]
+    fn test_traceparent_round_trip() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let interest = Interest::new(Name::from_uri("/udcn/traced").unwrap())
+            .with_context(vec![1u8, 2, 3])
+            .with_traceparent(traceparent);
+        
+        let decoded = Interest::from_bytes(&interest.to_bytes()).unwrap();
+        assert_eq!(decoded.traceparent(), Some(traceparent));
+        assert_eq!(decoded.context().map(|ctx| ctx.as_ref()), Some(&[1u8, 2, 3][..]));
+        
+        // Without one nothing is encoded
+        let plain = Interest::new(Name::from_uri("/udcn/traced").unwrap()).with_nonce(0);
+        assert!(!plain.to_bytes().contains(&tlv_type::TRACE_PARENT));
+        assert_eq!(Interest::from_bytes(&plain.to_bytes()).unwrap().traceparent(), None);
+    }
+    
+    #[test]
+    fn test_forwarding_hint_round_trip() {
+        let name = Name::from_uri("/udcn/hinted").unwrap();
+        let hint = Name::from_uri("/site/b").unwrap();
+        
+        let interest = Interest::new(name.clone()).must_be_fresh(false).with_forwarding_hint(hint.clone());
+        let wire = interest.to_bytes();
+        let decoded = Interest::from_bytes(&wire).unwrap();
+        assert_eq!(decoded.forwarding_hint(), Some(&hint));
+        assert_eq!(decoded.nonce(), interest.nonce());
+        
+        // The hint is a ForwardingHint TLV wrapping the delegation Name
+        let name_end = 2 + name.to_tlv().len();
+        let hint_tlv = hint.to_tlv();
+        assert_eq!(&wire[name_end..name_end + 2], &[tlv_type::FORWARDING_HINT, hint_tlv.len() as u8]);
+        assert_eq!(&wire[name_end + 2..name_end + 2 + hint_tlv.len()], &hint_tlv[..]);
+        
+        // Interests without a hint don't carry the TLV
+        assert!(Interest::from_bytes(&Interest::new(name).to_bytes()).unwrap().forwarding_hint().is_none());
+    }
+    
+    #[test]
+    fn test_parse_shares_content_buffer() {
+        let content = vec![0x42; 4096];
+        let wire = Data::new(Name::from_uri("/udcn/zero-copy").unwrap(), content.clone()).to_bytes();
+        let received = wire.as_ptr_range();
+        let in_wire = |bytes: &Bytes| received.contains(&bytes.as_ptr()) && bytes.len() <= wire.len();
+        
+        // The content is a view into the received buffer
+        let data = Data::parse(wire.clone()).unwrap();
+        assert_eq!(data.content().as_ref(), content.as_slice());
+        assert!(in_wire(data.content()));
+        
+        // So it is when dispatched as a packet
+        match Packet::parse(wire.clone()).unwrap() {
+            Packet::Data(data) => assert!(in_wire(data.content())),
+            other => panic!("unexpected packet: {:?}", other),
+        }
+        
+        // from_bytes copies
+        assert!(!in_wire(Data::from_bytes(&wire).unwrap().content()));
+    }
+    
+    #[test]
+    fn test_final_block_id_round_trip() {
+        let name = Name::from_uri("/udcn/segmented/seg=3").unwrap();
+        let last = Component::from_str("seg=9");
+        
+        let data = Data::new(name.clone(), "segment").final_block_id(last.clone());
+        let decoded = Data::from_bytes(&data.to_bytes()).unwrap();
+        assert_eq!(decoded.get_final_block_id(), Some(&last));
+        
+        // The FinalBlockId is a MetaInfo field after the content type and
+        // the 4-byte FreshnessPeriod of one hour
+        let meta_info = data.meta_info_tlv();
+        let last_tlv = last.to_tlv();
+        assert_eq!(&meta_info[3..9], &[tlv_type::FRESHNESS_PERIOD, 4, 0x00, 0x36, 0xEE, 0x80]);
+        assert_eq!(&meta_info[9..11], &[tlv_type::FINAL_BLOCK_ID, last_tlv.len() as u8]);
+        assert_eq!(&meta_info[11..], &last_tlv[..]);
+        
+        // Data without one doesn't carry the TLV
+        let plain = Data::from_bytes(&Data::new(name, "segment").to_bytes()).unwrap();
+        assert!(plain.get_final_block_id().is_none());
+    }
+    
+    #[test]
+    fn test_freshness_period_round_trip() {
+        let name = Name::from_uri("/udcn/fresh").unwrap();
+        
+        for period in [Duration::from_millis(200), Duration::from_secs(10), Duration::from_secs(3600)] {
+            let data = Data::new(name.clone(), "fresh").fresh_period(period);
+            let decoded = Data::from_bytes(&data.to_bytes()).unwrap();
+            assert_eq!(decoded.get_fresh_period(), period);
+            assert_eq!(decoded.to_bytes(), data.to_bytes());
+        }
+        
+        // A zero period is left off the wire, and Data without one is never fresh
+        let stale = Data::new(name.clone(), "stale").fresh_period(Duration::ZERO);
+        assert_eq!(stale.meta_info_tlv().len(), 3);
+        assert_eq!(Data::from_bytes(&stale.to_bytes()).unwrap().get_fresh_period(), Duration::ZERO);
+        
+        // A FreshnessPeriod that is not a NonNegativeInteger is rejected
+        let mut wire = BytesMut::new();
+        let name_tlv = name.to_tlv();
+        tlv::write_tlv_header(&mut wire, tlv_type::DATA, name_tlv.len() + 7);
+        wire.extend_from_slice(&name_tlv);
+        tlv::write_tlv(&mut wire, tlv_type::META_INFO, &[0, tlv_type::FRESHNESS_PERIOD, 3, 0, 0, 1]);
+        assert!(Data::from_bytes(&wire).is_err());
+    }
+    
+    #[test]
+    fn test_signed_interest_round_trip() {
+        let key = KeyPair::generate("/udcn/consumer/KEY/1").unwrap();
+        let other = KeyPair::generate("/udcn/other/KEY/1").unwrap();
+        let name = Name::from_uri("/udcn/command/start").unwrap();
+        
+        let interest = Interest::new(name.clone()).sign(&key);
+        assert!(interest.is_signed());
+        assert_eq!(decode_signature_info(interest.signature_info()),
+                   Some((SIGNATURE_ED25519, "/udcn/consumer/KEY/1".to_string())));
+        
+        let decoded = Interest::from_bytes(&interest.to_bytes()).unwrap();
+        assert_eq!(decoded.name(), &name);
+        assert_eq!(decoded.signature_value(), interest.signature_value());
+        assert!(decoded.verify(&key.public_key()).is_ok());
+        assert!(matches!(decoded.verify(&other.public_key()), Err(Error::SignatureVerification(_))));
+        
+        // A forwarder refreshing the nonce doesn't break the signature
+        assert!(decoded.clone().with_nonce(7).verify(&key.public_key()).is_ok());
+        
+        // Renaming does
+        let mut forged = Interest::from_bytes(&interest.to_bytes()).unwrap();
+        forged.name = Name::from_uri("/udcn/command/stop").unwrap();
+        assert!(forged.verify(&key.public_key()).is_err());
+        
+        // Unsigned Interests don't verify
+        let unsigned = Interest::from_bytes(&Interest::new(name).to_bytes()).unwrap();
+        assert!(!unsigned.is_signed());
+        assert!(unsigned.verify(&key.public_key()).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_packet_decode_dispatches_on_type() {
+        let name = Name::from_uri("/udcn/packet").unwrap();
+        let interest = Interest::new(name.clone());
+        
+        match Packet::decode(&interest.to_bytes()).unwrap() {
+            Packet::Interest(decoded) => assert_eq!(decoded.nonce(), interest.nonce()),
+            other => panic!("Expected an Interest, got {:?}", other),
+        }
+        match Packet::decode(&Data::new(name.clone(), "content").to_bytes()).unwrap() {
+            Packet::Data(decoded) => assert_eq!(decoded.content().as_ref(), b"content"),
+            other => panic!("Expected Data, got {:?}", other),
+        }
+        match Packet::decode(&Nack::new(interest, NackReason::Congestion).to_bytes()).unwrap() {
+            Packet::Nack(decoded) => assert_eq!(decoded.reason(), NackReason::Congestion),
+            other => panic!("Expected a NACK, got {:?}", other),
+        }
+        
+        let fragmenter = crate::fragmentation::Fragmenter::new(100);
+        let fragments = fragmenter.fragment(&Data::new(name, vec![7u8; 300])).await.unwrap();
+        match Packet::decode(&fragments[1]).unwrap() {
+            Packet::Fragment(fragment) => {
+                assert_eq!(fragment.sequence(), 1);
+                assert_eq!(fragment.total_fragments(), fragments.len());
+            }
+            other => panic!("Expected a fragment, got {:?}", other),
+        }
+        
+        assert!(Packet::decode(&[]).is_err());
+        assert!(Packet::decode(&[0x42, 0]).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//
+// μDCN Compact Wire Format
+//
+// This module implements the simplified TLV layout spoken by the standalone
+// `quic_ndn_test` tool: one-byte types, two-byte big-endian lengths, and the
+// name carried as a single URI string rather than as name components.
+// Only the name, nonce and content survive a round trip through this format.
+//
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::error::Error;
+use crate::name::Name;
+use crate::ndn::{tlv_type, Data, Interest};
+use crate::Result;
+
+/// Encode an Interest in the compact format
+pub fn encode_interest(interest: &Interest) -> Result<Bytes> {
+    let name = interest.name.to_string();
+    let mut value = BytesMut::with_capacity(3 + name.len() + 6);
+    
+    // Name as a URI string
+    value.put_u8(tlv_type::NAME);
+    value.put_u16(u16_len(name.len(), "Name")?);
+    value.put_slice(name.as_bytes());
+    
+    // Nonce (one-byte length)
+    value.put_u8(tlv_type::NONCE);
+    value.put_u8(4);
+    value.put_u32(interest.nonce);
+    
+    wrap(tlv_type::INTEREST, &value)
+}
+
+/// Decode an Interest from the compact format
+pub fn decode_interest(buf: &[u8]) -> Result<Interest> {
+    let mut value = unwrap(tlv_type::INTEREST, buf)?;
+    
+    let mut name = None;
+    let mut nonce = None;
+    
+    while value.has_remaining() {
+        let typ = value.get_u8();
+        match typ {
+            tlv_type::NAME => {
+                let uri = read_u16_value(&mut value)?;
+                name = Some(parse_name(&uri)?);
+            }
+            tlv_type::NONCE => {
+                let nonce_value = read_u8_value(&mut value)?;
+                if nonce_value.len() == 4 {
+                    nonce = Some(u32::from_be_bytes([nonce_value[0], nonce_value[1], nonce_value[2], nonce_value[3]]));
+                }
+            }
+            _ => {
+                // Unknown TLVs use a one-byte length in this format
+                read_u8_value(&mut value)?;
+            }
+        }
+    }
+    
+    let name = name.ok_or_else(|| Error::TlvParsing("Compact Interest missing Name".into()))?;
+    let mut interest = Interest::new(name);
+    if let Some(nonce) = nonce {
+        interest.nonce = nonce;
+    }
+    
+    Ok(interest)
+}
+
+/// Encode a Data packet in the compact format
+pub fn encode_data(data: &Data) -> Result<Bytes> {
+    let name = data.name.to_string();
+    let mut value = BytesMut::with_capacity(6 + name.len() + data.content.len());
+    
+    // Name as a URI string
+    value.put_u8(tlv_type::NAME);
+    value.put_u16(u16_len(name.len(), "Name")?);
+    value.put_slice(name.as_bytes());
+    
+    // Content
+    value.put_u8(tlv_type::CONTENT);
+    value.put_u16(u16_len(data.content.len(), "Content")?);
+    value.put_slice(&data.content);
+    
+    wrap(tlv_type::DATA, &value)
+}
+
+/// Decode a Data packet from the compact format
+pub fn decode_data(buf: &[u8]) -> Result<Data> {
+    let mut value = unwrap(tlv_type::DATA, buf)?;
+    
+    let mut name = None;
+    let mut content = Bytes::new();
+    
+    while value.has_remaining() {
+        let typ = value.get_u8();
+        match typ {
+            tlv_type::NAME => {
+                let uri = read_u16_value(&mut value)?;
+                name = Some(parse_name(&uri)?);
+            }
+            tlv_type::CONTENT => {
+                content = read_u16_value(&mut value)?;
+            }
+            _ => {
+                read_u8_value(&mut value)?;
+            }
+        }
+    }
+    
+    let name = name.ok_or_else(|| Error::TlvParsing("Compact Data missing Name".into()))?;
+    Ok(Data::new(name, content))
+}
+
+/// Prefix a value with the outer type and two-byte length
+fn wrap(typ: u8, value: &[u8]) -> Result<Bytes> {
+    let mut buf = BytesMut::with_capacity(3 + value.len());
+    buf.put_u8(typ);
+    buf.put_u16(u16_len(value.len(), "Packet")?);
+    buf.put_slice(value);
+    Ok(buf.freeze())
+}
+
+/// Check that a value fits the two-byte length field
+fn u16_len(len: usize, field: &str) -> Result<u16> {
+    u16::try_from(len).map_err(|_| Error::TlvParsing(format!(
+        "{} of {} bytes is too long for the compact format (max {})", field, len, u16::MAX)))
+}
+
+/// Read one compact packet from a stream: its type and two-byte length,
+/// then exactly the value
+pub async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R, max_packet_size: usize) -> Result<Vec<u8>> {
+    let mut packet = vec![0u8; 3];
+    read_fully(reader, &mut packet).await?;
+    
+    let len = u16::from_be_bytes([packet[1], packet[2]]) as usize;
+    if 3 + len > max_packet_size {
+        return Err(Error::ParsingError(format!(
+            "Compact TLV {} of {} bytes exceeds the maximum packet size", packet[0], len)));
+    }
+    
+    packet.resize(3 + len, 0);
+    read_fully(reader, &mut packet[3..]).await?;
+    Ok(packet)
+}
+
+/// Fill `buf` from a stream, reporting a stream that ends first as a
+/// truncated packet
+async fn read_fully<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> Result<()> {
+    match reader.read_exact(buf).await {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            Err(Error::ParsingError("Stream ended inside a compact packet".to_string()))
+        },
+        Err(e) => Err(Error::IoError(format!("Failed to read from stream: {}", e))),
+    }
+}
+
+/// Check the outer type and return the value
+fn unwrap(typ: u8, buf: &[u8]) -> Result<Bytes> {
+    let mut bytes = Bytes::copy_from_slice(buf);
+    
+    if bytes.len() < 3 {
+        return Err(Error::TlvParsing("Buffer too short for compact TLV".into()));
+    }
+    
+    let actual = bytes.get_u8();
+    if actual != typ {
+        return Err(Error::TlvParsing(format!("Unexpected TLV type: {}", actual)));
+    }
+    
+    let len = bytes.get_u16() as usize;
+    if bytes.len() < len {
+        return Err(Error::TlvParsing("Buffer too short for compact TLV value".into()));
+    }
+    
+    Ok(bytes.split_to(len))
+}
+
+/// Read a value with a two-byte length
+fn read_u16_value(buf: &mut Bytes) -> Result<Bytes> {
+    if buf.len() < 2 {
+        return Err(Error::TlvParsing("Truncated compact TLV length".into()));
+    }
+    let len = buf.get_u16() as usize;
+    if buf.len() < len {
+        return Err(Error::TlvParsing("Truncated compact TLV value".into()));
+    }
+    Ok(buf.split_to(len))
+}
+
+/// Read a value with a one-byte length
+fn read_u8_value(buf: &mut Bytes) -> Result<Bytes> {
+    if buf.is_empty() {
+        return Err(Error::TlvParsing("Truncated compact TLV length".into()));
+    }
+    let len = buf.get_u8() as usize;
+    if buf.len() < len {
+        return Err(Error::TlvParsing("Truncated compact TLV value".into()));
+    }
+    Ok(buf.split_to(len))
+}
+
+/// Parse a URI carried in a compact Name TLV
+fn parse_name(uri: &[u8]) -> Result<Name> {
+    let uri = std::str::from_utf8(uri)
+        .map_err(|_| Error::NameParsing("Compact Name is not valid UTF-8".into()))?;
+    Name::from_uri(uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ndn::WireFormat;
+    
+    // Bytes produced by quic_ndn_test for Interest("/test/data") with nonce 0x01020304
+    const TOOL_INTEREST: &[u8] = &[
+        0x05, 0x00, 0x13,
+        0x07, 0x00, 0x0A, b'/', b't', b'e', b's', b't', b'/', b'd', b'a', b't', b'a',
+        0x0A, 0x04, 0x01, 0x02, 0x03, 0x04,
+    ];
+    
+    // Bytes produced by quic_ndn_test for Data("/test/data", "hello")
+    const TOOL_DATA: &[u8] = &[
+        0x06, 0x00, 0x15,
+        0x07, 0x00, 0x0A, b'/', b't', b'e', b's', b't', b'/', b'd', b'a', b't', b'a',
+        0x15, 0x00, 0x05, b'h', b'e', b'l', b'l', b'o',
+    ];
+    
+    #[test]
+    fn test_compact_interest_matches_tool() {
+        let mut interest = Interest::new(Name::from_uri("/test/data").unwrap());
+        interest.nonce = 0x01020304;
+        
+        assert_eq!(interest.encode(WireFormat::Compact).unwrap().as_ref(), TOOL_INTEREST);
+        
+        let decoded = Interest::decode(TOOL_INTEREST, WireFormat::Compact).unwrap();
+        assert_eq!(decoded.name().to_string(), "/test/data");
+        assert_eq!(decoded.nonce(), 0x01020304);
+    }
+    
+    #[test]
+    fn test_compact_data_matches_tool() {
+        let data = Data::new(Name::from_uri("/test/data").unwrap(), "hello");
+        
+        assert_eq!(data.encode(WireFormat::Compact).unwrap().as_ref(), TOOL_DATA);
+        
+        let decoded = Data::decode(TOOL_DATA, WireFormat::Compact).unwrap();
+        assert_eq!(decoded.name().to_string(), "/test/data");
+        assert_eq!(decoded.content().as_ref(), b"hello");
+    }
+    
+    #[test]
+    fn test_formats_are_not_interchangeable() {
+        let interest = Interest::new(Name::from_uri("/test/data").unwrap());
+        
+        // A compact packet is rejected by the NDN decoder and vice versa
+        assert!(Interest::decode(&interest.encode(WireFormat::Compact).unwrap(), WireFormat::Ndn).is_err());
+        assert!(Interest::decode(&interest.encode(WireFormat::Ndn).unwrap(), WireFormat::Compact).is_err());
+    }
+    
+    #[test]
+    fn test_compact_rejects_oversized_fields() {
+        let name = Name::from_uri("/test/data").unwrap();
+        
+        // Content too long for the two-byte length
+        let data = Data::new(name.clone(), vec![0u8; 70_000]);
+        assert!(matches!(data.encode(WireFormat::Compact), Err(Error::TlvParsing(_))));
+        
+        // Each field fits but the packet as a whole does not
+        let data = Data::new(name, vec![0u8; u16::MAX as usize]);
+        assert!(matches!(data.encode(WireFormat::Compact), Err(Error::TlvParsing(_))));
+        
+        // The NDN encoding has no such limit
+        assert!(data.encode(WireFormat::Ndn).is_ok());
+    }
+}
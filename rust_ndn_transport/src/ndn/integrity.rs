@@ -0,0 +1,260 @@
+//
+// μDCN Packet Integrity Self-Test
+//
+// This module implements an optional diagnostic mode that appends a CRC32 over
+// the encoded packet as a trailing IntegrityCheck TLV on send and verifies it
+// on receive. It catches corruption introduced between the encoder and the
+// decoder (buffer reuse, framing bugs, misbehaving middleboxes in tests) and
+// is not a substitute for signatures: anyone can recompute a CRC. Both ends
+// must enable the mode, as a receiver that has it on rejects packets without
+// a trailer.
+//
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use tracing::warn;
+
+use crate::error::Error;
+use crate::ndn::{tlv, tlv_type};
+use crate::Result;
+
+/// Size of the trailing IntegrityCheck TLV (type + length + CRC32)
+pub const TRAILER_SIZE: usize = 2 + 4;
+
+/// Lookup table for the reflected IEEE 802.3 polynomial
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Compute the CRC32 (IEEE) of a buffer
+pub fn crc32(buf: &[u8]) -> u32 {
+    let crc = buf.iter().fold(0xFFFF_FFFFu32, |crc, b| {
+        CRC32_TABLE[((crc ^ *b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    });
+    !crc
+}
+
+/// Integrity check statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntegrityStats {
+    /// Packets sealed with a CRC on send
+    pub sealed: u64,
+    
+    /// Packets whose CRC was verified on receive
+    pub verified: u64,
+    
+    /// Packets whose CRC did not match
+    pub mismatches: u64,
+    
+    /// Packets received without an IntegrityCheck trailer
+    pub missing: u64,
+}
+
+/// CRC32 self-test for encoded packets
+#[derive(Debug, Default)]
+pub struct IntegrityCheck {
+    /// Whether packets are sealed and verified
+    enabled: bool,
+    
+    /// Number of packets sealed
+    sealed: AtomicU64,
+    
+    /// Number of packets verified
+    verified: AtomicU64,
+    
+    /// Number of CRC mismatches
+    mismatches: AtomicU64,
+    
+    /// Number of packets without a trailer
+    missing: AtomicU64,
+}
+
+impl IntegrityCheck {
+    /// Create a new integrity check, disabled unless `enabled` is set
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+    
+    /// Check whether the self-test mode is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    
+    /// Append the IntegrityCheck TLV to an encoded packet
+    pub fn seal(&self, packet: Bytes) -> Bytes {
+        if !self.enabled {
+            return packet;
+        }
+        
+        let mut buf = BytesMut::with_capacity(packet.len() + TRAILER_SIZE);
+        buf.extend_from_slice(&packet);
+        buf.put_u8(tlv_type::INTEGRITY_CHECK);
+        buf.put_u8(4);
+        buf.put_u32(crc32(&packet));
+        
+        self.sealed.fetch_add(1, Ordering::Relaxed);
+        buf.freeze()
+    }
+    
+    /// Verify and strip the IntegrityCheck TLV from a received packet
+    ///
+    /// The trailer is the TLV right after the end of the packet's outer TLV.
+    /// A packet without one is rejected and counted as missing, as is one
+    /// followed by anything else.
+    pub fn verify<'a>(&self, packet: &'a [u8]) -> Result<&'a [u8]> {
+        if !self.enabled {
+            return Ok(packet);
+        }
+        
+        let split = outer_tlv_len(packet)?;
+        let (body, trailer) = packet.split_at(split);
+        if trailer.len() != TRAILER_SIZE || trailer[0] != tlv_type::INTEGRITY_CHECK || trailer[1] != 4 {
+            self.missing.fetch_add(1, Ordering::Relaxed);
+            return Err(Error::ChecksumMismatch(format!(
+                "no IntegrityCheck trailer after the {}-byte packet ({} bytes follow)", body.len(), trailer.len())));
+        }
+        
+        let expected = u32::from_be_bytes([trailer[2], trailer[3], trailer[4], trailer[5]]);
+        let actual = crc32(body);
+        
+        if expected != actual {
+            self.mismatches.fetch_add(1, Ordering::Relaxed);
+            warn!("CRC32 mismatch on {}-byte packet: expected {:08x}, computed {:08x}",
+                  body.len(), expected, actual);
+            return Err(Error::ChecksumMismatch(format!(
+                "expected {:08x}, computed {:08x}", expected, actual)));
+        }
+        
+        self.verified.fetch_add(1, Ordering::Relaxed);
+        Ok(body)
+    }
+    
+    /// Get the integrity check statistics
+    pub fn stats(&self) -> IntegrityStats {
+        IntegrityStats {
+            sealed: self.sealed.load(Ordering::Relaxed),
+            verified: self.verified.load(Ordering::Relaxed),
+            mismatches: self.mismatches.load(Ordering::Relaxed),
+            missing: self.missing.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Get the size of the outer TLV at the start of a buffer
+fn outer_tlv_len(packet: &[u8]) -> Result<usize> {
+    let mut header = packet;
+    tlv::read_var_number(&mut header)?;
+    let value_len = tlv::read_var_number(&mut header)?;
+    let header_len = packet.len() - header.len();
+    
+    match usize::try_from(value_len).ok().and_then(|len| len.checked_add(header_len)) {
+        Some(len) if len <= packet.len() => Ok(len),
+        _ => Err(Error::TlvParsing(format!(
+            "TLV of {} bytes does not fit in a {}-byte packet", value_len, packet.len()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use tokio::net::UdpSocket;
+    use crate::name::Name;
+    use crate::ndn::{Data, Interest};
+    
+    /// A UDP socket that flips one byte of every datagram it sends
+    struct CorruptingSocket {
+        inner: UdpSocket,
+        offset: usize,
+    }
+    
+    impl CorruptingSocket {
+        async fn send_to(&self, buf: &[u8], target: SocketAddr) -> std::io::Result<usize> {
+            let mut corrupted = buf.to_vec();
+            corrupted[self.offset] ^= 0x01;
+            self.inner.send_to(&corrupted, target).await
+        }
+    }
+    
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_corruption_in_transit_is_detected() {
+        let sender = IntegrityCheck::new(true);
+        let receiver = IntegrityCheck::new(true);
+        
+        let rx = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let tx = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let rx_addr = rx.local_addr().unwrap();
+        let mut buf = vec![0u8; 2048];
+        
+        // A clean hop verifies and strips the trailer
+        let interest = Interest::new(Name::from_uri("/udcn/integrity/clean").unwrap());
+        tx.send_to(&sender.seal(interest.to_bytes()), rx_addr).await.unwrap();
+        let len = rx.recv(&mut buf).await.unwrap();
+        let body = receiver.verify(&buf[..len]).unwrap();
+        assert_eq!(Interest::from_bytes(body).unwrap().name(), interest.name());
+        
+        // A byte flipped inside the packet body is caught and counted
+        let corrupting = CorruptingSocket { inner: tx, offset: 10 };
+        let data = Data::new(Name::from_uri("/udcn/integrity/flipped").unwrap(), "payload");
+        corrupting.send_to(&sender.seal(data.to_bytes()), rx_addr).await.unwrap();
+        let len = rx.recv(&mut buf).await.unwrap();
+        let result = receiver.verify(&buf[..len]);
+        assert!(matches!(result, Err(Error::ChecksumMismatch(_))), "Expected a CRC mismatch but got: {:?}", result);
+        
+        assert_eq!(sender.stats().sealed, 2);
+        assert_eq!(receiver.stats(), IntegrityStats { sealed: 0, verified: 1, mismatches: 1, missing: 0 });
+    }
+    
+    #[test]
+    fn test_trailer_located_from_outer_tlv() {
+        let check = IntegrityCheck::new(true);
+        
+        // A packet whose own last bytes look like a trailer is not trusted
+        // to carry one
+        let mut content = vec![0u8; 16];
+        content.extend_from_slice(&[tlv_type::INTEGRITY_CHECK, 4, 0, 0, 0, 0]);
+        let data = Data::new(Name::from_uri("/udcn/integrity/lookalike").unwrap(), content).to_bytes();
+        assert!(matches!(check.verify(&data), Err(Error::ChecksumMismatch(_))));
+        assert_eq!(check.stats().missing, 1);
+        
+        // Neither is one with extra bytes after the trailer, or a truncated one
+        let mut sealed = check.seal(data.clone()).to_vec();
+        assert_eq!(check.verify(&sealed).unwrap(), data.as_ref());
+        sealed.push(0);
+        assert!(check.verify(&sealed).is_err());
+        assert!(matches!(check.verify(&data[..data.len() - 1]), Err(Error::TlvParsing(_))));
+        assert_eq!(check.stats().missing, 2);
+    }
+    
+    #[test]
+    fn test_disabled_mode_is_transparent() {
+        let check = IntegrityCheck::default();
+        let packet = Interest::new(Name::from_uri("/udcn/integrity/off").unwrap()).to_bytes();
+        
+        assert_eq!(check.seal(packet.clone()), packet);
+        assert_eq!(check.verify(&packet).unwrap(), packet.as_ref());
+        assert_eq!(check.stats(), IntegrityStats::default());
+    }
+}
@@ -0,0 +1,224 @@
+//
+// μDCN Prefix Announcements
+//
+// This module implements prefix announcements, the self-certifying Data
+// objects a producer publishes to advertise reachability for a prefix.
+// An announcement is named <prefix>/32=PA/<version> and carries its
+// expiration period as content.
+//
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+use crate::error::Error;
+use crate::name::{Component, Name};
+use crate::ndn::{tlv, ContentType, Data};
+use crate::security::{decode_signature_info, encode_signature_info, KeyStore};
+use crate::Result;
+
+/// Keyword component marking a prefix announcement name
+pub const PREFIX_ANN_KEYWORD: &str = "32=PA";
+
+/// TLV type of the ExpirationPeriod element
+const EXPIRATION_PERIOD: u8 = 0x6D;
+
+/// A prefix announcement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixAnnouncement {
+    /// The announced prefix
+    prefix: Name,
+    
+    /// How long the announcement remains valid
+    expiration: Duration,
+    
+    /// Version of the announcement (milliseconds since the epoch)
+    version: u64,
+}
+
+impl PrefixAnnouncement {
+    /// Create a new announcement for the given prefix
+    pub fn new(prefix: Name, expiration: Duration) -> Self {
+        let version = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        
+        Self {
+            prefix,
+            expiration,
+            version,
+        }
+    }
+    
+    /// Get the announced prefix
+    pub fn prefix(&self) -> &Name {
+        &self.prefix
+    }
+    
+    /// Get the expiration period
+    pub fn expiration(&self) -> Duration {
+        self.expiration
+    }
+    
+    /// Get the announcement version
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+    
+    /// Get the Data name of the announcement
+    pub fn data_name(&self) -> Name {
+        let mut name = self.prefix.clone();
+        name.push_str(PREFIX_ANN_KEYWORD);
+        name.push(Component::new(self.version.to_be_bytes().to_vec()));
+        name
+    }
+    
+    /// Build a PrefixAnn Data signed with the default key of the key store
+    pub fn to_data(&self, keychain: &KeyStore) -> Result<Data> {
+        let key_name = keychain.default_key()
+            .ok_or_else(|| Error::Other("Key store has no default key".into()))?;
+        
+        // Content is the ExpirationPeriod in milliseconds
+        let mut content = BytesMut::with_capacity(10);
+        tlv::write_tlv_header(&mut content, EXPIRATION_PERIOD, 8);
+        content.put_u64(self.expiration.as_millis() as u64);
+        
+        let data = Data::new(self.data_name(), content.freeze())
+            .content_type(ContentType::PrefixAnn)
+            .fresh_period(self.expiration)
+            .with_signature(encode_signature_info(key_name), Vec::new());
+        
+        // Sign Name + MetaInfo + Content + SignatureInfo
+        let signature = keychain.sign(key_name, &data.signed_portion())?;
+        let signature_info = data.signature_info().to_vec();
+        
+        Ok(data.with_signature(signature_info, signature))
+    }
+    
+    /// Parse an announcement from a Data packet without checking its signature
+    pub fn from_data(data: &Data) -> Result<Self> {
+        if data.get_content_type() != ContentType::PrefixAnn {
+            return Err(Error::ProtocolError(format!(
+                "Not a prefix announcement: content type {:?}", data.get_content_type())));
+        }
+        
+        // Name must end with 32=PA/<version>
+        let name = data.name();
+        if name.len() < 2 || name.get(name.len() - 2) != Some(&Component::from_str(PREFIX_ANN_KEYWORD)) {
+            return Err(Error::ProtocolError(format!(
+                "Invalid prefix announcement name: {}", name)));
+        }
+        
+        let version_bytes = name.get(name.len() - 1).unwrap().value();
+        let version = tlv::read_non_neg_int(version_bytes)
+            .ok_or_else(|| Error::ProtocolError("Invalid prefix announcement version".into()))?;
+        
+        let prefix = Name::from_components(name.components()[..name.len() - 2].to_vec());
+        
+        // Parse the ExpirationPeriod from the content
+        let mut content = data.content().clone();
+        let mut expiration = None;
+        while content.has_remaining() {
+            let (typ, value) = tlv::read_tlv(&mut content)?;
+            if typ == EXPIRATION_PERIOD as u64 {
+                expiration = tlv::read_non_neg_int(&value).map(Duration::from_millis);
+            }
+        }
+        
+        let expiration = expiration
+            .ok_or_else(|| Error::ProtocolError("Prefix announcement missing ExpirationPeriod".into()))?;
+        
+        Ok(Self {
+            prefix,
+            expiration,
+            version,
+        })
+    }
+    
+    /// Parse an announcement and verify its signature against the key store
+    pub fn verify(data: &Data, keychain: &KeyStore) -> Result<Self> {
+        let announcement = Self::from_data(data)?;
+        
+        let (_, key_name) = decode_signature_info(data.signature_info())
+            .ok_or_else(|| Error::SignatureVerification("Missing key locator".into()))?;
+        
+        keychain.verify(&key_name, &data.signed_portion(), data.signature_value())?;
+        
+        Ok(announcement)
+    }
+    
+    /// Parse and verify an announcement from its wire encoding
+    pub fn from_bytes(buf: &[u8], keychain: &KeyStore) -> Result<Self> {
+        let data = Data::from_bytes(buf)?;
+        Self::verify(&data, keychain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_prefix_announcement_round_trip() {
+        let mut keychain = KeyStore::new();
+        keychain.generate_key_pair("producer").unwrap();
+        
+        let prefix = Name::from_uri("/udcn/video").unwrap();
+        let announcement = PrefixAnnouncement::new(prefix.clone(), Duration::from_secs(3600));
+        
+        // Create and sign
+        let data = announcement.to_data(&keychain).unwrap();
+        assert_eq!(data.get_content_type(), ContentType::PrefixAnn);
+        assert_eq!(data.signature_value().len(), 64);
+        
+        // Serialize, parse and verify
+        let wire = data.to_bytes();
+        let parsed = PrefixAnnouncement::from_bytes(&wire, &keychain).unwrap();
+        
+        assert_eq!(parsed.prefix().to_string(), prefix.to_string());
+        assert_eq!(parsed.expiration(), Duration::from_secs(3600));
+        assert_eq!(parsed.version(), announcement.version());
+    }
+    
+    #[test]
+    fn test_prefix_announcement_signed_with_default_key() {
+        let announcement = PrefixAnnouncement::new(
+            Name::from_uri("/udcn/video").unwrap(), Duration::from_secs(60));
+        
+        // An empty key store has nothing to sign with
+        let mut keychain = KeyStore::new();
+        assert!(announcement.to_data(&keychain).is_err());
+        
+        // The first key generated signs until another is made the default
+        keychain.generate_key_pair("producer").unwrap();
+        keychain.generate_key_pair("operator").unwrap();
+        assert_eq!(keychain.default_key(), Some("producer"));
+        keychain.set_default_key("operator").unwrap();
+        assert!(keychain.set_default_key("stranger").is_err());
+        
+        let data = announcement.to_data(&keychain).unwrap();
+        let (_, key_name) = decode_signature_info(data.signature_info()).unwrap();
+        assert_eq!(key_name, "operator");
+        PrefixAnnouncement::verify(&data, &keychain).unwrap();
+    }
+    
+    #[test]
+    fn test_prefix_announcement_rejects_bad_signature() {
+        let mut keychain = KeyStore::new();
+        keychain.generate_key_pair("producer").unwrap();
+        
+        let announcement = PrefixAnnouncement::new(
+            Name::from_uri("/udcn/video").unwrap(), Duration::from_secs(60));
+        let data = announcement.to_data(&keychain).unwrap();
+        
+        // Tamper with the signature
+        let mut signature = data.signature_value().to_vec();
+        signature[0] ^= 0xFF;
+        let signature_info = data.signature_info().to_vec();
+        let tampered = data.with_signature(signature_info, signature);
+        
+        let result = PrefixAnnouncement::verify(&tampered, &keychain);
+        assert!(matches!(result, Err(Error::SignatureVerification(_))));
+    }
+}
@@ -0,0 +1,296 @@
+//
+// μDCN TLV Encoding
+//
+// This module implements the NDN variable-length encoding shared by every
+// TLV-TYPE and TLV-LENGTH: numbers below 253 take a single byte, larger ones
+// a 253, 254 or 255 marker followed by a 2, 4 or 8 byte big-endian number.
+// All TLV types used by μDCN are below 253, so they are written as one byte.
+// Packets read off a stream are framed by their own TLV header.
+//
+
+use bytes::{Buf, BufMut, Bytes};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::error::Error;
+use crate::Result;
+
+/// Marker for a number carried in the following 2 bytes
+const VAR_NUMBER_2: u8 = 253;
+
+/// Marker for a number carried in the following 4 bytes
+const VAR_NUMBER_4: u8 = 254;
+
+/// Marker for a number carried in the following 8 bytes
+const VAR_NUMBER_8: u8 = 255;
+
+/// Get the number of bytes the encoding of a number takes
+pub fn var_number_size(number: u64) -> usize {
+    if number < VAR_NUMBER_2 as u64 {
+        1
+    } else if number <= u16::MAX as u64 {
+        3
+    } else if number <= u32::MAX as u64 {
+        5
+    } else {
+        9
+    }
+}
+
+/// Write a number in its shortest encoding
+pub fn write_var_number<B: BufMut>(buf: &mut B, number: u64) {
+    if number < VAR_NUMBER_2 as u64 {
+        buf.put_u8(number as u8);
+    } else if number <= u16::MAX as u64 {
+        buf.put_u8(VAR_NUMBER_2);
+        buf.put_u16(number as u16);
+    } else if number <= u32::MAX as u64 {
+        buf.put_u8(VAR_NUMBER_4);
+        buf.put_u32(number as u32);
+    } else {
+        buf.put_u8(VAR_NUMBER_8);
+        buf.put_u64(number);
+    }
+}
+
+/// Read a number, failing if the buffer ends before it does
+pub fn read_var_number<B: Buf>(buf: &mut B) -> Result<u64> {
+    if !buf.has_remaining() {
+        return Err(Error::TlvParsing("Buffer too short for TLV number".into()));
+    }
+    
+    let first = buf.get_u8();
+    let size = match first {
+        VAR_NUMBER_2 => 2,
+        VAR_NUMBER_4 => 4,
+        VAR_NUMBER_8 => 8,
+        _ => return Ok(first as u64),
+    };
+    
+    if buf.remaining() < size {
+        return Err(Error::TlvParsing(format!("Buffer too short for {}-byte TLV number", size)));
+    }
+    
+    Ok(match size {
+        2 => buf.get_u16() as u64,
+        4 => buf.get_u32() as u64,
+        _ => buf.get_u64(),
+    })
+}
+
+/// Get the encoded size of a TLV with a one-byte type and a value of the given length
+pub fn tlv_size(value_len: usize) -> usize {
+    1 + var_number_size(value_len as u64) + value_len
+}
+
+/// Write the type and length of a TLV
+pub fn write_tlv_header<B: BufMut>(buf: &mut B, typ: u8, value_len: usize) {
+    buf.put_u8(typ);
+    write_var_number(buf, value_len as u64);
+}
+
+/// Write a complete TLV
+pub fn write_tlv<B: BufMut>(buf: &mut B, typ: u8, value: &[u8]) {
+    write_tlv_header(buf, typ, value.len());
+    buf.put_slice(value);
+}
+
+/// Get the number of bytes a NonNegativeInteger value takes: 1, 2, 4 or 8
+pub fn non_neg_int_size(number: u64) -> usize {
+    if number <= u8::MAX as u64 {
+        1
+    } else if number <= u16::MAX as u64 {
+        2
+    } else if number <= u32::MAX as u64 {
+        4
+    } else {
+        8
+    }
+}
+
+/// Write a NonNegativeInteger value in its shortest big-endian encoding
+pub fn write_non_neg_int<B: BufMut>(buf: &mut B, number: u64) {
+    match non_neg_int_size(number) {
+        1 => buf.put_u8(number as u8),
+        2 => buf.put_u16(number as u16),
+        4 => buf.put_u32(number as u32),
+        _ => buf.put_u64(number),
+    }
+}
+
+/// Read a NonNegativeInteger value of 1, 2, 4 or 8 big-endian bytes
+pub fn read_non_neg_int(bytes: &[u8]) -> Option<u64> {
+    match bytes.len() {
+        1 | 2 | 4 | 8 => Some(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)),
+        _ => None,
+    }
+}
+
+/// Read a TLV, returning its type and value
+pub fn read_tlv(buf: &mut Bytes) -> Result<(u64, Bytes)> {
+    let typ = read_var_number(buf)?;
+    let len = read_var_number(buf)?;
+    
+    if (buf.len() as u64) < len {
+        return Err(Error::TlvParsing(format!(
+            "Buffer too short for TLV {} value: {} of {} bytes", typ, buf.len(), len)));
+    }
+    
+    Ok((typ, buf.split_to(len as usize)))
+}
+
+/// Split a complete TLV, header included, off the front of the buffer
+pub fn split_tlv(buf: &mut Bytes) -> Result<Bytes> {
+    let mut rest = buf.clone();
+    read_tlv(&mut rest)?;
+    Ok(buf.split_to(buf.len() - rest.len()))
+}
+
+/// Read one TLV packet from a stream: its type and length, then exactly
+/// the value, however the peer's writes were split into STREAM frames
+///
+/// Bytes after the packet are left unread, so the stream may stay open. A
+/// stream ending inside the packet is a `ParsingError`.
+pub async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R, max_packet_size: usize) -> Result<Vec<u8>> {
+    let mut packet = Vec::new();
+    let typ = read_stream_var_number(reader, &mut packet).await?;
+    let len = read_stream_var_number(reader, &mut packet).await?;
+    
+    let header_len = packet.len();
+    if header_len as u64 + len > max_packet_size as u64 {
+        return Err(Error::ParsingError(format!(
+            "TLV {} of {} bytes exceeds the maximum packet size", typ, len)));
+    }
+    
+    packet.resize(header_len + len as usize, 0);
+    read_fully(reader, &mut packet[header_len..]).await?;
+    Ok(packet)
+}
+
+/// Read a TLV-TYPE or TLV-LENGTH number from a stream, appending its
+/// encoding to `packet`
+async fn read_stream_var_number<R: AsyncRead + Unpin>(reader: &mut R, packet: &mut Vec<u8>) -> Result<u64> {
+    let start = packet.len();
+    let mut first = [0u8; 1];
+    read_fully(reader, &mut first).await?;
+    packet.push(first[0]);
+    
+    // The first byte tells how many more bytes the number takes
+    let size = match first[0] {
+        253 => 2,
+        254 => 4,
+        255 => 8,
+        _ => 0,
+    };
+    packet.resize(start + 1 + size, 0);
+    read_fully(reader, &mut packet[start + 1..]).await?;
+    read_var_number(&mut &packet[start..])
+}
+
+/// Fill `buf` from a stream, reporting a stream that ends first as a
+/// truncated packet
+async fn read_fully<R: AsyncRead + Unpin>(reader: &mut R, buf: &mut [u8]) -> Result<()> {
+    match reader.read_exact(buf).await {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            Err(Error::ParsingError("Stream ended inside a TLV packet".to_string()))
+        },
+        Err(e) => Err(Error::IoError(format!("Failed to read from stream: {}", e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+    use tokio::io::AsyncWriteExt;
+    
+    use crate::name::Name;
+    use crate::ndn::Interest;
+    
+    #[test]
+    fn test_var_number_boundaries() {
+        let cases: [(u64, &[u8]); 7] = [
+            (0, &[0]),
+            (252, &[252]),
+            (253, &[253, 0, 253]),
+            (65535, &[253, 255, 255]),
+            (65536, &[254, 0, 1, 0, 0]),
+            (4294967295, &[254, 255, 255, 255, 255]),
+            (4294967296, &[255, 0, 0, 0, 1, 0, 0, 0, 0]),
+        ];
+        
+        for (number, encoding) in cases {
+            let mut buf = BytesMut::new();
+            write_var_number(&mut buf, number);
+            assert_eq!(&buf[..], encoding, "encoding of {}", number);
+            assert_eq!(var_number_size(number), encoding.len());
+            
+            let mut bytes = buf.freeze();
+            assert_eq!(read_var_number(&mut bytes).unwrap(), number);
+            assert!(bytes.is_empty());
+        }
+        
+        // A truncated multi-byte number is an error rather than a panic
+        let mut truncated = Bytes::from_static(&[254, 0, 1]);
+        assert!(read_var_number(&mut truncated).is_err());
+    }
+    
+    #[test]
+    fn test_non_neg_int_round_trip() {
+        for (number, size) in [(0, 1), (255, 1), (256, 2), (65536, 4), (u32::MAX as u64 + 1, 8)] {
+            let mut buf = BytesMut::new();
+            write_non_neg_int(&mut buf, number);
+            assert_eq!(buf.len(), size, "encoding of {}", number);
+            assert_eq!(read_non_neg_int(&buf), Some(number));
+        }
+        
+        // Other lengths are not valid encodings
+        assert_eq!(read_non_neg_int(&[0, 0, 1]), None);
+        assert_eq!(read_non_neg_int(&[]), None);
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_tlv_reader_reassembles_split_interest() -> Result<()> {
+        // A context long enough to need a multi-byte TLV-LENGTH
+        let interest = Interest::new(Name::from_uri("/split/reader")?).with_context(vec![7u8; 1000]);
+        let bytes = interest.to_bytes();
+        
+        // The Interest arrives a few bytes at a time on a stream that stays open
+        let (mut writer, mut reader) = tokio::io::duplex(64);
+        let chunks = bytes.clone();
+        let feeder = tokio::spawn(async move {
+            for chunk in chunks.chunks(3) {
+                writer.write_all(chunk).await.unwrap();
+                tokio::task::yield_now().await;
+            }
+            writer
+        });
+        
+        let packet = read_packet(&mut reader, 65535).await?;
+        assert_eq!(packet, bytes.to_vec());
+        assert_eq!(Interest::from_bytes(&packet)?.name(), interest.name());
+        
+        // Bytes after the packet are left on the stream
+        let mut writer = feeder.await.unwrap();
+        writer.write_all(&[0xAB]).await.unwrap();
+        assert_eq!(reader.read_u8().await.unwrap(), 0xAB);
+        Ok(())
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test)]
+    async fn test_tlv_reader_rejects_truncated_interest() -> Result<()> {
+        let interest = Interest::new(Name::from_uri("/truncated")?).with_context(vec![7u8; 1000]);
+        let bytes = interest.to_bytes();
+        
+        // Ending inside the type, the length or the value is a parsing error
+        for cut in [0, 1, 2, 4, bytes.len() - 1] {
+            let err = read_packet(&mut &bytes[..cut], 65535).await.unwrap_err();
+            assert!(matches!(err, Error::ParsingError(_)), "cut at {}: {}", cut, err);
+        }
+        
+        // So is a packet larger than allowed
+        let err = read_packet(&mut &bytes[..], bytes.len() - 1).await.unwrap_err();
+        assert!(matches!(err, Error::ParsingError(_)));
+        Ok(())
+    }
+}
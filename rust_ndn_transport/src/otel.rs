@@ -0,0 +1,257 @@
+//
+// μDCN Trace Context Propagation
+//
+// This module carries distributed traces across forwarding hops. An Interest
+// may carry the W3C traceparent of the span that sent it; each forwarder
+// opens a child span for its hop, linked to that remote parent through
+// OpenTelemetry, and replaces the traceparent with its own so the next hop
+// nests under it. The trace id is kept end to end.
+//
+
+use std::fmt;
+
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use tracing::{debug, info_span, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::error::{Error, Result};
+use crate::ndn::Interest;
+
+/// Version of the traceparent format written
+const TRACEPARENT_VERSION: u8 = 0x00;
+
+/// Reserved version, never valid
+const INVALID_VERSION: u8 = 0xff;
+
+/// W3C trace context identifying one span of a trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    /// Trace the span belongs to
+    pub trace_id: [u8; 16],
+    
+    /// The span itself
+    pub span_id: [u8; 8],
+    
+    /// Trace flags; bit 0 marks a sampled trace
+    pub flags: u8,
+}
+
+impl TraceContext {
+    /// Parse a traceparent header value
+    ///
+    /// Versions after 00 may append fields, which are ignored. Version ff
+    /// and all-zero ids are invalid.
+    pub fn parse(traceparent: &str) -> Result<Self> {
+        let invalid = || Error::ParsingError(format!("Invalid traceparent: {}", traceparent));
+        
+        let mut fields = traceparent.trim().split('-');
+        let mut version = [0u8; 1];
+        let mut flags = [0u8; 1];
+        let mut context = Self {
+            trace_id: [0; 16],
+            span_id: [0; 8],
+            flags: 0,
+        };
+        
+        hex::decode_to_slice(fields.next().ok_or_else(invalid)?, &mut version).map_err(|_| invalid())?;
+        hex::decode_to_slice(fields.next().ok_or_else(invalid)?, &mut context.trace_id).map_err(|_| invalid())?;
+        hex::decode_to_slice(fields.next().ok_or_else(invalid)?, &mut context.span_id).map_err(|_| invalid())?;
+        hex::decode_to_slice(fields.next().ok_or_else(invalid)?, &mut flags).map_err(|_| invalid())?;
+        context.flags = flags[0];
+        
+        let extra_fields = fields.next().is_some();
+        if version[0] == INVALID_VERSION
+            || (version[0] == TRACEPARENT_VERSION && extra_fields)
+            || context.trace_id == [0; 16]
+            || context.span_id == [0; 8]
+        {
+            return Err(invalid());
+        }
+        
+        Ok(context)
+    }
+    
+    /// Create the context of a child span, in the same trace
+    pub fn child(&self) -> Self {
+        let mut span_id = [0u8; 8];
+        while span_id == [0; 8] {
+            span_id = rand::random();
+        }
+        
+        Self {
+            span_id,
+            ..*self
+        }
+    }
+    
+    /// Get the trace id in hex
+    pub fn trace_id_hex(&self) -> String {
+        hex::encode(self.trace_id)
+    }
+    
+    /// Get the span id in hex
+    pub fn span_id_hex(&self) -> String {
+        hex::encode(self.span_id)
+    }
+    
+    /// Get the context of an OpenTelemetry span, if it is valid
+    fn from_span_context(span_context: &SpanContext) -> Option<Self> {
+        span_context.is_valid().then(|| Self {
+            trace_id: span_context.trace_id().to_bytes(),
+            span_id: span_context.span_id().to_bytes(),
+            flags: span_context.trace_flags().to_u8(),
+        })
+    }
+    
+    /// Get an OpenTelemetry context whose parent is this span, on another node
+    fn to_otel_context(self) -> opentelemetry::Context {
+        let span_context = SpanContext::new(
+            TraceId::from_bytes(self.trace_id),
+            SpanId::from_bytes(self.span_id),
+            TraceFlags::new(self.flags),
+            true,
+            TraceState::default(),
+        );
+        opentelemetry::Context::new().with_remote_span_context(span_context)
+    }
+}
+
+impl fmt::Display for TraceContext {
+    /// Format as a traceparent header value
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02x}-{}-{}-{:02x}", TRACEPARENT_VERSION, self.trace_id_hex(), self.span_id_hex(), self.flags)
+    }
+}
+
+/// Open the span of a forwarding hop and stamp the Interest with it
+///
+/// The span is a child of the traceparent the Interest carries or, failing
+/// that, of the current span when it belongs to a trace. The Interest is
+/// returned carrying the hop span's traceparent. An Interest outside any
+/// trace is returned unchanged, with a disabled span.
+pub fn forward_span(interest: Interest) -> (Interest, Span) {
+    let parent = interest.traceparent()
+        .and_then(|traceparent| TraceContext::parse(traceparent).ok())
+        .or_else(|| TraceContext::from_span_context(Span::current().context().span().span_context()));
+    let parent = match parent {
+        Some(parent) => parent,
+        None => return (interest, Span::none()),
+    };
+    
+    let span = info_span!(
+        "ndn.forward",
+        name = %interest.name(),
+        trace_id = %parent.trace_id_hex(),
+        parent_span_id = %parent.span_id_hex(),
+        span_id = tracing::field::Empty,
+    );
+    span.set_parent(parent.to_otel_context());
+    
+    // With an OpenTelemetry layer installed the hop span has an id of its
+    // own in the same trace; without one a child id is made up for it
+    let hop = TraceContext::from_span_context(span.context().span().span_context())
+        .filter(|hop| hop.trace_id == parent.trace_id)
+        .unwrap_or_else(|| parent.child());
+    span.record("span_id", hop.span_id_hex().as_str());
+    debug!("Forwarding {} in trace {}", interest.name(), hop.trace_id_hex());
+    
+    (interest.with_traceparent(hop.to_string()), span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::Name;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use parking_lot::Mutex;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+    
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    
+    /// Fields of a span by name
+    #[derive(Default)]
+    struct SpanFields(HashMap<String, String>);
+    
+    impl Visit for SpanFields {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+    
+    /// Layer keeping the name and fields of every span opened
+    #[derive(Clone, Default)]
+    struct SpanCapture(Arc<Mutex<Vec<HashMap<String, String>>>>);
+    
+    impl<S: Subscriber> Layer<S> for SpanCapture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = SpanFields::default();
+            fields.0.insert("span".to_string(), attrs.metadata().name().to_string());
+            attrs.record(&mut fields);
+            self.0.lock().push(fields.0);
+        }
+    }
+    
+    #[test]
+    fn test_traceparent_parse_and_child() {
+        let context = TraceContext::parse(TRACEPARENT).unwrap();
+        assert_eq!(context.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.span_id_hex(), "00f067aa0ba902b7");
+        assert_eq!(context.flags, 0x01);
+        assert_eq!(context.to_string(), TRACEPARENT);
+        
+        // Later versions may add fields; malformed values are refused
+        assert!(TraceContext::parse("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra").is_ok());
+        for invalid in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e47-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473g-00f067aa0ba902b7-01",
+        ] {
+            assert!(TraceContext::parse(invalid).is_err(), "{}", invalid);
+        }
+        
+        let child = context.child();
+        assert_eq!(child.trace_id, context.trace_id);
+        assert_eq!(child.flags, context.flags);
+        assert_ne!(child.span_id, context.span_id);
+    }
+    
+    #[test]
+    fn test_forward_span_nests_under_incoming() {
+        let capture = SpanCapture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        
+        let (forwarded, outside) = tracing::subscriber::with_default(subscriber, || {
+            let traced = Interest::new(Name::from_uri("/traced/object").unwrap()).with_traceparent(TRACEPARENT);
+            let (forwarded, _span) = forward_span(traced);
+            let (outside, _span) = forward_span(Interest::new(Name::from_uri("/untraced").unwrap()));
+            (forwarded, outside)
+        });
+        
+        // The Interest leaves in the same trace, under the hop's span
+        let incoming = TraceContext::parse(TRACEPARENT).unwrap();
+        let hop = TraceContext::parse(forwarded.traceparent().unwrap()).unwrap();
+        assert_eq!(hop.trace_id, incoming.trace_id);
+        assert_ne!(hop.span_id, incoming.span_id);
+        
+        // The hop span is a child of the incoming span
+        let spans = capture.0.lock();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0]["span"], "ndn.forward");
+        assert_eq!(spans[0]["name"], "/traced/object");
+        assert_eq!(spans[0]["trace_id"], incoming.trace_id_hex());
+        assert_eq!(spans[0]["parent_span_id"], incoming.span_id_hex());
+        
+        // Interests outside any trace are left alone
+        assert_eq!(outside.traceparent(), None);
+    }
+}
@@ -1,20 +1,28 @@
 // μDCN Interest Pipelining Implementation
 //
 // This module implements Interest pipelining, which allows multiple Interest
-// packets to be sent concurrently over a single QUIC connection.
+// packets to be sent concurrently over a single face (typically a QUIC
+// connection), and fetching of segmented objects with optional prefetching,
+// either through a pipeline or with a fixed window over any face.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use tokio::sync::{mpsc, Mutex, oneshot, RwLock};
+use bytes::{Bytes, BytesMut};
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::sync::{mpsc, Mutex, oneshot, RwLock, Semaphore};
 use tokio::task::JoinHandle;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, warn};
 
 use crate::error::{Error, Result};
+use crate::face::{NdnFace, QuicTransportFace};
+use crate::interest_retry::is_retryable;
+use crate::name::{Component, Name};
 use crate::ndn::{Data, Interest};
-use crate::quic_transport::{QuicTransport, ConnectionTracker};
+use crate::quic_transport::QuicTransport;
 
 /// Configuration for the Interest pipeline
 #[derive(Debug, Clone)]
@@ -39,6 +47,126 @@ impl Default for PipelineConfig {
     }
 }
 
+/// Get the name component for a segment number
+pub fn segment_component(segment: u64) -> Component {
+    Component::segment(segment)
+}
+
+/// Parse a segment number name component
+pub fn segment_number(component: &Component) -> Option<u64> {
+    component.to_segment()
+}
+
+/// Times a lost segment is re-expressed before a fetch gives up
+const MAX_SEGMENT_RETRIES: u32 = 3;
+
+/// Largest window `Pipeline::fetch` grows to
+const MAX_FETCH_WINDOW: usize = 128;
+
+/// Congestion control for the segment Interests a fetch keeps in flight
+pub trait CongestionController: Send {
+    /// Called when a segment's Data arrives
+    fn on_ack(&mut self, rtt: Duration);
+    
+    /// Called when a segment Interest times out or is NACKed for congestion
+    fn on_loss(&mut self);
+    
+    /// Number of segment Interests allowed in flight (at least 1)
+    fn window(&self) -> usize;
+}
+
+/// Additive-increase/multiplicative-decrease congestion control
+///
+/// Each acked segment grows the window by `1 / window`, about one segment
+/// per window's worth of Data; each loss halves it.
+#[derive(Debug, Clone)]
+pub struct AimdController {
+    /// Current window, fractional so growth accumulates across acks
+    window: f64,
+    
+    /// Largest window growth stops at
+    max_window: usize,
+}
+
+impl AimdController {
+    /// Create a controller starting at `initial_window` and growing to at most `max_window`
+    pub fn new(initial_window: usize, max_window: usize) -> Self {
+        let max_window = max_window.max(1);
+        
+        Self {
+            window: initial_window.clamp(1, max_window) as f64,
+            max_window,
+        }
+    }
+}
+
+impl CongestionController for AimdController {
+    fn on_ack(&mut self, _rtt: Duration) {
+        self.window = (self.window + 1.0 / self.window).min(self.max_window as f64);
+    }
+    
+    fn on_loss(&mut self) {
+        self.window = (self.window / 2.0).max(1.0);
+    }
+    
+    fn window(&self) -> usize {
+        self.window as usize
+    }
+}
+
+/// Window used by `InterestPipeline::fetch_object`, widening while segments arrive quickly
+struct PrefetchWindow {
+    /// Segments requested ahead of the one being fetched
+    window: usize,
+    
+    /// Largest window widening grows to, or `None` to keep it fixed
+    max_window: Option<usize>,
+    
+    /// Each segment answered faster than this widens the window by one
+    widen_below_rtt: Duration,
+}
+
+impl CongestionController for PrefetchWindow {
+    fn on_ack(&mut self, rtt: Duration) {
+        if let Some(max_window) = self.max_window {
+            if rtt < self.widen_below_rtt && self.window < max_window {
+                self.window += 1;
+                debug!("Widened prefetch window to {}", self.window);
+            }
+        }
+    }
+    
+    fn on_loss(&mut self) {}
+    
+    fn window(&self) -> usize {
+        self.window + 1
+    }
+}
+
+/// Options for fetching a segmented object
+#[derive(Debug, Clone)]
+pub struct FetchOptions {
+    /// Number of segments requested ahead of the one being fetched
+    /// (0 fetches one segment at a time)
+    pub prefetch_window: usize,
+    
+    /// Largest prefetch window adaptive widening grows to
+    pub max_prefetch_window: usize,
+    
+    /// Each segment answered faster than this widens the prefetch window by one
+    pub widen_below_rtt: Duration,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            prefetch_window: 0,
+            max_prefetch_window: 32,
+            widen_below_rtt: Duration::from_millis(20),
+        }
+    }
+}
+
 /// Request for sending an Interest via the pipeline
 #[derive(Debug)]
 struct PipelineRequest {
@@ -49,14 +177,10 @@ struct PipelineRequest {
     response_tx: oneshot::Sender<Result<Data>>,
 }
 
-/// Interest pipeline for a single QUIC connection
-#[derive(Debug)]
+/// Interest pipeline for a single face
 pub struct InterestPipeline {
-    /// QUIC transport
-    transport: Arc<QuicTransport>,
-    
-    /// Remote address
-    remote_addr: SocketAddr,
+    /// Face the Interests are sent over
+    face: Arc<dyn NdnFace>,
     
     /// Pipeline configuration
     config: PipelineConfig,
@@ -71,6 +195,15 @@ pub struct InterestPipeline {
     stats: Arc<RwLock<PipelineStats>>,
 }
 
+// Custom Debug implementation since faces don't implement Debug
+impl std::fmt::Debug for InterestPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterestPipeline")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Statistics for the Interest pipeline
 #[derive(Debug, Clone, Default)]
 pub struct PipelineStats {
@@ -97,167 +230,104 @@ pub struct PipelineStats {
 }
 
 impl InterestPipeline {
-    /// Create a new Interest pipeline for a connection
-    pub fn new(
-        transport: Arc<QuicTransport>,
-        remote_addr: SocketAddr,
-        config: PipelineConfig,
-    ) -> Self {
+    /// Create a new Interest pipeline over a face
+    pub fn new(face: Arc<dyn NdnFace>, config: PipelineConfig) -> Self {
         let (request_tx, request_rx) = mpsc::channel(config.max_queue_size);
         let stats = Arc::new(RwLock::new(PipelineStats::default()));
         
-        let mut pipeline = Self {
-            transport,
-            remote_addr,
+        // Start the pipeline worker
+        let handle = Self::start_worker(face.clone(), config.clone(), stats.clone(), request_rx);
+        
+        Self {
+            face,
             config,
             request_tx,
-            worker_handle: Mutex::new(None),
+            worker_handle: Mutex::new(Some(handle)),
             stats,
-        };
-        
-        // Start the pipeline worker
-        pipeline.start_worker(request_rx);
-        
-        pipeline
+        }
     }
     
     /// Start the pipeline worker task
-    fn start_worker(&mut self, mut request_rx: mpsc::Receiver<PipelineRequest>) {
-        let transport = self.transport.clone();
-        let remote_addr = self.remote_addr;
-        let config = self.config.clone();
-        let stats = self.stats.clone();
-        
-        let handle = tokio::spawn(async move {
-            // In-flight requests
-            let mut in_flight: HashMap<String, PipelineRequest> = HashMap::new();
+    fn start_worker(
+        face: Arc<dyn NdnFace>,
+        config: PipelineConfig,
+        stats: Arc<RwLock<PipelineStats>>,
+        mut request_rx: mpsc::Receiver<PipelineRequest>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            // Bound the number of Interests in flight
+            let permits = Arc::new(Semaphore::new(config.max_in_flight));
             
-            // Process requests
-            loop {
-                // Process as many requests as we can (up to max_in_flight)
-                while in_flight.len() < config.max_in_flight {
-                    match request_rx.try_recv() {
-                        Ok(request) => {
-                            // Get the Interest name
-                            let name = request.interest.name().to_string();
-                            debug!("Pipelining Interest for {}", name);
-                            
-                            // Add to in-flight map
-                            in_flight.insert(name.clone(), request);
+            while let Some(request) = request_rx.recv().await {
+                let permit = match permits.clone().acquire_owned().await {
+                    Ok(permit) => permit,
+                    Err(_) => break,
+                };
+                
+                let interest_name = request.interest.name().to_string();
+                debug!("Pipelining Interest for {}", interest_name);
+                
+                // Update stats
+                {
+                    let mut stats = stats.write().await;
+                    stats.in_flight += 1;
+                    stats.interests_sent += 1;
+                }
+                
+                // Send the Interest asynchronously
+                let face = face.clone();
+                let stats = stats.clone();
+                let timeout = Duration::from_millis(config.interest_timeout_ms);
+                
+                tokio::spawn(async move {
+                    // Hold the permit until the Interest completes
+                    let _permit = permit;
+                    let start_time = Instant::now();
+                    
+                    // Send Interest with timeout
+                    let result = tokio::time::timeout(timeout, face.send_interest(request.interest)).await;
+                    
+                    // Calculate RTT
+                    let rtt = start_time.elapsed().as_millis() as u64;
+                    
+                    let mut stats = stats.write().await;
+                    stats.in_flight -= 1;
+                    
+                    // Process result
+                    let response = match result {
+                        Ok(Ok(data)) => {
+                            debug!("Received Data for {}, RTT: {}ms", interest_name, rtt);
+                            stats.data_received += 1;
                             
-                            // Update stats
-                            {
-                                let mut stats = stats.write().await;
-                                stats.in_flight = in_flight.len();
-                                stats.interests_sent += 1;
+                            // Update average RTT
+                            if stats.avg_rtt_ms == 0 {
+                                stats.avg_rtt_ms = rtt;
+                            } else {
+                                // Moving average with 0.9 weight to existing average
+                                stats.avg_rtt_ms = ((stats.avg_rtt_ms as f64 * 0.9) + (rtt as f64 * 0.1)) as u64;
                             }
                             
-                            // Send the Interest asynchronously
-                            let transport_clone = transport.clone();
-                            let remote_addr_clone = remote_addr;
-                            let interest_name = name.clone();
-                            let stats_clone = stats.clone();
-                            let timeout = Duration::from_millis(config.interest_timeout_ms);
-                            
-                            tokio::spawn(async move {
-                                let start_time = Instant::now();
-                                
-                                // Send Interest with timeout
-                                let result = tokio::time::timeout(
-                                    timeout,
-                                    transport_clone.send_interest(remote_addr_clone, in_flight[&interest_name].interest.clone())
-                                ).await;
-                                
-                                // Calculate RTT
-                                let rtt = start_time.elapsed().as_millis() as u64;
-                                
-                                // Process result
-                                match result {
-                                    Ok(Ok(data)) => {
-                                        // Interest succeeded
-                                        debug!("Received Data for {}, RTT: {}ms", interest_name, rtt);
-                                        
-                                        // Update stats
-                                        {
-                                            let mut stats = stats_clone.write().await;
-                                            stats.data_received += 1;
-                                            stats.in_flight -= 1;
-                                            
-                                            // Update average RTT
-                                            if stats.avg_rtt_ms == 0 {
-                                                stats.avg_rtt_ms = rtt;
-                                            } else {
-                                                // Moving average with 0.9 weight to existing average
-                                                stats.avg_rtt_ms = ((stats.avg_rtt_ms as f64 * 0.9) + (rtt as f64 * 0.1)) as u64;
-                                            }
-                                        }
-                                        
-                                        // Send Data to requester
-                                        if let Some(request) = in_flight.remove(&interest_name) {
-                                            let _ = request.response_tx.send(Ok(data));
-                                        }
-                                    },
-                                    Ok(Err(e)) => {
-                                        // Interest failed
-                                        error!("Error sending Interest for {}: {}", interest_name, e);
-                                        
-                                        // Update stats
-                                        {
-                                            let mut stats = stats_clone.write().await;
-                                            stats.errors += 1;
-                                            stats.in_flight -= 1;
-                                        }
-                                        
-                                        // Send error to requester
-                                        if let Some(request) = in_flight.remove(&interest_name) {
-                                            let _ = request.response_tx.send(Err(e));
-                                        }
-                                    },
-                                    Err(_) => {
-                                        // Timeout
-                                        warn!("Timeout sending Interest for {}", interest_name);
-                                        
-                                        // Update stats
-                                        {
-                                            let mut stats = stats_clone.write().await;
-                                            stats.timeouts += 1;
-                                            stats.in_flight -= 1;
-                                        }
-                                        
-                                        // Send timeout error to requester
-                                        if let Some(request) = in_flight.remove(&interest_name) {
-                                            let _ = request.response_tx.send(Err(
-                                                Error::Timeout(format!("Interest for {} timed out after {}ms", interest_name, timeout.as_millis()))
-                                            ));
-                                        }
-                                    }
-                                }
-                            });
+                            Ok(data)
                         },
-                        Err(mpsc::error::TryRecvError::Empty) => {
-                            // No more requests to process
-                            break;
+                        Ok(Err(e)) => {
+                            error!("Error sending Interest for {}: {}", interest_name, e);
+                            stats.errors += 1;
+                            Err(e)
                         },
-                        Err(mpsc::error::TryRecvError::Disconnected) => {
-                            // Channel closed, exit the worker
-                            return;
+                        Err(_) => {
+                            warn!("Timeout sending Interest for {}", interest_name);
+                            stats.timeouts += 1;
+                            Err(Error::Timeout(format!(
+                                "Interest for {} timed out after {}ms", interest_name, timeout.as_millis())))
                         }
-                    }
-                }
-                
-                // Wait a bit before checking for more requests
-                tokio::time::sleep(Duration::from_millis(1)).await;
-                
-                // Update queue size in stats
-                {
-                    let mut stats = stats.write().await;
-                    stats.queue_size = request_rx.capacity().unwrap_or(0) - request_rx.capacity().unwrap_or(0);
-                }
+                    };
+                    drop(stats);
+                    
+                    // Send the result to the requester
+                    let _ = request.response_tx.send(response);
+                });
             }
-        });
-        
-        let mut worker_handle = self.worker_handle.blocking_lock();
-        *worker_handle = Some(handle);
+        })
     }
     
     /// Send an Interest via the pipeline
@@ -282,9 +352,31 @@ impl InterestPipeline {
         })?
     }
     
+    /// Fetch a segmented object and return its reassembled content
+    ///
+    /// Segment 0 is fetched first to learn the last segment from its
+    /// FinalBlockId; Data without one is a single-segment object. With a
+    /// prefetch window, later segments are requested that far ahead of the
+    /// next one needed, and the window widens while segments arrive quickly.
+    pub async fn fetch_object(&self, name: &Name, options: FetchOptions) -> Result<Bytes> {
+        let mut window = PrefetchWindow {
+            window: options.prefetch_window,
+            max_window: (options.prefetch_window > 0).then_some(options.max_prefetch_window),
+            widen_below_rtt: options.widen_below_rtt,
+        };
+        fetch_segments(name, &mut window, |interest| self.send_interest(interest)).await
+    }
+    
     /// Get pipeline statistics
     pub async fn stats(&self) -> PipelineStats {
-        self.stats.read().await.clone()
+        let mut stats = self.stats.read().await.clone();
+        stats.queue_size = self.config.max_queue_size - self.request_tx.capacity();
+        stats
+    }
+    
+    /// Get the face this pipeline sends over
+    pub fn face(&self) -> &Arc<dyn NdnFace> {
+        &self.face
     }
     
     /// Shut down the pipeline
@@ -299,6 +391,122 @@ impl InterestPipeline {
     }
 }
 
+/// Fetcher for segmented objects over any face, such as a `UdcnTransport`
+#[derive(Debug)]
+pub struct Pipeline;
+
+impl Pipeline {
+    /// Fetch a segmented object with an AIMD window starting at
+    /// `window_size` segment Interests in flight, and return its content
+    pub async fn fetch<F: NdnFace + ?Sized>(transport: &F, name: &Name, window_size: usize) -> Result<Bytes> {
+        if window_size == 0 {
+            return Err(Error::InvalidArgument("Pipeline window size must be at least 1".into()));
+        }
+        
+        let mut controller = AimdController::new(window_size, MAX_FETCH_WINDOW.max(window_size));
+        Self::fetch_with(transport, name, &mut controller).await
+    }
+    
+    /// Fetch a segmented object with the in-flight window sized by `controller`
+    ///
+    /// Segment 0 is fetched first to learn the last segment from its
+    /// FinalBlockId; segments may arrive in any order. Segments that time
+    /// out or are NACKed for congestion are re-expressed.
+    pub async fn fetch_with<F: NdnFace + ?Sized>(
+        transport: &F,
+        name: &Name,
+        controller: &mut dyn CongestionController,
+    ) -> Result<Bytes> {
+        fetch_segments(name, controller, |interest| transport.send_interest(interest)).await
+    }
+}
+
+/// Fetch the segments of `name` and concatenate their content in order
+///
+/// The controller sizes the window of segment Interests in flight and is
+/// told of every segment received or lost.
+async fn fetch_segments<S, Fut>(
+    name: &Name,
+    controller: &mut dyn CongestionController,
+    send: S,
+) -> Result<Bytes>
+where
+    S: Fn(Interest) -> Fut,
+    Fut: Future<Output = Result<Data>>,
+{
+    let segment_name = |segment: u64| {
+        let mut name = name.clone();
+        name.push(segment_component(segment));
+        name
+    };
+    
+    let mut segments = BTreeMap::new();
+    let mut last = None; // Learned from segment 0
+    let mut retransmit = VecDeque::from([0]);
+    let mut retries: HashMap<u64, u32> = HashMap::new();
+    let mut in_flight = FuturesUnordered::new();
+    let mut next = 1; // Next segment to request for the first time
+    
+    loop {
+        while in_flight.len() < controller.window().max(1) {
+            let segment = match (retransmit.pop_front(), last) {
+                (Some(segment), _) => segment,
+                (None, Some(last)) if next <= last => {
+                    next += 1;
+                    next - 1
+                }
+                _ => break,
+            };
+            
+            let response = send(Interest::new(segment_name(segment)));
+            in_flight.push(async move {
+                let start = Instant::now();
+                let result = response.await;
+                (segment, result, start.elapsed())
+            });
+        }
+        
+        let (segment, result, rtt) = match in_flight.next().await {
+            Some(response) => response,
+            None => break,
+        };
+        
+        match result {
+            Ok(data) => {
+                controller.on_ack(rtt);
+                
+                // Data without a FinalBlockId is a single-segment object
+                if segment == 0 {
+                    last = Some(match data.get_final_block_id() {
+                        Some(component) => segment_number(component).ok_or_else(|| Error::ProtocolError(format!(
+                            "Invalid FinalBlockId in {}", data.name())))?,
+                        None => 0,
+                    });
+                }
+                segments.insert(segment, data.content().clone());
+            }
+            Err(error) if is_retryable(&error) => {
+                let attempts = retries.entry(segment).or_insert(0);
+                if *attempts >= MAX_SEGMENT_RETRIES {
+                    return Err(error);
+                }
+                *attempts += 1;
+                
+                controller.on_loss();
+                debug!("Segment {} of {} lost ({}), window now {}", segment, name, error, controller.window());
+                retransmit.push_back(segment);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+    
+    let mut content = BytesMut::with_capacity(segments.values().map(|segment| segment.len()).sum());
+    for segment in segments.values() {
+        content.extend_from_slice(segment);
+    }
+    Ok(content.freeze())
+}
+
 /// Pipeline registry for managing multiple QUIC connections
 #[derive(Debug)]
 pub struct PipelineRegistry {
@@ -338,11 +546,8 @@ impl PipelineRegistry {
         }
         
         // Create a new pipeline
-        let pipeline = Arc::new(InterestPipeline::new(
-            self.transport.clone(),
-            remote_addr,
-            self.default_config.clone(),
-        ));
+        let face = Arc::new(QuicTransportFace::new(self.transport.clone(), remote_addr));
+        let pipeline = Arc::new(InterestPipeline::new(face, self.default_config.clone()));
         
         // Register the pipeline
         {
@@ -390,3 +595,288 @@ impl PipelineRegistry {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use async_trait::async_trait;
+    use crate::face::LoopbackFace;
+    use crate::ndn::NackReason;
+    use crate::quic::PrefixHandler;
+    
+    async fn loopback_with_producer(face: LoopbackFace) -> Arc<LoopbackFace> {
+        face.register_prefix(Name::from_uri("/test").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), interest.name().to_string()))
+        })).await.unwrap();
+        Arc::new(face)
+    }
+    
+    #[tokio::test]
+    async fn test_pipeline_over_loopback() {
+        let face = loopback_with_producer(LoopbackFace::new()).await;
+        
+        let pipeline = Arc::new(InterestPipeline::new(face.clone(), PipelineConfig::default()));
+        
+        // Send several Interests concurrently
+        let mut handles = Vec::new();
+        for i in 0..10 {
+            let pipeline = pipeline.clone();
+            handles.push(tokio::spawn(async move {
+                let name = Name::from_uri(&format!("/test/{}", i)).unwrap();
+                pipeline.send_interest(Interest::new(name)).await
+            }));
+        }
+        
+        for (i, handle) in handles.into_iter().enumerate() {
+            let data = handle.await.unwrap().unwrap();
+            assert_eq!(data.content().as_ref(), format!("/test/{}", i).as_bytes());
+        }
+        
+        let stats = pipeline.stats().await;
+        assert_eq!(stats.interests_sent, 10);
+        assert_eq!(stats.data_received, 10);
+        assert_eq!(stats.in_flight, 0);
+        assert_eq!(face.interests_received(), 10);
+    }
+    
+    #[tokio::test]
+    async fn test_pipeline_timeout() {
+        let face = loopback_with_producer(LoopbackFace::new().with_delay(Duration::from_millis(200))).await;
+        
+        let config = PipelineConfig {
+            interest_timeout_ms: 50,
+            ..PipelineConfig::default()
+        };
+        let pipeline = InterestPipeline::new(face, config);
+        
+        // The slow answer times out
+        let result = pipeline.send_interest(Interest::new(Name::from_uri("/test/slow").unwrap())).await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+        
+        let stats = pipeline.stats().await;
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.data_received, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_pipeline_limits_in_flight() {
+        let face = loopback_with_producer(LoopbackFace::new().with_delay(Duration::from_millis(50))).await;
+        
+        let config = PipelineConfig {
+            max_in_flight: 2,
+            ..PipelineConfig::default()
+        };
+        let pipeline = Arc::new(InterestPipeline::new(face, config));
+        
+        // Six Interests at two in flight need at least three round trips
+        let start = Instant::now();
+        let mut handles = Vec::new();
+        for i in 0..6 {
+            let pipeline = pipeline.clone();
+            handles.push(tokio::spawn(async move {
+                let name = Name::from_uri(&format!("/test/{}", i)).unwrap();
+                pipeline.send_interest(Interest::new(name)).await
+            }));
+        }
+        for handle in handles {
+            assert!(handle.await.unwrap().is_ok());
+        }
+        
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_object_prefetch_beats_sequential() {
+        const SEGMENTS: u64 = 20;
+        
+        // Each segment is a separate round trip over a 20 ms link
+        let face = LoopbackFace::new().with_delay(Duration::from_millis(20));
+        face.register_prefix(Name::from_uri("/video").unwrap(), Box::new(|interest: Interest| {
+            let name = interest.name();
+            let segment = segment_number(name.get(name.len() - 1).unwrap()).unwrap();
+            let data = Data::new(name.clone(), vec![segment as u8; 4])
+                .final_block_id(segment_component(SEGMENTS - 1));
+            
+            // The FinalBlockId has to survive the wire
+            Data::from_bytes(&data.to_bytes())
+        })).await.unwrap();
+        let face = Arc::new(face);
+        
+        let pipeline = InterestPipeline::new(face.clone(), PipelineConfig::default());
+        let name = Name::from_uri("/video/intro").unwrap();
+        let expected: Vec<u8> = (0..SEGMENTS).flat_map(|segment| vec![segment as u8; 4]).collect();
+        
+        let start = Instant::now();
+        let content = pipeline.fetch_object(&name, FetchOptions::default()).await.unwrap();
+        let sequential = start.elapsed();
+        assert_eq!(content.as_ref(), expected.as_slice());
+        
+        let options = FetchOptions {
+            prefetch_window: 8,
+            ..FetchOptions::default()
+        };
+        let start = Instant::now();
+        let content = pipeline.fetch_object(&name, options).await.unwrap();
+        let prefetched = start.elapsed();
+        assert_eq!(content.as_ref(), expected.as_slice());
+        
+        // Every segment was requested exactly once per fetch
+        assert_eq!(face.interests_received(), 2 * SEGMENTS);
+        assert!(sequential >= Duration::from_millis(20 * SEGMENTS));
+        assert!(prefetched * 2 < sequential, "prefetched {:?} vs sequential {:?}", prefetched, sequential);
+    }
+    
+    /// Serves `/file/seg=N` with later segments answered sooner, so they
+    /// arrive out of order
+    struct ReorderingFace {
+        segments: u64,
+        in_flight: AtomicUsize,
+        peak_in_flight: AtomicUsize,
+    }
+    
+    #[async_trait]
+    impl NdnFace for ReorderingFace {
+        async fn send_interest(&self, interest: Interest) -> Result<Data> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            
+            let name = interest.name();
+            let segment = segment_number(name.get(name.len() - 1).unwrap()).unwrap();
+            tokio::time::sleep(Duration::from_millis(5 * (self.segments - segment))).await;
+            
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            let data = Data::new(name.clone(), format!("[{}]", segment))
+                .final_block_id(segment_component(self.segments - 1));
+            Data::from_bytes(&data.to_bytes())
+        }
+        
+        async fn register_prefix(&self, _prefix: Name, _handler: PrefixHandler) -> Result<u64> {
+            Err(Error::OperationError("Not a producer".into()))
+        }
+        
+        async fn push_data(&self, _data: Data) -> Result<()> {
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_reassembles_out_of_order_segments() {
+        let face = ReorderingFace {
+            segments: 12,
+            in_flight: AtomicUsize::new(0),
+            peak_in_flight: AtomicUsize::new(0),
+        };
+        let name = Name::from_uri("/file").unwrap();
+        
+        let content = Pipeline::fetch(&face, &name, 4).await.unwrap();
+        let expected: String = (0..12).map(|segment| format!("[{}]", segment)).collect();
+        assert_eq!(content.as_ref(), expected.as_bytes());
+        
+        // The window started at 4 and grew by about one segment per window
+        let peak = face.peak_in_flight.load(Ordering::SeqCst);
+        assert!((4..=6).contains(&peak), "{}", peak);
+        
+        assert!(matches!(Pipeline::fetch(&face, &name, 0).await, Err(Error::InvalidArgument(_))));
+    }
+    
+    /// Records the window after every loss
+    struct RecordingController {
+        inner: AimdController,
+        windows_after_loss: Vec<usize>,
+    }
+    
+    impl CongestionController for RecordingController {
+        fn on_ack(&mut self, rtt: Duration) {
+            self.inner.on_ack(rtt);
+        }
+        
+        fn on_loss(&mut self) {
+            self.inner.on_loss();
+            self.windows_after_loss.push(self.inner.window());
+        }
+        
+        fn window(&self) -> usize {
+            self.inner.window()
+        }
+    }
+    
+    #[test]
+    fn test_aimd_grows_additively_and_halves_on_loss() {
+        let mut aimd = AimdController::new(4, 64);
+        
+        // A window's worth of acks grows the window by one segment
+        for _ in 0..4 {
+            aimd.on_ack(Duration::from_millis(1));
+        }
+        assert_eq!(aimd.window(), 4);
+        aimd.on_ack(Duration::from_millis(1));
+        assert_eq!(aimd.window(), 5);
+        
+        aimd.on_loss();
+        assert_eq!(aimd.window(), 2);
+        aimd.on_loss();
+        aimd.on_loss();
+        assert_eq!(aimd.window(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_recovers_from_dropped_segments() {
+        const SEGMENTS: u64 = 40;
+        
+        // Every 7th segment is NACKed for congestion the first time it is asked for
+        let dropped = Arc::new(std::sync::Mutex::new(std::collections::HashSet::new()));
+        let face = LoopbackFace::new();
+        let handler_dropped = dropped.clone();
+        face.register_prefix(Name::from_uri("/lossy").unwrap(), Box::new(move |interest: Interest| {
+            let name = interest.name();
+            let segment = segment_number(name.get(name.len() - 1).unwrap()).unwrap();
+            if segment % 7 == 6 && handler_dropped.lock().unwrap().insert(segment) {
+                return Err(Error::Nack { reason: NackReason::Congestion, message: String::new() });
+            }
+            Ok(Data::new(name.clone(), vec![segment as u8; 3]).final_block_id(segment_component(SEGMENTS - 1)))
+        })).await.unwrap();
+        
+        let mut controller = RecordingController {
+            inner: AimdController::new(16, 64),
+            windows_after_loss: Vec::new(),
+        };
+        let name = Name::from_uri("/lossy/object").unwrap();
+        let content = Pipeline::fetch_with(&face, &name, &mut controller).await.unwrap();
+        
+        let expected: Vec<u8> = (0..SEGMENTS).flat_map(|segment| vec![segment as u8; 3]).collect();
+        assert_eq!(content.as_ref(), expected.as_slice());
+        assert_eq!(face.interests_received(), SEGMENTS + SEGMENTS / 7);
+        
+        // Each drop backed the window off
+        let windows = &controller.windows_after_loss;
+        assert_eq!(windows.len() as u64, SEGMENTS / 7);
+        assert!(windows[0] <= 8, "{:?}", windows);
+        assert!(windows.iter().all(|&window| window < 16), "{:?}", windows);
+        
+        // A segment that does not exist fails the fetch without a retry
+        let before = face.interests_received();
+        let mut controller = AimdController::new(4, 64);
+        let result = Pipeline::fetch_with(&face, &Name::from_uri("/missing").unwrap(), &mut controller).await;
+        assert!(matches!(result, Err(Error::NotFound(_))));
+        assert_eq!(face.interests_received(), before + 1);
+        
+        // A segment lost on every attempt fails the fetch once its retries run out
+        let attempts = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let handler_attempts = attempts.clone();
+        face.register_prefix(Name::from_uri("/hopeless").unwrap(), Box::new(move |interest: Interest| {
+            let name = interest.name();
+            let segment = segment_number(name.get(name.len() - 1).unwrap()).unwrap();
+            if segment == 2 {
+                handler_attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                return Err(Error::Nack { reason: NackReason::Congestion, message: String::new() });
+            }
+            Ok(Data::new(name.clone(), vec![segment as u8; 3]).final_block_id(segment_component(4)))
+        })).await.unwrap();
+        
+        let mut controller = AimdController::new(4, 64);
+        let result = Pipeline::fetch_with(&face, &Name::from_uri("/hopeless/object").unwrap(), &mut controller).await;
+        assert!(matches!(result, Err(Error::Nack { reason: NackReason::Congestion, .. })), "{:?}", result);
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1 + MAX_SEGMENT_RETRIES);
+    }
+}
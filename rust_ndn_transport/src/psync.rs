@@ -0,0 +1,541 @@
+//
+// μDCN Partial Sync
+//
+// This module implements a lightweight publish/subscribe sync protocol in the
+// spirit of PSync. Each member of a sync group keeps the set of names it knows
+// about, summarized as a small invertible Bloom lookup table (IBLT). A member
+// asks a peer for updates by sending its IBLT as the last component of a sync
+// Interest under the group prefix; the peer subtracts it from its own table,
+// decodes the names the requester lacks and answers with them. The requester
+// then fetches the Data for each discovered name as usual. The table size and
+// the reply size are set per group by `SyncConfig`.
+//
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use futures::stream::{self, Stream};
+use parking_lot::RwLock;
+use sha2::{Digest, Sha256};
+use tokio::sync::broadcast;
+use tracing::{debug, trace};
+
+use crate::error::Error;
+use crate::face::NdnFace;
+use crate::name::{Component, Name};
+use crate::ndn::{Data, Interest};
+use crate::Result;
+
+/// Number of cells each key is stored in (one per sub-table)
+const IBLT_HASHES: usize = 3;
+
+/// Encoded size of a cell (count + key sum + hash sum)
+const CELL_SIZE: usize = 2 + 8 + 4;
+
+/// Capacity of the discovered-name channel
+const DISCOVERED_CHANNEL_CAPACITY: usize = 1024;
+
+/// Mix a key with a seed (SplitMix64 finalizer)
+fn mix(key: u64, seed: u64) -> u64 {
+    let mut z = key ^ seed.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Get the IBLT key of a name
+fn name_key(name: &Name) -> u64 {
+    let digest = Sha256::digest(name.to_tlv());
+    u64::from_be_bytes([digest[0], digest[1], digest[2], digest[3],
+                        digest[4], digest[5], digest[6], digest[7]])
+}
+
+/// Sizing of a sync group member
+///
+/// Every member of a group must use the same `iblt_cells`.
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// Number of IBLT cells, rounded down to a multiple of three. A table
+    /// usually decodes a difference of up to about 3/4 as many names.
+    pub iblt_cells: usize,
+    
+    /// Largest total size of the names in a sync reply; names beyond it
+    /// are offered in the next round
+    pub max_reply_bytes: usize,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            iblt_cells: 60,
+            max_reply_bytes: 8192,
+        }
+    }
+}
+
+/// A single IBLT cell
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Cell {
+    /// Number of keys stored in the cell (negative after subtraction)
+    count: i16,
+    
+    /// XOR of the keys stored in the cell
+    key_sum: u64,
+    
+    /// XOR of the check hashes of the keys stored in the cell
+    hash_sum: u32,
+}
+
+impl Cell {
+    /// Check whether the cell holds exactly one key
+    fn is_pure(&self) -> bool {
+        (self.count == 1 || self.count == -1) && self.hash_sum == Iblt::check(self.key_sum)
+    }
+}
+
+/// An invertible Bloom lookup table of name keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Iblt {
+    cells: Vec<Cell>,
+}
+
+impl Iblt {
+    /// Create an empty table of `width` cells per sub-table
+    fn new(width: usize) -> Self {
+        Self { cells: vec![Cell::default(); width * IBLT_HASHES] }
+    }
+    
+    /// Check hash of a key
+    fn check(key: u64) -> u32 {
+        (mix(key, IBLT_HASHES as u64 + 1) >> 32) as u32
+    }
+    
+    /// Number of cells per sub-table
+    fn width(&self) -> usize {
+        self.cells.len() / IBLT_HASHES
+    }
+    
+    /// Cells a key is stored in, one per sub-table so they are distinct
+    fn indices(width: usize, key: u64) -> impl Iterator<Item = usize> {
+        (0..IBLT_HASHES).map(move |i| i * width + (mix(key, i as u64) % width as u64) as usize)
+    }
+    
+    /// Add (`sign` 1) or remove (`sign` -1) a key
+    fn update(&mut self, key: u64, sign: i16) {
+        let check = Self::check(key);
+        for i in Self::indices(self.width(), key) {
+            let cell = &mut self.cells[i];
+            cell.count = cell.count.wrapping_add(sign);
+            cell.key_sum ^= key;
+            cell.hash_sum ^= check;
+        }
+    }
+    
+    /// Subtract another table, leaving the keys only one of them holds
+    fn subtract(&self, other: &Iblt) -> Iblt {
+        let mut diff = self.clone();
+        for (cell, theirs) in diff.cells.iter_mut().zip(other.cells.iter()) {
+            cell.count = cell.count.wrapping_sub(theirs.count);
+            cell.key_sum ^= theirs.key_sum;
+            cell.hash_sum ^= theirs.hash_sum;
+        }
+        diff
+    }
+    
+    /// Decode a difference into the keys only we hold and the keys only they hold
+    ///
+    /// Returns `None` if the difference is too large to decode completely.
+    fn peel(mut self) -> Option<(Vec<u64>, Vec<u64>)> {
+        let mut ours = Vec::new();
+        let mut theirs = Vec::new();
+        
+        while let Some(cell) = self.cells.iter().find(|cell| cell.is_pure()).copied() {
+            if cell.count == 1 {
+                ours.push(cell.key_sum);
+            } else {
+                theirs.push(cell.key_sum);
+            }
+            self.update(cell.key_sum, -cell.count);
+        }
+        
+        if self.cells.iter().all(|cell| *cell == Cell::default()) {
+            Some((ours, theirs))
+        } else {
+            None
+        }
+    }
+    
+    /// Encode the table
+    fn to_bytes(&self) -> Bytes {
+        let mut buf = BytesMut::with_capacity(self.cells.len() * CELL_SIZE);
+        for cell in &self.cells {
+            buf.put_i16(cell.count);
+            buf.put_u64(cell.key_sum);
+            buf.put_u32(cell.hash_sum);
+        }
+        buf.freeze()
+    }
+    
+    /// Decode a table of `width` cells per sub-table
+    fn from_bytes(mut buf: &[u8], width: usize) -> Result<Self> {
+        let size = width * IBLT_HASHES * CELL_SIZE;
+        if buf.len() != size {
+            return Err(Error::TlvParsing(format!(
+                "Sync state must be {} bytes, got {}", size, buf.len())));
+        }
+        
+        let mut iblt = Self::new(width);
+        for cell in iblt.cells.iter_mut() {
+            cell.count = buf.get_i16();
+            cell.key_sum = buf.get_u64();
+            cell.hash_sum = buf.get_u32();
+        }
+        Ok(iblt)
+    }
+}
+
+/// Names known to a sync group member
+#[derive(Debug)]
+struct SyncState {
+    /// Known names by IBLT key
+    names: HashMap<u64, Name>,
+    
+    /// Summary of the known names
+    iblt: Iblt,
+    
+    /// Largest total size of the names in a sync reply
+    max_reply_bytes: usize,
+}
+
+impl SyncState {
+    /// Create an empty state sized by the configuration
+    fn new(config: &SyncConfig) -> Self {
+        Self {
+            names: HashMap::new(),
+            iblt: Iblt::new(config.iblt_cells / IBLT_HASHES),
+            max_reply_bytes: config.max_reply_bytes,
+        }
+    }
+    
+    /// Add a name, returning whether it was new
+    fn insert(&mut self, name: Name) -> bool {
+        let key = name_key(&name);
+        if self.names.contains_key(&key) {
+            return false;
+        }
+        
+        self.iblt.update(key, 1);
+        self.names.insert(key, name);
+        true
+    }
+    
+    /// Answer a sync Interest with the names the requester lacks
+    fn reply(&self, interest: &Interest) -> Result<Data> {
+        let state = interest.name().get(interest.name().len().saturating_sub(1))
+            .ok_or_else(|| Error::InvalidArgument("Sync Interest has no state component".into()))?;
+        let theirs = Iblt::from_bytes(state.value(), self.iblt.width())?;
+        
+        // Fall back to offering everything if the difference doesn't decode
+        let missing: Vec<&Name> = match self.iblt.subtract(&theirs).peel() {
+            Some((ours, _)) => ours.iter().filter_map(|key| self.names.get(key)).collect(),
+            None => {
+                debug!("Sync difference for {} too large to decode", interest.name());
+                self.names.values().collect()
+            }
+        };
+        
+        let mut content = BytesMut::new();
+        for (offered, name) in missing.iter().enumerate() {
+            let tlv = name.to_tlv();
+            if content.len() + tlv.len() > self.max_reply_bytes {
+                debug!("Sync reply for {} holds {} of {} missing names",
+                       interest.name(), offered, missing.len());
+                break;
+            }
+            content.extend_from_slice(&tlv);
+        }
+        
+        Ok(Data::new(interest.name().clone(), content.freeze()))
+    }
+}
+
+/// A member of a publish/subscribe sync group
+pub struct SyncGroup {
+    /// Face sync Interests are exchanged over
+    face: Arc<dyn NdnFace>,
+    
+    /// Sync group prefix
+    prefix: Name,
+    
+    /// Known names
+    state: Arc<RwLock<SyncState>>,
+    
+    /// Channel of names discovered from peers
+    discovered_tx: broadcast::Sender<Name>,
+}
+
+// Custom Debug implementation since faces don't implement Debug
+impl std::fmt::Debug for SyncGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyncGroup")
+            .field("prefix", &self.prefix)
+            .field("names", &self.state.read().names.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SyncGroup {
+    /// Join a sync group, serving sync Interests for the prefix on the face
+    pub async fn new(face: Arc<dyn NdnFace>, prefix: Name) -> Result<Self> {
+        Self::with_config(face, prefix, SyncConfig::default()).await
+    }
+    
+    /// Join a sync group with the given sizing
+    pub async fn with_config(face: Arc<dyn NdnFace>, prefix: Name, config: SyncConfig) -> Result<Self> {
+        if config.iblt_cells < IBLT_HASHES {
+            return Err(Error::InvalidArgument(format!(
+                "Sync groups need at least {} IBLT cells, got {}", IBLT_HASHES, config.iblt_cells)));
+        }
+        
+        let state = Arc::new(RwLock::new(SyncState::new(&config)));
+        let (discovered_tx, _) = broadcast::channel(DISCOVERED_CHANNEL_CAPACITY);
+        
+        let handler_state = state.clone();
+        face.register_prefix(prefix.clone(), Box::new(move |interest: Interest| {
+            handler_state.read().reply(&interest)
+        })).await?;
+        
+        Ok(Self {
+            face,
+            prefix,
+            state,
+            discovered_tx,
+        })
+    }
+    
+    /// Get the sync group prefix
+    pub fn prefix(&self) -> &Name {
+        &self.prefix
+    }
+    
+    /// Announce a name to the group, returning whether it was new
+    ///
+    /// The Data for the name must be served separately.
+    pub fn publish(&self, name: Name) -> bool {
+        self.state.write().insert(name)
+    }
+    
+    /// Check whether a name is known to this member
+    pub fn contains(&self, name: &Name) -> bool {
+        self.state.read().names.contains_key(&name_key(name))
+    }
+    
+    /// Get the number of names known to this member
+    pub fn len(&self) -> usize {
+        self.state.read().names.len()
+    }
+    
+    /// Check whether no names are known to this member
+    pub fn is_empty(&self) -> bool {
+        self.state.read().names.is_empty()
+    }
+    
+    /// Run one sync round with the peer, returning the Data fetched for the
+    /// newly discovered names
+    ///
+    /// Newly discovered names are also sent to every `discovered` stream. A
+    /// name whose Data can't be fetched is left unknown, so the next round
+    /// discovers it again.
+    pub async fn sync(&self) -> Result<Vec<Data>> {
+        let mut name = self.prefix.clone();
+        name.push(Component::new(self.state.read().iblt.to_bytes()));
+        
+        let data = self.face.send_interest(Interest::new(name)).await?;
+        
+        let mut content = data.content().clone();
+        let mut fetched = Vec::new();
+        while content.has_remaining() {
+            let name = Name::from_tlv(&mut content)?;
+            if self.contains(&name) {
+                continue;
+            }
+            
+            let data = match self.face.send_interest(Interest::new(name.clone())).await {
+                Ok(data) => data,
+                Err(e) => {
+                    debug!("Failed to fetch {} discovered in sync group {}: {}", name, self.prefix, e);
+                    continue;
+                }
+            };
+            
+            if self.state.write().insert(name.clone()) {
+                trace!("Discovered {} in sync group {}", name, self.prefix);
+                
+                // Having no subscribers is not an error
+                let _ = self.discovered_tx.send(name);
+                fetched.push(data);
+            }
+        }
+        
+        Ok(fetched)
+    }
+    
+    /// Get a stream of names discovered from peers from now on
+    pub fn discovered(&self) -> impl Stream<Item = Name> {
+        stream::unfold(self.discovered_tx.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(name) => return Some((name, rx)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        debug!("Discovered-name stream lagged by {} names", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use futures::StreamExt;
+    use crate::face::LoopbackFace;
+    use crate::quic::PrefixHandler;
+    
+    /// One end of a link: serves the local node and forwards Interests to the peer
+    struct LinkFace {
+        local: Arc<LoopbackFace>,
+        peer: Arc<LoopbackFace>,
+    }
+    
+    #[async_trait]
+    impl NdnFace for LinkFace {
+        async fn send_interest(&self, interest: Interest) -> Result<Data> {
+            self.peer.send_interest(interest).await
+        }
+        
+        async fn register_prefix(&self, prefix: Name, handler: PrefixHandler) -> Result<u64> {
+            self.local.register_prefix(prefix, handler).await
+        }
+        
+        async fn push_data(&self, data: Data) -> Result<()> {
+            self.peer.push_data(data).await
+        }
+    }
+    
+    #[test]
+    fn test_iblt_difference_decodes() {
+        let names: Vec<Name> = (0..5)
+            .map(|i| Name::from_uri(&format!("/sync/test/{}", i)).unwrap())
+            .collect();
+        
+        let mut a = Iblt::new(3);
+        let mut b = Iblt::new(3);
+        for name in &names[..4] {
+            a.update(name_key(name), 1);
+        }
+        for name in &names[1..] {
+            b.update(name_key(name), 1);
+        }
+        
+        let (ours, theirs) = a.subtract(&b).peel().unwrap();
+        assert_eq!(ours, vec![name_key(&names[0])]);
+        assert_eq!(theirs, vec![name_key(&names[4])]);
+        
+        assert_eq!(Iblt::from_bytes(&a.to_bytes(), 3).unwrap(), a);
+        assert!(Iblt::from_bytes(&[0u8; 3], 3).is_err());
+        assert!(Iblt::from_bytes(&a.to_bytes(), 4).is_err());
+    }
+    
+    #[test]
+    fn test_default_iblt_decodes_realistic_difference() {
+        let config = SyncConfig::default();
+        let mut ours = SyncState::new(&config);
+        let theirs = SyncState::new(&config);
+        for i in 0..40 {
+            ours.insert(Name::from_uri(&format!("/sync/test/{}", i)).unwrap());
+        }
+        
+        let (missing, extra) = ours.iblt.subtract(&theirs.iblt).peel().unwrap();
+        assert_eq!(missing.len(), 40);
+        assert!(extra.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_two_nodes_discover_and_fetch() {
+        let node_a = Arc::new(LoopbackFace::new());
+        let node_b = Arc::new(LoopbackFace::new());
+        let face_a = Arc::new(LinkFace { local: node_a.clone(), peer: node_b.clone() });
+        let face_b = Arc::new(LinkFace { local: node_b.clone(), peer: node_a.clone() });
+        
+        let prefix = Name::from_uri("/udcn/sync/chat").unwrap();
+        let group_a = SyncGroup::new(face_a.clone(), prefix.clone()).await.unwrap();
+        let group_b = SyncGroup::new(face_b.clone(), prefix.clone()).await.unwrap();
+        
+        // Node A produces a message and announces it to the group
+        face_a.register_prefix(Name::from_uri("/udcn/chat/a").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "hello from a"))
+        })).await.unwrap();
+        let message = Name::from_uri("/udcn/chat/a/1").unwrap();
+        assert!(group_a.publish(message.clone()));
+        assert!(!group_a.publish(message.clone()));
+        
+        // Node B discovers the name through a sync round and fetches the
+        // Data from node A
+        let mut discovered = Box::pin(group_b.discovered());
+        let fetched = group_b.sync().await.unwrap();
+        assert_eq!(fetched.len(), 1);
+        assert_eq!(fetched[0].name(), &message);
+        assert_eq!(fetched[0].content().as_ref(), b"hello from a");
+        assert_eq!(discovered.next().await.unwrap(), message);
+        assert!(group_b.contains(&message));
+        
+        // Once in sync, further rounds discover nothing
+        assert!(group_b.sync().await.unwrap().is_empty());
+        assert!(group_a.sync().await.unwrap().is_empty());
+        assert_eq!(group_a.len(), 1);
+        assert_eq!(group_b.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_sync_reply_size_and_fetch_failures() {
+        let node_a = Arc::new(LoopbackFace::new());
+        let node_b = Arc::new(LoopbackFace::new());
+        let face_a = Arc::new(LinkFace { local: node_a.clone(), peer: node_b.clone() });
+        let face_b = Arc::new(LinkFace { local: node_b.clone(), peer: node_a.clone() });
+        
+        // Replies hold only a few names each
+        let config = SyncConfig { max_reply_bytes: 64, ..SyncConfig::default() };
+        let prefix = Name::from_uri("/udcn/sync/chat").unwrap();
+        let group_a = SyncGroup::with_config(face_a.clone(), prefix.clone(), config.clone()).await.unwrap();
+        let group_b = SyncGroup::with_config(face_b.clone(), prefix.clone(), config).await.unwrap();
+        
+        face_a.register_prefix(Name::from_uri("/udcn/chat/a").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "hello from a"))
+        })).await.unwrap();
+        for i in 0..10 {
+            group_a.publish(Name::from_uri(&format!("/udcn/chat/a/{}", i)).unwrap());
+        }
+        
+        // A name nobody serves
+        let unserved = Name::from_uri("/udcn/chat/gone/1").unwrap();
+        group_a.publish(unserved.clone());
+        
+        // Every round makes progress until all served names are fetched
+        let mut rounds = 0;
+        let mut fetched = 0;
+        while fetched < 10 && rounds < 20 {
+            fetched += group_b.sync().await.unwrap().len();
+            rounds += 1;
+        }
+        assert_eq!(fetched, 10);
+        assert!(rounds > 2);
+        
+        // The unserved name is left to be discovered again
+        assert!(!group_b.contains(&unserved));
+        assert_eq!(group_b.len(), 10);
+    }
+}
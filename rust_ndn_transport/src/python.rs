@@ -31,6 +31,33 @@ fn convert_error(err: crate::Error) -> PyErr {
     PyRuntimeError::new_err(format!("μDCN Error: {}", err))
 }
 
+// Apply the supported keys of a Python configuration dict
+fn apply_config_dict(config: &mut Config, cfg: &PyDict) -> PyResult<()> {
+    if let Some(mtu) = cfg.get_item("mtu") {
+        config.mtu = mtu.extract()?;
+    }
+    if let Some(cache_capacity) = cfg.get_item("cache_capacity") {
+        config.cache_capacity = cache_capacity.extract()?;
+    }
+    if let Some(cache_capacity_bytes) = cfg.get_item("cache_capacity_bytes") {
+        config.cache_capacity_bytes = cache_capacity_bytes.extract()?;
+    }
+    if let Some(idle_timeout) = cfg.get_item("idle_timeout") {
+        config.idle_timeout = idle_timeout.extract()?;
+    }
+    if let Some(bind_address) = cfg.get_item("bind_address") {
+        config.bind_address = bind_address.extract()?;
+    }
+    if let Some(enable_metrics) = cfg.get_item("enable_metrics") {
+        config.enable_metrics = enable_metrics.extract()?;
+    }
+    if let Some(metrics_port) = cfg.get_item("metrics_port") {
+        config.metrics_port = metrics_port.extract()?;
+    }
+    
+    Ok(())
+}
+
 /// Python-friendly wrapper for UdcnTransport
 #[pyclass(name = "UdcnTransport")]
 struct PyUdcnTransport {
@@ -48,24 +75,7 @@ impl PyUdcnTransport {
         
         // Apply custom configuration if provided
         if let Some(cfg) = config_dict {
-            if let Some(mtu) = cfg.get_item("mtu") {
-                config.mtu = mtu.extract()?;
-            }
-            if let Some(cache_capacity) = cfg.get_item("cache_capacity") {
-                config.cache_capacity = cache_capacity.extract()?;
-            }
-            if let Some(idle_timeout) = cfg.get_item("idle_timeout") {
-                config.idle_timeout = idle_timeout.extract()?;
-            }
-            if let Some(bind_address) = cfg.get_item("bind_address") {
-                config.bind_address = bind_address.extract()?;
-            }
-            if let Some(enable_metrics) = cfg.get_item("enable_metrics") {
-                config.enable_metrics = enable_metrics.extract()?;
-            }
-            if let Some(metrics_port) = cfg.get_item("metrics_port") {
-                config.metrics_port = metrics_port.extract()?;
-            }
+            apply_config_dict(&mut config, cfg)?;
         }
         
         // Create a runtime for async operations
@@ -257,24 +267,7 @@ impl PyUdcnTransport {
         let mut config = Config::default();
         
         // Apply custom configuration
-        if let Some(mtu) = config_dict.get_item("mtu") {
-            config.mtu = mtu.extract()?;
-        }
-        if let Some(cache_capacity) = config_dict.get_item("cache_capacity") {
-            config.cache_capacity = cache_capacity.extract()?;
-        }
-        if let Some(idle_timeout) = config_dict.get_item("idle_timeout") {
-            config.idle_timeout = idle_timeout.extract()?;
-        }
-        if let Some(bind_address) = config_dict.get_item("bind_address") {
-            config.bind_address = config_dict.get_item("bind_address").unwrap().extract()?;
-        }
-        if let Some(enable_metrics) = config_dict.get_item("enable_metrics") {
-            config.enable_metrics = enable_metrics.extract()?;
-        }
-        if let Some(metrics_port) = config_dict.get_item("metrics_port") {
-            config.metrics_port = metrics_port.extract()?;
-        }
+        apply_config_dict(&mut config, config_dict)?;
         
         py.allow_threads(|| {
             self.runtime.block_on(async {
@@ -284,6 +277,94 @@ impl PyUdcnTransport {
     }
 }
 
+/// Asyncio-friendly transport whose methods return awaitables
+///
+/// Futures run on the pyo3-asyncio Tokio runtime without holding the GIL,
+/// so the Python event loop keeps running while they are pending.
+#[pyclass(name = "Transport")]
+struct PyTransport {
+    transport: Arc<UdcnTransport>,
+}
+
+#[pymethods]
+impl PyTransport {
+    /// Create a new transport instance
+    #[new]
+    fn new(py: Python, config_dict: Option<&PyDict>) -> PyResult<Self> {
+        let mut config = Config::default();
+        if let Some(cfg) = config_dict {
+            apply_config_dict(&mut config, cfg)?;
+        }
+        
+        let transport = py.allow_threads(|| {
+            pyo3_asyncio::tokio::get_runtime()
+                .block_on(UdcnTransport::new(config))
+                .map_err(convert_error)
+        })?;
+        
+        Ok(Self {
+            transport: Arc::new(transport),
+        })
+    }
+    
+    /// Start the transport
+    fn start<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let transport = self.transport.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            transport.start().await.map_err(convert_error)
+        })
+    }
+    
+    /// Stop the transport
+    fn stop<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let transport = self.transport.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            transport.stop().await.map_err(convert_error)
+        })
+    }
+    
+    /// Send an Interest for `name`, resolving to the content of the Data
+    fn send_interest<'p>(&self, py: Python<'p>, name: &str) -> PyResult<&'p PyAny> {
+        let transport = self.transport.clone();
+        let name = Name::from_uri(name).map_err(|e| PyValueError::new_err(format!("Invalid name: {}", e)))?;
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let data = transport.send_interest(Interest::new(name)).await.map_err(convert_error)?;
+            Ok(Python::with_gil(|py| PyObject::from(PyBytes::new(py, data.content()))))
+        })
+    }
+    
+    /// Serve `prefix` with `callback`, which takes the Interest name and
+    /// returns the bytes to send back as the Data content; resolves to the
+    /// registration ID
+    fn register_prefix<'p>(&self, py: Python<'p>, prefix: &str, callback: PyObject) -> PyResult<&'p PyAny> {
+        let transport = self.transport.clone();
+        let prefix = Name::from_uri(prefix).map_err(|e| PyValueError::new_err(format!("Invalid prefix: {}", e)))?;
+        
+        let handler = Box::new(move |interest: Interest| -> Result<Data> {
+            let name = interest.name().clone();
+            let content = Python::with_gil(|py| {
+                callback.call1(py, (name.to_string(),))
+                    .and_then(|result| result.extract::<Vec<u8>>(py))
+            }).map_err(|e| crate::Error::Other(format!("Python callback error: {}", e)))?;
+            
+            Ok(Data::new(name, content))
+        });
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            transport.register_prefix(prefix, handler).await.map_err(convert_error)
+        })
+    }
+    
+    /// Unregister a prefix by its registration ID
+    fn unregister_prefix<'p>(&self, py: Python<'p>, registration_id: u64) -> PyResult<&'p PyAny> {
+        let transport = self.transport.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            transport.unregister_prefix(registration_id).await.map_err(convert_error)
+        })
+    }
+}
+
 /// Helper function to parse an NDN Data packet
 #[pyfunction]
 fn parse_data(py: Python, data_bytes: &[u8]) -> PyResult<PyObject> {
@@ -350,6 +431,7 @@ fn create_data(py: Python, name: &str, content: &[u8], freshness_sec: Option<u64
 #[pymodule]
 fn udcn_transport(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyUdcnTransport>()?;
+    m.add_class::<PyTransport>()?;
     m.add_function(wrap_pyfunction!(parse_data, m)?)?;
     m.add_function(wrap_pyfunction!(parse_interest, m)?)?;
     m.add_function(wrap_pyfunction!(create_interest, m)?)?;
@@ -361,3 +443,47 @@ fn udcn_transport(_py: Python, m: &PyModule) -> PyResult<()> {
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_gil_released_while_awaiting() {
+        pyo3::prepare_freethreaded_python();
+        
+        Python::with_gil(|py| {
+            let transport = PyCell::new(py, PyTransport::new(py, None).unwrap()).unwrap();
+            let globals = PyDict::new(py);
+            globals.set_item("__builtins__", py.import("builtins").unwrap()).unwrap();
+            globals.set_item("transport", transport).unwrap();
+            
+            // The callback needs the GIL on a runtime thread while the event
+            // loop awaits, and the ticker only advances if the loop is free
+            py.run(r#"
+import asyncio
+
+async def main():
+    ticks = 0
+    async def ticker():
+        nonlocal ticks
+        while True:
+            ticks += 1
+            await asyncio.sleep(0)
+    
+    task = asyncio.ensure_future(ticker())
+    await transport.register_prefix("/test/python", lambda name: ("hello " + name).encode())
+    content = await transport.send_interest("/test/python/async")
+    task.cancel()
+    return content, ticks
+
+content, ticks = asyncio.run(main())
+"#, Some(globals), None).unwrap();
+            
+            let content: Vec<u8> = globals.get_item("content").unwrap().extract().unwrap();
+            assert_eq!(content, b"hello /test/python/async");
+            let ticks: u64 = globals.get_item("ticks").unwrap().extract().unwrap();
+            assert!(ticks > 0);
+        });
+    }
+}
@@ -4,28 +4,55 @@
 // names to QUIC stream IDs and handles fragmentation/reassembly.
 //
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
-// use bytes::{Bytes, BytesMut, BufMut};
+use bytes::{BufMut, Bytes, BytesMut};
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
 use quinn::{Connection, Endpoint, ServerConfig};
 use rustls::{Certificate, PrivateKey};
 // use tokio::net::{TcpListener, UdpSocket};
-use tokio::sync::{mpsc, RwLock};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::{mpsc, oneshot, Mutex, Notify, RwLock};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 // use futures::StreamExt;
 
-use crate::ndn::{Interest, Data, Nack};
+use crate::ndn::{lp_type, tlv, Interest, Data, Nack, NackReason, Packet};
+use crate::ndn::integrity::{IntegrityCheck, IntegrityStats, TRAILER_SIZE};
 use crate::name::Name;
-use crate::security::generate_self_signed_cert;
-use crate::fragmentation::Fragmenter;
-// use crate::metrics;
+use crate::dispatch::{HandlerTable, MatchKind};
+use crate::admission::AdmissionController;
+use crate::acl::SharedAcl;
+use crate::circuit_breaker::{BreakerState, CircuitBreaker};
+use crate::rate_limit::RateLimiter;
+use crate::cache::ContentStore;
+use crate::security::{build_client_config, server_identity, verify_data_with_store, CommandValidator, SharedCertStore};
+use crate::fragmentation::{Fragment, Fragmenter, FRAGMENT_MAGIC};
+use crate::metrics::{MetricsCollector, INTEREST_RTT_METRIC};
+use crate::quic_transport::FlowControlWindows;
+use crate::trace::TraceHook;
 use crate::{Config, Result};
 
+/// First byte of a unidirectional stream carrying a pushed, sealed Data packet
+pub(crate) const PUSH_DATA_FLAG: u8 = 0x01;
+
+/// First byte of a unidirectional stream carrying one fragment of a pushed Data
+pub(crate) const PUSH_FRAGMENT_FLAG: u8 = 0x02;
+
+/// Application close code sent to peers when the engine shuts down
+pub const SHUTDOWN_CODE: u32 = 0x2;
+
+/// Name of the Interest a connection is health-checked with; peers answer
+/// it with an empty Data without consulting any handler. Longer names under
+/// it are left to the handlers, such as the timestamped pings of
+/// `UdcnTransport::ping`
+pub const PING_PREFIX: &str = "/localhop/ping";
+
 /// Handler function type for serving prefix registrations
 pub type PrefixHandler = Box<dyn Fn(Interest) -> Result<Data> + Send + Sync>;
 
@@ -156,12 +183,18 @@ pub struct ConnectionStats {
     pub data_sent: u64,
     /// Number of data packets received
     pub data_received: u64,
+    /// Bytes of data received
+    pub bytes_received: u64,
     /// Average round-trip time in milliseconds
     pub avg_rtt_ms: f64,
     /// Packet loss rate (0.0 - 1.0)
     pub packet_loss_rate: f64,
+    /// When the statistics started accumulating
+    pub started_at: std::time::Instant,
     /// Last activity timestamp
     pub last_activity: std::time::Instant,
+    /// Circuit breaker state of the remote
+    pub breaker_state: BreakerState,
 }
 
 impl Default for ConnectionStats {
@@ -171,9 +204,12 @@ impl Default for ConnectionStats {
             interests_received: 0,
             data_sent: 0,
             data_received: 0,
+            bytes_received: 0,
             avg_rtt_ms: 0.0,
             packet_loss_rate: 0.0,
+            started_at: std::time::Instant::now(),
             last_activity: std::time::Instant::now(),
+            breaker_state: BreakerState::Closed,
         }
     }
 }
@@ -193,6 +229,27 @@ pub struct ConnectionTracker {
     congestion_window: RwLock<usize>,
     /// Health check interval for this connection
     health_check_interval: RwLock<Duration>,
+    /// Health-check pings missed in a row
+    missed_pings: AtomicU32,
+    /// Streams being served on this connection
+    in_flight: AtomicUsize,
+    /// Most streams served at once on this connection
+    peak_in_flight: AtomicUsize,
+    /// Woken when a stream slot is released
+    slot_released: Notify,
+    /// Collector recording the RTT of each satisfied Interest
+    metrics: Option<Arc<MetricsCollector>>,
+}
+
+/// A stream slot in a connection's congestion window, released when dropped
+#[derive(Debug)]
+pub struct StreamSlot(Arc<ConnectionTracker>);
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.0.slot_released.notify_waiters();
+    }
 }
 
 impl ConnectionTracker {
@@ -205,9 +262,20 @@ impl ConnectionTracker {
             remote_addr,
             congestion_window: RwLock::new(10),  // Initial congestion window size
             health_check_interval: RwLock::new(Duration::from_secs(30)),
+            missed_pings: AtomicU32::new(0),
+            in_flight: AtomicUsize::new(0),
+            peak_in_flight: AtomicUsize::new(0),
+            slot_released: Notify::new(),
+            metrics: None,
         }
     }
     
+    /// Record Interest RTTs in the given collector
+    pub fn with_metrics(mut self, metrics: Option<Arc<MetricsCollector>>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+    
     /// Update connection state
     pub async fn set_state(&self, state: ConnectionState) {
         let mut current_state = self.state.write().await;
@@ -234,6 +302,7 @@ impl ConnectionTracker {
         let mut stats = self.stats.write().await;
         stats.interests_sent += 1;
         stats.data_received += 1;
+        stats.bytes_received += data_size as u64;
         stats.avg_rtt_ms = rtt_ms as f64; // Use avg_rtt_ms instead of rtt_ms
         stats.last_activity = std::time::Instant::now();
         
@@ -247,6 +316,25 @@ impl ConnectionTracker {
         if *window < 100 {  // Cap at reasonable maximum
             *window += 1;    // Additive increase
         }
+        drop(window);
+        drop(stats);
+        
+        if let Some(metrics) = &self.metrics {
+            metrics.observe(INTEREST_RTT_METRIC, rtt_ms as f64).await;
+        }
+    }
+    
+    /// Record an Interest served for a registered prefix: satisfied with
+    /// Data since `start_time`, or answered with a NACK
+    async fn record_prefix(&self, prefix: &str, satisfied: bool, start_time: std::time::Instant) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_prefix_interest(prefix).await;
+            if satisfied {
+                metrics.record_prefix_rtt(prefix, start_time.elapsed().as_secs_f64() * 1000.0).await;
+            } else {
+                metrics.record_prefix_nack(prefix).await;
+            }
+        }
     }
     
     /// Report nack or timeout
@@ -277,17 +365,407 @@ impl ConnectionTracker {
         stats.last_activity.elapsed() > idle_threshold
     }
     
+    /// Get the interval between health-check pings
+    pub async fn health_check_interval(&self) -> Duration {
+        *self.health_check_interval.read().await
+    }
+    
+    /// Set the interval between health-check pings
+    pub async fn set_health_check_interval(&self, interval: Duration) {
+        *self.health_check_interval.write().await = interval;
+    }
+    
+    /// Get the number of health-check pings missed in a row
+    pub fn missed_pings(&self) -> u32 {
+        self.missed_pings.load(Ordering::Relaxed)
+    }
+    
+    /// Send a ping Interest on a new stream and wait up to `timeout` for
+    /// the peer to answer it, returning the round-trip time
+    pub async fn ping(&self, integrity: &IntegrityCheck, timeout: Duration) -> Result<Duration> {
+        let start = Instant::now();
+        let ping = Interest::new(Name::from_uri(PING_PREFIX)?);
+        
+        let exchange = async {
+            let (mut send, mut recv) = self.connection.open_bi().await
+                .map_err(|e| crate::error::Error::connection("Failed to open ping stream", e))?;
+            send.write_all(&integrity.seal(ping.to_bytes())).await
+                .map_err(|e| crate::error::Error::stream("Failed to send ping", e))?;
+            if let Err(e) = send.finish().await {
+                debug!("Error finishing ping stream: {}", e);
+            }
+            read_sealed_packet(&mut recv, integrity, 64 * 1024).await
+        };
+        
+        match tokio::time::timeout(timeout, exchange).await {
+            Ok(Ok(_)) => Ok(start.elapsed()),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(crate::error::Error::Timeout(format!("No ping response from {}", self.remote_addr))),
+        }
+    }
+    
+    /// Record the outcome of a health-check ping
+    ///
+    /// A missed ping counts as a loss; after `max_missed` in a row the
+    /// connection is marked failed, so it is replaced on next use. Returns
+    /// whether the connection was marked failed.
+    pub async fn record_ping(&self, result: &Result<Duration>, max_missed: u32) -> bool {
+        match result {
+            Ok(rtt) => {
+                self.missed_pings.store(0, Ordering::Relaxed);
+                
+                // An answered ping shows the path is delivering again, but is
+                // not activity that keeps the connection from going idle
+                let mut stats = self.stats.write().await;
+                if stats.packet_loss_rate > 0.01 {
+                    stats.packet_loss_rate *= 0.95;
+                }
+                debug!("Ping to {} answered in {:?}", self.remote_addr, rtt);
+                false
+            }
+            Err(e) => {
+                let missed = self.missed_pings.fetch_add(1, Ordering::Relaxed) + 1;
+                self.report_failure(&format!("Missed health check: {}", e)).await;
+                
+                if missed < max_missed {
+                    return false;
+                }
+                warn!("{} health checks to {} missed, marking the connection failed", missed, self.remote_addr);
+                self.set_state(ConnectionState::Failed(format!("{} health checks missed", missed))).await;
+                true
+            }
+        }
+    }
+    
+    /// Ping the peer every health check interval until the connection
+    /// closes or `max_missed` pings in a row go unanswered
+    pub fn spawn_health_check(self: &Arc<Self>, integrity: Arc<IntegrityCheck>, max_missed: u32) -> JoinHandle<()> {
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if tracker.connection.close_reason().is_some() {
+                    break;
+                }
+                if matches!(tracker.state().await, ConnectionState::Closing | ConnectionState::Failed(_)) {
+                    break;
+                }
+                
+                // A ping is missed if unanswered by the next one
+                let interval = tracker.health_check_interval().await;
+                let started = Instant::now();
+                let result = tracker.ping(&integrity, interval).await;
+                if tracker.record_ping(&result, max_missed).await {
+                    break;
+                }
+                
+                tokio::time::sleep(interval.saturating_sub(started.elapsed())).await;
+            }
+        })
+    }
+    
     /// Get congestion window size
     pub async fn congestion_window(&self) -> usize {
         *self.congestion_window.read().await
     }
     
+    /// Wait until fewer streams than the congestion window are in flight, then take a slot
+    pub async fn acquire_stream_slot(self: &Arc<Self>) -> StreamSlot {
+        loop {
+            // Registered before checking, so a slot released in between is not missed
+            let released = self.slot_released.notified();
+            
+            let window = self.congestion_window().await.max(1);
+            let in_flight = self.in_flight.load(Ordering::SeqCst);
+            if in_flight < window {
+                if self.in_flight.compare_exchange(in_flight, in_flight + 1, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
+                    self.peak_in_flight.fetch_max(in_flight + 1, Ordering::SeqCst);
+                    return StreamSlot(self.clone());
+                }
+                continue;
+            }
+            
+            released.await;
+        }
+    }
+    
+    /// Get the number of streams being served on this connection
+    pub fn in_flight_streams(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+    
+    /// Get the most streams served at once on this connection
+    pub fn peak_in_flight_streams(&self) -> usize {
+        self.peak_in_flight.load(Ordering::SeqCst)
+    }
+    
     /// Get connection
     pub fn connection(&self) -> &Connection {
         &self.connection
     }
 }
 
+/// Outcome of adding an Interest to the pending Interest table
+#[derive(Debug)]
+pub enum PendingInsert {
+    /// Nothing was pending for the name; the caller must produce the Data
+    New,
+    
+    /// The name is already pending; its Data will arrive on the receiver
+    Aggregated(oneshot::Receiver<Data>),
+    
+    /// An Interest with the same nonce is already pending for the name
+    Duplicate,
+}
+
+/// An Interest name whose Data is being produced
+#[derive(Debug)]
+struct PendingEntry {
+    /// Nonces of the Interests aggregated on the entry
+    nonces: HashSet<u32>,
+    
+    /// Requesters waiting for the Data, beyond the first
+    waiters: Vec<oneshot::Sender<Data>>,
+    
+    /// When the longest-lived aggregated Interest expires
+    expires_at: Instant,
+}
+
+impl PendingEntry {
+    /// Create an entry for its first Interest
+    fn new(interest: &Interest, now: Instant) -> Self {
+        Self {
+            nonces: HashSet::from([interest.nonce()]),
+            waiters: Vec::new(),
+            expires_at: now + interest.get_lifetime(),
+        }
+    }
+}
+
+/// Pending Interest table (PIT)
+///
+/// Interests for a name whose Data is already being produced are attached
+/// to the pending entry instead of being processed again, and all of them
+/// are answered by the one Data. An entry lives until it is satisfied,
+/// removed, or its longest Interest lifetime passes.
+#[derive(Debug, Default)]
+pub struct PendingInterestTable {
+    /// Pending entries by Interest name
+    entries: DashMap<Name, PendingEntry>,
+}
+
+impl PendingInterestTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Add an Interest, aggregating it with a pending one for the same name
+    pub fn insert(&self, interest: &Interest) -> PendingInsert {
+        let now = Instant::now();
+        
+        match self.entries.entry(interest.name().clone()) {
+            Entry::Occupied(mut occupied) if occupied.get().expires_at > now => {
+                let entry = occupied.get_mut();
+                if !entry.nonces.insert(interest.nonce()) {
+                    return PendingInsert::Duplicate;
+                }
+                
+                let (tx, rx) = oneshot::channel();
+                entry.waiters.push(tx);
+                entry.expires_at = entry.expires_at.max(now + interest.get_lifetime());
+                PendingInsert::Aggregated(rx)
+            }
+            Entry::Occupied(mut occupied) => {
+                // The previous entry timed out; its waiters see the sender dropped
+                occupied.insert(PendingEntry::new(interest, now));
+                PendingInsert::New
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(PendingEntry::new(interest, now));
+                PendingInsert::New
+            }
+        }
+    }
+    
+    /// Answer every Interest aggregated on the name and remove its entry
+    ///
+    /// Returns the number of waiting requesters that received the Data.
+    pub fn satisfy(&self, name: &Name, data: &Data) -> usize {
+        let entry = match self.entries.remove(name) {
+            Some((_, entry)) => entry,
+            None => return 0,
+        };
+        
+        let mut delivered = 0;
+        for waiter in entry.waiters {
+            if waiter.send(data.clone()).is_ok() {
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+    
+    /// Remove an entry without Data; its waiters see the sender dropped
+    pub fn remove(&self, name: &Name) -> bool {
+        self.entries.remove(name).is_some()
+    }
+    
+    /// Remove the entries whose Interests have all expired
+    pub fn expire(&self) -> usize {
+        let now = Instant::now();
+        let before = self.entries.len();
+        self.entries.retain(|_, entry| entry.expires_at > now);
+        before - self.entries.len()
+    }
+    
+    /// Get the number of pending names
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    /// Check whether nothing is pending
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Tracks the Interest streams being served so shutdown can wait for them
+#[derive(Debug, Default)]
+pub struct StreamDrain {
+    /// Set once the engine stops taking new streams
+    draining: AtomicBool,
+    
+    /// Streams being served
+    in_flight: AtomicUsize,
+}
+
+/// A stream counted as in flight until dropped
+struct InFlightStream(Arc<StreamDrain>);
+
+impl Drop for InFlightStream {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl StreamDrain {
+    /// Count a new stream as in flight, or refuse it while draining
+    fn enter(self: &Arc<Self>) -> Option<InFlightStream> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let stream = InFlightStream(self.clone());
+        
+        // Checked after counting, so a stream is either refused or waited for
+        if self.draining.load(Ordering::SeqCst) {
+            return None;
+        }
+        Some(stream)
+    }
+    
+    /// Get the number of streams being served
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+    
+    /// Check if the engine has stopped taking new streams
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+    
+    /// Stop taking new streams and wait up to `timeout` for the in-flight ones
+    ///
+    /// Returns whether every in-flight stream finished in time.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.draining.store(true, Ordering::SeqCst);
+        
+        let deadline = Instant::now() + timeout;
+        while self.in_flight() > 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        true
+    }
+}
+
+/// Limit on the size of Data sent in answer to an Interest
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DataSizeLimit {
+    /// Largest encoded Data sent without checking the policy below
+    pub(crate) max_packet_size: usize,
+    
+    /// Whether larger Data is fragmented rather than refused
+    pub(crate) fragment: bool,
+}
+
+impl DataSizeLimit {
+    /// Take the limit from the transport configuration
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            max_packet_size: config.max_packet_size,
+            fragment: config.fragment_oversize_data,
+        }
+    }
+}
+
+/// What a connection task needs from the engine, shared by every connection
+struct ConnectionContext {
+    prefixes: Arc<RwLock<HandlerTable<PrefixHandler>>>,
+    fragmenter: Arc<Fragmenter>,
+    integrity: Arc<IntegrityCheck>,
+    admission: Arc<AdmissionController>,
+    rate_limiter: Arc<RateLimiter>,
+    acl: SharedAcl,
+    commands: Arc<CommandValidator>,
+    content_store: Arc<RwLock<ContentStore>>,
+    pit: Arc<PendingInterestTable>,
+    drain: Arc<StreamDrain>,
+    trace: TraceHook,
+    recv_timeout: Duration,
+    data_limit: DataSizeLimit,
+}
+
+impl ConnectionContext {
+    /// Build what the stream tasks of one connection need
+    fn stream_context(&self, remote: SocketAddr, conn_tracker: Arc<ConnectionTracker>) -> StreamContext {
+        StreamContext {
+            prefixes: self.prefixes.clone(),
+            fragmenter: self.fragmenter.clone(),
+            integrity: self.integrity.clone(),
+            admission: self.admission.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            acl: self.acl.clone(),
+            commands: self.commands.clone(),
+            content_store: self.content_store.clone(),
+            pit: self.pit.clone(),
+            recv_timeout: self.recv_timeout,
+            data_limit: self.data_limit,
+            conn_tracker,
+            remote,
+            trace: self.trace.clone(),
+        }
+    }
+}
+
+/// What a stream task needs to serve an Interest on a connection
+#[derive(Clone)]
+struct StreamContext {
+    prefixes: Arc<RwLock<HandlerTable<PrefixHandler>>>,
+    fragmenter: Arc<Fragmenter>,
+    integrity: Arc<IntegrityCheck>,
+    admission: Arc<AdmissionController>,
+    rate_limiter: Arc<RateLimiter>,
+    acl: SharedAcl,
+    commands: Arc<CommandValidator>,
+    content_store: Arc<RwLock<ContentStore>>,
+    pit: Arc<PendingInterestTable>,
+    recv_timeout: Duration,
+    data_limit: DataSizeLimit,
+    conn_tracker: Arc<ConnectionTracker>,
+    remote: SocketAddr,
+    trace: TraceHook,
+}
+
 /// QUIC-based NDN transport engine
 pub struct QuicEngine {
     /// Configuration
@@ -296,14 +774,39 @@ pub struct QuicEngine {
     /// QUIC endpoint
     endpoint: Endpoint,
     
-    /// Active connections with enhanced tracking
-    connections: DashMap<SocketAddr, Arc<ConnectionTracker>>,
+    /// Endpoint every outgoing connection is opened from
+    client_endpoint: Endpoint,
+    
+    /// Certificate the endpoint presents to connecting peers
+    certificate: Certificate,
+    
+    /// Active connections with enhanced tracking, one per remote
+    connections: Arc<DashMap<SocketAddr, Arc<ConnectionTracker>>>,
+    
+    /// Per-remote locks serializing connection attempts
+    connect_locks: DashMap<SocketAddr, Arc<Mutex<()>>>,
+    
+    /// Per-remote circuit breakers refusing to reconnect to unreachable peers
+    breaker: CircuitBreaker,
+    
+    /// Number of outgoing connections opened
+    connections_opened: AtomicU64,
+    
+    /// Client configuration per remote, keeping its session tickets for 0-RTT
+    client_configs: DashMap<SocketAddr, quinn::ClientConfig>,
+    
+    /// Transport parameters, with the configured flow-control windows, of
+    /// every connection in either direction
+    transport_config: Arc<quinn::TransportConfig>,
+    
+    /// Number of outgoing connections whose 0-RTT data the server accepted
+    zero_rtt_accepted: Arc<AtomicU64>,
     
     /// Name stream mapper
     mapper: Arc<NameStreamMapper>,
     
-    /// Prefix registrations
-    prefixes: Arc<RwLock<HashMap<Name, PrefixHandler>>>,
+    /// Prefix and exact-name registrations
+    prefixes: Arc<RwLock<HandlerTable<PrefixHandler>>>,
     
     /// Server task handle
     server_handle: Option<JoinHandle<()>>,
@@ -311,9 +814,45 @@ pub struct QuicEngine {
     /// Connection maintenance task handle
     maintenance_handle: Option<JoinHandle<()>>,
     
+    /// Stale reassembly sweeper task handle
+    sweeper_handle: Option<JoinHandle<()>>,
+    
+    /// CPU load sampler task handle
+    cpu_sampler_handle: Option<JoinHandle<()>>,
+    
     /// Fragmenter for large data objects
     fragmenter: Arc<Fragmenter>,
     
+    /// CRC32 self-test applied to packets on the wire
+    integrity: Arc<IntegrityCheck>,
+    
+    /// Admission control for incoming Interests
+    admission: Arc<AdmissionController>,
+    
+    /// Per-peer token buckets for incoming Interests
+    rate_limiter: Arc<RateLimiter>,
+    
+    /// Peers allowed to request each prefix
+    acl: SharedAcl,
+    
+    /// Signature check of incoming command Interests
+    commands: Arc<CommandValidator>,
+    
+    /// Data served to peers, answering repeated Interests without the handler
+    content_store: Arc<RwLock<ContentStore>>,
+    
+    /// Incoming Interests whose Data is being produced
+    pit: Arc<PendingInterestTable>,
+    
+    /// Collector the connection trackers record Interest RTTs in
+    metrics: Option<Arc<MetricsCollector>>,
+    
+    /// Tracer shown every frame sent or received
+    trace: TraceHook,
+    
+    /// Interest streams being served
+    drain: Arc<StreamDrain>,
+    
     /// Running flag
     running: Arc<RwLock<bool>>,
 }
@@ -334,38 +873,111 @@ impl std::fmt::Debug for QuicEngine {
 impl QuicEngine {
     /// Create a new QUIC engine
     pub async fn new(config: &Config) -> Result<Self> {
-        // Generate self-signed certificate for QUIC server
-        let (cert, key) = generate_self_signed_cert()?;
+        // Present the configured certificate, or a self-signed one
+        let (chain, key) = server_identity(config.tls_cert_path.as_deref(), config.tls_key_path.as_deref())?;
+        
+        // Apply the configured flow-control windows to both directions
+        let mut transport_config = quinn::TransportConfig::default();
+        FlowControlWindows::from(config).apply(&mut transport_config)?;
+        let transport_config = Arc::new(transport_config);
         
         // Create server config with the certificate
-        let server_config = quinn::ServerConfig::with_single_cert(vec![cert], key)?;
+        let mut server_config = quinn::ServerConfig::with_single_cert(chain.clone(), key)?;
+        server_config.transport_config(transport_config.clone());
         
         // Create QUIC endpoint
-        let mut addr = config.bind_address.parse::<SocketAddr>()?;
-        addr.set_port(config.port);
+        let addr = config.bind_addr()?;
         
         let endpoint = Endpoint::server(server_config, addr)?;
         info!("QUIC endpoint bound to {}", addr);
         
+        // Create the client endpoint shared by all outgoing connections,
+        // verifying servers as the TLS configuration says
+        let mut client_endpoint = Endpoint::client(SocketAddr::new(addr.ip(), 0))?;
+        let mut client_config = quinn::ClientConfig::new(Arc::new(build_client_config(&config.tls)?));
+        client_config.transport_config(transport_config.clone());
+        client_endpoint.set_default_client_config(client_config);
+        
         // Create name-to-stream mapper
         let mapper = Arc::new(NameStreamMapper::new());
         
         // Create fragmenter
-        let fragmenter = Arc::new(Fragmenter::new(config.mtu));
+        let fragmenter = Arc::new(Fragmenter::new(config.mtu).with_max_reassembly_bytes(config.max_reassembly_bytes));
+        
+        // Commands are only checked when they must be signed
+        let command_prefix = if config.require_signed_commands {
+            Some(Name::from_uri(&config.command_prefix)?)
+        } else {
+            None
+        };
         
         Ok(Self {
             config: config.clone(),
             endpoint,
-            connections: DashMap::new(),
+            client_endpoint,
+            certificate: chain[0].clone(),
+            connections: Arc::new(DashMap::new()),
+            connect_locks: DashMap::new(),
+            breaker: CircuitBreaker::new(config.breaker_failure_threshold, Duration::from_millis(config.breaker_cooldown)),
+            connections_opened: AtomicU64::new(0),
+            client_configs: DashMap::new(),
+            transport_config,
+            zero_rtt_accepted: Arc::new(AtomicU64::new(0)),
             mapper,
-            prefixes: Arc::new(RwLock::new(HashMap::new())),
+            prefixes: Arc::new(RwLock::new(HandlerTable::new())),
             fragmenter,
+            integrity: Arc::new(IntegrityCheck::new(config.integrity_check)),
+            admission: Arc::new(AdmissionController::new(config.admission.clone())),
+            rate_limiter: Arc::new(RateLimiter::new(config.max_interests_per_sec, config.interest_burst)),
+            acl: SharedAcl::default(),
+            commands: Arc::new(CommandValidator::new(command_prefix, SharedCertStore::default())
+                .with_grace_period(Duration::from_millis(config.command_grace_period))),
+            content_store: Arc::new(RwLock::new(ContentStore::from_config(config))),
+            pit: Arc::new(PendingInterestTable::new()),
+            drain: Arc::new(StreamDrain::default()),
+            metrics: None,
+            trace: TraceHook::new(),
             server_handle: None,
             maintenance_handle: None,
+            sweeper_handle: None,
+            cpu_sampler_handle: None,
             running: Arc::new(RwLock::new(false)),
         })
     }
     
+    /// Record the RTT of Interests satisfied over this engine's connections
+    ///
+    /// Takes effect for connections established afterwards, so call it
+    /// before `start`.
+    pub fn set_metrics(&mut self, metrics: Arc<MetricsCollector>) {
+        self.metrics = Some(metrics);
+    }
+    
+    /// Share a trace hook, so the tracer set in it sees every frame this
+    /// engine sends or receives
+    ///
+    /// Takes effect for connections established afterwards, so call it
+    /// before `start`.
+    pub fn set_trace_hook(&mut self, trace: TraceHook) {
+        self.trace = trace;
+    }
+    
+    /// Check the peers of incoming Interests against a shared access
+    /// control policy
+    ///
+    /// Call it before `start`; the policy in it may be replaced at any time.
+    pub fn set_acl(&mut self, acl: SharedAcl) {
+        self.acl = acl;
+    }
+    
+    /// Check signed commands against keys in a shared certificate store
+    ///
+    /// Call it before `start`.
+    pub fn set_cert_store(&mut self, certs: SharedCertStore) {
+        self.commands = Arc::new(CommandValidator::new(self.commands.prefix().cloned(), certs)
+            .with_grace_period(self.commands.grace_period()));
+    }
+    
     /// Start the QUIC engine
     pub async fn start(&mut self) -> Result<()> {
         // Set running state
@@ -375,9 +987,22 @@ impl QuicEngine {
         
         // Clone required references for the server task
         let endpoint = self.endpoint.clone();
-        let mapper = self.mapper.clone();
-        let prefixes = self.prefixes.clone();
-        let fragmenter = self.fragmenter.clone();
+        let metrics = self.metrics.clone();
+        let conn_ctx = Arc::new(ConnectionContext {
+            prefixes: self.prefixes.clone(),
+            fragmenter: self.fragmenter.clone(),
+            integrity: self.integrity.clone(),
+            admission: self.admission.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            acl: self.acl.clone(),
+            commands: self.commands.clone(),
+            content_store: self.content_store.clone(),
+            pit: self.pit.clone(),
+            drain: self.drain.clone(),
+            trace: self.trace.clone(),
+            recv_timeout: Duration::from_millis(self.config.recv_timeout),
+            data_limit: DataSizeLimit::from_config(&self.config),
+        });
         let connections = self.connections.clone();
         let running_ref = self.running.clone();
         
@@ -401,28 +1026,18 @@ impl QuicEngine {
                                 info!("Accepted connection from {}", remote);
                                 
                                 // Create connection tracker
-                                let conn_tracker = Arc::new(ConnectionTracker::new(conn.clone(), conn.remote_address()));
+                                let conn_tracker = Arc::new(ConnectionTracker::new(conn.clone(), conn.remote_address())
+                                    .with_metrics(metrics.clone()));
                                 connections.insert(remote, conn_tracker.clone());
                                 
                                 // Spawn a new task to handle the connection
-                                let mapper_clone = mapper.clone();
-                                let prefixes_clone = prefixes.clone();
-                                let fragmenter_clone = fragmenter.clone();
-                                let conn_tracker_clone = conn_tracker.clone();
-                                
+                                let conn_ctx = conn_ctx.clone();
                                 tokio::spawn(async move {
                                     // Mark connection as connected
-                                    conn_tracker_clone.set_state(ConnectionState::Connected).await;
+                                    conn_tracker.set_state(ConnectionState::Connected).await;
                                     
                                     // Handle the connection
-                                    Self::handle_connection(
-                                        conn,
-                                        remote,
-                                        mapper_clone,
-                                        prefixes_clone,
-                                        fragmenter_clone,
-                                        conn_tracker_clone
-                                    ).await;
+                                    Self::handle_connection(conn, conn_ctx, conn_tracker).await;
                                 });
                             },
                             Err(e) => {
@@ -447,6 +1062,7 @@ impl QuicEngine {
         
         // Start the connection maintenance task
         let connections = self.connections.clone();
+        let pit = self.pit.clone();
         let running_ref = self.running.clone();
         let idle_timeout = Duration::from_secs(self.config.idle_timeout);
         
@@ -461,6 +1077,12 @@ impl QuicEngine {
                     break;
                 }
                 
+                // Drop pending Interests that were never satisfied
+                let expired = pit.expire();
+                if expired > 0 {
+                    debug!("Expired {} pending Interests", expired);
+                }
+                
                 // Check each connection for health
                 for mut entry in connections.iter_mut() {
                     let addr = *entry.key();
@@ -499,36 +1121,45 @@ impl QuicEngine {
             info!("QUIC maintenance task terminated");
         }));
         
+        // Reap reassemblies left behind by peers that stopped sending fragments
+        self.sweeper_handle = Some(self.fragmenter.spawn_sweeper(
+            Duration::from_millis(self.config.reassembly_timeout),
+            Duration::from_millis(self.config.reassembly_sweep_interval),
+        ));
+        
+        // Feed the CPU load to admission control
+        self.cpu_sampler_handle = Some(self.admission.spawn_cpu_sampler());
+        
         info!("QUIC engine started");
         Ok(())
     }
     
     /// Handle a new QUIC connection
     async fn handle_connection(
-        connection: quinn::Connection, 
-        remote: SocketAddr,
-        _mapper: Arc<NameStreamMapper>,
-        prefixes: Arc<RwLock<HashMap<Name, PrefixHandler>>>,
-        fragmenter: Arc<Fragmenter>,
-        conn_tracker: Arc<ConnectionTracker>
+        connection: quinn::Connection,
+        conn_ctx: Arc<ConnectionContext>,
+        conn_tracker: Arc<ConnectionTracker>,
     ) {
+        let remote = connection.remote_address();
         info!("Handling connection from {}", remote);
         
         // Set initial state as connected
         conn_tracker.set_state(ConnectionState::Connected).await;
         
+        let recv_timeout = conn_ctx.recv_timeout;
+        let ctx = conn_ctx.stream_context(remote, conn_tracker.clone());
+        
+        // Pushed Data arrives on unidirectional streams, independently of Interests
+        let push_handle = tokio::spawn(Self::accept_pushed_data(connection.clone(), ctx.clone()));
+        
         loop {
-            // Check congestion window before accepting a new stream
-            let window_size = conn_tracker.congestion_window().await;
-            if window_size < 1 {
-                // Back off briefly if congestion window is zero
-                tokio::time::sleep(Duration::from_millis(100)).await;
-                continue;
-            }
+            // Wait for room in the congestion window before accepting a new stream
+            let slot = conn_tracker.acquire_stream_slot().await;
             
-            // Accept a new stream from the remote peer with timeout
+            // Accept a new stream from the remote peer, waiting at most the
+            // receive timeout before checking the connection again
             let stream_result = tokio::time::timeout(
-                Duration::from_secs(30),
+                recv_timeout,
                 connection.accept_bi()
             ).await;
             
@@ -559,176 +1190,482 @@ impl QuicEngine {
             };
             
             // Unpack the bidirectional stream
-            let (mut send, mut recv) = stream;
+            let (mut send, recv) = stream;
             
-            // Start time for RTT measurement
-            let start_time = std::time::Instant::now();
-            
-            // Read the request with timeout
-            let data_result = tokio::time::timeout(
-                Duration::from_secs(10),
-                recv.read_to_end(64 * 1024)
-            ).await;
-            
-            let data = match data_result {
-                Ok(read_result) => match read_result {
-                    Ok(data) => data,
-                    Err(e) => {
-                        error!("Error reading from stream: {}", e);
-                        conn_tracker.report_failure(&format!("Read error: {}", e)).await;
-                        continue;
-                    }
-                },
-                Err(_) => {
-                    // Timeout occurred
-                    warn!("Timeout reading from stream");
-                    conn_tracker.report_failure("Read timeout").await;
-                    continue;
+            // Refuse new streams once the engine is draining; the guard keeps
+            // an accepted stream in flight until its response is finished
+            let in_flight = match conn_ctx.drain.enter() {
+                Some(in_flight) => in_flight,
+                None => {
+                    debug!("Draining, refusing stream from {}", remote);
+                    let _ = send.reset(SHUTDOWN_CODE.into());
+                    break;
                 }
             };
             
-            // Try to parse as an interest
-            match Interest::from_bytes(&data) {
-                Ok(interest) => {
-                    debug!("Received Interest for {}", interest.name());
-                    
-                    // Find handler for this interest
-                    let mut handler_opt = None;
-                    
-                    {
-                        // Scope to ensure prefixes_lock is dropped after we're done with it
-                        let prefixes_lock = prefixes.read().await;
-                        
-                        // Longest prefix match
-                        let mut best_match_len = 0;
-                        for (prefix, handler) in prefixes_lock.iter() {
-                            if interest.name().starts_with(prefix) && prefix.len() > best_match_len {
-                                best_match_len = prefix.len();
-                                handler_opt = Some(handler.clone());
-                            }
-                        }
-                    } // prefixes_lock is automatically dropped here
-                    
-                    // Process the Interest with the handler
-                    if let Some(handler) = handler_opt {
-                        // Process the interest
-                        match handler(interest.clone()) {
-                        Ok(mut data) => {
-                            // Check if we need to fragment the data
-                            let mtu = fragmenter.mtu().await;
-                            let data_bytes = data.to_bytes();
-                            
-                            if data_bytes.len() > mtu {
-                                // Fragment the data
-                                debug!("Fragmenting data for {} ({} bytes > {} MTU)", 
-                                       interest.name(), data_bytes.len(), mtu);
-                                let mtu = fragmenter.mtu().await;
-                                let data_bytes = data.to_bytes();
-                                
-                                if data_bytes.len() > mtu {
-                                    // Fragment the data
-                                    debug!("Fragmenting data for {} ({} bytes > {} MTU)", 
-                                           interest.name(), data_bytes.len(), mtu);
-                                    
-                                    let fragments = fragmenter.fragment(&data).await;
-                                    
-                                    // Send all fragments
-                                    for fragment in fragments {
-                                        if let Err(e) = send.write_all(&fragment).await {
-                                            error!("Error sending fragment: {}", e);
-                                            conn_tracker.report_failure(&format!("Send error: {}", e)).await;
-                                            break;
-                                        }
-                                    }
-                                } else {
-                                    // Send the data directly
-                                    debug!("Sending Data for {}", interest.name());
-                                    if let Err(e) = send.write_all(&data_bytes).await {
-                                        error!("Error sending data: {}", e);
-                                        conn_tracker.report_failure(&format!("Send error: {}", e)).await;
-                                    }
-                                }
-                                
-                                // Calculate RTT and data size for statistics
-                                let rtt = start_time.elapsed().as_millis() as u64;
-                                let data_size = data_bytes.len();
-                                
-                                // Update connection statistics
-                                conn_tracker.report_success(rtt, data_size).await;
-                                
-                                // Close the stream
-                                if let Err(e) = send.finish().await {
-                                    error!("Error finishing stream: {}", e);
-                                }
-                            },
-                            Err(e) => {
-                                // Create a NACK
-                                let nack = Nack::from_interest(interest.clone(), e.to_string());
-                                let nack_bytes = nack.to_bytes();
-                                
-                                // Send the NACK
-                                warn!("Sending NACK for {}: {}", interest.name(), e);
-                                if let Err(e) = send.write_all(&nack_bytes).await {
-                                    error!("Error sending NACK: {}", e);
-                                    conn_tracker.report_failure(&format!("NACK error: {}", e)).await;
-                                }
-                                
-                                // Update failure statistics
-                                conn_tracker.report_failure(&format!("Handler error: {}", e)).await;
-                                
-                                // Close the stream
-                                if let Err(e) = send.finish().await {
-                                    error!("Error finishing stream: {}", e);
-                                }
-                            }
-                        }
-                    } else {
-                        // No handler found, send a NACK
-                        let nack = Nack::from_interest(
-                            interest.clone(),
-                            "No handler found for prefix".to_string()
-                        );
-                        
-                        // Send the NACK
-                        warn!("No handler for {}, sending NACK", interest.name());
-                        if let Err(e) = send.write_all(&nack.to_bytes()).await {
-                            error!("Error sending NACK: {}", e);
-                            conn_tracker.report_failure(&format!("NACK error: {}", e)).await;
-                        }
-                        
-                        // Update failure statistics
-                        conn_tracker.report_failure("No handler for prefix").await;
-                        
-                        // Close the stream
-                        if let Err(e) = send.finish().await {
-                            error!("Error finishing stream: {}", e);
-                        }
-                    }
-                },
-                Err(e) => {
-                    // Handle the error directly without pattern matching
-                    if e.to_string().contains("ApplicationClosed") {
-                        info!("Connection closed gracefully");
-                    } else {
-                        error!("Connection error: {}", e);
-                    }
-                    break;
-                }
-            }
+            // Serve the stream concurrently; the slot is released once its response is finished
+            tokio::spawn(Self::handle_stream(ctx.clone(), send, recv, slot, in_flight));
         }
         
+        push_handle.abort();
+        conn_ctx.rate_limiter.remove(&remote);
         info!("Connection handler finished for {}", remote);
     }
     
-    /// Register a prefix with a handler function
-    pub async fn register_prefix(&self, prefix: Name, handler: PrefixHandler) -> Result<u64> {
-        info!("Registering prefix: {}", prefix);
+    /// Serve the Interest on one stream, holding its congestion window slot and
+    /// drain guard until the response is finished
+    async fn handle_stream(
+        ctx: StreamContext,
+        mut send: quinn::SendStream,
+        mut recv: quinn::RecvStream,
+        _slot: StreamSlot,
+        _in_flight: InFlightStream,
+    ) {
+        let StreamContext {
+            prefixes,
+            fragmenter,
+            integrity,
+            admission,
+            rate_limiter,
+            acl,
+            commands,
+            content_store,
+            pit,
+            recv_timeout,
+            data_limit,
+            conn_tracker,
+            remote,
+            trace,
+        } = ctx;
         
-        // Store the prefix and handler
-        let mut prefixes = self.prefixes.write().await;
-        prefixes.insert(prefix.clone(), handler);
+        // Start time for RTT measurement
+        let start_time = std::time::Instant::now();
         
-        // Create a channel for this prefix
+        // Read the Interest, however its STREAM frames were split, with timeout
+        let data_result = tokio::time::timeout(
+            recv_timeout,
+            read_sealed_packet(&mut recv, &integrity, data_limit.max_packet_size)
+        ).await;
+        
+        let data = match data_result {
+            Ok(read_result) => match read_result {
+                Ok(data) => data,
+                Err(e) => {
+                    error!("Error reading from stream: {}", e);
+                    conn_tracker.report_failure(&format!("Read error: {}", e)).await;
+                    return;
+                }
+            },
+            Err(_) => {
+                // Timeout occurred
+                warn!("Timeout reading from stream");
+                conn_tracker.report_failure("Read timeout").await;
+                return;
+            }
+        };
+        
+        // Check the CRC trailer when the integrity self-test is enabled
+        let data = Bytes::from(data);
+        trace.on_recv(&data, remote);
+        let body_len = match integrity.verify(&data) {
+            Ok(body) => body.len(),
+            Err(e) => {
+                conn_tracker.report_failure(&format!("Integrity error: {}", e)).await;
+                return;
+            }
+        };
+        
+        // Requests on a stream must be Interests
+        match Packet::parse(data.slice(..body_len)) {
+            Ok(Packet::Interest(interest)) => {
+                debug!("Received Interest for {}", interest.name());
+                
+                // Answer health-check pings straight away
+                if is_ping(&interest) {
+                    let pong = Data::new(interest.name().clone(), Bytes::new());
+                    if let Err(e) = Self::write_frame(&mut send, &integrity.seal(pong.to_bytes()), &trace, remote).await {
+                        debug!("Error answering ping from {}: {}", remote, e);
+                    }
+                    if let Err(e) = send.finish().await {
+                        debug!("Error finishing ping stream: {}", e);
+                    }
+                    return;
+                }
+                
+                // Hold each peer to its Interest budget, refuse prefixes
+                // the peer may not request and commands without a trusted
+                // signature, then shed load with a Congestion NACK while
+                // overloaded
+                let _permit = match rate_limiter.check(remote, &interest)
+                    .and_then(|()| acl.read().check(remote, &interest))
+                    .and_then(|()| commands.check(&interest))
+                    .and_then(|()| admission.admit(&interest))
+                {
+                    Ok(permit) => permit,
+                    Err(nack) => {
+                        if let Err(e) = Self::write_frame(&mut send, &integrity.seal(nack.to_bytes()), &trace, remote).await {
+                            error!("Error sending NACK: {}", e);
+                        }
+                        if let Err(e) = send.finish().await {
+                            error!("Error finishing stream: {}", e);
+                        }
+                        return;
+                    }
+                };
+                
+                // Answer from the content store when it holds matching Data
+                let cached = content_store.read().await.get_for_interest(&interest);
+                if let Some(data) = cached {
+                    debug!("Serving cached Data for {}", interest.name());
+                    Self::send_data_response(&mut send, &interest, &data, &fragmenter, &integrity, data_limit, &conn_tracker, &trace, start_time).await;
+                    return;
+                }
+                
+                // Attach to a pending Interest for the same name rather than
+                // producing the Data twice
+                match pit.insert(&interest) {
+                    PendingInsert::New => {}
+                    PendingInsert::Duplicate => {
+                        debug!("Duplicate nonce for pending {}, sending NACK", interest.name());
+                        let nack = Nack::new(interest.clone(), NackReason::Duplicate);
+                        if let Err(e) = Self::write_frame(&mut send, &integrity.seal(nack.to_bytes()), &trace, remote).await {
+                            error!("Error sending NACK: {}", e);
+                        }
+                        if let Err(e) = send.finish().await {
+                            error!("Error finishing stream: {}", e);
+                        }
+                        return;
+                    }
+                    PendingInsert::Aggregated(pending) => {
+                        debug!("Aggregated Interest for pending {}", interest.name());
+                        match tokio::time::timeout(interest.get_lifetime(), pending).await {
+                            Ok(Ok(data)) => {
+                                Self::send_data_response(&mut send, &interest, &data, &fragmenter, &integrity, data_limit, &conn_tracker, &trace, start_time).await;
+                            }
+                            _ => {
+                                conn_tracker.report_failure("Pending Interest not satisfied").await;
+                                let nack = Nack::from_interest(interest.clone(), "Pending Interest not satisfied".to_string());
+                                if let Err(e) = Self::write_frame(&mut send, &integrity.seal(nack.to_bytes()), &trace, remote).await {
+                                    error!("Error sending NACK: {}", e);
+                                }
+                                if let Err(e) = send.finish().await {
+                                    error!("Error finishing stream: {}", e);
+                                }
+                            }
+                        }
+                        return;
+                    }
+                }
+                
+                // Run the handler for this interest, exact match then longest prefix
+                let response = {
+                    let prefixes_lock = prefixes.read().await;
+                    prefixes_lock.lookup_entry(interest.name())
+                        .map(|(prefix, handler)| (prefix.to_string(), handler(interest.clone())))
+                };
+                
+                // Process the Interest with the handler
+                if let Some((prefix, response)) = response {
+                    conn_tracker.record_prefix(&prefix, response.is_ok(), start_time).await;
+                    match response {
+                        Ok(data) => {
+                            // Keep the Data for later Interests and answer aggregated ones
+                            content_store.write().await.insert(data.clone());
+                            pit.satisfy(interest.name(), &data);
+                            
+                            debug!("Sending Data for {}", interest.name());
+                            Self::send_data_response(&mut send, &interest, &data, &fragmenter, &integrity, data_limit, &conn_tracker, &trace, start_time).await;
+                        },
+                        Err(e) => {
+                            pit.remove(interest.name());
+                            
+                            // Create a NACK
+                            let nack = Nack::from_interest(interest.clone(), e.to_string());
+                            let nack_bytes = integrity.seal(nack.to_bytes());
+                            
+                            // Send the NACK
+                            warn!("Sending NACK for {}: {}", interest.name(), e);
+                            if let Err(e) = Self::write_frame(&mut send, &nack_bytes, &trace, remote).await {
+                                error!("Error sending NACK: {}", e);
+                                conn_tracker.report_failure(&format!("NACK error: {}", e)).await;
+                            }
+                            
+                            // Update failure statistics
+                            conn_tracker.report_failure(&format!("Handler error: {}", e)).await;
+                            
+                            // Close the stream
+                            if let Err(e) = send.finish().await {
+                                error!("Error finishing stream: {}", e);
+                            }
+                        }
+                    }
+                } else {
+                    pit.remove(interest.name());
+                    
+                    // No handler found, send a NACK
+                    let nack = Nack::from_interest(
+                        interest.clone(),
+                        "No handler found for prefix".to_string()
+                    );
+                    
+                    // Send the NACK
+                    warn!("No handler for {}, sending NACK", interest.name());
+                    if let Err(e) = Self::write_frame(&mut send, &integrity.seal(nack.to_bytes()), &trace, remote).await {
+                        error!("Error sending NACK: {}", e);
+                        conn_tracker.report_failure(&format!("NACK error: {}", e)).await;
+                    }
+                    
+                    // Update failure statistics
+                    conn_tracker.report_failure("No handler for prefix").await;
+                    
+                    // Close the stream
+                    if let Err(e) = send.finish().await {
+                        error!("Error finishing stream: {}", e);
+                    }
+                }
+            },
+            Ok(packet) => {
+                warn!("Ignoring unexpected {:?} from {}", packet, remote);
+                conn_tracker.report_failure("Unexpected packet").await;
+                if let Err(e) = send.finish().await {
+                    error!("Error finishing stream: {}", e);
+                }
+            }
+            Err(e) => {
+                warn!("Failed to decode packet from {}: {}", remote, e);
+                conn_tracker.report_failure(&format!("Decode error: {}", e)).await;
+                if let Err(e) = send.finish().await {
+                    error!("Error finishing stream: {}", e);
+                }
+            }
+        }
+    }
+    
+    /// Accept unidirectional streams of pushed Data and cache what they carry
+    ///
+    /// Pushed Data is held to the peer's rate limit and the access control
+    /// policy for its name, and must carry a trusted signature once the
+    /// certificate store has keys.
+    async fn accept_pushed_data(connection: quinn::Connection, ctx: StreamContext) {
+        let StreamContext {
+            fragmenter, integrity, rate_limiter, acl, commands, content_store, trace, recv_timeout, data_limit, remote, ..
+        } = ctx;
+        let max_packet_size = data_limit.max_packet_size;
+        
+        loop {
+            let mut recv = match connection.accept_uni().await {
+                Ok(recv) => recv,
+                Err(e) => {
+                    debug!("Stopped accepting pushed Data from {}: {}", remote, e);
+                    break;
+                }
+            };
+            
+            // A sealed Data is read by its TLV header; a fragment fills the
+            // rest of its stream
+            let read = async {
+                let flag = recv.read_u8().await
+                    .map_err(|e| crate::error::Error::IoError(format!("Failed to read push flag: {}", e)))?;
+                let packet = match flag {
+                    PUSH_DATA_FLAG => read_sealed_packet(&mut recv, &integrity, max_packet_size).await?,
+                    _ => recv.read_to_end(max_packet_size).await
+                        .map_err(|e| crate::error::Error::stream("Failed to read pushed fragment", e))?,
+                };
+                let mut bytes = Vec::with_capacity(1 + packet.len());
+                bytes.push(flag);
+                bytes.extend(packet);
+                Ok::<_, crate::error::Error>(bytes)
+            };
+            
+            let bytes = match tokio::time::timeout(recv_timeout, read).await {
+                Ok(Ok(bytes)) => bytes,
+                Ok(Err(e)) => {
+                    warn!("Error reading pushed Data from {}: {}", remote, e);
+                    continue;
+                }
+                Err(_) => {
+                    warn!("Timeout reading pushed Data from {}", remote);
+                    continue;
+                }
+            };
+            
+            // The frame follows the push flag
+            if let Some(frame) = bytes.get(1..) {
+                trace.on_recv(frame, remote);
+            }
+            
+            match Self::decode_pushed(&bytes, &fragmenter, &integrity).await {
+                Ok(Some(data)) => {
+                    let interest = Interest::new(data.name().clone());
+                    if let Err(nack) = rate_limiter.check(remote, &interest)
+                        .and_then(|()| acl.read().check(remote, &interest))
+                    {
+                        warn!("Dropping Data {} pushed by {}: {:?}", data.name(), remote, nack.reason());
+                        continue;
+                    }
+                    
+                    let verified = {
+                        let certs = commands.certs().read();
+                        if certs.is_empty() { Ok(()) } else { verify_data_with_store(&data, &certs) }
+                    };
+                    if let Err(e) = verified {
+                        warn!("Dropping Data {} pushed by {}: {}", data.name(), remote, e);
+                        continue;
+                    }
+                    
+                    debug!("Caching Data {} pushed by {}", data.name(), remote);
+                    content_store.write().await.insert(data);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Dropping pushed packet from {}: {}", remote, e),
+            }
+        }
+    }
+    
+    /// Decode the contents of a push stream, returning the Data once it is complete
+    pub(crate) async fn decode_pushed(bytes: &[u8], fragmenter: &Fragmenter, integrity: &IntegrityCheck) -> Result<Option<Data>> {
+        match bytes.split_first() {
+            Some((&PUSH_DATA_FLAG, packet)) => {
+                let packet = integrity.verify(packet)?;
+                Ok(Some(Data::from_bytes(packet)?))
+            }
+            Some((&PUSH_FRAGMENT_FLAG, packet)) => {
+                let fragment = Fragment::from_bytes(&mut Bytes::copy_from_slice(packet))?;
+                fragmenter.add_fragment(fragment).await
+            }
+            Some((flag, _)) => Err(crate::error::Error::ProtocolError(format!("Unknown push stream flag {:#04x}", flag))),
+            None => Err(crate::error::Error::ProtocolError("Empty push stream".to_string())),
+        }
+    }
+
+    /// Build the writes carrying a Data response: the sealed Data when it
+    /// fits in the MTU, otherwise its fragments
+    pub(crate) async fn data_response_frames(data: &Data, fragmenter: &Fragmenter, integrity: &IntegrityCheck) -> Result<Vec<Bytes>> {
+        let mtu = fragmenter.mtu().await;
+        let data_bytes = data.to_bytes();
+        
+        if data_bytes.len() > mtu {
+            debug!("Fragmenting data for {} ({} bytes > {} MTU)", data.name(), data_bytes.len(), mtu);
+            fragmenter.fragment(data).await
+        } else {
+            Ok(vec![integrity.seal(data_bytes)])
+        }
+    }
+    
+    /// Build the writes answering an Interest with Data, or refuse the Data
+    /// with a NoResource NACK when it is larger than the limit allows or
+    /// cannot be fragmented
+    pub(crate) async fn data_reply_frames(
+        interest: &Interest,
+        data: &Data,
+        fragmenter: &Fragmenter,
+        integrity: &IntegrityCheck,
+        limit: DataSizeLimit,
+    ) -> std::result::Result<Vec<Bytes>, Nack> {
+        let size = data.to_bytes().len();
+        let frames = if size > limit.max_packet_size && !limit.fragment {
+            Err(crate::error::Error::Fragmentation(format!(
+                "{} bytes is over the {}-byte packet limit", size, limit.max_packet_size)))
+        } else {
+            Self::data_response_frames(data, fragmenter, integrity).await
+        };
+        
+        frames.map_err(|e| {
+            warn!("Refusing Data for {}: {}", interest.name(), e);
+            Nack::new(interest.clone(), NackReason::NoResource)
+        })
+    }
+    
+    /// Send a Data response, record the exchange and finish the stream
+    #[allow(clippy::too_many_arguments)]
+    async fn send_data_response(
+        send: &mut quinn::SendStream,
+        interest: &Interest,
+        data: &Data,
+        fragmenter: &Fragmenter,
+        integrity: &IntegrityCheck,
+        limit: DataSizeLimit,
+        conn_tracker: &ConnectionTracker,
+        trace: &TraceHook,
+        start_time: Instant,
+    ) {
+        let (frames, satisfied) = match Self::data_reply_frames(interest, data, fragmenter, integrity, limit).await {
+            Ok(frames) => (frames, true),
+            Err(nack) => {
+                conn_tracker.report_failure(&format!("Data too large for {}", data.name())).await;
+                (vec![integrity.seal(nack.to_bytes())], false)
+            }
+        };
+        
+        let mut sent = satisfied;
+        for frame in frames {
+            let frame = Self::frame_reply(frame);
+            if let Err(e) = Self::write_frame(send, &frame, trace, conn_tracker.remote_addr).await {
+                error!("Error sending data: {}", e);
+                conn_tracker.report_failure(&format!("Send error: {}", e)).await;
+                sent = false;
+                break;
+            }
+        }
+        
+        if sent {
+            conn_tracker.report_success(start_time.elapsed().as_millis() as u64, data.to_bytes().len()).await;
+        }
+        
+        // Close the stream
+        if let Err(e) = send.finish().await {
+            error!("Error finishing stream: {}", e);
+        }
+    }
+    
+    /// Delimit a reply frame on a bidirectional stream
+    ///
+    /// A sealed Data or NACK is framed by its own TLV header. Fragments
+    /// carry no length, so each is wrapped in a Fragment TLV for the client
+    /// to tell where one ends and the next begins.
+    pub(crate) fn frame_reply(frame: Bytes) -> Bytes {
+        if !frame.starts_with(&FRAGMENT_MAGIC.to_be_bytes()) {
+            return frame;
+        }
+        
+        let mut buf = BytesMut::with_capacity(1 + tlv::var_number_size(frame.len() as u64) + frame.len());
+        tlv::write_var_number(&mut buf, lp_type::FRAGMENT);
+        tlv::write_var_number(&mut buf, frame.len() as u64);
+        buf.put_slice(&frame);
+        buf.freeze()
+    }
+    
+    /// Write a frame to a stream, showing it to the tracer first
+    async fn write_frame(
+        send: &mut quinn::SendStream,
+        frame: &[u8],
+        trace: &TraceHook,
+        remote: SocketAddr,
+    ) -> std::result::Result<(), quinn::WriteError> {
+        trace.on_send(frame, remote);
+        send.write_all(frame).await
+    }
+    
+    /// Register a prefix with a handler function
+    pub async fn register_prefix(&self, prefix: Name, handler: PrefixHandler) -> Result<u64> {
+        self.register(prefix, MatchKind::Prefix, handler).await
+    }
+    
+    /// Register a handler that serves only Interests for exactly this name
+    ///
+    /// It takes precedence over a prefix handler registered for the same name.
+    pub async fn register_exact(&self, name: Name, handler: PrefixHandler) -> Result<u64> {
+        self.register(name, MatchKind::Exact, handler).await
+    }
+    
+    async fn register(&self, prefix: Name, kind: MatchKind, handler: PrefixHandler) -> Result<u64> {
+        info!("Registering {:?} handler: {}", kind, prefix);
+        
+        // Store the prefix and handler
+        let mut prefixes = self.prefixes.write().await;
+        prefixes.insert(prefix.clone(), kind, handler);
+        
+        // Create a channel for this prefix
         let (tx, _rx) = mpsc::channel(100);
         
         // Associate the prefix with a stream ID
@@ -737,82 +1674,183 @@ impl QuicEngine {
         Ok(stream_id)
     }
     
-    /// Connect to a remote NDN router
+    /// Get the address the endpoint is bound to
+    pub async fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.endpoint.local_addr()?)
+    }
+    
+    /// Connect to a remote NDN router, reusing the pooled connection if it is usable
+    ///
+    /// Concurrent callers for the same remote wait for a single connection
+    /// attempt rather than racing; a failed or closed connection is replaced.
+    /// While the remote's circuit breaker is open no attempt is made and a
+    /// `ConnectionError` is returned straight away.
     pub async fn connect(&self, remote_addr: SocketAddr) -> Result<Arc<ConnectionTracker>> {
-        // Check if we already have a connection
-        if let Some(conn) = self.connections.get(&remote_addr) {
-            return Ok(conn.clone());
+        if let Some(conn_tracker) = self.pooled_connection(&remote_addr).await {
+            return Ok(conn_tracker);
         }
         
-        // Use basic client config without certificate verification for development
-        let client_config = quinn::ClientConfig::new(Arc::new(rustls::ClientConfig::builder()
-            .with_safe_defaults()
-            .with_root_certificates(rustls::RootCertStore::empty())
-            .with_no_client_auth()
-        ));
+        // Serialize connection attempts to the same remote
+        let lock = self.connect_locks.entry(remote_addr).or_default().clone();
+        let _guard = lock.lock().await;
         
-        // Connect to the remote endpoint
-        let endpoint = Endpoint::client("0.0.0.0:0".parse().unwrap())?;
-        let connecting = endpoint.connect_with(client_config, remote_addr, "localhost")?;
-        let connection = connecting.await?;
+        // Another caller may have connected while we waited
+        if let Some(conn_tracker) = self.pooled_connection(&remote_addr).await {
+            return Ok(conn_tracker);
+        }
+        
+        // Back off from a remote that keeps failing
+        self.breaker.check(remote_addr)?;
+        let connection = match self.dial(remote_addr).await {
+            Ok(connection) => {
+                self.breaker.record_success(remote_addr);
+                connection
+            }
+            Err(e) => {
+                self.breaker.record_failure(remote_addr);
+                return Err(e);
+            }
+        };
         
         // Create a connection tracker
-        let conn_tracker = Arc::new(ConnectionTracker::new(connection, remote_addr));
+        let conn_tracker = Arc::new(ConnectionTracker::new(connection, remote_addr)
+            .with_metrics(self.metrics.clone()));
+        conn_tracker.set_state(ConnectionState::Connected).await;
         
-        // Store the connection tracker
-        self.connections.insert(remote_addr, conn_tracker.clone());
+        // Detect a peer that silently stopped answering before an Interest fails
+        if self.config.health_check_interval > 0 {
+            conn_tracker.set_health_check_interval(Duration::from_millis(self.config.health_check_interval)).await;
+            conn_tracker.spawn_health_check(self.integrity.clone(), self.config.max_missed_pings);
+        }
+        
+        // Replace any stale tracker for the remote
+        if let Some(stale) = self.connections.insert(remote_addr, conn_tracker.clone()) {
+            stale.connection().close(0u32.into(), b"replaced");
+        }
         
         Ok(conn_tracker)
     }
     
-    /// Send an Interest packet to a remote peer
-    pub async fn send_interest(&self, remote_addr: SocketAddr, interest: Interest) -> Result<Data> {
-        // Get or create connection tracker for this remote address
-        let conn_tracker = if let Some(tracker) = self.connections.get(&remote_addr) {
-            tracker.clone()
-        } else {
-            // Connect to the remote peer
-            debug!("Connecting to {}", remote_addr);
-            let connection = self.connect(remote_addr).await?;
-            // Create a new connection tracker with the new connection
-            let tracker = Arc::new(ConnectionTracker::new(connection, remote_addr));
-            self.connections.insert(remote_addr, tracker.clone());
-            tracker
+    /// Open a new connection to a remote endpoint
+    async fn dial(&self, remote_addr: SocketAddr) -> Result<Connection> {
+        debug!("Connecting to {}", remote_addr);
+        let connecting = self.client_endpoint.connect_with(self.client_config_for(remote_addr)?, remote_addr, &self.config.tls_server_name)?;
+        let connection = match connecting.into_0rtt() {
+            // A session ticket from an earlier connection lets Interests go
+            // out before the handshake completes
+            Ok((connection, accepted)) => {
+                debug!("Resuming connection to {} with 0-RTT", remote_addr);
+                let zero_rtt_accepted = self.zero_rtt_accepted.clone();
+                tokio::spawn(async move {
+                    if accepted.await {
+                        zero_rtt_accepted.fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        debug!("0-RTT to {} rejected, continuing after the full handshake", remote_addr);
+                    }
+                });
+                connection
+            }
+            Err(connecting) => connecting.await?,
         };
+        self.connections_opened.fetch_add(1, Ordering::Relaxed);
+        
+        Ok(connection)
+    }
+    
+    /// Get the client configuration for a remote
+    ///
+    /// Each remote gets its own, so the session tickets it caches are only
+    /// offered back to the server that issued them.
+    fn client_config_for(&self, remote_addr: SocketAddr) -> Result<quinn::ClientConfig> {
+        if let Some(config) = self.client_configs.get(&remote_addr) {
+            return Ok(config.clone());
+        }
+        
+        let mut crypto = build_client_config(&self.config.tls)?;
+        crypto.enable_early_data = true;
+        
+        let mut config = quinn::ClientConfig::new(Arc::new(crypto));
+        config.transport_config(self.transport_config.clone());
+        self.client_configs.insert(remote_addr, config.clone());
+        Ok(config)
+    }
+    
+    /// Get the pooled connection to a remote unless it has failed or closed
+    async fn pooled_connection(&self, remote_addr: &SocketAddr) -> Option<Arc<ConnectionTracker>> {
+        let conn_tracker = self.connections.get(remote_addr)?.clone();
         
-        // Check connection state
-        let state = conn_tracker.state().await;
-        match state {
+        match conn_tracker.state().await {
             ConnectionState::Failed(reason) => {
-                // Connection previously failed, try to reconnect
                 debug!("Connection to {} previously failed: {}, reconnecting", remote_addr, reason);
-                let connection = self.connect(remote_addr).await?;
-                // Create a new tracker with the new connection
-                let tracker = Arc::new(ConnectionTracker::new(connection, remote_addr));
-                self.connections.insert(remote_addr, tracker.clone());
-                // Continue with the reconnected tracker
-                conn_tracker = tracker;
-                // No early return, continue with the rest of the function
+                None
             },
             ConnectionState::Closing => {
-                // Connection is closing, try to reconnect
                 debug!("Connection to {} is closing, reconnecting", remote_addr);
-                let connection = self.connect(remote_addr).await?;
-                // Create a new tracker with the new connection
-                let tracker = Arc::new(ConnectionTracker::new(connection, remote_addr));
-                self.connections.insert(remote_addr, tracker.clone());
-                // Continue with the reconnected tracker
-                conn_tracker = tracker;
-                // No early return, continue with the rest of the function
+                None
             },
-            ConnectionState::Idle => {
-                // Connection is idle but may still be usable
-                debug!("Connection to {} is idle, checking...", remote_addr);
-                // We'll try to use it anyway and reconnect if needed
-            },
-            _ => {}
+            _ if conn_tracker.connection().close_reason().is_some() => None,
+            _ => Some(conn_tracker),
+        }
+    }
+    
+    /// Send an Interest packet to a remote peer
+    pub async fn send_interest(&self, remote_addr: SocketAddr, interest: Interest) -> Result<Data> {
+        self.send_interest_with_deadline(remote_addr, interest, None).await
+    }
+    
+    /// Send an Interest packet to a remote peer, failing with a timeout if no
+    /// Data has arrived by `deadline`
+    ///
+    /// The deadline bounds the whole exchange (connecting, opening the stream,
+    /// sending and receiving) independently of the per-step stream timeouts.
+    pub async fn send_interest_with_deadline(
+        &self,
+        remote_addr: SocketAddr,
+        interest: Interest,
+        deadline: Option<Instant>,
+    ) -> Result<Data> {
+        let name = interest.name().clone();
+        
+        let data = match deadline {
+            Some(deadline) => {
+                match tokio::time::timeout_at(deadline.into(), self.exchange_interest(remote_addr, interest)).await {
+                    Ok(result) => result?,
+                    Err(_) => {
+                        warn!("Deadline expired for Interest {} to {}", name, remote_addr);
+                        return Err(crate::error::Error::Timeout(format!("Deadline expired for Interest {}", name)));
+                    }
+                }
+            }
+            None => self.exchange_interest(remote_addr, interest).await?,
+        };
+        
+        // Reject Data that does not match an implicit digest in the Interest name
+        if let Err(e) = data.verify_implicit_digest(&name) {
+            warn!("Rejecting Data from {}: {}", remote_addr, e);
+            return Err(e);
         }
         
+        Ok(data)
+    }
+    
+    /// Exchange an Interest for Data over a stream to the remote peer
+    async fn exchange_interest(&self, remote_addr: SocketAddr, interest: Interest) -> Result<Data> {
+        match self.exchange_interest_once(remote_addr, interest.clone()).await {
+            // Streams sent as 0-RTT data are lost when the server rejects it,
+            // but the connection carries on once the full handshake completes
+            Err(e) if is_zero_rtt_rejection(&e) => {
+                debug!("0-RTT rejected by {}, resending {}", remote_addr, interest.name());
+                self.exchange_interest_once(remote_addr, interest).await
+            }
+            result => result,
+        }
+    }
+    
+    /// Exchange an Interest for Data over a single stream to the remote peer
+    async fn exchange_interest_once(&self, remote_addr: SocketAddr, interest: Interest) -> Result<Data> {
+        // Get the pooled connection, connecting or reconnecting as needed
+        let conn_tracker = self.connect(remote_addr).await?;
+        
         // Start time for RTT measurement
         let start_time = std::time::Instant::now();
         
@@ -828,7 +1866,7 @@ impl QuicEngine {
         
         // Open a bidirectional stream with timeout
         let stream_result = tokio::time::timeout(
-            Duration::from_secs(10),
+            Duration::from_millis(self.config.send_timeout),
             connection.open_bi()
         ).await;
         
@@ -839,7 +1877,7 @@ impl QuicEngine {
                     // Stream opening failed, mark connection as failed
                     conn_tracker.set_state(ConnectionState::Failed(e.to_string())).await;
                     conn_tracker.report_failure(&format!("Stream open error: {}", e)).await;
-                    return Err(crate::error::Error::ConnectionError(format!("Failed to open stream: {}", e)));
+                    return Err(crate::error::Error::connection("Failed to open stream", e));
                 }
             },
             Err(_) => {
@@ -849,20 +1887,25 @@ impl QuicEngine {
             }
         };
         
+        // The response is waited for no longer than the Interest lives, up
+        // to the configured maximum
+        let lifetime = interest.get_lifetime().min(Duration::from_millis(self.config.max_interest_lifetime));
+        let expires_at = tokio::time::Instant::now() + lifetime;
+        
         // Serialize the interest
-        let interest_bytes = interest.to_bytes();
+        let interest_bytes = self.integrity.seal(interest.to_bytes());
         
         // Send the interest with timeout
         let send_result = tokio::time::timeout(
-            Duration::from_secs(5),
-            send.write_all(&interest_bytes)
+            Duration::from_millis(self.config.send_timeout),
+            Self::write_frame(&mut send, &interest_bytes, &self.trace, remote_addr)
         ).await;
         
         match send_result {
             Ok(result) => {
                 if let Err(e) = result {
                     conn_tracker.report_failure(&format!("Write error: {}", e)).await;
-                    return Err(crate::error::Error::IoError(format!("Failed to send interest: {}", e)));
+                    return Err(crate::error::Error::stream("Failed to send interest", e));
                 }
             },
             Err(_) => {
@@ -877,107 +1920,64 @@ impl QuicEngine {
             warn!("Error finishing send stream: {}", e);
         }
         
-        // Get the response with timeout
-        let mut fragments = Vec::new();
-        // Explicitly type the reassembler Option with the ReassemblyContext from our fragmentation module
-        let mut reassembler: Option<crate::fragmentation::ReassemblyContext> = None;
-        
+        // Read the response a packet at a time, so a fragmented Data is
+        // reassembled from every fragment on the stream
         loop {
-            let response_result = tokio::time::timeout(
-                Duration::from_secs(30),  // Longer timeout for receiving data
-                recv.read_to_end(self.config.max_packet_size)
+            let response_result = tokio::time::timeout_at(
+                expires_at,
+                read_reply_packet(&mut recv, &self.integrity, self.config.max_packet_size)
             ).await;
             
             let response_bytes = match response_result {
                 Ok(result) => match result {
-                    Ok(bytes) => bytes,
+                    Ok(Some(bytes)) => bytes,
+                    Ok(None) => break, // End of stream
                     Err(e) => {
                         conn_tracker.report_failure(&format!("Read error: {}", e)).await;
-                        return Err(crate::error::Error::IoError(format!("Failed to read response: {}", e)));
+                        return Err(e);
                     }
                 },
                 Err(_) => {
-                    // Timeout occurred
+                    // The Interest expired
                     conn_tracker.report_failure("Read timeout").await;
-                    return Err(crate::error::Error::Timeout("Timed out receiving response".to_string()));
+                    return Err(crate::error::Error::Timeout(format!(
+                        "Interest {} expired after {} ms", interest.name(), lifetime.as_millis())));
                 }
             };
             
-            if response_bytes.is_empty() {
-                break; // End of stream
-            }
+            self.trace.on_recv(&response_bytes, remote_addr);
             
-            // Check if this is a fragment
-            if let Ok(fragment) = Fragment::from_bytes(&response_bytes) {
-                debug!("Received fragment {}/{} for interest {}", 
-                        fragment.header().sequence(), fragment.header().total_fragments(), interest.name());
-                fragments.push(fragment.clone());
-                
-                // Check if it's a final fragment
-                if fragment.header().is_final() {
-                    debug!("Received final fragment for interest {}", interest.name());
-                    
-                    // Initialize reassembler if not already done
-                    if reassembler.is_none() {
-                        // Access the fragmenter through the Arc dereference
-                        let fragmenter = &*self.fragmenter;
-                        
-                        // Create a new reassembly context
-                        reassembler = Some(fragmenter.new_reassembly_context(
-                            fragment.header().fragment_id(),
-                            fragment.header().total_fragments()
-                        ));
-                    }
-                    
-                    // Add all fragments to the reassembler
-                    if let Some(ref mut ctx) = reassembler {
-                        for frag in &fragments {
-                            ctx.add_fragment(frag.header().sequence(), frag.payload().clone());
-                        }
-                        
-                        // Try to reassemble the fragments
-                        match ctx.reassemble() {
-                            Ok(data_bytes) => {
-                                // Parse reassembled data
-                                match Data::from_bytes(&data_bytes) {
-                                    Ok(data) => {
-                                        // Calculate RTT and data size for statistics
-                                        let rtt = start_time.elapsed().as_millis() as u64;
-                                        let data_size = data_bytes.len();
-                                        
-                                        // Update connection statistics
-                                        conn_tracker.report_success(rtt, data_size).await;
-                                        
-                                        debug!("Successfully reassembled {} fragments into data for interest {}", 
-                                               fragments.len(), interest.name());
-                                        return Ok(data);
-                                    },
-                                    Err(e) => {
-                                        conn_tracker.report_failure(&format!("Parsing error: {}", e)).await;
-                                        return Err(crate::error::Error::ParsingError(format!("Failed to parse reassembled data: {}", e)));
-                                    }
-                                }
-                            },
-                            Err(e) => {
-                                // Reassembly failed
-                                conn_tracker.report_failure(&format!("Reassembly error: {}", e)).await;
-                                return Err(crate::error::Error::ReassemblyError("Failed to reassemble fragments".to_string()));
-                            }
-                        }
-                    }
-                    
-                    break;
+            // Decoded in place, so received content is not copied
+            let parsed = if response_bytes.first() == Some(&(lp_type::FRAGMENT as u8)) {
+                tlv::read_tlv(&mut response_bytes.clone())
+                    .and_then(|(_, mut fragment)| Fragment::from_bytes(&mut fragment))
+                    .map(Packet::Fragment)
+            } else {
+                Packet::parse(response_bytes.clone())
+            };
+            let packet = match parsed {
+                Ok(packet) => packet,
+                Err(e) => {
+                    error!("Failed to parse response: {}", e);
+                    conn_tracker.report_failure(&format!("Parse error: {}", e)).await;
+                    return Err(e);
+                }
+            };
+            
+            // Check the CRC trailer when the integrity self-test is enabled;
+            // fragments are sent without one
+            if !matches!(packet, Packet::Fragment(_)) {
+                if let Err(e) = self.integrity.verify(&response_bytes) {
+                    conn_tracker.report_failure(&format!("Integrity error: {}", e)).await;
+                    return Err(e);
                 }
-                
-                continue;
             }
             
-            // Try to parse as Data if not a fragment
-            match Data::from_bytes(&response_bytes) {
-                Ok(data) => {
+            match packet {
+                Packet::Data(data) => {
                     // Calculate RTT and data size for statistics
                     let rtt = start_time.elapsed().as_millis() as u64;
-                    let data_size = response_bytes.len();
+                    let data_size = data.to_bytes().len();
                     
                     // Update connection statistics
                     conn_tracker.report_success(rtt, data_size).await;
@@ -985,21 +1985,39 @@ impl QuicEngine {
                     debug!("Received Data for Interest {}", interest.name());
                     return Ok(data);
                 },
-                Err(_) => {
-                    // Try to parse as NACK
-                    match Nack::from_bytes(&response_bytes) {
-                        Ok(nack) => {
-                            warn!("Received NACK for Interest {}: {:?}", interest.name(), nack.reason());
-                            // Convert NackReason to string representation for reporting
-                            conn_tracker.report_failure(&format!("NACK: {:?}", nack.reason())).await;
-                            return Err(crate::error::Error::Other(format!("NACK: {:?}", nack.reason())));
+                Packet::Nack(nack) => {
+                    warn!("Received NACK for Interest {}: {:?}", interest.name(), nack.reason());
+                    // Convert NackReason to string representation for reporting
+                    conn_tracker.report_failure(&format!("NACK: {:?}", nack.reason())).await;
+                    return Err(crate::error::Error::nack(&nack));
+                },
+                Packet::Fragment(fragment) => {
+                    debug!("Received fragment {}/{} for interest {}", 
+                           fragment.sequence(), fragment.total_fragments(), interest.name());
+                    
+                    match self.fragmenter.add_fragment(fragment).await {
+                        Ok(Some(data)) => {
+                            // Calculate RTT and data size for statistics
+                            let rtt = start_time.elapsed().as_millis() as u64;
+                            let data_size = data.to_bytes().len();
+                            
+                            // Update connection statistics
+                            conn_tracker.report_success(rtt, data_size).await;
+                            
+                            debug!("Reassembled data for interest {}", interest.name());
+                            return Ok(data);
                         },
+                        Ok(None) => continue,
                         Err(e) => {
-                            error!("Failed to parse response: {}", e);
-                            conn_tracker.report_failure(&format!("Parse error: {}", e)).await;
-                            return Err(e);
+                            // Reassembly failed
+                            conn_tracker.report_failure(&format!("Reassembly error: {}", e)).await;
+                            return Err(crate::error::Error::ReassemblyError(format!("Failed to reassemble fragments: {}", e)));
                         }
                     }
+                },
+                Packet::Interest(_) => {
+                    conn_tracker.report_failure("Protocol error").await;
+                    return Err(crate::error::Error::ProtocolError("Received an Interest as a response".to_string()));
                 }
             }
         }
@@ -1009,26 +2027,462 @@ impl QuicEngine {
         Err(crate::error::Error::ProtocolError("Unexpected end of stream".to_string()))
     }
     
+    /// Push a Data packet to a peer without a matching Interest
+    ///
+    /// The peer caches the Data in its content store. A Data larger than the
+    /// MTU is pushed as one fragment per unidirectional stream.
+    pub async fn push_data(&self, remote: SocketAddr, data: Data) -> Result<()> {
+        let conn_tracker = self.connect(remote).await?;
+        let connection = conn_tracker.connection();
+        
+        let flag = if data.to_bytes().len() > self.fragmenter.mtu().await {
+            PUSH_FRAGMENT_FLAG
+        } else {
+            PUSH_DATA_FLAG
+        };
+        
+        for frame in Self::data_response_frames(&data, &self.fragmenter, &self.integrity).await? {
+            let mut send = connection.open_uni().await
+                .map_err(|e| crate::error::Error::connection("Failed to open push stream", e))?;
+            
+            let write = async {
+                send.write_all(&[flag]).await?;
+                Self::write_frame(&mut send, &frame, &self.trace, remote).await?;
+                send.finish().await
+            };
+            if let Err(e) = tokio::time::timeout(Duration::from_millis(self.config.send_timeout), write).await
+                .map_err(|_| crate::error::Error::Timeout(format!("Timed out pushing {}", data.name())))?
+            {
+                conn_tracker.report_failure(&format!("Push error: {}", e)).await;
+                return Err(crate::error::Error::stream("Failed to push Data", e));
+            }
+        }
+        
+        debug!("Pushed Data {} to {}", data.name(), remote);
+        Ok(())
+    }
+    
+    /// Get the state of the circuit breaker of a remote
+    pub fn breaker_state(&self, remote_addr: SocketAddr) -> BreakerState {
+        self.breaker.state(remote_addr)
+    }
+    
+    /// Get the statistics of the pooled connection to a remote, with the
+    /// state of its circuit breaker; a remote without a connection has
+    /// empty statistics
+    pub async fn connection_stats(&self, remote_addr: SocketAddr) -> ConnectionStats {
+        let conn_tracker = self.connections.get(&remote_addr).map(|entry| entry.value().clone());
+        let mut stats = match conn_tracker {
+            Some(conn_tracker) => conn_tracker.stats().await,
+            None => ConnectionStats::default(),
+        };
+        stats.breaker_state = self.breaker.state(remote_addr);
+        stats
+    }
+    
+    /// Get the number of outgoing connections opened so far
+    pub fn connections_opened(&self) -> u64 {
+        self.connections_opened.load(Ordering::Relaxed)
+    }
+    
+    /// Get the number of outgoing connections resumed with accepted 0-RTT data
+    pub fn zero_rtt_accepted(&self) -> u64 {
+        self.zero_rtt_accepted.load(Ordering::Relaxed)
+    }
+    
+    /// Get the admission controller
+    pub fn admission(&self) -> &AdmissionController {
+        &self.admission
+    }
+    
+    /// Get the per-peer rate limiter
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+    
+    /// Get the validator of signed commands
+    pub fn command_validator(&self) -> &CommandValidator {
+        &self.commands
+    }
+    
+    /// Get the content store holding served Data
+    pub fn content_store(&self) -> &Arc<RwLock<ContentStore>> {
+        &self.content_store
+    }
+    
+    /// Get the number of incoming Interest names whose Data is being produced
+    pub fn pending_count(&self) -> usize {
+        self.pit.len()
+    }
+    
+    /// Stop taking new connections and streams, and wait up to `timeout` for
+    /// the Interests being served to finish
+    ///
+    /// Returns whether every in-flight Interest finished in time. Connections
+    /// stay open until `stop`.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.endpoint.set_server_config(None);
+        
+        let drained = self.drain.drain(timeout).await;
+        if !drained {
+            warn!("{} streams still in flight after {:?}", self.drain.in_flight(), timeout);
+        }
+        drained
+    }
+    
+    /// Get the stream drain state
+    pub fn stream_drain(&self) -> &StreamDrain {
+        &self.drain
+    }
+    
+    /// Get the integrity self-test statistics
+    pub fn integrity_stats(&self) -> IntegrityStats {
+        self.integrity.stats()
+    }
+    
+    /// Get the number of partial reassemblies dropped as stale
+    pub fn reassembly_timeouts(&self) -> u64 {
+        self.fragmenter.reassembly_timeouts()
+    }
+    
+    /// Get the MTU Data is fragmented at
+    pub async fn mtu(&self) -> usize {
+        self.fragmenter.mtu().await
+    }
+    
+    /// Change the MTU Data is fragmented at, on open connections too
+    pub async fn set_mtu(&self, mtu: usize) {
+        self.fragmenter.update_mtu(mtu).await;
+    }
+    
+    /// Get the certificate this engine presents, for peers to pin
+    pub fn certificate(&self) -> &Certificate {
+        &self.certificate
+    }
+    
+    /// Get the flow-control windows of this engine's connections
+    pub fn windows(&self) -> FlowControlWindows {
+        FlowControlWindows::from(&self.config)
+    }
+    
     /// Stop the QUIC engine
     pub async fn stop(&mut self) -> Result<()> {
         if let Some(handle) = self.server_handle.take() {
             handle.abort();
         }
         
+        if let Some(handle) = self.sweeper_handle.take() {
+            handle.abort();
+        }
+        
+        if let Some(handle) = self.cpu_sampler_handle.take() {
+            handle.abort();
+        }
+        
         // Close all connections
         for conn in self.connections.iter_mut() {
             // Access the connection field directly
-            conn.connection.close(0u32.into(), b"server shutting down");
+            conn.connection.close(SHUTDOWN_CODE.into(), b"server shutting down");
         }
         
         self.connections.clear();
-        self.endpoint.close(0u32.into(), b"server shutting down");
+        self.endpoint.close(SHUTDOWN_CODE.into(), b"server shutting down");
+        self.client_endpoint.close(SHUTDOWN_CODE.into(), b"server shutting down");
         
         Ok(())
     }
 }
 
+// Check whether an exchange failed only because its 0-RTT data was rejected
+fn is_zero_rtt_rejection(error: &crate::error::Error) -> bool {
+    let source = match std::error::Error::source(error) {
+        Some(source) => source,
+        None => return false,
+    };
+    
+    matches!(source.downcast_ref::<quinn::WriteError>(), Some(quinn::WriteError::ZeroRttRejected))
+        || matches!(source.downcast_ref::<quinn::ReadToEndError>(),
+                    Some(quinn::ReadToEndError::Read(quinn::ReadError::ZeroRttRejected)))
+}
+
+// Check whether an Interest is a health-check ping
+fn is_ping(interest: &Interest) -> bool {
+    Name::from_uri(PING_PREFIX).map_or(false, |ping| interest.name() == &ping)
+}
+
+// Read one sealed packet from a stream: the packet TLV, then its
+// IntegrityCheck TLV when the self-test is enabled
+async fn read_sealed_packet<R: AsyncRead + Unpin>(recv: &mut R, integrity: &IntegrityCheck, max_packet_size: usize) -> Result<Vec<u8>> {
+    let mut packet = tlv::read_packet(recv, max_packet_size).await?;
+    if integrity.is_enabled() {
+        packet.extend(tlv::read_packet(recv, TRAILER_SIZE).await?);
+    }
+    Ok(packet)
+}
+
+/// Read the next packet of a reply stream: a Fragment TLV, or a sealed
+/// Data or NACK with its trailer, or `None` once the stream has ended
+async fn read_reply_packet(recv: &mut quinn::RecvStream, integrity: &IntegrityCheck, max_packet_size: usize) -> Result<Option<Bytes>> {
+    let mut first = [0u8; 1];
+    match recv.read(&mut first).await
+        .map_err(|e| crate::error::Error::stream("Failed to read response", e))?
+    {
+        Some(1) => {}
+        _ => return Ok(None),
+    }
+    
+    // Fragments are sent without an integrity trailer
+    let mut reader = (&first[..]).chain(recv);
+    let packet = if first[0] as u64 == lp_type::FRAGMENT {
+        tlv::read_packet(&mut reader, max_packet_size).await?
+    } else {
+        read_sealed_packet(&mut reader, integrity, max_packet_size).await?
+    };
+    Ok(Some(Bytes::from(packet)))
+}
+
 // Helper function to create a name from a string
 fn from_str(s: &str) -> Result<Name> {
     Name::from_uri(s).map_err(|e| crate::error::Error::NameParsing(e.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{generate_self_signed_cert, TlsConfig};
+    
+    fn local_config() -> Config {
+        Config {
+            bind_address: "127.0.0.1".to_string(),
+            port: 0,
+            enable_metrics: false,
+            ..Default::default()
+        }
+    }
+    
+    // Connect to a peer that accepts streams but never answers them
+    async fn connect_to_silent_peer() -> (Endpoint, Connection, JoinHandle<()>) {
+        let (cert, key) = generate_self_signed_cert().unwrap();
+        let server_config = ServerConfig::with_single_cert(vec![cert], key).unwrap();
+        let server = Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let server_addr = server.local_addr().unwrap();
+        
+        let peer = tokio::spawn(async move {
+            let connection = server.accept().await.unwrap().await.unwrap();
+            let mut streams = Vec::new();
+            while let Ok(stream) = connection.accept_bi().await {
+                streams.push(stream);
+            }
+        });
+        
+        let mut client = Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        let crypto = build_client_config(&TlsConfig::Insecure).unwrap();
+        client.set_default_client_config(quinn::ClientConfig::new(Arc::new(crypto)));
+        let connection = client.connect(server_addr, "localhost").unwrap().await.unwrap();
+        (client, connection, peer)
+    }
+    
+    #[tokio::test]
+    async fn test_silent_peer_fails_health_check() {
+        let (_client, connection, peer) = connect_to_silent_peer().await;
+        let remote = connection.remote_address();
+        let tracker = Arc::new(ConnectionTracker::new(connection, remote));
+        tracker.set_state(ConnectionState::Connected).await;
+        tracker.set_health_check_interval(Duration::from_millis(100)).await;
+        
+        let start = Instant::now();
+        let health_check = tracker.spawn_health_check(Arc::new(IntegrityCheck::new(false)), 3);
+        tokio::time::timeout(Duration::from_secs(2), health_check).await
+            .expect("health check never gave up")
+            .unwrap();
+        
+        // Three pings each went unanswered for an interval
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(300), "failed after {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(1), "failed after {:?}", elapsed);
+        assert!(matches!(tracker.state().await, ConnectionState::Failed(_)));
+        assert_eq!(tracker.missed_pings(), 3);
+        assert!(tracker.stats().await.packet_loss_rate > 0.0);
+        
+        peer.abort();
+    }
+    
+    #[tokio::test]
+    async fn test_answered_pings_keep_connection() {
+        let mut server = QuicEngine::new(&local_config()).await.unwrap();
+        server.start().await.unwrap();
+        let server_addr = server.local_addr().await.unwrap();
+        
+        let client = QuicEngine::new(&Config {
+            health_check_interval: 50,
+            ..local_config()
+        }).await.unwrap();
+        let tracker = client.connect(server_addr).await.unwrap();
+        assert_eq!(tracker.health_check_interval().await, Duration::from_millis(50));
+        
+        // Several rounds of pings later the connection is still healthy
+        assert!(tracker.ping(&IntegrityCheck::new(false), Duration::from_secs(1)).await.is_ok());
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(tracker.state().await, ConnectionState::Connected);
+        assert_eq!(tracker.missed_pings(), 0);
+        
+        server.stop().await.unwrap();
+    }
+    
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_interest_lifetime_bounds_wait() {
+        let mut server = QuicEngine::new(&local_config()).await.unwrap();
+        server.register_prefix(Name::from_uri("/slow").unwrap(), Box::new(|interest: Interest| {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(Data::new(interest.name().clone(), "late"))
+        })).await.unwrap();
+        server.start().await.unwrap();
+        let server_addr = server.local_addr().await.unwrap();
+        
+        let client = QuicEngine::new(&local_config()).await.unwrap();
+        client.connect(server_addr).await.unwrap();
+        
+        // A short-lived Interest fails once its lifetime is up
+        let name = Name::from_uri("/slow/object").unwrap();
+        let start = Instant::now();
+        let result = client.send_interest(server_addr, Interest::new(name.clone()).lifetime(Duration::from_millis(500))).await;
+        let elapsed = start.elapsed();
+        match result {
+            Err(crate::error::Error::Timeout(message)) => assert!(message.contains(&name.to_string()), "{}", message),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(elapsed >= Duration::from_millis(450), "timed out after {:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(1000), "timed out after {:?}", elapsed);
+        
+        // A long-lived one waits for the Data
+        let data = client.send_interest(server_addr, Interest::new(Name::from_uri("/slow/other").unwrap())
+            .lifetime(Duration::from_secs(5))).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"late");
+        
+        server.stop().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_oversize_data_fragmented_or_nacked() {
+        let interest = Interest::new(Name::from_uri("/large/object").unwrap());
+        let data = Data::new(interest.name().clone(), vec![0xA5; 10 * 1024 * 1024]);
+        let integrity = IntegrityCheck::new(false);
+        let fragmenter = Fragmenter::with_max_fragments(1400, u16::MAX as usize);
+        let limit = DataSizeLimit { max_packet_size: 65535, fragment: true };
+        
+        // Allowed to, the engine sends the 10MB Data as fragments that reassemble
+        let frames = QuicEngine::data_reply_frames(&interest, &data, &fragmenter, &integrity, limit).await.unwrap();
+        assert!(frames.len() > 1);
+        let receiver = Fragmenter::new(1400);
+        let mut reassembled = None;
+        for mut frame in frames {
+            assert!(reassembled.is_none());
+            reassembled = receiver.add_fragment(Fragment::from_bytes(&mut frame).unwrap()).await.unwrap();
+        }
+        assert_eq!(reassembled.unwrap().content(), data.content());
+        
+        // Otherwise, or past the fragment count limit, it is refused
+        let refusals = [
+            (fragmenter, DataSizeLimit { fragment: false, ..limit }),
+            (Fragmenter::new(1400), limit),
+        ];
+        for (fragmenter, limit) in refusals {
+            let nack = QuicEngine::data_reply_frames(&interest, &data, &fragmenter, &integrity, limit).await.unwrap_err();
+            assert_eq!(nack.reason(), NackReason::NoResource);
+        }
+        
+        // A producer configured not to fragment answers with the NACK
+        let server_config = Config { fragment_oversize_data: false, ..local_config() };
+        let mut server = QuicEngine::new(&server_config).await.unwrap();
+        server.register_prefix(Name::from_uri("/large").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), vec![0xA5; 10 * 1024 * 1024]))
+        })).await.unwrap();
+        server.start().await.unwrap();
+        let server_addr = server.local_addr().await.unwrap();
+        
+        let client = QuicEngine::new(&local_config()).await.unwrap();
+        client.connect(server_addr).await.unwrap();
+        match client.send_interest(server_addr, interest).await {
+            Err(crate::error::Error::Nack { reason, .. }) => assert_eq!(reason, NackReason::NoResource),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        
+        server.stop().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_breaker_fails_fast_during_cooldown() {
+        // A peer refusing every connection
+        let (cert, key) = generate_self_signed_cert().unwrap();
+        let mut server_config = ServerConfig::with_single_cert(vec![cert], key).unwrap();
+        server_config.concurrent_connections(0);
+        let refusing = Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let remote = refusing.local_addr().unwrap();
+        
+        let config = Config {
+            breaker_failure_threshold: 3,
+            breaker_cooldown: 300,
+            ..local_config()
+        };
+        let client = QuicEngine::new(&config).await.unwrap();
+        
+        // Refused attempts trip the breaker
+        for _ in 0..3 {
+            assert!(matches!(client.connect(remote).await, Err(crate::error::Error::TransportError(_))));
+        }
+        assert_eq!(client.connections_opened(), 0);
+        assert_eq!(client.breaker_state(remote), BreakerState::Open);
+        assert_eq!(client.connection_stats(remote).await.breaker_state, BreakerState::Open);
+        
+        // During the cooldown Interests fail without trying to connect
+        let start = Instant::now();
+        for _ in 0..10 {
+            match client.send_interest(remote, Interest::new(Name::from_uri("/unreachable").unwrap())).await {
+                Err(crate::error::Error::ConnectionError(message)) => assert!(message.contains("Circuit breaker"), "{}", message),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+        assert!(start.elapsed() < Duration::from_millis(100), "failed after {:?}", start.elapsed());
+        
+        // Half-open after the cooldown, a single failed attempt reopens it
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        assert_eq!(client.breaker_state(remote), BreakerState::HalfOpen);
+        assert!(matches!(client.connect(remote).await, Err(crate::error::Error::TransportError(_))));
+        assert_eq!(client.breaker_state(remote), BreakerState::Open);
+    }
+    
+    #[tokio::test]
+    async fn test_reconnect_replaces_failed_tracker() {
+        let mut server = QuicEngine::new(&local_config()).await.unwrap();
+        server.register_prefix(Name::from_uri("/echo").unwrap(), Box::new(|interest: Interest| {
+            Ok(Data::new(interest.name().clone(), "echo"))
+        })).await.unwrap();
+        server.start().await.unwrap();
+        let server_addr = server.local_addr().await.unwrap();
+        
+        let client = QuicEngine::new(&local_config()).await.unwrap();
+        let failed = client.connect(server_addr).await.unwrap();
+        failed.set_state(ConnectionState::Failed("simulated".to_string())).await;
+        
+        // Concurrent callers share the one replacement connection
+        let trackers = futures::future::join_all((0..8).map(|_| client.connect(server_addr))).await;
+        let replacement = trackers[0].as_ref().unwrap().clone();
+        for tracker in &trackers {
+            assert!(Arc::ptr_eq(tracker.as_ref().unwrap(), &replacement));
+        }
+        assert!(!Arc::ptr_eq(&failed, &replacement));
+        assert_eq!(client.connections_opened(), 2);
+        
+        // Exactly one tracker is left for the remote, and the stale
+        // connection is closed
+        assert_eq!(client.connections.len(), 1);
+        assert!(Arc::ptr_eq(&*client.connections.get(&server_addr).unwrap(), &replacement));
+        assert!(failed.connection().close_reason().is_some());
+        
+        let data = client.send_interest(server_addr, Interest::new(Name::from_uri("/echo/a").unwrap())).await.unwrap();
+        assert_eq!(data.content().as_ref(), b"echo");
+        assert_eq!(client.connections.len(), 1);
+        
+        server.stop().await.unwrap();
+    }
+}
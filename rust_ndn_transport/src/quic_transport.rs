@@ -4,7 +4,7 @@
 // using the quinn crate. It provides a clean interface for exchanging
 // Interest and Data packets over QUIC streams.
 
-use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
@@ -12,35 +12,20 @@ use std::time::{Duration, Instant};
 
 use bytes::{Bytes, BytesMut, BufMut};
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
 use quinn::{ClientConfig, Connection, Endpoint, RecvStream, SendStream, ServerConfig, TransportConfig};
-use rustls::{Certificate, PrivateKey, client::ServerCertVerifier, Error as RustlsError};
-use tokio::sync::{mpsc, RwLock, Mutex};
+use rustls::{Certificate, PrivateKey};
+use tokio::io::AsyncReadExt;
+use tokio::sync::{mpsc, oneshot, RwLock, Mutex};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn, trace};
 
-/// An insecure certificate verifier that accepts any server certificate
-/// WARNING: This should only be used for development and testing
-struct InsecureServerVerifier {}
-
-impl ServerCertVerifier for InsecureServerVerifier {
-    fn verify_server_cert(
-        &self,
-        _end_entity: &rustls::Certificate,
-        _intermediates: &[rustls::Certificate],
-        _server_name: &rustls::ServerName,
-        _scts: &mut dyn Iterator<Item = &[u8]>,
-        _ocsp_response: &[u8],
-        _now: std::time::SystemTime,
-    ) -> Result<rustls::client::ServerCertVerified, RustlsError> {
-        // WARNING: This accepts any certificate without verification
-        Ok(rustls::client::ServerCertVerified::assertion())
-    }
-}
-
+use crate::dispatch::{HandlerTable, MatchKind};
 use crate::error::{Error, Result};
-use crate::ndn::{Data, Interest};
+use crate::ndn::{compact, tlv, tlv_type, Data, Interest, Nack, NackReason, Packet, WireFormat};
 use crate::name::Name;
-use crate::security::generate_self_signed_cert;
+use crate::security::{build_client_config, server_identity, TlsConfig};
+use crate::{parse_bind_addr, Config};
 
 /// Connection state tracking enum
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -202,6 +187,256 @@ impl ConnectionTracker {
 /// Handler type for Interest packets - processes an Interest and returns a Data packet
 pub type InterestHandler = Arc<dyn Fn(Interest) -> Result<Data> + Send + Sync>;
 
+/// Hook consulted for each incoming connection before the handshake completes;
+/// returning false rejects the peer
+pub type IncomingHook = Arc<dyn Fn(SocketAddr) -> bool + Send + Sync>;
+
+/// Application close code sent to peers rejected by the incoming hook
+const CONNECTION_REFUSED: u32 = 0x1;
+
+/// Datagram carrying an Interest
+const DATAGRAM_INTEREST: u8 = 0x01;
+
+/// Datagram carrying the nonce of an Interest followed by its Data
+const DATAGRAM_DATA: u8 = 0x02;
+
+/// Datagram asking the sender to re-send the Interest with this nonce over a stream
+const DATAGRAM_USE_STREAM: u8 = 0x03;
+
+/// First byte of a bi-stream carrying a `MultiplexedChannel`; a one-shot
+/// stream starts with the Interest TLV type instead
+const CHANNEL_STREAM: u8 = 0xC0;
+
+/// Interest sent in a datagram, waiting for its reply
+#[derive(Debug)]
+struct DatagramWaiter {
+    /// Interest the Data in the reply must satisfy
+    interest: Interest,
+    /// Where the reply goes; `None` means the Interest has to be re-sent
+    /// over a stream
+    reply: oneshot::Sender<Option<Data>>,
+}
+
+/// Interests sent in datagrams, by remote and nonce, waiting for their reply
+type DatagramWaiters = Arc<DashMap<(SocketAddr, u32), DatagramWaiter>>;
+
+/// Data produced for datagram Interests it did not fit in, by remote and
+/// nonce, with the time the Interest expires
+///
+/// The Interest re-sent over a stream is answered with it rather than by
+/// running the handler again.
+type DatagramOverflow = Arc<DashMap<(SocketAddr, u32), (Data, Instant)>>;
+
+/// Timeouts applied to every stream, distinct from the connection idle timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamTimeouts {
+    /// Longest time to open a stream and write a packet
+    pub send: Duration,
+    /// Longest time to wait for a packet on a stream
+    pub recv: Duration,
+}
+
+impl Default for StreamTimeouts {
+    fn default() -> Self {
+        Self {
+            send: Duration::from_millis(5000),
+            recv: Duration::from_millis(30000),
+        }
+    }
+}
+
+impl From<&Config> for StreamTimeouts {
+    fn from(config: &Config) -> Self {
+        Self {
+            send: Duration::from_millis(config.send_timeout),
+            recv: Duration::from_millis(config.recv_timeout),
+        }
+    }
+}
+
+/// QUIC flow-control windows applied to every connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowControlWindows {
+    /// Per-stream receive window in bytes
+    pub stream_receive_window: u64,
+    /// Per-connection receive window in bytes
+    pub receive_window: u64,
+    /// Per-connection send window in bytes
+    pub send_window: u64,
+}
+
+impl Default for FlowControlWindows {
+    fn default() -> Self {
+        Self {
+            stream_receive_window: 1_250_000,
+            receive_window: 8_000_000,
+            send_window: 8_000_000,
+        }
+    }
+}
+
+impl From<&Config> for FlowControlWindows {
+    fn from(config: &Config) -> Self {
+        Self {
+            stream_receive_window: config.stream_receive_window,
+            receive_window: config.receive_window,
+            send_window: config.send_window,
+        }
+    }
+}
+
+impl FlowControlWindows {
+    /// Set the windows in a transport configuration, rejecting zero or
+    /// oversize windows
+    pub(crate) fn apply(&self, transport_config: &mut TransportConfig) -> Result<()> {
+        transport_config.stream_receive_window(Self::varint("stream_receive_window", self.stream_receive_window)?);
+        transport_config.receive_window(Self::varint("receive_window", self.receive_window)?);
+        if self.send_window == 0 {
+            return Err(Error::ConfigurationError("send_window must be non-zero".to_string()));
+        }
+        transport_config.send_window(self.send_window);
+        Ok(())
+    }
+    
+    /// Validate a receive window and convert it to a QUIC variable-length integer
+    fn varint(name: &str, window: u64) -> Result<quinn::VarInt> {
+        if window == 0 {
+            return Err(Error::ConfigurationError(format!("{} must be non-zero", name)));
+        }
+        quinn::VarInt::from_u64(window)
+            .map_err(|_| Error::ConfigurationError(format!("{} too large: {}", name, window)))
+    }
+}
+
+/// Write one packet to a channel stream, prefixed by its length as a
+/// 4-byte big-endian integer
+async fn write_frame(send: &mut SendStream, packet: &[u8]) -> Result<()> {
+    send.write_all(&(packet.len() as u32).to_be_bytes()).await
+        .map_err(|e| Error::stream("Failed to write frame length", e))?;
+    send.write_all(packet).await
+        .map_err(|e| Error::stream("Failed to write frame", e))
+}
+
+/// Read one length-prefixed packet from a channel stream, or None when the
+/// peer finished the stream between frames
+async fn read_frame(recv: &mut RecvStream, max_packet_size: usize) -> Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match recv.read_exact(&mut len).await {
+        Ok(()) => {},
+        Err(quinn::ReadExactError::FinishedEarly) => return Ok(None),
+        Err(e) => return Err(Error::stream("Failed to read frame length", e)),
+    }
+    
+    let len = u32::from_be_bytes(len) as usize;
+    if len > max_packet_size {
+        return Err(Error::ParsingError(format!("Frame of {} bytes exceeds the maximum packet size", len)));
+    }
+    
+    let mut packet = vec![0u8; len];
+    recv.read_exact(&mut packet).await
+        .map_err(|e| Error::stream("Failed to read frame", e))?;
+    Ok(Some(packet))
+}
+
+/// Long-lived bi-stream pipelining Interest/Data exchanges with one peer
+///
+/// Interests and their replies travel as frames, each a 4-byte big-endian
+/// length followed by the encoded packet. The peer answers in order, so
+/// several Interests can be sent before reading their replies. Finishing or
+/// dropping the channel ends the stream.
+#[derive(Debug)]
+pub struct MultiplexedChannel {
+    /// Sending half of the stream
+    send: SendStream,
+    /// Receiving half of the stream
+    recv: RecvStream,
+    /// Connection the stream belongs to
+    conn_tracker: Arc<ConnectionTracker>,
+    /// Maximum packet size
+    max_packet_size: usize,
+    /// Timeouts for writing an Interest and reading its reply
+    timeouts: StreamTimeouts,
+    /// Encoding of the Interests and Data on the channel
+    wire_format: WireFormat,
+    /// Send time of each Interest awaiting its reply, oldest first
+    pending: VecDeque<Instant>,
+}
+
+impl MultiplexedChannel {
+    /// Send an Interest without waiting for its reply
+    pub async fn send(&mut self, interest: &Interest) -> Result<()> {
+        let interest_bytes = interest.encode(self.wire_format)?;
+        debug!("Sending Interest for {} on channel, size={} bytes", interest.name(), interest_bytes.len());
+        tokio::time::timeout(self.timeouts.send, write_frame(&mut self.send, &interest_bytes)).await
+            .map_err(|_| Error::Timeout(format!("Timed out sending Interest {} on channel", interest.name())))??;
+        self.pending.push_back(Instant::now());
+        Ok(())
+    }
+    
+    /// Wait for the reply to the oldest Interest sent
+    ///
+    /// A Nack from the peer is returned as `Error::Nack`.
+    pub async fn recv(&mut self) -> Result<Data> {
+        let remote_addr = self.conn_tracker.remote_addr();
+        let start_time = self.pending.pop_front()
+            .ok_or_else(|| Error::InvalidState("No Interest awaiting a reply".to_string()))?;
+        
+        let read = match tokio::time::timeout(self.timeouts.recv, read_frame(&mut self.recv, self.max_packet_size)).await {
+            Ok(read) => read,
+            Err(_) => {
+                self.conn_tracker.report_failure(true, &format!("No reply on channel within {:?}", self.timeouts.recv)).await;
+                return Err(Error::Timeout(format!("Channel to {} timed out", remote_addr)));
+            }
+        };
+        let frame = match read {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return Err(Error::ConnectionError(format!("Channel to {} closed", remote_addr))),
+            Err(e) => {
+                self.conn_tracker.report_failure(false, &format!("Channel read error: {}", e)).await;
+                return Err(e);
+            }
+        };
+        
+        // The compact format has no NACK, so NACKs stay in NDN TLV
+        let packet = match self.wire_format {
+            WireFormat::Compact if frame.first() == Some(&tlv_type::DATA) => {
+                Data::decode(&frame, WireFormat::Compact).map(Packet::Data)
+            }
+            _ => Packet::decode(&frame),
+        };
+        match packet {
+            Ok(Packet::Data(data)) => {
+                let rtt = start_time.elapsed().as_millis() as u64;
+                self.conn_tracker.report_success(rtt, frame.len()).await;
+                Ok(data)
+            },
+            Ok(Packet::Nack(nack)) => Err(Error::nack(&nack)),
+            Ok(_) => Err(Error::ProtocolError("Unexpected packet on channel".to_string())),
+            Err(e) => {
+                self.conn_tracker.report_failure(false, &format!("Data parsing error: {}", e)).await;
+                Err(Error::ParsingError(format!("Failed to decode Data: {}", e)))
+            }
+        }
+    }
+    
+    /// Send an Interest and wait for its reply
+    pub async fn send_interest(&mut self, interest: Interest) -> Result<Data> {
+        self.send(&interest).await?;
+        self.recv().await
+    }
+    
+    /// Get the number of Interests sent whose reply has not been read
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+    
+    /// Finish the stream, telling the peer no more Interests follow
+    pub async fn finish(mut self) -> Result<()> {
+        self.send.finish().await
+            .map_err(|e| Error::stream("Failed to finish channel", e))
+    }
+}
+
 /// The main QUIC transport implementation for NDN
 #[derive(Debug)]
 pub struct QuicTransport {
@@ -211,8 +446,8 @@ pub struct QuicTransport {
     connections: DashMap<SocketAddr, Arc<ConnectionTracker>>,
     /// Handle for the server task
     server_handle: Option<JoinHandle<()>>,
-    /// Registered handlers for Interest packets based on name prefix
-    handlers: Arc<RwLock<HashMap<Name, InterestHandler>>>,
+    /// Registered handlers for Interest packets by prefix or exact name
+    handlers: Arc<RwLock<HandlerTable<InterestHandler>>>,
     /// Idle timeout in seconds
     idle_timeout: u64,
     /// Maximum packet size
@@ -223,6 +458,24 @@ pub struct QuicTransport {
     bind_addr: SocketAddr,
     /// Local port
     port: u16,
+    /// Hook deciding whether to accept an incoming connection
+    on_incoming: Option<IncomingHook>,
+    /// Flow-control windows for new connections
+    windows: FlowControlWindows,
+    /// Interests sent in datagrams awaiting a reply
+    datagram_waiters: DatagramWaiters,
+    /// Data produced for datagram Interests, waiting for them over a stream
+    datagram_overflow: DatagramOverflow,
+    /// Per-remote locks serializing connection attempts
+    connect_locks: DashMap<SocketAddr, Arc<Mutex<()>>>,
+    /// How outgoing connections verify the server certificate
+    tls: TlsConfig,
+    /// Name the server certificate must be issued for
+    server_name: String,
+    /// Encoding of Interests and Data sent one per stream
+    wire_format: WireFormat,
+    /// Timeouts for writing and reading packets on streams
+    timeouts: StreamTimeouts,
 }
 
 impl QuicTransport {
@@ -232,15 +485,55 @@ impl QuicTransport {
         port: u16, 
         idle_timeout_secs: u64, 
         max_packet_size: usize
+    ) -> Result<Self> {
+        Self::with_windows(bind_addr, port, idle_timeout_secs, max_packet_size, FlowControlWindows::default()).await
+    }
+    
+    /// Create a new QUIC transport instance from the transport configuration
+    pub async fn from_config(config: &Config) -> Result<Self> {
+        let mut transport = Self::with_identity(
+            &config.bind_address,
+            config.port,
+            config.idle_timeout,
+            config.max_packet_size,
+            FlowControlWindows::from(config),
+            server_identity(config.tls_cert_path.as_deref(), config.tls_key_path.as_deref())?,
+        ).await?;
+        transport.set_tls_config(config.tls.clone());
+        transport.set_tls_server_name(config.tls_server_name.clone());
+        transport.set_wire_format(config.wire_format);
+        transport.set_timeouts(StreamTimeouts::from(config));
+        Ok(transport)
+    }
+    
+    /// Create a new QUIC transport instance with the given flow-control windows
+    pub async fn with_windows(
+        bind_addr: &str,
+        port: u16,
+        idle_timeout_secs: u64,
+        max_packet_size: usize,
+        windows: FlowControlWindows,
+    ) -> Result<Self> {
+        // Present a self-signed certificate
+        let identity = server_identity(None, None)?;
+        Self::with_identity(bind_addr, port, idle_timeout_secs, max_packet_size, windows, identity).await
+    }
+    
+    /// Create a new QUIC transport instance presenting the given certificate
+    /// chain and key
+    async fn with_identity(
+        bind_addr: &str,
+        port: u16,
+        idle_timeout_secs: u64,
+        max_packet_size: usize,
+        windows: FlowControlWindows,
+        (chain, key): (Vec<Certificate>, PrivateKey),
     ) -> Result<Self> {
         // Parse bind address
-        let addr = format!("{}:{}", bind_addr, port).parse::<SocketAddr>()?;
-        
-        // Generate self-signed certificate
-        let (cert, key) = generate_self_signed_cert()?;
+        let addr = parse_bind_addr(bind_addr, port)?;
         
         // Create server config
-        let server_config = create_server_config(vec![cert], key)?;
+        let server_config = Self::create_server_config(chain, key, windows)?;
         
         // Create endpoint
         let endpoint = Endpoint::server(server_config, addr)?;
@@ -249,18 +542,27 @@ impl QuicTransport {
         Ok(Self {
             endpoint,
             connections: DashMap::new(),
-            handlers: Arc::new(RwLock::new(HashMap::new())),
+            handlers: Arc::new(RwLock::new(HandlerTable::new())),
             server_handle: None,
             idle_timeout: idle_timeout_secs,
             max_packet_size,
             server_running: Arc::new(Mutex::new(false)),
             bind_addr: addr,
             port,
+            on_incoming: None,
+            windows,
+            datagram_waiters: Arc::new(DashMap::new()),
+            datagram_overflow: Arc::new(DashMap::new()),
+            connect_locks: DashMap::new(),
+            tls: TlsConfig::Insecure,
+            server_name: "localhost".to_string(),
+            wire_format: WireFormat::Ndn,
+            timeouts: StreamTimeouts::default(),
         })
     }
     
     /// Helper method to create a proper transport configuration
-    fn create_transport_config(idle_timeout: u64) -> Result<TransportConfig> {
+    fn create_transport_config(idle_timeout: u64, windows: FlowControlWindows) -> Result<TransportConfig> {
         let mut transport_config = TransportConfig::default();
         
         // Set keepalive interval (15 seconds)
@@ -272,11 +574,8 @@ impl QuicTransport {
         // Allow a reasonably large number of concurrent bi-directional streams
         transport_config.max_concurrent_bidi_streams(100_u32.into());
         
-        // Set receive window (8MB)
-        transport_config.receive_window(8_000_000u32);
-        
-        // Set send window (8MB)
-        transport_config.send_window(8_000_000u32);
+        // Apply the flow-control windows
+        windows.apply(&mut transport_config)?;
         
         // Set initial MTU (1400 is a safe default)
         transport_config.initial_mtu(1400);
@@ -285,36 +584,85 @@ impl QuicTransport {
     }
     
     /// Create server configuration with the provided certificate and key
-    fn create_server_config(certs: Vec<Certificate>, key: PrivateKey) -> Result<ServerConfig> {
+    fn create_server_config(certs: Vec<Certificate>, key: PrivateKey, windows: FlowControlWindows) -> Result<ServerConfig> {
         let mut server_config = ServerConfig::with_single_cert(certs, key)
             .map_err(|e| Error::CryptoError(format!("Failed to create server config: {}", e)))?;
         
         // Configure transport parameters
-        let transport_config = Self::create_transport_config(30)?; // 30 second default idle timeout for server
+        let transport_config = Self::create_transport_config(30, windows)?; // 30 second default idle timeout for server
         
         // Apply transport config to server config
-        server_config.transport_config(transport_config);
+        server_config.transport_config(Arc::new(transport_config));
         
         Ok(server_config)
     }
     
     /// Create client configuration for connecting to servers
-    fn create_client_config(idle_timeout: u64) -> Result<ClientConfig> {
-        // Create a basic client config without certificate verification for development
-        let crypto = rustls::ClientConfig::builder()
-            .with_safe_defaults()
-            .with_custom_certificate_verifier(Arc::new(InsecureServerVerifier {}))
-            .with_no_client_auth();
-            
+    fn create_client_config(idle_timeout: u64, windows: FlowControlWindows, tls: &TlsConfig) -> Result<ClientConfig> {
+        // Verify servers as the TLS configuration says
+        let crypto = build_client_config(tls)?;
+        
         let mut client_config = ClientConfig::new(Arc::new(crypto));
         
         // Apply transport configuration
-        let transport_config = Self::create_transport_config(idle_timeout)?;
-        client_config.transport_config(transport_config);
+        let transport_config = Self::create_transport_config(idle_timeout, windows)?;
+        client_config.transport_config(Arc::new(transport_config));
         
         Ok(client_config)
     }
     
+    /// Get the flow-control windows applied to new connections
+    pub fn windows(&self) -> FlowControlWindows {
+        self.windows
+    }
+    
+    /// Set how outgoing connections verify the server certificate
+    ///
+    /// Applies to connections opened afterwards.
+    pub fn set_tls_config(&mut self, tls: TlsConfig) {
+        self.tls = tls;
+    }
+    
+    /// Set the name the server certificate must be issued for when it is
+    /// validated against roots
+    ///
+    /// Applies to connections opened afterwards.
+    pub fn set_tls_server_name(&mut self, server_name: impl Into<String>) {
+        self.server_name = server_name.into();
+    }
+    
+    /// Set the encoding of Interests and Data sent one per stream
+    ///
+    /// Channels and datagrams always use the NDN encoding. Must be set
+    /// before `start_server` to apply to the server side.
+    pub fn set_wire_format(&mut self, format: WireFormat) {
+        self.wire_format = format;
+    }
+    
+    /// Get the encoding of Interests and Data sent one per stream
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_format
+    }
+    
+    /// Set the timeouts for writing and reading packets on streams
+    ///
+    /// Must be set before `start_server` to apply to the server side.
+    pub fn set_timeouts(&mut self, timeouts: StreamTimeouts) {
+        self.timeouts = timeouts;
+    }
+    
+    /// Get the timeouts for writing and reading packets on streams
+    pub fn timeouts(&self) -> StreamTimeouts {
+        self.timeouts
+    }
+    
+    /// Set the hook consulted for each incoming connection (e.g. an IP allowlist)
+    ///
+    /// Must be set before `start_server` to take effect.
+    pub fn set_on_incoming(&mut self, hook: impl Fn(SocketAddr) -> bool + Send + Sync + 'static) {
+        self.on_incoming = Some(Arc::new(hook));
+    }
+    
     /// Start the QUIC transport server
     pub async fn start_server(&mut self) -> Result<()> {
         // Set the server as running
@@ -330,7 +678,12 @@ impl QuicTransport {
         let handlers = self.handlers.clone();
         let connections = self.connections.clone();
         let max_packet_size = self.max_packet_size;
+        let wire_format = self.wire_format;
+        let timeouts = self.timeouts;
         let server_running = self.server_running.clone();
+        let on_incoming = self.on_incoming.clone();
+        let datagram_waiters = self.datagram_waiters.clone();
+        let datagram_overflow = self.datagram_overflow.clone();
         
         // Start the server task
         self.server_handle = Some(tokio::spawn(async move {
@@ -341,6 +694,17 @@ impl QuicTransport {
                 info!("Incoming connection from {:?}", conn.remote_address());
                 let remote = conn.remote_address();
                 
+                // Let the hook reject the peer before completing the handshake
+                if let Some(hook) = &on_incoming {
+                    if !hook(remote) {
+                        info!("Rejecting connection from {}", remote);
+                        if let Ok((connection, _)) = conn.into_0rtt() {
+                            connection.close(CONNECTION_REFUSED.into(), b"connection refused");
+                        }
+                        continue;
+                    }
+                }
+                
                 // Accept the connection
                 match conn.await {
                     Ok(connection) => {
@@ -353,13 +717,22 @@ impl QuicTransport {
                         // Add to known connections
                         connections.insert(remote, conn_tracker.clone());
                         
+                        // Serve Interests sent in datagrams
+                        tokio::spawn(Self::handle_datagrams(
+                            conn_tracker.connection().clone(),
+                            handlers.clone(),
+                            datagram_waiters.clone(),
+                            datagram_overflow.clone(),
+                        ));
+                        
                         // Clone required handlers for this connection
                         let handlers = handlers.clone();
                         let connections = connections.clone();
+                        let overflow = datagram_overflow.clone();
                         
                         // Handle this connection in separate task
                         tokio::spawn(async move {
-                            if let Err(e) = Self::handle_connection(conn_tracker.clone(), handlers, max_packet_size).await {
+                            if let Err(e) = Self::handle_connection(conn_tracker.clone(), handlers, overflow, max_packet_size, wire_format, timeouts).await {
                                 error!("Connection error: {}", e);
                                 conn_tracker.set_state(ConnectionState::Failed(e.to_string())).await;
                             }
@@ -387,8 +760,11 @@ impl QuicTransport {
     /// Handle a QUIC connection
     async fn handle_connection(
         conn_tracker: Arc<ConnectionTracker>,
-        handlers: Arc<RwLock<HashMap<Name, InterestHandler>>>,
+        handlers: Arc<RwLock<HandlerTable<InterestHandler>>>,
+        overflow: DatagramOverflow,
         max_packet_size: usize,
+        wire_format: WireFormat,
+        timeouts: StreamTimeouts,
     ) -> Result<()> {
         let connection = conn_tracker.connection().clone();
         let remote_addr = conn_tracker.remote_addr();
@@ -399,11 +775,12 @@ impl QuicTransport {
         while let Ok((send, recv)) = connection.accept_bi().await {
             // Clone handlers for this stream
             let handlers = handlers.clone();
+            let overflow = overflow.clone();
             let conn_tracker = conn_tracker.clone();
             
             // Handle stream in a new task
             tokio::spawn(async move {
-                if let Err(e) = Self::handle_stream(send, recv, handlers, conn_tracker.clone(), max_packet_size).await {
+                if let Err(e) = Self::handle_stream(send, recv, handlers, overflow, conn_tracker.clone(), max_packet_size, wire_format, timeouts).await {
                     error!("Stream handling error: {}", e);
                 }
             });
@@ -416,25 +793,142 @@ impl QuicTransport {
         Ok(())
     }
     
+    /// Handle the datagrams of a connection: answer Interests and hand
+    /// replies to the Interests this side sent
+    async fn handle_datagrams(
+        connection: Connection,
+        handlers: Arc<RwLock<HandlerTable<InterestHandler>>>,
+        waiters: DatagramWaiters,
+        overflow: DatagramOverflow,
+    ) {
+        let remote = connection.remote_address();
+        while let Ok(datagram) = connection.read_datagram().await {
+            match datagram.split_first() {
+                Some((&DATAGRAM_INTEREST, packet)) => {
+                    let reply = Self::answer_datagram_interest(&connection, packet, &handlers, &overflow).await;
+                    if let Some(reply) = reply {
+                        if let Err(e) = connection.send_datagram(reply) {
+                            warn!("Failed to send datagram reply: {}", e);
+                        }
+                    }
+                },
+                Some((&flag @ (DATAGRAM_DATA | DATAGRAM_USE_STREAM), reply)) if reply.len() >= 4 => {
+                    let nonce = u32::from_be_bytes([reply[0], reply[1], reply[2], reply[3]]);
+                    let data = match flag {
+                        DATAGRAM_DATA => Data::from_bytes(&reply[4..]).ok(),
+                        _ => None,
+                    };
+                    
+                    // Data must satisfy the Interest sent with its nonce
+                    let waiter = waiters.remove_if(&(remote, nonce), |_, waiter| match &data {
+                        Some(data) => waiter.interest.matches_data(data),
+                        None => true,
+                    });
+                    match waiter {
+                        Some((_, waiter)) => {
+                            let _ = waiter.reply.send(data);
+                        },
+                        None => debug!("Ignoring datagram reply from {} that answers no pending Interest", remote),
+                    }
+                },
+                _ => debug!("Ignoring malformed datagram from {}", remote),
+            }
+        }
+    }
+    
+    /// Produce the reply to an Interest received in a datagram
+    ///
+    /// Data that does not fit in a datagram, and Interests without a handler,
+    /// are answered with a request to use a stream instead. Data that does
+    /// not fit is kept for the Interest to come over the stream.
+    async fn answer_datagram_interest(
+        connection: &Connection,
+        packet: &[u8],
+        handlers: &RwLock<HandlerTable<InterestHandler>>,
+        overflow: &DatagramOverflow,
+    ) -> Option<Bytes> {
+        let interest = match Interest::from_bytes(packet) {
+            Ok(interest) => interest,
+            Err(e) => {
+                debug!("Ignoring undecodable Interest datagram: {}", e);
+                return None;
+            }
+        };
+        
+        let handler = handlers.read().await.lookup(interest.name()).cloned();
+        let data = handler.and_then(|handler| handler(interest.clone()).ok());
+        
+        let mut reply = BytesMut::new();
+        if let Some(data) = data {
+            reply.put_u8(DATAGRAM_DATA);
+            reply.put_u32(interest.nonce());
+            reply.put_slice(&data.to_bytes());
+            
+            if matches!(connection.max_datagram_size(), Some(max) if reply.len() <= max) {
+                return Some(reply.freeze());
+            }
+            reply.clear();
+            
+            let now = Instant::now();
+            overflow.retain(|_, (_, expires)| *expires > now);
+            overflow.insert((connection.remote_address(), interest.nonce()), (data, now + interest.get_lifetime()));
+        }
+        
+        reply.put_u8(DATAGRAM_USE_STREAM);
+        reply.put_u32(interest.nonce());
+        Some(reply.freeze())
+    }
+    
     /// Handle a bi-directional QUIC stream
+    #[allow(clippy::too_many_arguments)]
     async fn handle_stream(
         mut send: SendStream,
         mut recv: RecvStream,
-        handlers: Arc<RwLock<HashMap<Name, InterestHandler>>>,
+        handlers: Arc<RwLock<HandlerTable<InterestHandler>>>,
+        overflow: DatagramOverflow,
         conn_tracker: Arc<ConnectionTracker>,
         max_packet_size: usize,
+        wire_format: WireFormat,
+        timeouts: StreamTimeouts,
     ) -> Result<()> {
-        // Read the stream until we have the Interest packet
-        let interest_bytes = match recv.read_to_end(max_packet_size).await {
+        // The first byte tells a channel from a stream carrying one Interest
+        let mut first = [0u8; 1];
+        match tokio::time::timeout(timeouts.recv, recv.read_exact(&mut first)).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => {
+                conn_tracker.report_failure(false, &format!("Stream read error: {}", e)).await;
+                return Err(Error::IoError(format!("Failed to read from stream: {}", e)))
+            }
+            Err(_) => {
+                conn_tracker.report_failure(true, "Stream opened without a packet").await;
+                return Err(Error::Timeout(format!("No packet within {:?}", timeouts.recv)))
+            }
+        }
+        if first[0] == CHANNEL_STREAM {
+            return Self::handle_channel(send, recv, handlers, conn_tracker, max_packet_size, wire_format).await;
+        }
+        
+        // Read exactly the Interest TLV, which may arrive split across
+        // STREAM frames while the peer keeps the stream open
+        let mut reader = (&first[..]).chain(&mut recv);
+        let read = async {
+            match wire_format {
+                WireFormat::Ndn => tlv::read_packet(&mut reader, max_packet_size).await,
+                WireFormat::Compact => compact::read_packet(&mut reader, max_packet_size).await,
+            }
+        };
+        let read = tokio::time::timeout(timeouts.recv, read).await
+            .unwrap_or_else(|_| Err(Error::Timeout(format!("No Interest within {:?}", timeouts.recv))));
+        let interest_bytes = match read {
             Ok(bytes) => bytes,
             Err(e) => {
                 conn_tracker.report_failure(false, &format!("Stream read error: {}", e)).await;
-                return Err(Error::IoError(format!("Failed to read from stream: {}", e)))
+                return Err(e)
             }
         };
         
         // Parse the Interest packet
-        let interest = match Interest::from_bytes(&interest_bytes) {
+        let interest = match Interest::decode(&interest_bytes, wire_format) {
             Ok(interest) => {
                 debug!("Received Interest for {}", interest.name());
                 interest
@@ -447,32 +941,43 @@ impl QuicTransport {
         
         // Find a handler for this Interest
         let handlers = handlers.read().await;
-        let mut matching_handler = None;
-        let mut longest_prefix = 0;
-        
-        // Find the best matching prefix (longest match)
-        for (prefix, handler) in handlers.iter() {
-            if interest.name().starts_with(prefix) && prefix.len() > longest_prefix {
-                matching_handler = Some(handler);
-                longest_prefix = prefix.len();
-            }
-        }
+        
+        // Exact match first, then the best matching prefix (longest match)
+        let matching_handler = handlers.lookup(interest.name());
         
         // Process the Interest with the matching handler
         if let Some(handler) = matching_handler {
             // Get start time for RTT calculation
             let start_time = Instant::now();
             
-            // Call handler to get Data response
-            match handler(interest.clone()) {
+            // Answer with the Data produced when the Interest came in a
+            // datagram it did not fit, or call handler to get Data response
+            let response = match Self::take_overflow(&overflow, conn_tracker.remote_addr(), &interest) {
+                Some(data) => Ok(data),
+                None => handler(interest.clone()),
+            };
+            match response {
                 Ok(data) => {
                     // Encode Data packet
-                    let data_bytes = data.to_bytes();
+                    let data_bytes = match data.encode(wire_format) {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            conn_tracker.report_failure(false, &format!("Data encoding error: {}", e)).await;
+                            return Err(e)
+                        }
+                    };
                     
                     // Send Data response
-                    if let Err(e) = send.write_all(&data_bytes).await {
-                        conn_tracker.report_failure(false, &format!("Write error: {}", e)).await;
-                        return Err(Error::IoError(format!("Failed to send Data: {}", e)))
+                    match tokio::time::timeout(timeouts.send, send.write_all(&data_bytes)).await {
+                        Ok(Ok(())) => {},
+                        Ok(Err(e)) => {
+                            conn_tracker.report_failure(false, &format!("Write error: {}", e)).await;
+                            return Err(Error::IoError(format!("Failed to send Data: {}", e)))
+                        }
+                        Err(_) => {
+                            conn_tracker.report_failure(true, "Write timeout").await;
+                            return Err(Error::Timeout(format!("Timed out sending Data for {}", interest.name())))
+                        }
                     }
                     
                     // Finish the stream
@@ -504,6 +1009,67 @@ impl QuicTransport {
         Ok(())
     }
     
+    /// Take the Data kept for an Interest that came in a datagram, unless the
+    /// Interest has expired since
+    fn take_overflow(overflow: &DatagramOverflow, remote: SocketAddr, interest: &Interest) -> Option<Data> {
+        let (_, (data, expires)) = overflow.remove(&(remote, interest.nonce()))?;
+        (expires > Instant::now() && interest.matches_data(&data)).then_some(data)
+    }
+    
+    /// Answer the Interests pipelined on a channel stream, in order, until
+    /// the peer finishes it
+    ///
+    /// An Interest without a handler, or whose handler fails, is answered
+    /// with a Nack so the replies that follow stay matched to their Interests.
+    async fn handle_channel(
+        mut send: SendStream,
+        mut recv: RecvStream,
+        handlers: Arc<RwLock<HandlerTable<InterestHandler>>>,
+        conn_tracker: Arc<ConnectionTracker>,
+        max_packet_size: usize,
+        wire_format: WireFormat,
+    ) -> Result<()> {
+        debug!("Opened Interest channel from {}", conn_tracker.remote_addr());
+        
+        while let Some(frame) = read_frame(&mut recv, max_packet_size).await? {
+            let interest = match Interest::decode(&frame, wire_format) {
+                Ok(interest) => interest,
+                Err(e) => {
+                    conn_tracker.report_failure(false, &format!("Interest parsing error: {}", e)).await;
+                    return Err(Error::ParsingError(format!("Failed to parse Interest: {}", e)))
+                }
+            };
+            
+            let start_time = Instant::now();
+            let handler = handlers.read().await.lookup(interest.name()).cloned();
+            // Data the wire format cannot carry is refused like a failed handler
+            let reply = match handler.map(|handler| handler(interest.clone()).and_then(|data| data.encode(wire_format))) {
+                Some(Ok(data_bytes)) => Ok(data_bytes),
+                Some(Err(e)) => Err(Nack::from_interest(interest.clone(), e.to_string())),
+                None => Err(Nack::new(interest.clone(), NackReason::NoRoute)),
+            };
+            
+            match reply {
+                Ok(data_bytes) => {
+                    write_frame(&mut send, &data_bytes).await?;
+                    let rtt = start_time.elapsed().as_millis() as u64;
+                    conn_tracker.report_success(rtt, data_bytes.len()).await;
+                },
+                Err(nack) => {
+                    conn_tracker.report_failure(false, &format!("No Data for {}", interest.name())).await;
+                    write_frame(&mut send, &nack.to_bytes()).await?;
+                }
+            }
+        }
+        
+        if let Err(e) = send.finish().await {
+            warn!("Error finishing channel: {}", e);
+        }
+        debug!("Closed Interest channel from {}", conn_tracker.remote_addr());
+        
+        Ok(())
+    }
+    
     /// Register a handler for a specific name prefix
     pub async fn register_handler(
         &self,
@@ -511,36 +1077,78 @@ impl QuicTransport {
         handler: impl Fn(Interest) -> Result<Data> + Send + Sync + 'static
     ) -> Result<()> {
         let mut handlers = self.handlers.write().await;
-        handlers.insert(prefix.clone(), Arc::new(handler));
+        handlers.insert(prefix.clone(), MatchKind::Prefix, Arc::new(handler));
         info!("Registered handler for prefix: {}", prefix);
         Ok(())
     }
     
-    /// Connect to a remote QUIC NDN server
+    /// Remove the handler registered for a prefix
+    pub async fn unregister_handler(&self, prefix: &Name) -> Result<()> {
+        match self.handlers.write().await.remove(prefix, MatchKind::Prefix) {
+            Some(_) => {
+                info!("Unregistered handler for prefix: {}", prefix);
+                Ok(())
+            }
+            None => Err(Error::NotFound(format!("No handler for prefix {}", prefix))),
+        }
+    }
+    
+    /// Register a handler for exactly one name; it wins over a prefix handler for the same name
+    pub async fn register_exact_handler(
+        &self,
+        name: Name,
+        handler: impl Fn(Interest) -> Result<Data> + Send + Sync + 'static
+    ) -> Result<()> {
+        let mut handlers = self.handlers.write().await;
+        handlers.insert(name.clone(), MatchKind::Exact, Arc::new(handler));
+        info!("Registered exact handler for name: {}", name);
+        Ok(())
+    }
+    
+    /// Connect to a remote QUIC NDN server, reusing an open connection to it
+    ///
+    /// Concurrent callers for the same address wait for a single connection
+    /// attempt rather than racing.
     pub async fn connect(&self, remote_addr: &str, remote_port: u16) -> Result<Arc<ConnectionTracker>> {
         // Parse remote address
-        let addr = format!("{}:{}", remote_addr, remote_port).parse::<SocketAddr>()
-            .map_err(|e| Error::AddrParseError(format!("Failed to parse address: {}", e)))?;
+        let addr = parse_bind_addr(remote_addr, remote_port)?;
         
         // Check if we already have a connection to this address
-        if let Some(conn) = self.connections.get(&addr) {
-            return Ok(conn.clone());
+        if let Some(conn) = self.open_connection(&addr) {
+            return Ok(conn);
+        }
+        
+        // Serialize connection attempts to the same address
+        let lock = self.connect_locks.entry(addr).or_default().clone();
+        let _guard = lock.lock().await;
+        
+        // Another caller may have connected while we waited
+        if let Some(conn) = self.open_connection(&addr) {
+            return Ok(conn);
         }
         
         // Create client config
-        let client_config = Self::create_client_config(self.idle_timeout)?;
+        let client_config = Self::create_client_config(self.idle_timeout, self.windows, &self.tls)?;
         
         // Connect to the remote endpoint
         info!("Connecting to {}...", addr);
-        let connecting = self.endpoint.connect_with(client_config, addr, "localhost")
-            .map_err(|e| Error::ConnectionError(format!("Failed to connect: {}", e)))?;
+        let connecting = self.endpoint.connect_with(client_config, addr, &self.server_name)
+            .map_err(|e| Error::connection("Failed to connect", e))?;
         
         // Wait for connection to be established
         let connection = connecting.await
-            .map_err(|e| Error::ConnectionError(format!("Connection failed: {}", e)))?;
+            .map_err(|e| Error::connection("Connection failed", e))?;
         
         info!("Connected to {}", addr);
         
+        // Replies to datagram Interests arrive on the connection's datagrams
+        tokio::spawn(Self::handle_datagrams(
+            connection.clone(),
+            self.handlers.clone(),
+            self.datagram_waiters.clone(),
+            self.datagram_overflow.clone(),
+        ));
+        
         // Create connection tracker
         let conn_tracker = Arc::new(ConnectionTracker::new(connection, addr));
         conn_tracker.set_state(ConnectionState::Connected).await;
@@ -551,6 +1159,12 @@ impl QuicTransport {
         Ok(conn_tracker)
     }
     
+    /// Get the connection to an address unless it has been closed
+    fn open_connection(&self, addr: &SocketAddr) -> Option<Arc<ConnectionTracker>> {
+        let conn = self.connections.get(addr)?.clone();
+        conn.connection().close_reason().is_none().then_some(conn)
+    }
+    
     /// Send an Interest packet to a remote peer and wait for Data
     pub async fn send_interest(&self, remote_addr: SocketAddr, interest: Interest) -> Result<Data> {
         // Get connection to the remote peer (or error if not connected)
@@ -565,30 +1179,43 @@ impl QuicTransport {
         // Measure start time for RTT calculation
         let start_time = Instant::now();
         
-        // Open a bi-directional stream
-        let (mut send, mut recv) = connection.open_bi().await
-            .map_err(|e| Error::ConnectionError(format!("Failed to open stream: {}", e)))?;
-        
         // Encode Interest
-        let interest_bytes = interest.to_bytes();
+        let interest_bytes = interest.encode(self.wire_format)?;
         debug!("Sending Interest for {}, size={} bytes", interest.name(), interest_bytes.len());
         
-        // Send Interest
-        send.write_all(&interest_bytes).await
-            .map_err(|e| Error::IoError(format!("Failed to send Interest: {}", e)))?;
-        
-        // Finish sending
-        send.finish().await
-            .map_err(|e| Error::IoError(format!("Failed to finish stream: {}", e)))?;
+        // Open a bi-directional stream, send the Interest and finish sending
+        let sent = tokio::time::timeout(self.timeouts.send, async {
+            let (mut send, recv) = connection.open_bi().await
+                .map_err(|e| Error::connection("Failed to open stream", e))?;
+            send.write_all(&interest_bytes).await
+                .map_err(|e| Error::stream("Failed to send Interest", e))?;
+            send.finish().await
+                .map_err(|e| Error::stream("Failed to finish stream", e))?;
+            Ok::<_, Error>(recv)
+        }).await;
+        let mut recv = match sent {
+            Ok(recv) => recv?,
+            Err(_) => {
+                conn_tracker.report_failure(true, "Write timeout").await;
+                return Err(Error::Timeout(format!("Timed out sending Interest {}", interest.name())));
+            }
+        };
         
         // Wait for Data
-        match recv.read_to_end(self.max_packet_size).await {
+        let read = match tokio::time::timeout(self.timeouts.recv, recv.read_to_end(self.max_packet_size)).await {
+            Ok(read) => read,
+            Err(_) => {
+                conn_tracker.report_failure(true, &format!("No Data within {:?}", self.timeouts.recv)).await;
+                return Err(Error::Timeout(format!("Interest timed out: {}", interest.name())));
+            }
+        };
+        match read {
             Ok(data_bytes) => {
                 // Calculate RTT
                 let rtt = start_time.elapsed().as_millis() as u64;
                 
                 // Decode Data
-                match Data::from_bytes(&data_bytes) {
+                match Data::decode(&data_bytes, self.wire_format) {
                     Ok(data) => {
                         // Update statistics
                         conn_tracker.report_success(rtt, data_bytes.len()).await;
@@ -618,6 +1245,107 @@ impl QuicTransport {
         }
     }
     
+    /// Open a channel pipelining Interests to a connected peer over one bi-stream
+    ///
+    /// Opening a stream per Interest costs a stream setup each time; a
+    /// channel pays it once and carries any number of exchanges.
+    pub async fn open_channel(&self, remote_addr: SocketAddr) -> Result<MultiplexedChannel> {
+        let conn_tracker = match self.connections.get(&remote_addr) {
+            Some(tracker) => tracker.clone(),
+            None => return Err(Error::ConnectionError(format!("No connection to {}", remote_addr)))
+        };
+        
+        let (mut send, recv) = conn_tracker.connection().open_bi().await
+            .map_err(|e| Error::connection("Failed to open stream", e))?;
+        send.write_all(&[CHANNEL_STREAM]).await
+            .map_err(|e| Error::stream("Failed to open channel", e))?;
+        debug!("Opened Interest channel to {}", remote_addr);
+        
+        Ok(MultiplexedChannel {
+            send,
+            recv,
+            conn_tracker,
+            max_packet_size: self.max_packet_size,
+            timeouts: self.timeouts,
+            wire_format: self.wire_format,
+            pending: VecDeque::new(),
+        })
+    }
+    
+    /// Send an Interest in a QUIC datagram, falling back to a stream when it does not fit
+    ///
+    /// The Interest goes in a single unreliable datagram when its encoding plus a
+    /// one-byte tag is at most the connection's `max_datagram_size()` (bounded by
+    /// the path MTU, about 1200 bytes before MTU discovery). The Data comes back
+    /// in a datagram keyed by the Interest nonce. Larger Interests, and Interests
+    /// whose Data does not fit in a datagram, go over a bi-stream as with
+    /// `send_interest`. A lost datagram surfaces as a timeout after the Interest
+    /// lifetime.
+    pub async fn send_interest_datagram(&self, remote_addr: SocketAddr, interest: Interest) -> Result<Data> {
+        let conn_tracker = match self.connections.get(&remote_addr) {
+            Some(tracker) => tracker.clone(),
+            None => return Err(Error::ConnectionError(format!("No connection to {}", remote_addr)))
+        };
+        let connection = conn_tracker.connection().clone();
+        
+        let interest_bytes = interest.to_bytes();
+        if !matches!(connection.max_datagram_size(), Some(max) if interest_bytes.len() < max) {
+            debug!("Interest for {} ({} bytes) too large for a datagram, using a stream",
+                   interest.name(), interest_bytes.len());
+            return self.send_interest(remote_addr, interest).await;
+        }
+        
+        // Measure start time for RTT calculation
+        let start_time = Instant::now();
+        
+        // A reply could not be told apart from that of another Interest
+        // pending with the same nonce, so send this one over a stream
+        let key = (remote_addr, interest.nonce());
+        let (tx, rx) = oneshot::channel();
+        let pending = match self.datagram_waiters.entry(key) {
+            Entry::Occupied(_) => true,
+            Entry::Vacant(entry) => {
+                entry.insert(DatagramWaiter { interest: interest.clone(), reply: tx });
+                false
+            }
+        };
+        if pending {
+            debug!("Datagram Interest with nonce {} already pending, using a stream", interest.nonce());
+            return self.send_interest(remote_addr, interest).await;
+        }
+        
+        let mut datagram = BytesMut::with_capacity(1 + interest_bytes.len());
+        datagram.put_u8(DATAGRAM_INTEREST);
+        datagram.put_slice(&interest_bytes);
+        if let Err(e) = connection.send_datagram(datagram.freeze()) {
+            self.datagram_waiters.remove(&key);
+            conn_tracker.report_failure(false, &format!("Datagram send error: {}", e)).await;
+            return Err(Error::connection("Failed to send Interest datagram", e));
+        }
+        debug!("Sent Interest datagram for {}, size={} bytes", interest.name(), interest_bytes.len());
+        
+        match tokio::time::timeout(interest.get_lifetime(), rx).await {
+            Ok(Ok(Some(data))) => {
+                let rtt = start_time.elapsed().as_millis() as u64;
+                conn_tracker.report_success(rtt, data.to_bytes().len()).await;
+                debug!("Received Data datagram for {}, RTT={}ms", interest.name(), rtt);
+                Ok(data)
+            },
+            Ok(Ok(None)) => {
+                debug!("Peer asked for {} over a stream", interest.name());
+                self.send_interest(remote_addr, interest).await
+            },
+            Ok(Err(_)) => {
+                Err(Error::ConnectionError(format!("Connection to {} closed", remote_addr)))
+            },
+            Err(_) => {
+                self.datagram_waiters.remove(&key);
+                conn_tracker.report_failure(true, &format!("No datagram reply for {}", interest.name())).await;
+                Err(Error::Timeout(format!("Interest timed out: {}", interest.name())))
+            }
+        }
+    }
+    
     /// Close a specific connection
     pub async fn close_connection(&self, remote_addr: SocketAddr) -> Result<()> {
         if let Some(conn_tracker) = self.connections.get(&remote_addr) {
@@ -686,7 +1414,7 @@ impl QuicTransport {
     async fn handle_connection(
         conn: Connection,
         remote: SocketAddr,
-        handlers: Arc<RwLock<HashMap<Name, InterestHandler>>>,
+        handlers: Arc<RwLock<HandlerTable<InterestHandler>>>,
         conn_tracker: Arc<ConnectionTracker>,
         max_packet_size: usize
     ) {
@@ -718,7 +1446,7 @@ impl QuicTransport {
     async fn handle_stream(
         send: &mut SendStream,
         recv: &mut RecvStream,
-        handlers: Arc<RwLock<HashMap<Name, InterestHandler>>>,
+        handlers: Arc<RwLock<HandlerTable<InterestHandler>>>,
         conn_tracker: Arc<ConnectionTracker>,
         max_packet_size: usize
     ) {
@@ -746,15 +1474,7 @@ impl QuicTransport {
         
         // Find handler for this name
         let handlers_guard = handlers.read().await;
-        let mut handler_opt = None;
-        let mut longest_prefix = 0;
-        
-        for (prefix, handler) in handlers_guard.iter() {
-            if interest.name().has_prefix(prefix) && prefix.len() > longest_prefix {
-                handler_opt = Some(handler.clone());
-                longest_prefix = prefix.len();
-            }
-        }
+        let handler_opt = handlers_guard.lookup(interest.name()).cloned();
         
         // Process Interest
         let response = match handler_opt {
@@ -800,7 +1520,7 @@ impl QuicTransport {
     // Register a handler for a name prefix
     pub async fn register_handler(&self, prefix: Name, handler: impl Fn(Interest) -> Result<Data> + Send + Sync + 'static) -> Result<()> {
         let mut handlers = self.handlers.write().await;
-        handlers.insert(prefix.clone(), Arc::new(handler));
+        handlers.insert(prefix.clone(), MatchKind::Prefix, Arc::new(handler));
         info!("Registered handler for prefix: {}", prefix);
         Ok(())
     }
@@ -808,7 +1528,7 @@ impl QuicTransport {
     // Connect to a remote NDN node
     pub async fn connect(&self, remote_addr: &str, remote_port: u16) -> Result<Arc<ConnectionTracker>> {
         // Parse remote address
-        let addr = format!("{}:{}", remote_addr, remote_port).parse::<SocketAddr>()?;
+        let addr = parse_bind_addr(remote_addr, remote_port)?;
         
         // Check if we already have a connection
         if let Some(conn) = self.connections.get(&addr) {
@@ -820,7 +1540,7 @@ impl QuicTransport {
         
         // Connect to the remote endpoint
         info!("Connecting to {}:{}", remote_addr, remote_port);
-        let connecting = self.endpoint.connect_with(client_config, addr, "localhost")?;
+        let connecting = self.endpoint.connect_with(client_config, addr, &self.server_name)?;
         
         // Wait for connection
         let connection = connecting.await?;
@@ -857,24 +1577,24 @@ impl QuicTransport {
         // Open bidirectional stream
         let connection = conn_tracker.connection();
         let (mut send, mut recv) = connection.open_bi().await
-            .map_err(|e| Error::ConnectionError(format!("Failed to open stream: {}", e)))?;
+            .map_err(|e| Error::connection("Failed to open stream", e))?;
         
         // Encode Interest
         let interest_bytes = interest.to_bytes();
         
         // Send Interest
         send.write_all(&interest_bytes).await
-            .map_err(|e| Error::IoError(format!("Failed to send Interest: {}", e)))?;
+            .map_err(|e| Error::stream("Failed to send Interest", e))?;
         
         // Finish sending
         send.finish().await
-            .map_err(|e| Error::IoError(format!("Failed to finish stream: {}", e)))?;
+            .map_err(|e| Error::stream("Failed to finish stream", e))?;
         
         debug!("Sent Interest for {}", interest.name());
         
         // Wait for Data
         let data_bytes = recv.read_to_end(self.max_packet_size).await
-            .map_err(|e| Error::IoError(format!("Failed to receive Data: {}", e)))?;
+            .map_err(|e| Error::stream("Failed to receive Data", e))?;
         
         // Calculate RTT
         let rtt = start_time.elapsed().as_millis() as u64;
@@ -959,11 +1679,158 @@ fn create_server_config(certs: Vec<Certificate>, key: PrivateKey) -> Result<Serv
 // Helper function to create a client configuration
 fn create_client_config() -> Result<ClientConfig> {
     // Use basic client config without certificate verification for development
-    let client_config = ClientConfig::new(Arc::new(rustls::ClientConfig::builder()
-        .with_safe_defaults()
-        .with_root_certificates(rustls::RootCertStore::empty())
-        .with_no_client_auth()
-    ));
+    let client_config = ClientConfig::new(Arc::new(build_client_config(&TlsConfig::Insecure)?));
     
     Ok(client_config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_channel_pipelines_exchanges() -> Result<()> {
+        let mut server = QuicTransport::new("127.0.0.1", 14320, 30, 65535).await?;
+        server.start_server().await?;
+        server.register_handler(Name::from_uri("/channel")?, |interest| {
+            let content = format!("reply for {}", interest.name());
+            Ok(Data::new(interest.name().clone(), content.into_bytes()))
+        }).await?;
+        
+        let client = QuicTransport::new("127.0.0.1", 14321, 30, 65535).await?;
+        client.connect("127.0.0.1", 14320).await?;
+        let server_addr: SocketAddr = "127.0.0.1:14320".parse()?;
+        let mut channel = client.open_channel(server_addr).await?;
+        
+        // Send all Interests before reading any reply; replies come back in order
+        let names: Vec<Name> = (0..20)
+            .map(|i| Name::from_uri(&format!("/channel/item/{}", i)))
+            .collect::<Result<_>>()?;
+        for name in &names {
+            channel.send(&Interest::new(name.clone())).await?;
+        }
+        assert_eq!(channel.pending(), 20);
+        
+        for name in &names {
+            let data = channel.recv().await?;
+            assert_eq!(data.name(), name);
+            assert_eq!(data.content().as_ref(), format!("reply for {}", name).as_bytes());
+        }
+        assert_eq!(channel.pending(), 0);
+        
+        // An Interest without a handler is NACKed and the channel stays usable
+        let err = channel.send_interest(Interest::new(Name::from_uri("/elsewhere")?)).await.unwrap_err();
+        assert!(matches!(err, Error::Nack { reason: NackReason::NoRoute, .. }));
+        let data = channel.send_interest(Interest::new(names[0].clone())).await?;
+        assert_eq!(data.name(), &names[0]);
+        
+        channel.finish().await?;
+        
+        // All the exchanges shared the one stream's connection
+        let stats = client.get_connection_stats(server_addr).await.unwrap();
+        assert_eq!(stats.data_received, 21);
+        Ok(())
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_datagram_replies_checked_and_not_reproduced() -> Result<()> {
+        let mut server = QuicTransport::new("127.0.0.1", 14340, 30, 65535).await?;
+        server.start_server().await?;
+        let produced = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = produced.clone();
+        server.register_handler(Name::from_uri("/bulky")?, move |interest| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Data::new(interest.name().clone(), vec![0x42; 4000]))
+        }).await?;
+        server.register_handler(Name::from_uri("/wrong")?, |_| {
+            Ok(Data::new(Name::from_uri("/elsewhere")?, "small"))
+        }).await?;
+        
+        let client = QuicTransport::new("127.0.0.1", 14341, 30, 65535).await?;
+        client.connect("127.0.0.1", 14340).await?;
+        let server_addr: SocketAddr = "127.0.0.1:14340".parse()?;
+        
+        // Data too large for a datagram is produced once and sent over the stream
+        let data = client.send_interest_datagram(server_addr, Interest::new(Name::from_uri("/bulky/1")?)).await?;
+        assert_eq!(data.content().len(), 4000);
+        assert_eq!(produced.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(server.datagram_overflow.is_empty());
+        
+        // Interests pending with the same nonce each get their Data
+        let interest = Interest::new(Name::from_uri("/bulky/2")?);
+        let (first, second) = tokio::join!(
+            client.send_interest_datagram(server_addr, interest.clone()),
+            client.send_interest_datagram(server_addr, interest),
+        );
+        assert_eq!(first?.name(), second?.name());
+        
+        // Data not satisfying the Interest is dropped, leaving it to time out
+        let interest = Interest::new(Name::from_uri("/wrong/1")?).lifetime(Duration::from_millis(300));
+        let err = client.send_interest_datagram(server_addr, interest).await.unwrap_err();
+        assert!(matches!(err, Error::Timeout(_)), "{}", err);
+        assert!(client.datagram_waiters.is_empty());
+        Ok(())
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+    async fn test_wire_format_from_config() -> Result<()> {
+        let config = Config { port: 14360, wire_format: WireFormat::Compact, ..Config::default() };
+        let mut server = QuicTransport::from_config(&config).await?;
+        assert_eq!(server.wire_format(), WireFormat::Compact);
+        server.start_server().await?;
+        server.register_handler(Name::from_uri("/test")?, |interest| {
+            Ok(Data::new(interest.name().clone(), "hello"))
+        }).await?;
+        
+        let config = Config { port: 14361, wire_format: WireFormat::Compact, ..Config::default() };
+        let client = QuicTransport::from_config(&config).await?;
+        client.connect("127.0.0.1", 14360).await?;
+        let server_addr: SocketAddr = "127.0.0.1:14360".parse()?;
+        
+        let data = client.send_interest(server_addr, Interest::new(Name::from_uri("/test/data")?)).await?;
+        assert_eq!(data.content().as_ref(), b"hello");
+        
+        // Channels speak the same format, NACKs included
+        let mut channel = client.open_channel(server_addr).await?;
+        let data = channel.send_interest(Interest::new(Name::from_uri("/test/channel")?)).await?;
+        assert_eq!(data.content().as_ref(), b"hello");
+        let err = channel.send_interest(Interest::new(Name::from_uri("/elsewhere")?)).await.unwrap_err();
+        assert!(matches!(err, Error::Nack { reason: NackReason::NoRoute, .. }));
+        channel.finish().await?;
+        
+        // Data too large for the compact length field is refused, not truncated
+        server.register_handler(Name::from_uri("/big")?, |interest| {
+            Ok(Data::new(interest.name().clone(), vec![0u8; 70_000]))
+        }).await?;
+        assert!(client.send_interest(server_addr, Interest::new(Name::from_uri("/big/1")?)).await.is_err());
+        Ok(())
+    }
+    
+    #[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread", worker_threads = 4))]
+    async fn test_recv_timeout_bounds_slow_producer() -> Result<()> {
+        let mut server = QuicTransport::new("127.0.0.1", 14370, 30, 65535).await?;
+        server.start_server().await?;
+        server.register_handler(Name::from_uri("/slow")?, |interest| {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(Data::new(interest.name().clone(), "late"))
+        }).await?;
+        
+        // A short receive timeout, far below the 30 s idle timeout
+        let config = Config { bind_address: "127.0.0.1".to_string(), port: 14371, recv_timeout: 200, ..Config::default() };
+        let client = QuicTransport::from_config(&config).await?;
+        assert_eq!(client.timeouts().recv, Duration::from_millis(200));
+        client.connect("127.0.0.1", 14370).await?;
+        let server_addr: SocketAddr = "127.0.0.1:14370".parse()?;
+        
+        let start = Instant::now();
+        let err = client.send_interest(server_addr, Interest::new(Name::from_uri("/slow/1")?)).await.unwrap_err();
+        assert!(matches!(err, Error::Timeout(_)), "{}", err);
+        assert!(start.elapsed() < Duration::from_millis(900));
+        
+        // The same bound applies on a channel
+        let mut channel = client.open_channel(server_addr).await?;
+        let err = channel.send_interest(Interest::new(Name::from_uri("/slow/2")?)).await.unwrap_err();
+        assert!(matches!(err, Error::Timeout(_)), "{}", err);
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//
+// μDCN Rate Limiting
+//
+// This module implements per-peer token buckets for incoming Interests. Each
+// remote address gets a bucket holding up to `burst` tokens that refills at
+// `interests_per_sec`; an Interest arriving at an empty bucket is answered
+// with a Congestion NACK instead of reaching a handler, so one flooding peer
+// cannot exhaust the handlers serving everyone else.
+//
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use dashmap::DashMap;
+use prometheus::{register_int_counter, IntCounter};
+use tracing::debug;
+
+use crate::ndn::{Interest, Nack, NackReason};
+
+lazy_static! {
+    static ref INTERESTS_RATE_LIMITED: IntCounter = register_int_counter!(
+        "udcn_interests_rate_limited_total", "Interests rejected by per-peer rate limiting").unwrap();
+}
+
+/// Token bucket of one peer
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens available, one per Interest
+    tokens: f64,
+    
+    /// Time tokens were last added
+    refilled_at: Instant,
+}
+
+/// Per-peer token bucket rate limiter for incoming Interests
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens added per second, or None for no limit
+    interests_per_sec: Option<f64>,
+    
+    /// Largest number of tokens a bucket holds
+    burst: f64,
+    
+    /// Bucket of each peer
+    buckets: DashMap<SocketAddr, TokenBucket>,
+    
+    /// Number of Interests rejected
+    limited: AtomicU64,
+}
+
+impl RateLimiter {
+    /// Create a rate limiter allowing each peer `interests_per_sec` Interests
+    /// per second in bursts of up to `burst`
+    ///
+    /// Without a rate every Interest is allowed. Without a burst, a peer may
+    /// send one second's worth of Interests at once.
+    pub fn new(interests_per_sec: Option<u32>, burst: Option<u32>) -> Self {
+        let rate = interests_per_sec.map(f64::from);
+        Self {
+            interests_per_sec: rate,
+            burst: burst.map(f64::from).or(rate).unwrap_or(0.0).max(1.0),
+            buckets: DashMap::new(),
+            limited: AtomicU64::new(0),
+        }
+    }
+    
+    /// Take a token for an Interest from `remote`, or reject it with a
+    /// Congestion NACK when the peer's bucket is empty
+    pub fn check(&self, remote: SocketAddr, interest: &Interest) -> std::result::Result<(), Nack> {
+        let rate = match self.interests_per_sec {
+            Some(rate) => rate,
+            None => return Ok(()),
+        };
+        
+        let now = Instant::now();
+        let mut bucket = self.buckets.entry(remote).or_insert_with(|| TokenBucket {
+            tokens: self.burst,
+            refilled_at: now,
+        });
+        
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(self.burst);
+        bucket.refilled_at = now;
+        
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        drop(bucket);
+        
+        self.limited.fetch_add(1, Ordering::Relaxed);
+        INTERESTS_RATE_LIMITED.inc();
+        debug!("Rate limiting Interest for {} from {}", interest.name(), remote);
+        Err(Nack::new(interest.clone(), NackReason::Congestion))
+    }
+    
+    /// Forget the bucket of a peer whose connection closed
+    pub fn remove(&self, remote: &SocketAddr) {
+        self.buckets.remove(remote);
+    }
+    
+    /// Get the number of Interests rejected
+    pub fn rate_limited(&self) -> u64 {
+        self.limited.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::Name;
+    use std::time::Duration;
+    
+    #[tokio::test]
+    async fn test_bucket_limits_each_peer() {
+        let limiter = RateLimiter::new(Some(20), Some(5));
+        let interest = Interest::new(Name::from_uri("/flood").unwrap());
+        let flooder: SocketAddr = "127.0.0.1:1000".parse().unwrap();
+        let other: SocketAddr = "127.0.0.1:2000".parse().unwrap();
+        
+        // The burst is allowed, then the peer is held to the rate
+        let allowed = (0..20).filter(|_| limiter.check(flooder, &interest).is_ok()).count();
+        assert_eq!(allowed, 5);
+        assert_eq!(limiter.rate_limited(), 15);
+        let nack = limiter.check(flooder, &interest).unwrap_err();
+        assert_eq!(nack.reason(), NackReason::Congestion);
+        
+        // Other peers have their own budget
+        assert!(limiter.check(other, &interest).is_ok());
+        
+        // Tokens come back over time
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(limiter.check(flooder, &interest).is_ok());
+        
+        // Without a rate nothing is limited
+        let unlimited = RateLimiter::new(None, None);
+        assert!((0..1000).all(|_| unlimited.check(flooder, &interest).is_ok()));
+    }
+}
@@ -6,55 +6,194 @@
 // and trust management.
 //
 
-// use std::sync::Arc;
-use std::time::SystemTime;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use ring::{rand, signature};
 use ring::rand::SecureRandom;
-use ring::signature::KeyPair;
-use rustls::{Certificate, PrivateKey};
+use ring::signature::KeyPair as _;
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerName};
 use sha2::{Sha256, Digest};
+use bytes::Bytes;
+use tracing::warn;
 
 use crate::error::Error;
+use crate::name::Name;
+use crate::ndn::{tlv, Data, Interest, Nack, NackReason};
 use crate::Result;
 
-/// Generate a self-signed certificate for the transport layer
+/// TLV type of the SignatureType element
+const SIGNATURE_TYPE: u8 = 0x1B;
+
+/// TLV type of the KeyLocator element
+const KEY_LOCATOR: u8 = 0x1C;
+
+/// Signature type code for Ed25519 signatures
+pub const SIGNATURE_ED25519: u8 = 5;
+
+/// TLV type of the SignatureNonce element of an InterestSignatureInfo
+const SIGNATURE_NONCE: u8 = 0x26;
+
+/// TLV type of the SignatureTime element of an InterestSignatureInfo
+const SIGNATURE_TIME: u8 = 0x28;
+
+/// How far the SignatureTime of a command may be from the local clock by
+/// default
+pub const DEFAULT_COMMAND_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Generate a self-signed X.509 certificate for the transport layer
+///
+/// The certificate is issued for "localhost", the server name the QUIC
+/// transports connect with by default, so peers can pin it.
 pub fn generate_self_signed_cert() -> Result<(Certificate, PrivateKey)> {
-    // This is a simplified implementation for the prototype
-    // In a real system, this would use proper X.509 certificate generation
-    
-    // Generate a random key pair
-    let rng = rand::SystemRandom::new();
-    let pkcs8_bytes = signature::Ed25519KeyPair::generate_pkcs8(&rng)
-        .map_err(|_| Error::Other("Failed to generate key pair".into()))?;
-    
-    // Extract the private key
-    let key_pair = signature::Ed25519KeyPair::from_pkcs8(pkcs8_bytes.as_ref())
-        .map_err(|_| Error::Other("Failed to parse key pair".into()))?;
-    
-    // Create a simple certificate (in a real system, this would be X.509)
-    let cert_data = format!(
-        "μDCN Self-Signed Certificate\n\
-         Issued: {}\n\
-         Subject: μDCN Node\n\
-         PublicKey: {:?}",
-        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs(),
-        key_pair.public_key().as_ref()
-    );
+    let generated = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])
+        .map_err(|e| Error::Other(format!("Failed to generate certificate: {}", e)))?;
+    
+    let cert_der = generated.serialize_der()
+        .map_err(|e| Error::Other(format!("Failed to encode certificate: {}", e)))?;
+    
+    Ok((Certificate(cert_der), PrivateKey(generated.serialize_private_key_der())))
+}
+
+/// Load a certificate chain, leaf first, and its private key from PEM files
+pub fn load_cert_chain(cert_path: &str, key_path: &str) -> Result<(Vec<Certificate>, PrivateKey)> {
+    let read = |path: &str| std::fs::read(path)
+        .map_err(|e| Error::IoError(format!("Failed to read {}: {}", path, e)));
+    
+    let chain: Vec<Certificate> = rustls_pemfile::certs(&mut &read(cert_path)?[..])
+        .map_err(|e| Error::ParsingError(format!("Invalid certificate file {}: {}", cert_path, e)))?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if chain.is_empty() {
+        return Err(Error::NotFound(format!("No certificate in {}", cert_path)));
+    }
+    
+    let key = rustls_pemfile::read_all(&mut &read(key_path)?[..])
+        .map_err(|e| Error::ParsingError(format!("Invalid key file {}: {}", key_path, e)))?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| Error::NotFound(format!("No private key in {}", key_path)))?;
     
-    // Sign the certificate data
-    let signature = key_pair.sign(cert_data.as_bytes());
+    Ok((chain, key))
+}
+
+/// Get the certificate chain and key a QUIC endpoint presents: the ones in
+/// the given PEM files, or else a self-signed certificate generated afresh,
+/// which peers cannot pin across restarts
+pub fn server_identity(cert_path: Option<&str>, key_path: Option<&str>) -> Result<(Vec<Certificate>, PrivateKey)> {
+    match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => load_cert_chain(cert_path, key_path),
+        (None, None) => generate_self_signed_cert().map(|(cert, key)| (vec![cert], key)),
+        _ => Err(Error::ConfigurationError("tls_cert_path and tls_key_path must be set together".into())),
+    }
+}
+
+/// How a QUIC client authenticates the certificate presented by a server
+#[derive(Debug, Clone, Default)]
+pub enum TlsConfig {
+    /// Accept any certificate
+    /// WARNING: This should only be used for development and testing
+    #[default]
+    Insecure,
+    
+    /// Accept only servers presenting one of these certificates
+    PinnedCert(Vec<Certificate>),
     
-    // Combine the certificate data and signature
-    let mut cert_bytes = Vec::new();
-    cert_bytes.extend_from_slice(cert_data.as_bytes());
-    cert_bytes.extend_from_slice(signature.as_ref());
+    /// Validate the certificate chain against the operating system's roots
+    ///
+    /// The server's certificate must be issued for the configured
+    /// `tls_server_name`.
+    SystemRoots,
+}
+
+/// An insecure certificate verifier that accepts any server certificate
+struct InsecureServerVerifier;
+
+impl ServerCertVerifier for InsecureServerVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        // WARNING: This accepts any certificate without verification
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+/// A certificate verifier accepting only an exact match of a pinned certificate
+///
+/// Pinning the certificate itself, rather than trusting it as a root, works
+/// for self-signed node certificates and ignores the server name.
+struct PinnedCertVerifier {
+    /// The certificates a server may present
+    pinned: Vec<Certificate>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        if self.pinned.contains(end_entity) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificateData("Server certificate is not pinned".into()))
+        }
+    }
+}
+
+/// Build the rustls client configuration for a TLS verification mode
+pub fn build_client_config(tls: &TlsConfig) -> Result<rustls::ClientConfig> {
+    let builder = rustls::ClientConfig::builder().with_safe_defaults();
     
-    // Convert to rustls Certificate and PrivateKey
-    let cert = Certificate(cert_bytes);
-    let key = PrivateKey(pkcs8_bytes.as_ref().to_vec());
+    let config = match tls {
+        TlsConfig::Insecure => builder
+            .with_custom_certificate_verifier(Arc::new(InsecureServerVerifier))
+            .with_no_client_auth(),
+        TlsConfig::PinnedCert(pinned) => {
+            if pinned.is_empty() {
+                return Err(Error::InvalidArgument("No certificates pinned".into()));
+            }
+            builder
+                .with_custom_certificate_verifier(Arc::new(PinnedCertVerifier { pinned: pinned.clone() }))
+                .with_no_client_auth()
+        }
+        TlsConfig::SystemRoots => {
+            let native = rustls_native_certs::load_native_certs()
+                .map_err(|e| Error::IoError(format!("Failed to load system root certificates: {}", e)))?;
+            
+            let mut roots = RootCertStore::empty();
+            let der: Vec<Vec<u8>> = native.into_iter().map(|cert| cert.0).collect();
+            let (added, _) = roots.add_parsable_certificates(&der);
+            if added == 0 {
+                return Err(Error::NotFound("No usable system root certificates".into()));
+            }
+            
+            builder
+                .with_root_certificates(roots)
+                .with_no_client_auth()
+        }
+    };
     
-    Ok((cert, key))
+    Ok(config)
 }
 
 /// Verify a signature against a data hash and public key
@@ -96,6 +235,9 @@ pub struct KeyStore {
     
     /// Map of key names to public keys
     public_keys: std::collections::HashMap<String, Vec<u8>>,
+    
+    /// Name of the key signing by default
+    default_key: Option<String>,
 }
 
 impl KeyStore {
@@ -104,9 +246,25 @@ impl KeyStore {
         Self {
             private_keys: std::collections::HashMap::new(),
             public_keys: std::collections::HashMap::new(),
+            default_key: None,
         }
     }
     
+    /// Get the name of the key signing by default: the one set with
+    /// `set_default_key`, or else the first key generated
+    pub fn default_key(&self) -> Option<&str> {
+        self.default_key.as_deref()
+    }
+    
+    /// Sign by default with a stored key
+    pub fn set_default_key(&mut self, name: &str) -> Result<()> {
+        if !self.private_keys.contains_key(name) {
+            return Err(Error::Other(format!("Private key not found: {}", name)));
+        }
+        self.default_key = Some(name.to_string());
+        Ok(())
+    }
+    
     /// Generate a new key pair and store it under the given name
     pub fn generate_key_pair(&mut self, name: &str) -> Result<()> {
         // Generate a random key pair
@@ -121,6 +279,7 @@ impl KeyStore {
         // Store the keys
         self.private_keys.insert(name.to_string(), pkcs8_bytes.as_ref().to_vec());
         self.public_keys.insert(name.to_string(), key_pair.public_key().as_ref().to_vec());
+        self.default_key.get_or_insert_with(|| name.to_string());
         
         Ok(())
     }
@@ -157,6 +316,424 @@ impl KeyStore {
     }
 }
 
+/// An Ed25519 key pair used to sign Data packets
+pub struct KeyPair {
+    /// Name carried in the KeyLocator of signed packets
+    name: String,
+    
+    /// PKCS#8 encoding of the key pair
+    pkcs8: Vec<u8>,
+    
+    /// Parsed key pair
+    key_pair: signature::Ed25519KeyPair,
+}
+
+impl KeyPair {
+    /// Generate a new random key pair with the given key name
+    pub fn generate(name: &str) -> Result<Self> {
+        let rng = rand::SystemRandom::new();
+        let pkcs8_bytes = signature::Ed25519KeyPair::generate_pkcs8(&rng)
+            .map_err(|_| Error::Other("Failed to generate key pair".into()))?;
+        
+        Self::from_pkcs8(name, pkcs8_bytes.as_ref())
+    }
+    
+    /// Load a key pair from its PKCS#8 encoding
+    pub fn from_pkcs8(name: &str, pkcs8: &[u8]) -> Result<Self> {
+        let key_pair = signature::Ed25519KeyPair::from_pkcs8(pkcs8)
+            .map_err(|_| Error::Other("Failed to parse key pair".into()))?;
+        
+        Ok(Self {
+            name: name.to_string(),
+            pkcs8: pkcs8.to_vec(),
+            key_pair,
+        })
+    }
+    
+    /// Get the key name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    /// Get the PKCS#8 encoding of the key pair
+    pub fn to_pkcs8(&self) -> &[u8] {
+        &self.pkcs8
+    }
+    
+    /// Get the public half of the key pair
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::new(&self.name, self.key_pair.public_key().as_ref())
+    }
+    
+    /// Sign a message
+    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.key_pair.sign(message).as_ref().to_vec()
+    }
+}
+
+impl std::fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The private key is never printed
+        f.debug_struct("KeyPair")
+            .field("name", &self.name)
+            .field("public_key", &hex::encode(self.key_pair.public_key().as_ref()))
+            .finish()
+    }
+}
+
+/// An Ed25519 public key used to verify Data packets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    /// Name the key is published under
+    name: String,
+    
+    /// Raw 32-byte public key
+    bytes: Bytes,
+}
+
+impl PublicKey {
+    /// Create a public key from its name and raw bytes
+    pub fn new(name: &str, bytes: &[u8]) -> Self {
+        Self {
+            name: name.to_string(),
+            bytes: Bytes::copy_from_slice(bytes),
+        }
+    }
+    
+    /// Get the key name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    /// Get the raw public key
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    
+    /// Verify a signature over a message
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> Result<()> {
+        verify_signature(message, signature, &self.bytes)
+    }
+}
+
+/// Encode a SignatureInfo with an Ed25519 SignatureType and a KeyLocator naming the key
+pub fn encode_signature_info(key_name: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(3 + tlv::tlv_size(key_name.len()));
+    tlv::write_tlv(&mut buf, SIGNATURE_TYPE, &[SIGNATURE_ED25519]);
+    tlv::write_tlv(&mut buf, KEY_LOCATOR, key_name.as_bytes());
+    buf
+}
+
+/// Extract the SignatureType and key name from a SignatureInfo
+pub fn decode_signature_info(signature_info: &[u8]) -> Option<(u8, String)> {
+    let mut buf = Bytes::copy_from_slice(signature_info);
+    let mut signature_type = None;
+    let mut key_name = None;
+    
+    while !buf.is_empty() {
+        let (typ, value) = tlv::read_tlv(&mut buf).ok()?;
+        match u8::try_from(typ) {
+            Ok(SIGNATURE_TYPE) if value.len() == 1 => signature_type = Some(value[0]),
+            Ok(KEY_LOCATOR) => key_name = Some(String::from_utf8(value.to_vec()).ok()?),
+            _ => {}
+        }
+    }
+    
+    Some((signature_type?, key_name?))
+}
+
+/// Encode an InterestSignatureInfo: a SignatureInfo followed by the
+/// SignatureNonce and the SignatureTime in milliseconds since the epoch
+pub fn encode_interest_signature_info(key_name: &str, time_ms: u64, nonce: &[u8]) -> Vec<u8> {
+    let mut buf = encode_signature_info(key_name);
+    tlv::write_tlv(&mut buf, SIGNATURE_NONCE, nonce);
+    tlv::write_tlv(&mut buf, SIGNATURE_TIME, &time_ms.to_be_bytes());
+    buf
+}
+
+/// Extract the SignatureTime and SignatureNonce from an InterestSignatureInfo
+pub fn decode_signature_freshness(signature_info: &[u8]) -> Option<(u64, Vec<u8>)> {
+    let mut buf = Bytes::copy_from_slice(signature_info);
+    let mut time = None;
+    let mut nonce = None;
+    
+    while !buf.is_empty() {
+        let (typ, value) = tlv::read_tlv(&mut buf).ok()?;
+        match u8::try_from(typ) {
+            Ok(SIGNATURE_TIME) if !value.is_empty() && value.len() <= 8 => {
+                time = Some(value.iter().fold(0u64, |time, byte| time << 8 | *byte as u64));
+            }
+            Ok(SIGNATURE_NONCE) => nonce = Some(value.to_vec()),
+            _ => {}
+        }
+    }
+    
+    Some((time?, nonce?))
+}
+
+/// Get the current time in milliseconds since the epoch
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+/// A policy constraining which keys may sign which Data names
+///
+/// Each rule pairs a Data name prefix with a key name prefix: Data under the
+/// first may only be signed by keys under the second. A Data name no rule
+/// covers is rejected.
+#[derive(Debug, Clone, Default)]
+pub struct TrustSchema {
+    /// (data_prefix, signer_prefix) pairs
+    rules: Vec<(Name, Name)>,
+}
+
+impl TrustSchema {
+    /// Create a schema without rules, rejecting every pairing
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Allow keys under `signer_prefix` to sign Data under `data_prefix`
+    pub fn add_rule(&mut self, data_prefix: Name, signer_prefix: Name) {
+        self.rules.push((data_prefix, signer_prefix));
+    }
+    
+    /// Get the (data_prefix, signer_prefix) rules
+    pub fn rules(&self) -> &[(Name, Name)] {
+        &self.rules
+    }
+    
+    /// Check that a key may sign a Data name under some rule
+    pub fn validate(&self, data_name: &Name, signer_name: &Name) -> Result<()> {
+        let allowed = self.rules.iter().any(|(data_prefix, signer_prefix)| {
+            data_name.starts_with(data_prefix) && signer_name.starts_with(signer_prefix)
+        });
+        
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::TrustSchemaViolation(format!("{} may not sign {}", signer_name, data_name)))
+        }
+    }
+}
+
+/// Public keys of Data signers, looked up by the key name in a KeyLocator
+#[derive(Debug, Clone, Default)]
+pub struct CertStore {
+    /// Map of key names to public keys
+    keys: HashMap<Name, PublicKey>,
+    
+    /// Policy the signer of a Data must satisfy, if any
+    schema: Option<TrustSchema>,
+}
+
+impl CertStore {
+    /// Create a new empty certificate store
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Store the public key published under a key name, returning any it replaces
+    pub fn insert_cert(&mut self, name: Name, key: PublicKey) -> Option<PublicKey> {
+        self.keys.insert(name, key)
+    }
+    
+    /// Look up the public key published under a key name
+    pub fn resolve(&self, name: &Name) -> Option<PublicKey> {
+        self.keys.get(name).cloned()
+    }
+    
+    /// Require verified Data to also satisfy a trust schema
+    pub fn set_trust_schema(&mut self, schema: TrustSchema) {
+        self.schema = Some(schema);
+    }
+    
+    /// Get the trust schema verified Data must satisfy, if any
+    pub fn trust_schema(&self) -> Option<&TrustSchema> {
+        self.schema.as_ref()
+    }
+    
+    /// Get the number of keys in the store
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+    
+    /// Check if the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// Verify a Data packet with the key its KeyLocator names
+///
+/// Fails with `Error::KeyNotFound` when the store has no such key, so the
+/// caller can fetch the certificate and retry. If the store has a trust
+/// schema, the key must also be allowed to sign the Data's name.
+pub fn verify_data_with_store(data: &Data, store: &CertStore) -> Result<()> {
+    let key_name = key_locator_name(data.signature_info())?;
+    
+    // No need to fetch a key the schema would not accept
+    if let Some(schema) = &store.schema {
+        schema.validate(data.name(), &key_name)?;
+    }
+    
+    let key = store.resolve(&key_name).ok_or(Error::KeyNotFound(key_name))?;
+    data.verify(&key)
+}
+
+/// Verify a signed Interest with the key its KeyLocator names
+///
+/// Fails as `verify_data_with_store` does, and with
+/// `Error::SignatureVerification` when the Interest is not signed.
+pub fn verify_interest_with_store(interest: &Interest, store: &CertStore) -> Result<()> {
+    if !interest.is_signed() {
+        return Err(Error::SignatureVerification(format!("{} is not signed", interest.name())));
+    }
+    let key_name = key_locator_name(interest.signature_info())?;
+    
+    if let Some(schema) = &store.schema {
+        schema.validate(interest.name(), &key_name)?;
+    }
+    
+    let key = store.resolve(&key_name).ok_or(Error::KeyNotFound(key_name))?;
+    interest.verify(&key)
+}
+
+/// Get the key name in the KeyLocator of a SignatureInfo value
+fn key_locator_name(signature_info: &[u8]) -> Result<Name> {
+    let (_, key_name) = decode_signature_info(signature_info)
+        .ok_or_else(|| Error::SignatureVerification("Missing KeyLocator".into()))?;
+    
+    Name::from_uri(&key_name)
+        .map_err(|e| Error::SignatureVerification(format!("Invalid KeyLocator {}: {}", key_name, e)))
+}
+
+/// Certificate store shared by a transport and the engine checking commands
+pub type SharedCertStore = Arc<parking_lot::RwLock<CertStore>>;
+
+/// Commands accepted recently, to reject replayed ones
+#[derive(Debug, Default)]
+struct ReplayState {
+    /// SignatureTime of the last command accepted from each key
+    last_time: HashMap<Name, u64>,
+    
+    /// SignatureNonces accepted within the grace period, with their time
+    nonces: HashMap<Vec<u8>, u64>,
+}
+
+/// Authentication of command Interests under a control prefix
+///
+/// A command must be signed by a key in the certificate store, with a
+/// SignatureTime within the grace period of the local clock, later than the
+/// last command of the same key and a SignatureNonce not seen before; any
+/// other command is rejected with a NotAuth NACK before it reaches a handler.
+#[derive(Debug)]
+pub struct CommandValidator {
+    /// Prefix of the commands, or None when commands need no signature
+    prefix: Option<Name>,
+    
+    /// Keys allowed to sign commands
+    certs: SharedCertStore,
+    
+    /// How far a SignatureTime may be from the local clock
+    grace_period: Duration,
+    
+    /// Commands accepted recently
+    replay: parking_lot::Mutex<ReplayState>,
+    
+    /// Number of commands rejected
+    rejected: AtomicU64,
+}
+
+impl CommandValidator {
+    /// Require Interests under `prefix` to be signed by a key in `certs`
+    ///
+    /// Without a prefix every Interest is accepted.
+    pub fn new(prefix: Option<Name>, certs: SharedCertStore) -> Self {
+        Self {
+            prefix,
+            certs,
+            grace_period: DEFAULT_COMMAND_GRACE_PERIOD,
+            replay: Default::default(),
+            rejected: AtomicU64::new(0),
+        }
+    }
+    
+    /// Set how far the SignatureTime of a command may be from the local clock
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+    
+    /// Get how far the SignatureTime of a command may be from the local clock
+    pub fn grace_period(&self) -> Duration {
+        self.grace_period
+    }
+    
+    /// Get the prefix of the commands checked, if any
+    pub fn prefix(&self) -> Option<&Name> {
+        self.prefix.as_ref()
+    }
+    
+    /// Get the keys allowed to sign commands
+    pub fn certs(&self) -> &SharedCertStore {
+        &self.certs
+    }
+    
+    /// Accept an Interest outside the command prefix or carrying a valid
+    /// signature, or reject it with a NotAuth NACK
+    pub fn check(&self, interest: &Interest) -> std::result::Result<(), Nack> {
+        match &self.prefix {
+            Some(prefix) if interest.name().starts_with(prefix) => {}
+            _ => return Ok(()),
+        }
+        
+        let result = verify_interest_with_store(interest, &self.certs.read())
+            .and_then(|()| self.check_freshness(interest));
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                warn!("Rejecting command {}: {}", interest.name(), e);
+                Err(Nack::new(interest.clone(), NackReason::NotAuth))
+            }
+        }
+    }
+    
+    /// Accept a verified command once, within the grace period, and after
+    /// the last command of the same key
+    fn check_freshness(&self, interest: &Interest) -> Result<()> {
+        let stale = |reason: &str| Error::SignatureVerification(format!("{} {}", interest.name(), reason));
+        let (time, nonce) = decode_signature_freshness(interest.signature_info())
+            .ok_or_else(|| stale("has no SignatureTime and SignatureNonce"))?;
+        let key_name = key_locator_name(interest.signature_info())?;
+        
+        let now = now_ms();
+        let grace = self.grace_period.as_millis() as u64;
+        if time.abs_diff(now) > grace {
+            return Err(stale("was signed outside the grace period"));
+        }
+        
+        let mut replay = self.replay.lock();
+        if matches!(replay.last_time.get(&key_name), Some(last) if time < *last) {
+            return Err(stale("is older than the last command of its key"));
+        }
+        if replay.nonces.contains_key(&nonce) {
+            return Err(stale("replays a SignatureNonce"));
+        }
+        
+        replay.nonces.retain(|_, seen| now.saturating_sub(*seen) <= grace);
+        replay.nonces.insert(nonce, time);
+        replay.last_time.insert(key_name, time);
+        Ok(())
+    }
+    
+    /// Get the number of commands rejected
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+}
+
 /// A certificate chain for use in TLS
 pub struct CertificateChain {
     /// The certificates in the chain
@@ -221,6 +798,74 @@ mod tests {
         assert!(!key.0.is_empty());
     }
     
+    // Connect to a fresh server presenting `cert` with the given TLS mode
+    async fn handshake(cert: Certificate, key: PrivateKey, tls: &TlsConfig) -> std::result::Result<(), quinn::ConnectionError> {
+        handshake_chain(vec![cert], key, tls).await
+    }
+    
+    // Connect to a fresh server presenting `chain` with the given TLS mode
+    async fn handshake_chain(chain: Vec<Certificate>, key: PrivateKey, tls: &TlsConfig) -> std::result::Result<(), quinn::ConnectionError> {
+        let server_config = quinn::ServerConfig::with_single_cert(chain, key).unwrap();
+        let server = quinn::Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        
+        let client = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        let client_config = quinn::ClientConfig::new(Arc::new(build_client_config(tls).unwrap()));
+        client.connect_with(client_config, server.local_addr().unwrap(), "localhost").unwrap()
+            .await
+            .map(|_| ())
+    }
+    
+    #[tokio::test]
+    async fn test_pinned_cert_handshake() {
+        let (cert, key) = generate_self_signed_cert().unwrap();
+        let (other, _) = generate_self_signed_cert().unwrap();
+        
+        // The pinned certificate is accepted
+        let pinned = TlsConfig::PinnedCert(vec![other.clone(), cert.clone()]);
+        assert!(handshake(cert.clone(), key.clone(), &pinned).await.is_ok());
+        
+        // Any other certificate is rejected, even though it is valid
+        let unpinned = TlsConfig::PinnedCert(vec![other]);
+        assert!(handshake(cert.clone(), key.clone(), &unpinned).await.is_err());
+        
+        // Without pinning it is accepted again
+        assert!(handshake(cert, key, &TlsConfig::Insecure).await.is_ok());
+        assert!(build_client_config(&TlsConfig::PinnedCert(Vec::new())).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_server_identity_from_pem_files() {
+        let generated = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let dir = std::env::temp_dir().join(format!("udcn-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, generated.serialize_pem().unwrap()).unwrap();
+        std::fs::write(&key_path, generated.serialize_private_key_pem()).unwrap();
+        let (cert_path, key_path) = (cert_path.to_str().unwrap(), key_path.to_str().unwrap());
+        
+        // Every load presents the same certificate, so a pin survives restarts
+        let (chain, key) = server_identity(Some(cert_path), Some(key_path)).unwrap();
+        assert_eq!(chain.len(), 1);
+        let (reloaded, _) = server_identity(Some(cert_path), Some(key_path)).unwrap();
+        assert_eq!(reloaded, chain);
+        let pinned = TlsConfig::PinnedCert(chain.clone());
+        assert!(handshake_chain(chain, key, &pinned).await.is_ok());
+        
+        // Without files a new certificate is generated each time
+        let (first, _) = server_identity(None, None).unwrap();
+        let (second, _) = server_identity(None, None).unwrap();
+        assert_ne!(first, second);
+        
+        // Both files are needed, and must hold what they are for
+        assert!(matches!(server_identity(Some(cert_path), None), Err(Error::ConfigurationError(_))));
+        assert!(matches!(load_cert_chain(key_path, key_path), Err(Error::NotFound(_))));
+        assert!(matches!(load_cert_chain(cert_path, cert_path), Err(Error::NotFound(_))));
+        assert!(load_cert_chain("/nonexistent/cert.pem", key_path).is_err());
+        
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    
     #[test]
     fn test_key_store() {
         let mut key_store = KeyStore::new();
@@ -245,4 +890,135 @@ mod tests {
         let result = key_store.verify("test", data, &signature.unwrap());
         assert!(result.is_ok());
     }
+    
+    #[test]
+    fn test_signature_info_round_trip() {
+        let key = KeyPair::generate("/udcn/producer/KEY/1").unwrap();
+        let info = encode_signature_info(key.name());
+        
+        assert_eq!(decode_signature_info(&info),
+                   Some((SIGNATURE_ED25519, "/udcn/producer/KEY/1".to_string())));
+        
+        // The public key verifies what the key pair signs, and nothing else
+        let public_key = key.public_key();
+        let signature = key.sign(b"message");
+        assert!(public_key.verify(b"message", &signature).is_ok());
+        assert!(public_key.verify(b"massage", &signature).is_err());
+        
+        // Reloading from PKCS#8 gives the same key
+        let reloaded = KeyPair::from_pkcs8(key.name(), key.to_pkcs8()).unwrap();
+        assert_eq!(reloaded.public_key(), public_key);
+    }
+    
+    #[test]
+    fn test_verify_data_with_store() {
+        let key_name = Name::from_uri("/udcn/producer/KEY/1").unwrap();
+        let key = KeyPair::generate("/udcn/producer/KEY/1").unwrap();
+        let data = Data::new(Name::from_uri("/udcn/producer/data").unwrap(), "signed").sign(&key);
+        
+        // Without the signer's key, the caller learns which key to fetch
+        let mut store = CertStore::new();
+        match verify_data_with_store(&data, &store) {
+            Err(Error::KeyNotFound(name)) => assert_eq!(name, key_name),
+            other => panic!("Expected KeyNotFound, got {:?}", other),
+        }
+        
+        // Once the key is stored, the Data verifies
+        store.insert_cert(key_name.clone(), key.public_key());
+        assert_eq!(store.resolve(&key_name), Some(key.public_key()));
+        verify_data_with_store(&data, &store).unwrap();
+        
+        // A different key published under the same name does not
+        let impostor = KeyPair::generate("/udcn/producer/KEY/1").unwrap();
+        store.insert_cert(key_name, impostor.public_key());
+        assert!(matches!(verify_data_with_store(&data, &store), Err(Error::SignatureVerification(_))));
+    }
+    
+    #[test]
+    fn test_trust_schema() {
+        let name = |uri: &str| Name::from_uri(uri).unwrap();
+        let mut schema = TrustSchema::new();
+        schema.add_rule(name("/edu/site"), name("/edu/site/KEY"));
+        
+        // A site key may sign site Data, but not another site's
+        schema.validate(&name("/edu/site/paper/1"), &name("/edu/site/KEY/1")).unwrap();
+        assert!(matches!(schema.validate(&name("/edu/other/paper/1"), &name("/edu/site/KEY/1")),
+                         Err(Error::TrustSchemaViolation(_))));
+        assert!(schema.validate(&name("/edu/site/paper/1"), &name("/edu/other/KEY/1")).is_err());
+        
+        // Verification checks both the schema and the signature
+        let site_key = KeyPair::generate("/edu/site/KEY/1").unwrap();
+        let other_key = KeyPair::generate("/edu/other/KEY/1").unwrap();
+        let mut store = CertStore::new();
+        store.insert_cert(name("/edu/site/KEY/1"), site_key.public_key());
+        store.insert_cert(name("/edu/other/KEY/1"), other_key.public_key());
+        store.set_trust_schema(schema);
+        
+        let allowed = Data::new(name("/edu/site/paper/1"), "paper").sign(&site_key);
+        verify_data_with_store(&allowed, &store).unwrap();
+        
+        let cross = Data::new(name("/edu/site/paper/2"), "paper").sign(&other_key);
+        assert!(matches!(verify_data_with_store(&cross, &store), Err(Error::TrustSchemaViolation(_))));
+    }
+    
+    #[test]
+    fn test_command_validator() {
+        let name = |uri: &str| Name::from_uri(uri).unwrap();
+        let operator = KeyPair::generate("/udcn/operator/KEY/1").unwrap();
+        let stranger = KeyPair::generate("/udcn/stranger/KEY/1").unwrap();
+        let certs: SharedCertStore = Default::default();
+        certs.write().insert_cert(name("/udcn/operator/KEY/1"), operator.public_key());
+        let validator = CommandValidator::new(Some(name("/localhost/nfd")), certs.clone());
+        
+        // A command signed by a stored key is accepted
+        let command = Interest::new(name("/localhost/nfd/rib/register/udcn")).sign(&operator);
+        validator.check(&command).unwrap();
+        
+        // Unsigned commands and commands signed by unknown keys are not
+        let unsigned = Interest::new(name("/localhost/nfd/rib/register/udcn"));
+        assert_eq!(validator.check(&unsigned).unwrap_err().reason(), NackReason::NotAuth);
+        let foreign = Interest::new(name("/localhost/nfd/rib/register/udcn")).sign(&stranger);
+        assert_eq!(validator.check(&foreign).unwrap_err().reason(), NackReason::NotAuth);
+        assert_eq!(validator.rejected(), 2);
+        
+        // Keys added to the shared store are trusted from then on
+        certs.write().insert_cert(name("/udcn/stranger/KEY/1"), stranger.public_key());
+        validator.check(&foreign).unwrap();
+        
+        // Interests outside the prefix, or without one, need no signature
+        validator.check(&Interest::new(name("/udcn/data"))).unwrap();
+        CommandValidator::new(None, certs).check(&unsigned).unwrap();
+    }
+    
+    #[test]
+    fn test_command_replay_window() {
+        let name = |uri: &str| Name::from_uri(uri).unwrap();
+        let operator = KeyPair::generate("/udcn/operator/KEY/1").unwrap();
+        let certs: SharedCertStore = Default::default();
+        certs.write().insert_cert(name("/udcn/operator/KEY/1"), operator.public_key());
+        let validator = CommandValidator::new(Some(name("/localhost/nfd")), certs)
+            .with_grace_period(Duration::from_millis(200));
+        
+        // Signing covers a fresh SignatureTime and SignatureNonce
+        let command = Interest::new(name("/localhost/nfd/rib/register/udcn")).sign(&operator);
+        let (time, nonce) = decode_signature_freshness(command.signature_info()).unwrap();
+        assert!(time.abs_diff(now_ms()) < 1000);
+        assert_eq!(nonce.len(), 8);
+        
+        // A command is accepted once
+        validator.check(&command).unwrap();
+        assert_eq!(validator.check(&command).unwrap_err().reason(), NackReason::NotAuth);
+        
+        // A command signed earlier than the last one of the key is stale
+        let earlier = Interest::new(name("/localhost/nfd/rib/unregister/udcn")).sign(&operator);
+        std::thread::sleep(Duration::from_millis(5));
+        validator.check(&Interest::new(name("/localhost/nfd/rib/register/other")).sign(&operator)).unwrap();
+        assert!(validator.check(&earlier).is_err());
+        
+        // So is one kept past the grace period
+        let late = Interest::new(name("/localhost/nfd/rib/register/late")).sign(&operator);
+        std::thread::sleep(Duration::from_millis(250));
+        assert!(validator.check(&late).is_err());
+        assert_eq!(validator.rejected(), 3);
+    }
 }
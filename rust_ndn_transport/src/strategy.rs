@@ -0,0 +1,622 @@
+//
+// μDCN Forwarding Strategies
+//
+// This module implements forwarding strategies that decide which next-hop
+// faces an Interest is sent to. Next-hops are registered under a name prefix
+// and an Interest is forwarded to the next-hops of its longest matching prefix.
+// The multicast strategy sends a copy of the Interest to several of them and
+// returns the first Data that comes back; the load-balancing strategy sends
+// each Interest to one of them in turn, skipping next-hops marked as failed.
+//
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use prometheus::{register_int_counter_vec, IntCounterVec};
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
+
+use crate::error::{Error, ErrorKind};
+use crate::face::NdnFace;
+use crate::name::Name;
+use crate::ndn::{Data, Interest};
+use crate::Result;
+
+lazy_static! {
+    static ref INTERESTS_FORWARDED: IntCounterVec = register_int_counter_vec!(
+        "udcn_interests_forwarded_total", "Interests forwarded upstream", &["prefix"]).unwrap();
+    static ref INTERESTS_SATISFIED: IntCounterVec = register_int_counter_vec!(
+        "udcn_interests_satisfied_total", "Forwarded Interests satisfied upstream", &["prefix"]).unwrap();
+    static ref INTERESTS_NACKED: IntCounterVec = register_int_counter_vec!(
+        "udcn_interests_nacked_total", "Forwarded Interests rejected by every next-hop", &["prefix"]).unwrap();
+    static ref INTERESTS_TIMED_OUT: IntCounterVec = register_int_counter_vec!(
+        "udcn_interests_timed_out_total", "Forwarded Interests whose lifetime expired", &["prefix"]).unwrap();
+    static ref INTERESTS_FAILED: IntCounterVec = register_int_counter_vec!(
+        "udcn_interests_failed_total", "Forwarded Interests that failed without a NACK or timeout", &["prefix"]).unwrap();
+}
+
+/// A next-hop face with its routing cost
+#[derive(Clone)]
+pub struct NextHop {
+    /// Registration ID of the next-hop
+    pub id: u64,
+    
+    /// Prefix the next-hop serves
+    pub prefix: Name,
+    
+    /// Face the Interest is forwarded on
+    pub face: Arc<dyn NdnFace>,
+    
+    /// Routing cost (lower is preferred)
+    pub cost: u32,
+}
+
+impl std::fmt::Debug for NextHop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NextHop")
+            .field("id", &self.id)
+            .field("prefix", &self.prefix)
+            .field("cost", &self.cost)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Outcome counters for Interests forwarded under one prefix
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForwardingStats {
+    /// Interests forwarded to at least one next-hop
+    pub forwarded: u64,
+    
+    /// Interests answered with Data
+    pub satisfied: u64,
+    
+    /// Interests every next-hop rejected
+    pub nacked: u64,
+    
+    /// Interests whose lifetime expired before any Data arrived
+    pub timed_out: u64,
+    
+    /// Interests that failed otherwise (e.g. the next-hop was unreachable)
+    pub failed: u64,
+}
+
+/// Forwarding outcome of a single Interest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Satisfied,
+    Nacked,
+    TimedOut,
+    Failed,
+}
+
+impl Outcome {
+    /// Classify the result of forwarding an Interest
+    fn of(result: &Result<Data>) -> Self {
+        match result {
+            Ok(_) => Outcome::Satisfied,
+            Err(e) => match e.kind() {
+                ErrorKind::Nack => Outcome::Nacked,
+                ErrorKind::Timeout => Outcome::TimedOut,
+                _ => Outcome::Failed,
+            },
+        }
+    }
+}
+
+/// Forwarding counters by matched prefix, also exported with a prefix label
+#[derive(Debug, Default)]
+pub(crate) struct ForwardingCounters {
+    stats: DashMap<Name, ForwardingStats>,
+}
+
+impl ForwardingCounters {
+    /// Count an Interest forwarded under the prefix
+    pub(crate) fn record_forwarded(&self, prefix: &Name) {
+        self.stats.entry(prefix.clone()).or_default().forwarded += 1;
+        INTERESTS_FORWARDED.with_label_values(&[&prefix.to_string()]).inc();
+    }
+    
+    /// Count the outcome of an Interest forwarded under the prefix
+    pub(crate) fn record_result(&self, prefix: &Name, result: &Result<Data>) {
+        let label = prefix.to_string();
+        let mut stats = self.stats.entry(prefix.clone()).or_default();
+        
+        match Outcome::of(result) {
+            Outcome::Satisfied => {
+                stats.satisfied += 1;
+                INTERESTS_SATISFIED.with_label_values(&[&label]).inc();
+            }
+            Outcome::Nacked => {
+                stats.nacked += 1;
+                INTERESTS_NACKED.with_label_values(&[&label]).inc();
+            }
+            Outcome::TimedOut => {
+                stats.timed_out += 1;
+                INTERESTS_TIMED_OUT.with_label_values(&[&label]).inc();
+            }
+            Outcome::Failed => {
+                stats.failed += 1;
+                INTERESTS_FAILED.with_label_values(&[&label]).inc();
+            }
+        }
+    }
+    
+    /// Get the counters for a prefix
+    pub(crate) fn get(&self, prefix: &Name) -> ForwardingStats {
+        self.stats.get(prefix).map(|stats| *stats).unwrap_or_default()
+    }
+}
+
+/// Get the next-hops registered under the longest prefix matching `name`,
+/// in registration order
+fn longest_match(next_hops: &[NextHop], name: &Name) -> Vec<NextHop> {
+    let longest = next_hops
+        .iter()
+        .filter(|hop| name.starts_with(&hop.prefix))
+        .map(|hop| hop.prefix.len())
+        .max();
+    
+    match longest {
+        Some(len) => next_hops
+            .iter()
+            .filter(|hop| hop.prefix.len() == len && name.starts_with(&hop.prefix))
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Configuration for the multicast strategy
+#[derive(Debug, Clone, Default)]
+pub struct MulticastConfig {
+    /// Maximum number of next-hops that receive a copy of each Interest
+    /// (None means every next-hop)
+    pub max_fanout: Option<usize>,
+}
+
+/// Strategy forwarding each Interest to multiple next-hops
+#[derive(Debug)]
+pub struct MulticastStrategy {
+    /// Strategy configuration
+    config: MulticastConfig,
+    
+    /// Registered next-hops
+    next_hops: RwLock<Vec<NextHop>>,
+    
+    /// Next registration ID
+    next_id: AtomicU64,
+    
+    /// Forwarding counters by matched prefix
+    stats: ForwardingCounters,
+}
+
+impl MulticastStrategy {
+    /// Create a new multicast strategy
+    pub fn new(config: MulticastConfig) -> Self {
+        Self {
+            config,
+            next_hops: RwLock::new(Vec::new()),
+            next_id: AtomicU64::new(1),
+            stats: ForwardingCounters::default(),
+        }
+    }
+    
+    /// Get the strategy configuration
+    pub fn config(&self) -> &MulticastConfig {
+        &self.config
+    }
+    
+    /// Add a next-hop face for a prefix with the given cost
+    pub fn add_next_hop(&self, prefix: Name, face: Arc<dyn NdnFace>, cost: u32) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.next_hops.write().push(NextHop { id, prefix, face, cost });
+        id
+    }
+    
+    /// Remove a next-hop by registration ID
+    pub fn remove_next_hop(&self, id: u64) -> Result<()> {
+        let mut next_hops = self.next_hops.write();
+        let before = next_hops.len();
+        next_hops.retain(|hop| hop.id != id);
+        
+        if next_hops.len() < before {
+            Ok(())
+        } else {
+            Err(Error::NotFound(format!("Next-hop {}", id)))
+        }
+    }
+    
+    /// Get the forwarding counters for a registered prefix
+    pub fn forwarding_stats(&self, prefix: &Name) -> ForwardingStats {
+        self.stats.get(prefix)
+    }
+    
+    /// Select the next-hops that receive an Interest for `name`: the
+    /// lowest-cost ones under the longest matching prefix, capped at `max_fanout`
+    pub fn select_next_hops(&self, name: &Name) -> Vec<NextHop> {
+        let mut next_hops = longest_match(&self.next_hops.read(), name);
+        
+        // Stable sort keeps registration order among equal costs
+        next_hops.sort_by_key(|hop| hop.cost);
+        
+        if let Some(max_fanout) = self.config.max_fanout {
+            next_hops.truncate(max_fanout);
+        }
+        
+        next_hops
+    }
+    
+    /// Forward an Interest to the selected next-hops and return the first Data
+    ///
+    /// Fails with a timeout if no Data arrives within the Interest lifetime.
+    pub async fn forward(&self, interest: Interest) -> Result<Data> {
+        let next_hops = self.select_next_hops(interest.name());
+        if next_hops.is_empty() {
+            return Err(Error::NoConnections);
+        }
+        
+        let prefix = next_hops[0].prefix.clone();
+        let lifetime = interest.get_lifetime();
+        self.stats.record_forwarded(&prefix);
+        
+        debug!("Multicasting Interest for {} to {} next-hops", interest.name(), next_hops.len());
+        
+        // Every selected next-hop gets its copy in its own task
+        let mut attempts = JoinSet::new();
+        for hop in next_hops {
+            let interest = interest.clone();
+            attempts.spawn(async move { hop.face.send_interest(interest).await });
+        }
+        
+        // First Data wins; if every next-hop fails, report the last error
+        let first_data = async {
+            let mut last_error = Error::NoConnections;
+            while let Some(result) = attempts.join_next().await {
+                match result {
+                    Ok(Ok(data)) => return Ok(data),
+                    Ok(Err(e)) => last_error = e,
+                    Err(e) => last_error = Error::Other(format!("Forwarding task failed: {}", e)),
+                }
+            }
+            Err(last_error)
+        };
+        
+        let result = match tokio::time::timeout(lifetime, first_data).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout(format!("Interest {} expired after {:?}", interest.name(), lifetime))),
+        };
+        
+        // Nobody waits for the next-hops still outstanding
+        attempts.abort_all();
+        
+        if let Err(e) = &result {
+            warn!("Forwarding Interest for {} failed: {}", interest.name(), e);
+        }
+        self.stats.record_result(&prefix, &result);
+        
+        result
+    }
+}
+
+/// Strategy forwarding each Interest to one next-hop, in round-robin order
+#[derive(Debug)]
+pub struct LoadBalanceStrategy {
+    /// Registered next-hops
+    next_hops: RwLock<Vec<NextHop>>,
+    
+    /// Registration IDs of next-hops marked as failed
+    failed: RwLock<HashSet<u64>>,
+    
+    /// Round-robin position by matched prefix
+    cursors: DashMap<Name, usize>,
+    
+    /// Next registration ID
+    next_id: AtomicU64,
+    
+    /// Forwarding counters by matched prefix
+    stats: ForwardingCounters,
+}
+
+impl LoadBalanceStrategy {
+    /// Create a new load-balancing strategy
+    pub fn new() -> Self {
+        Self {
+            next_hops: RwLock::new(Vec::new()),
+            failed: RwLock::new(HashSet::new()),
+            cursors: DashMap::new(),
+            next_id: AtomicU64::new(1),
+            stats: ForwardingCounters::default(),
+        }
+    }
+    
+    /// Add a next-hop face for a prefix with the given cost
+    ///
+    /// The cost is kept for reporting; every healthy next-hop gets an equal share.
+    pub fn add_next_hop(&self, prefix: Name, face: Arc<dyn NdnFace>, cost: u32) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.next_hops.write().push(NextHop { id, prefix, face, cost });
+        id
+    }
+    
+    /// Remove a next-hop by registration ID
+    pub fn remove_next_hop(&self, id: u64) -> Result<()> {
+        let mut next_hops = self.next_hops.write();
+        let before = next_hops.len();
+        next_hops.retain(|hop| hop.id != id);
+        
+        if next_hops.len() < before {
+            self.failed.write().remove(&id);
+            Ok(())
+        } else {
+            Err(Error::NotFound(format!("Next-hop {}", id)))
+        }
+    }
+    
+    /// Mark a next-hop as failed (skipped) or healthy again
+    pub fn set_failed(&self, id: u64, failed: bool) -> Result<()> {
+        if !self.next_hops.read().iter().any(|hop| hop.id == id) {
+            return Err(Error::NotFound(format!("Next-hop {}", id)));
+        }
+        
+        let mut marked = self.failed.write();
+        if failed {
+            marked.insert(id);
+        } else {
+            marked.remove(&id);
+        }
+        Ok(())
+    }
+    
+    /// Check whether a next-hop is marked as failed
+    pub fn is_failed(&self, id: u64) -> bool {
+        self.failed.read().contains(&id)
+    }
+    
+    /// Get the forwarding counters for a registered prefix
+    pub fn forwarding_stats(&self, prefix: &Name) -> ForwardingStats {
+        self.stats.get(prefix)
+    }
+    
+    /// Select the next-hop that receives the next Interest for `name`: the
+    /// healthy next-hops under the longest matching prefix take turns
+    pub fn select_next_hop(&self, name: &Name) -> Option<NextHop> {
+        let mut next_hops = longest_match(&self.next_hops.read(), name);
+        
+        let failed = self.failed.read();
+        next_hops.retain(|hop| !failed.contains(&hop.id));
+        if next_hops.is_empty() {
+            return None;
+        }
+        
+        let mut cursor = self.cursors.entry(next_hops[0].prefix.clone()).or_insert(0);
+        let hop = next_hops.swap_remove(*cursor % next_hops.len());
+        *cursor = cursor.wrapping_add(1);
+        Some(hop)
+    }
+    
+    /// Forward an Interest to the next healthy next-hop in turn
+    ///
+    /// Fails with a timeout if no Data arrives within the Interest lifetime.
+    pub async fn forward(&self, interest: Interest) -> Result<Data> {
+        let hop = self.select_next_hop(interest.name()).ok_or(Error::NoConnections)?;
+        let lifetime = interest.get_lifetime();
+        self.stats.record_forwarded(&hop.prefix);
+        
+        debug!("Forwarding Interest for {} to next-hop {}", interest.name(), hop.id);
+        
+        let result = match tokio::time::timeout(lifetime, hop.face.send_interest(interest.clone())).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout(format!("Interest {} expired after {:?}", interest.name(), lifetime))),
+        };
+        
+        if let Err(e) = &result {
+            warn!("Forwarding Interest for {} to next-hop {} failed: {}", interest.name(), hop.id, e);
+        }
+        self.stats.record_result(&hop.prefix, &result);
+        
+        result
+    }
+}
+
+impl Default for LoadBalanceStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use crate::face::LoopbackFace;
+    use crate::ndn::NackReason;
+    use crate::quic::PrefixHandler;
+    use std::time::Duration;
+    
+    /// A next-hop that never answers, counting the Interests it is still holding
+    #[derive(Default)]
+    struct StalledFace {
+        pending: Arc<AtomicU64>,
+    }
+    
+    /// Releases a held Interest when its task ends or is cancelled
+    struct Held(Arc<AtomicU64>);
+    
+    impl Drop for Held {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+    
+    #[async_trait]
+    impl NdnFace for StalledFace {
+        async fn send_interest(&self, _interest: Interest) -> Result<Data> {
+            self.pending.fetch_add(1, Ordering::SeqCst);
+            let _held = Held(self.pending.clone());
+            std::future::pending().await
+        }
+        
+        async fn register_prefix(&self, _prefix: Name, _handler: PrefixHandler) -> Result<u64> {
+            Ok(0)
+        }
+        
+        async fn push_data(&self, _data: Data) -> Result<()> {
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_multicast_max_fanout() {
+        let strategy = MulticastStrategy::new(MulticastConfig { max_fanout: Some(2) });
+        
+        // Five next-hops registered out of cost order
+        let costs = [30, 10, 50, 20, 40];
+        let mut faces = Vec::new();
+        for cost in costs {
+            let face = Arc::new(LoopbackFace::new());
+            face.register_prefix(Name::from_uri("/test").unwrap(), Box::new(|interest: Interest| {
+                Ok(Data::new(interest.name().clone(), "content"))
+            })).await.unwrap();
+            strategy.add_next_hop(Name::from_uri("/test").unwrap(), face.clone(), cost);
+            faces.push((cost, face));
+        }
+        
+        let data = strategy.forward(Interest::new(Name::from_uri("/test/data").unwrap())).await;
+        assert!(data.is_ok());
+        
+        // Only the two lowest-cost next-hops see the Interest
+        for (cost, face) in &faces {
+            let expected = if *cost <= 20 { 1 } else { 0 };
+            assert_eq!(face.interests_received(), expected, "next-hop with cost {}", cost);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_context_survives_forwarding_hop() {
+        let strategy = MulticastStrategy::new(MulticastConfig::default());
+        
+        // The producer records the context its handler sees
+        let seen = Arc::new(parking_lot::Mutex::new(None));
+        let producer = Arc::new(LoopbackFace::new());
+        let seen_by_handler = seen.clone();
+        producer.register_prefix(Name::from_uri("/traced").unwrap(), Box::new(move |interest: Interest| {
+            *seen_by_handler.lock() = interest.context().cloned();
+            Ok(Data::new(interest.name().clone(), "content"))
+        })).await.unwrap();
+        strategy.add_next_hop(Name::from_uri("/traced").unwrap(), producer, 1);
+        
+        // The consumer's Interest crosses the wire before being forwarded
+        let context = bytes::Bytes::from_static(b"traceparent=00-4bf92f35-00f067aa-01");
+        let interest = Interest::new(Name::from_uri("/traced/object").unwrap()).with_context(context.clone());
+        let received = Interest::from_bytes(&interest.to_bytes()).unwrap();
+        
+        strategy.forward(received).await.unwrap();
+        assert_eq!(seen.lock().as_ref(), Some(&context));
+    }
+    
+    #[tokio::test]
+    async fn test_forwarding_counters() {
+        let strategy = MulticastStrategy::new(MulticastConfig::default());
+        let video = Name::from_uri("/stats/video").unwrap();
+        let dead = Name::from_uri("/stats/dead").unwrap();
+        
+        // Upstream node serving /stats/video, NACKing anything under
+        // /stats/video/private and failing under /stats/video/broken
+        let upstream = Arc::new(LoopbackFace::new());
+        upstream.register_prefix(video.clone(), Box::new(|interest: Interest| {
+            let name = interest.name().to_string();
+            if name.starts_with("/stats/video/private") {
+                return Err(Error::Nack { reason: NackReason::NotAuth, message: "access denied".into() });
+            }
+            if name.starts_with("/stats/video/broken") {
+                return Err(Error::Other("producer failed".into()));
+            }
+            Ok(Data::new(interest.name().clone(), "frame"))
+        })).await.unwrap();
+        strategy.add_next_hop(video.clone(), upstream, 1);
+        
+        // A next-hop that never answers
+        let dead_hop = Arc::new(StalledFace::default());
+        let held = dead_hop.pending.clone();
+        strategy.add_next_hop(dead.clone(), dead_hop, 1);
+        
+        for i in 0..3 {
+            let interest = Interest::new(Name::from_uri(&format!("/stats/video/{}", i)).unwrap());
+            assert!(strategy.forward(interest).await.is_ok());
+        }
+        
+        let denied = Interest::new(Name::from_uri("/stats/video/private/1").unwrap());
+        assert!(strategy.forward(denied).await.is_err());
+        
+        let broken = Interest::new(Name::from_uri("/stats/video/broken/1").unwrap());
+        assert!(strategy.forward(broken).await.is_err());
+        
+        let expiring = Interest::new(Name::from_uri("/stats/dead/1").unwrap())
+            .lifetime(Duration::from_millis(200));
+        assert!(matches!(strategy.forward(expiring).await, Err(Error::Timeout(_))));
+        
+        // The expired attempt is cancelled rather than left waiting
+        tokio::task::yield_now().await;
+        assert_eq!(held.load(Ordering::SeqCst), 0);
+        
+        assert_eq!(strategy.forwarding_stats(&video), ForwardingStats {
+            forwarded: 5,
+            satisfied: 3,
+            nacked: 1,
+            timed_out: 0,
+            failed: 1,
+        });
+        assert_eq!(strategy.forwarding_stats(&dead), ForwardingStats {
+            forwarded: 1,
+            satisfied: 0,
+            nacked: 0,
+            timed_out: 1,
+            failed: 0,
+        });
+        
+        // The same counts are exported with a prefix label
+        assert_eq!(INTERESTS_SATISFIED.with_label_values(&["/stats/video"]).get(), 3);
+        assert_eq!(INTERESTS_TIMED_OUT.with_label_values(&["/stats/dead"]).get(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_load_balance_round_robin_skips_failed() {
+        let strategy = LoadBalanceStrategy::new();
+        let prefix = Name::from_uri("/balanced").unwrap();
+        
+        let mut hops = Vec::new();
+        for _ in 0..3 {
+            let face = Arc::new(LoopbackFace::new());
+            face.register_prefix(prefix.clone(), Box::new(|interest: Interest| {
+                Ok(Data::new(interest.name().clone(), "content"))
+            })).await.unwrap();
+            let id = strategy.add_next_hop(prefix.clone(), face.clone(), 1);
+            hops.push((id, face));
+        }
+        
+        // Every next-hop gets an equal share
+        for i in 0..30 {
+            let interest = Interest::new(Name::from_uri(&format!("/balanced/{}", i)).unwrap());
+            strategy.forward(interest).await.unwrap();
+        }
+        for (id, face) in &hops {
+            assert_eq!(face.interests_received(), 10, "next-hop {}", id);
+        }
+        
+        // A downed next-hop is skipped and the others share its load
+        strategy.set_failed(hops[1].0, true).unwrap();
+        for i in 30..60 {
+            let interest = Interest::new(Name::from_uri(&format!("/balanced/{}", i)).unwrap());
+            strategy.forward(interest).await.unwrap();
+        }
+        assert_eq!(hops[0].1.interests_received(), 25);
+        assert_eq!(hops[1].1.interests_received(), 10);
+        assert_eq!(hops[2].1.interests_received(), 25);
+        
+        assert_eq!(strategy.forwarding_stats(&prefix).satisfied, 60);
+        assert!(strategy.set_failed(999, true).is_err());
+    }
+}
@@ -5,9 +5,12 @@
 //
 
 use super::*;
-use crate::fragmentation::Fragmenter;
+use crate::fragmentation::{Fragmenter, FRAGMENT_HEADER_SIZE};
 use crate::metrics::init_metrics;
-use crate::quic::ConnectionState;
+use crate::ndn::integrity::IntegrityCheck;
+use crate::quic::{ConnectionState, PendingInsert, PendingInterestTable, PUSH_DATA_FLAG, PUSH_FRAGMENT_FLAG};
+use crate::acl::{AclPolicy, Subnet};
+use crate::security::{CertStore, KeyPair, TlsConfig};
 
 use std::time::Duration;
 use std::sync::Arc;
@@ -270,3 +273,564 @@ async fn test_congestion_control() {
     client.stop().await.expect("Failed to stop client");
     server.stop().await.expect("Failed to stop server");
 }
+
+// Test that a deadline bounds send_interest against a slow producer
+#[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_send_interest_deadline() {
+    init_metrics();
+    
+    // Create server engine
+    let config = test_config();
+    let mut server = QuicEngine::new(&config).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    
+    // Get the server address
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    
+    // Register a handler that takes two seconds to produce Data
+    server.register_prefix(
+        Name::from_uri("/slow").unwrap(),
+        Box::new(|interest: Interest| -> Result<Data> {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(Data::new(interest.name().clone(), "late"))
+        })
+    ).await.expect("Failed to register prefix");
+    
+    // Create client engine
+    let mut client = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    // Send an interest with a 300 ms deadline
+    let start = std::time::Instant::now();
+    let deadline = start + Duration::from_millis(300);
+    let interest = create_test_interest("/slow/data");
+    let result = client.send_interest_with_deadline(server_addr, interest, Some(deadline)).await;
+    let elapsed = start.elapsed();
+    
+    // The call fails with a timeout shortly after the deadline
+    assert!(matches!(result, Err(Error::Timeout(_))), "Expected a timeout but got: {:?}", result);
+    assert!(elapsed < Duration::from_secs(1), "Deadline not honoured: took {:?}", elapsed);
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that Data fetched by implicit digest is checked against the digest
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_implicit_digest_mismatch() {
+    init_metrics();
+    
+    // The Data the consumer expects, identified by its full name
+    let genuine = create_test_data("/digest/object", b"genuine content");
+    let full_name = genuine.full_name();
+    
+    // A man-in-the-middle answers with different content under the same name
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    server.register_prefix(
+        Name::from_uri("/digest").unwrap(),
+        create_test_handler(create_test_data("/digest/object", b"substituted content"))
+    ).await.expect("Failed to register prefix");
+    
+    let mut client = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    // The substituted Data is rejected
+    let result = client.send_interest(server_addr, Interest::new(full_name.clone())).await;
+    assert!(matches!(result, Err(Error::DigestMismatch(_))), "Expected a digest mismatch but got: {:?}", result);
+    
+    // The same name without the digest is still served
+    let result = client.send_interest(server_addr, Interest::new(full_name.without_implicit_digest())).await;
+    assert!(result.is_ok(), "Expected Data but got: {:?}", result);
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that recv_timeout bounds waiting for a slow producer, independent of the idle timeout
+#[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_recv_timeout() {
+    init_metrics();
+    
+    // Create server engine with a producer that takes two seconds
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    server.register_prefix(
+        Name::from_uri("/slow").unwrap(),
+        Box::new(|interest: Interest| -> Result<Data> {
+            std::thread::sleep(Duration::from_secs(2));
+            Ok(Data::new(interest.name().clone(), "late"))
+        })
+    ).await.expect("Failed to register prefix");
+    
+    // Create a client with a short receive timeout and a long idle timeout
+    let client_config = Config {
+        recv_timeout: 300,
+        idle_timeout: 60,
+        ..test_config()
+    };
+    let mut client = QuicEngine::new(&client_config).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    let start = std::time::Instant::now();
+    let result = client.send_interest(server_addr, create_test_interest("/slow/data")).await;
+    let elapsed = start.elapsed();
+    
+    // The receive times out at the configured bound, not the idle timeout
+    assert!(matches!(result, Err(Error::Timeout(_))), "Expected a timeout but got: {:?}", result);
+    assert!(elapsed >= Duration::from_millis(300), "Timed out too early: {:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(1), "recv_timeout not honoured: took {:?}", elapsed);
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that Interests for a pending name are aggregated and duplicate nonces detected
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_pending_interest_table() {
+    let pit = PendingInterestTable::new();
+    let first = create_test_interest("/pit/object");
+    let second = create_test_interest("/pit/object");
+    
+    // The first Interest creates the entry, the second attaches to it
+    assert!(matches!(pit.insert(&first), PendingInsert::New));
+    let pending = match pit.insert(&second) {
+        PendingInsert::Aggregated(pending) => pending,
+        other => panic!("Expected aggregation but got: {:?}", other),
+    };
+    assert_eq!(pit.len(), 1);
+    
+    // A repeated nonce is a duplicate (e.g. a looping Interest)
+    assert!(matches!(pit.insert(&first.clone()), PendingInsert::Duplicate));
+    
+    // One Data satisfies the waiting requester and removes the entry
+    let data = create_test_data("/pit/object", b"shared");
+    assert_eq!(pit.satisfy(first.name(), &data), 1);
+    assert_eq!(pending.await.unwrap().content().as_ref(), b"shared");
+    assert!(pit.is_empty());
+    
+    // Entries whose Interests have expired are removed
+    let short = create_test_interest("/pit/short").lifetime(Duration::from_millis(20));
+    assert!(matches!(pit.insert(&short), PendingInsert::New));
+    sleep(Duration::from_millis(30)).await;
+    assert_eq!(pit.expire(), 1);
+    assert!(pit.is_empty());
+}
+
+// Test that concurrent Interests for one name from two consumers call the handler once
+#[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_interest_aggregation() {
+    init_metrics();
+    
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    
+    // A slow producer, so the second Interest arrives while the first is pending
+    let calls = Arc::new(AtomicUsize::new(0));
+    let handler_calls = calls.clone();
+    server.register_prefix(
+        Name::from_uri("/aggregate").unwrap(),
+        Box::new(move |interest: Interest| -> Result<Data> {
+            handler_calls.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(300));
+            Ok(Data::new(interest.name().clone(), "shared"))
+        })
+    ).await.expect("Failed to register prefix");
+    
+    let mut first = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    first.start().await.expect("Failed to start client");
+    let mut second = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    second.start().await.expect("Failed to start client");
+    
+    let (a, b) = tokio::join!(
+        first.send_interest(server_addr, create_test_interest("/aggregate/object")),
+        second.send_interest(server_addr, create_test_interest("/aggregate/object")),
+    );
+    
+    assert_eq!(a.expect("First consumer failed").content().as_ref(), b"shared");
+    assert_eq!(b.expect("Second consumer failed").content().as_ref(), b"shared");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(server.pending_count(), 0);
+    
+    // Clean up
+    first.stop().await.expect("Failed to stop client");
+    second.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that a Data larger than the MTU is fragmented once into the expected frames
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_data_response_frames() {
+    let mtu = 500;
+    let fragmenter = Fragmenter::new(mtu);
+    let integrity = IntegrityCheck::new(true);
+    
+    // A Data that fits is sent as a single sealed packet
+    let small = create_test_data("/frames/small", b"fits");
+    let frames = QuicEngine::data_response_frames(&small, &fragmenter, &integrity).await.unwrap();
+    assert_eq!(frames, vec![integrity.seal(small.to_bytes())]);
+    
+    // A larger Data is split into one frame per MTU-sized piece
+    let large = create_test_data("/frames/large", &[0x5A; 2000]);
+    let encoded_len = large.to_bytes().len();
+    let max_payload = mtu - FRAGMENT_HEADER_SIZE - large.name().to_tlv().len();
+    let expected = (encoded_len + max_payload - 1) / max_payload;
+    
+    let frames = QuicEngine::data_response_frames(&large, &fragmenter, &integrity).await.unwrap();
+    assert_eq!(frames.len(), expected);
+    assert!(frames.iter().all(|frame| frame.len() <= mtu));
+    
+    // Every frame is written once, so the receiver reassembles the Data on the last one
+    let receiver = Fragmenter::new(mtu);
+    let (last, rest) = frames.split_last().unwrap();
+    for frame in rest {
+        assert!(receiver.process_fragment(frame.clone()).await.unwrap().is_none());
+    }
+    let reassembled = receiver.process_fragment(last.clone()).await.unwrap().unwrap();
+    assert_eq!(reassembled.name(), large.name());
+    assert_eq!(reassembled.content(), large.content());
+    assert_eq!(receiver.reassembly_stats().await.contexts, 0);
+}
+
+// Test that Data larger than the MTU, and than a single packet, is fetched as fragments
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_fetch_fragmented_data() {
+    init_metrics();
+    
+    let config = test_config();
+    let mut server = QuicEngine::new(&config).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    
+    let over_mtu = create_test_data("/fragmented/over-mtu", &[0x5A; 4000]);
+    let over_packet = create_test_data("/fragmented/over-packet", &[0xA5; 200 * 1024]);
+    assert!(over_mtu.to_bytes().len() > config.mtu);
+    assert!(over_packet.to_bytes().len() > config.max_packet_size);
+    for data in [&over_mtu, &over_packet] {
+        server.register_prefix(data.name().clone(), create_test_handler(data.clone())).await
+            .expect("Failed to register prefix");
+    }
+    
+    let mut client = QuicEngine::new(&config).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    for data in [&over_mtu, &over_packet] {
+        let fetched = client.send_interest(server_addr, Interest::new(data.name().clone())).await
+            .expect("Failed to fetch fragmented Data");
+        assert_eq!(fetched.name(), data.name());
+        assert_eq!(fetched.content(), data.content());
+    }
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that Data pushed without an Interest lands in the peer's content store
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_push_data() {
+    init_metrics();
+    
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    
+    let mut client = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    // One Data fits in a single stream, the other is pushed as fragments
+    let small = create_test_data("/push/small", b"announcement");
+    let large = create_test_data("/push/large", &[0xA5; 4000]);
+    client.push_data(server_addr, small.clone()).await.expect("Failed to push small Data");
+    client.push_data(server_addr, large.clone()).await.expect("Failed to push large Data");
+    
+    // Uni streams are accepted asynchronously
+    for _ in 0..50 {
+        let cs = server.content_store().read().await;
+        if cs.contains(small.name()) && cs.contains(large.name()) {
+            break;
+        }
+        drop(cs);
+        sleep(Duration::from_millis(20)).await;
+    }
+    
+    let cs = server.content_store().read().await;
+    assert_eq!(cs.get(small.name(), false).expect("Small Data not cached").content(), small.content());
+    assert_eq!(cs.get(large.name(), false).expect("Large Data not cached").content(), large.content());
+    drop(cs);
+    
+    // The cached Data now answers Interests without a registered handler
+    let data = client.send_interest(server_addr, create_test_interest("/push/small")).await
+        .expect("Pushed Data not served");
+    assert_eq!(data.content(), small.content());
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that pushed Data is only cached when the ACL allows it and a trusted key signed it
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_pushed_data_checked() {
+    init_metrics();
+    
+    let key = KeyPair::generate("/push/KEY/1").expect("Failed to generate key");
+    let mut store = CertStore::new();
+    store.insert_cert(Name::from_uri("/push/KEY/1").unwrap(), key.public_key());
+    
+    let loopback = Subnet::from("127.0.0.1".parse::<std::net::IpAddr>().unwrap());
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.set_cert_store(Arc::new(parking_lot::RwLock::new(store)));
+    server.set_acl(Arc::new(parking_lot::RwLock::new(
+        AclPolicy::new().deny(Name::from_uri("/push/denied").unwrap(), [loopback]))));
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    
+    let mut client = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    // Unsigned, denied and forged Data go first, the valid Data last
+    let impostor = KeyPair::generate("/push/KEY/1").expect("Failed to generate key");
+    let unsigned = create_test_data("/push/unsigned", b"poison");
+    let denied = create_test_data("/push/denied/item", b"poison").sign(&key);
+    let forged = create_test_data("/push/forged", b"poison").sign(&impostor);
+    let valid = create_test_data("/push/valid", b"announcement").sign(&key);
+    for data in [&unsigned, &denied, &forged, &valid] {
+        client.push_data(server_addr, data.clone()).await.expect("Failed to push Data");
+    }
+    
+    for _ in 0..50 {
+        if server.content_store().read().await.contains(valid.name()) {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    
+    let cs = server.content_store().read().await;
+    assert!(cs.contains(valid.name()), "Signed Data not cached");
+    assert!(!cs.contains(unsigned.name()), "Unsigned Data cached");
+    assert!(!cs.contains(denied.name()), "Data denied by the ACL cached");
+    assert!(!cs.contains(forged.name()), "Data signed by an unknown key cached");
+    drop(cs);
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that the first byte of a push stream selects how the rest is decoded
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_decode_pushed() {
+    let fragmenter = Fragmenter::new(500);
+    let integrity = IntegrityCheck::new(true);
+    
+    let data = create_test_data("/push/decode", b"pushed");
+    let mut stream = vec![PUSH_DATA_FLAG];
+    stream.extend_from_slice(&integrity.seal(data.to_bytes()));
+    let decoded = QuicEngine::decode_pushed(&stream, &fragmenter, &integrity).await.unwrap().unwrap();
+    assert_eq!(decoded.name(), data.name());
+    
+    // Fragments only yield the Data once the last one arrives
+    let large = create_test_data("/push/decode/large", &[0x11; 1200]);
+    let frames = fragmenter.fragment(&large).await.unwrap();
+    let receiver = Fragmenter::new(500);
+    let mut reassembled = None;
+    for frame in &frames {
+        let mut stream = vec![PUSH_FRAGMENT_FLAG];
+        stream.extend_from_slice(frame);
+        assert!(reassembled.is_none());
+        reassembled = QuicEngine::decode_pushed(&stream, &receiver, &integrity).await.unwrap();
+    }
+    assert_eq!(reassembled.unwrap().content(), large.content());
+    
+    // Unknown flags and empty streams are rejected
+    assert!(QuicEngine::decode_pushed(&[0x7F, 0x00], &fragmenter, &integrity).await.is_err());
+    assert!(QuicEngine::decode_pushed(&[], &fragmenter, &integrity).await.is_err());
+}
+
+// Test that concurrent Interests to a new peer share a single connection
+#[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_concurrent_interests_share_connection() {
+    init_metrics();
+    
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    server.register_prefix(
+        Name::from_uri("/pool").unwrap(),
+        Box::new(|interest: Interest| -> Result<Data> {
+            Ok(Data::new(interest.name().clone(), "pooled"))
+        })
+    ).await.expect("Failed to register prefix");
+    
+    let mut client = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    let results = futures::future::join_all((0..10).map(|i| {
+        client.send_interest(server_addr, create_test_interest(&format!("/pool/{}", i)))
+    })).await;
+    
+    for result in results {
+        assert_eq!(result.expect("Interest failed").content().as_ref(), b"pooled");
+    }
+    assert_eq!(client.connections_opened(), 1);
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that a connection never serves more streams at once than its congestion window
+#[cfg_attr(feature = "tokio-test", tokio::test(flavor = "multi_thread", worker_threads = 16))]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_stream_backpressure() {
+    init_metrics();
+    
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    
+    // A slow producer counting how many Interests it serves at once
+    let current = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (handler_current, handler_peak) = (current.clone(), peak.clone());
+    server.register_prefix(
+        Name::from_uri("/burst").unwrap(),
+        Box::new(move |interest: Interest| -> Result<Data> {
+            let now = handler_current.fetch_add(1, Ordering::SeqCst) + 1;
+            handler_peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(200));
+            handler_current.fetch_sub(1, Ordering::SeqCst);
+            Ok(Data::new(interest.name().clone(), "burst"))
+        })
+    ).await.expect("Failed to register prefix");
+    
+    let mut client = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    // A burst larger than the initial window of 10 is served in two waves
+    let results = futures::future::join_all((0..15).map(|i| {
+        client.send_interest(server_addr, create_test_interest(&format!("/burst/{}", i)))
+    })).await;
+    for result in results {
+        assert_eq!(result.expect("Interest failed").content().as_ref(), b"burst");
+    }
+    assert_eq!(peak.load(Ordering::SeqCst), 10);
+    
+    // Slots are taken up to the window and handed out again as they are released
+    let tracker = client.connect(server_addr).await.expect("Failed to connect");
+    let window = tracker.congestion_window().await;
+    let mut slots = Vec::new();
+    for _ in 0..window {
+        slots.push(tracker.acquire_stream_slot().await);
+    }
+    assert_eq!(tracker.in_flight_streams(), window);
+    assert!(tokio::time::timeout(Duration::from_millis(50), tracker.acquire_stream_slot()).await.is_err());
+    
+    slots.pop();
+    let slot = tokio::time::timeout(Duration::from_millis(50), tracker.acquire_stream_slot()).await
+        .expect("Released slot not handed out");
+    assert_eq!(tracker.in_flight_streams(), window);
+    assert_eq!(tracker.peak_in_flight_streams(), window);
+    drop(slot);
+    drop(slots);
+    assert_eq!(tracker.in_flight_streams(), 0);
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that a client pinning the server's certificate connects, and one pinning another does not
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_pinned_certificate() {
+    init_metrics();
+    
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    
+    // Pinning the server's own certificate connects
+    let pinned_config = Config {
+        tls: TlsConfig::PinnedCert(vec![server.certificate().clone()]),
+        ..test_config()
+    };
+    let mut pinned = QuicEngine::new(&pinned_config).await.expect("Failed to create client");
+    pinned.start().await.expect("Failed to start client");
+    pinned.connect(server_addr).await.expect("Pinned certificate rejected");
+    
+    // Pinning any other certificate fails the handshake
+    let (other, _) = crate::security::generate_self_signed_cert().unwrap();
+    let unpinned_config = Config {
+        tls: TlsConfig::PinnedCert(vec![other]),
+        ..test_config()
+    };
+    let mut unpinned = QuicEngine::new(&unpinned_config).await.expect("Failed to create client");
+    unpinned.start().await.expect("Failed to start client");
+    assert!(unpinned.connect(server_addr).await.is_err());
+    
+    // Clean up
+    pinned.stop().await.expect("Failed to stop client");
+    unpinned.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
+
+// Test that reconnecting to a server resumes the session with 0-RTT
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+#[cfg_attr(not(feature = "tokio-test"), test)]
+async fn test_zero_rtt_reconnect() {
+    init_metrics();
+    
+    let mut server = QuicEngine::new(&test_config()).await.expect("Failed to create server");
+    server.start().await.expect("Failed to start server");
+    let server_addr = server.local_addr().await.expect("Failed to get local address");
+    server.register_prefix(
+        Name::from_uri("/resume").unwrap(),
+        Box::new(|interest: Interest| -> Result<Data> {
+            Ok(Data::new(interest.name().clone(), "resumed"))
+        })
+    ).await.expect("Failed to register prefix");
+    
+    let mut client = QuicEngine::new(&test_config()).await.expect("Failed to create client");
+    client.start().await.expect("Failed to start client");
+    
+    // The first connection performs the full handshake and receives a session ticket
+    let data = client.send_interest(server_addr, create_test_interest("/resume/1")).await.expect("Interest failed");
+    assert_eq!(data.content().as_ref(), b"resumed");
+    assert_eq!(client.zero_rtt_accepted(), 0);
+    
+    // Drop the connection so the next Interest reconnects
+    let tracker = client.connect(server_addr).await.expect("Failed to connect");
+    tracker.connection().close(0u32.into(), b"reconnect");
+    
+    // The reconnect sends the Interest as 0-RTT data, which the server accepts
+    let data = client.send_interest(server_addr, create_test_interest("/resume/2")).await.expect("Interest failed");
+    assert_eq!(data.content().as_ref(), b"resumed");
+    assert_eq!(client.connections_opened(), 2);
+    
+    // Acceptance is recorded once the handshake confirms it
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(client.zero_rtt_accepted(), 1);
+    
+    // Clean up
+    client.stop().await.expect("Failed to stop client");
+    server.stop().await.expect("Failed to stop server");
+}
@@ -0,0 +1,262 @@
+//
+// μDCN Packet Tracing
+//
+// This module lets a tracer see every frame the QUIC engine sends or
+// receives, for diagnosing interop problems. Frames are passed as they cross
+// the wire: one encoded Interest, Data, Nack or fragment, followed by its CRC
+// trailer when the integrity self-test is enabled. `PcapTracer` records them
+// in a pcapng file that Wireshark can open. Health-check pings, which a
+// connection sends on its own, are not traced.
+//
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::{Mutex, RwLock};
+use tracing::warn;
+
+use crate::error::Result;
+
+/// Link type of the capture interface, LINKTYPE_USER0; Wireshark decodes
+/// the frames as NDN when "ndn" is set for User 0 in its DLT_USER table
+pub const PCAP_LINKTYPE: u16 = 147;
+
+/// pcapng block types
+const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+
+/// pcapng option codes
+const OPT_END: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const EPB_FLAGS: u16 = 2;
+
+/// Direction bits of the epb_flags option
+const EPB_INBOUND: u32 = 0x1;
+const EPB_OUTBOUND: u32 = 0x2;
+
+/// Observer of the frames crossing the transport
+///
+/// The methods are called on the I/O path, so they should return quickly.
+pub trait Tracer: Send + Sync {
+    /// Called with a frame about to be sent to `peer`
+    fn on_send(&self, frame: &[u8], peer: SocketAddr);
+    
+    /// Called with a frame received from `peer`
+    fn on_recv(&self, frame: &[u8], peer: SocketAddr);
+}
+
+/// Slot holding the tracer of an engine, shared with its connection tasks
+/// so a tracer can be set or cleared while they run
+#[derive(Clone, Default)]
+pub struct TraceHook {
+    tracer: Arc<RwLock<Option<Box<dyn Tracer>>>>,
+}
+
+impl TraceHook {
+    /// Create a hook without a tracer
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Replace the tracer, or remove it with None
+    pub fn set(&self, tracer: Option<Box<dyn Tracer>>) {
+        *self.tracer.write() = tracer;
+    }
+    
+    /// Check whether a tracer is set
+    pub fn is_set(&self) -> bool {
+        self.tracer.read().is_some()
+    }
+    
+    /// Pass a frame being sent to the tracer, if any
+    pub fn on_send(&self, frame: &[u8], peer: SocketAddr) {
+        if let Some(tracer) = self.tracer.read().as_ref() {
+            tracer.on_send(frame, peer);
+        }
+    }
+    
+    /// Pass a received frame to the tracer, if any
+    pub fn on_recv(&self, frame: &[u8], peer: SocketAddr) {
+        if let Some(tracer) = self.tracer.read().as_ref() {
+            tracer.on_recv(frame, peer);
+        }
+    }
+}
+
+impl std::fmt::Debug for TraceHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceHook")
+            .field("is_set", &self.is_set())
+            .finish()
+    }
+}
+
+/// Tracer writing frames to a pcapng capture
+///
+/// Each frame is an Enhanced Packet Block on a single interface, flagged
+/// inbound or outbound and commented with the peer address.
+pub struct PcapTracer {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl PcapTracer {
+    /// Create a capture file at `path`, replacing any existing file
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::create(path)?;
+        Self::new(BufWriter::new(file))
+    }
+    
+    /// Write a capture to `writer`, starting with the section header
+    pub fn new<W: Write + Send + 'static>(mut writer: W) -> Result<Self> {
+        writer.write_all(&section_header_block())?;
+        writer.write_all(&interface_description_block())?;
+        Ok(Self {
+            writer: Mutex::new(Box::new(writer)),
+        })
+    }
+    
+    /// Flush buffered packets to the capture
+    pub fn flush(&self) -> Result<()> {
+        self.writer.lock().flush()?;
+        Ok(())
+    }
+    
+    /// Append one frame to the capture
+    fn record(&self, frame: &[u8], peer: SocketAddr, direction: u32) {
+        let block = enhanced_packet_block(frame, &peer.to_string(), direction, SystemTime::now());
+        if let Err(e) = self.writer.lock().write_all(&block) {
+            warn!("Failed to write packet trace: {}", e);
+        }
+    }
+}
+
+impl Tracer for PcapTracer {
+    fn on_send(&self, frame: &[u8], peer: SocketAddr) {
+        self.record(frame, peer, EPB_OUTBOUND);
+    }
+    
+    fn on_recv(&self, frame: &[u8], peer: SocketAddr) {
+        self.record(frame, peer, EPB_INBOUND);
+    }
+}
+
+impl Drop for PcapTracer {
+    fn drop(&mut self) {
+        let _ = self.writer.get_mut().flush();
+    }
+}
+
+/// Frame a block body with its type and total length
+fn block(block_type: u32, body: &[u8]) -> Vec<u8> {
+    let total_len = (12 + body.len()) as u32;
+    let mut block = Vec::with_capacity(total_len as usize);
+    block.extend_from_slice(&block_type.to_le_bytes());
+    block.extend_from_slice(&total_len.to_le_bytes());
+    block.extend_from_slice(body);
+    block.extend_from_slice(&total_len.to_le_bytes());
+    block
+}
+
+/// Append `bytes` padded to a 32-bit boundary
+fn put_padded(body: &mut Vec<u8>, bytes: &[u8]) {
+    body.extend_from_slice(bytes);
+    body.resize(body.len() + (4 - bytes.len() % 4) % 4, 0);
+}
+
+/// Append an option
+fn put_option(body: &mut Vec<u8>, code: u16, value: &[u8]) {
+    body.extend_from_slice(&code.to_le_bytes());
+    body.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    put_padded(body, value);
+}
+
+fn section_header_block() -> Vec<u8> {
+    let mut body = Vec::with_capacity(16);
+    body.extend_from_slice(&0x1A2B_3C4Du32.to_le_bytes()); // Byte-order magic
+    body.extend_from_slice(&1u16.to_le_bytes()); // Major version
+    body.extend_from_slice(&0u16.to_le_bytes()); // Minor version
+    body.extend_from_slice(&(-1i64).to_le_bytes()); // Section length unknown
+    block(SECTION_HEADER_BLOCK, &body)
+}
+
+fn interface_description_block() -> Vec<u8> {
+    let mut body = Vec::with_capacity(8);
+    body.extend_from_slice(&PCAP_LINKTYPE.to_le_bytes());
+    body.extend_from_slice(&0u16.to_le_bytes()); // Reserved
+    body.extend_from_slice(&0u32.to_le_bytes()); // No snapshot length limit
+    block(INTERFACE_DESCRIPTION_BLOCK, &body)
+}
+
+fn enhanced_packet_block(frame: &[u8], comment: &str, direction: u32, time: SystemTime) -> Vec<u8> {
+    // Timestamps are in microseconds, the default resolution
+    let micros = time.duration_since(UNIX_EPOCH).map(|d| d.as_micros() as u64).unwrap_or(0);
+    
+    let mut body = Vec::with_capacity(40 + frame.len() + comment.len());
+    body.extend_from_slice(&0u32.to_le_bytes()); // Interface ID
+    body.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+    body.extend_from_slice(&(micros as u32).to_le_bytes());
+    body.extend_from_slice(&(frame.len() as u32).to_le_bytes()); // Captured length
+    body.extend_from_slice(&(frame.len() as u32).to_le_bytes()); // Original length
+    put_padded(&mut body, frame);
+    put_option(&mut body, EPB_FLAGS, &direction.to_le_bytes());
+    put_option(&mut body, OPT_COMMENT, comment.as_bytes());
+    put_option(&mut body, OPT_END, &[]);
+    block(ENHANCED_PACKET_BLOCK, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn read_u32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+    
+    #[test]
+    fn test_pcap_tracer_writes_blocks() {
+        let path = std::env::temp_dir().join(format!("udcn-trace-{}.pcapng", std::process::id()));
+        let peer: SocketAddr = "127.0.0.1:6363".parse().unwrap();
+        
+        let tracer = PcapTracer::create(&path).unwrap();
+        tracer.on_send(&[0x05, 0x03, 0x07, 0x01, 0x08], peer);
+        tracer.on_recv(&[0x06, 0x00], peer);
+        drop(tracer);
+        
+        let capture = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        // Walk the blocks, checking each is framed by the same length
+        let mut blocks = Vec::new();
+        let mut at = 0;
+        while at < capture.len() {
+            let len = read_u32(&capture, at + 4) as usize;
+            assert_eq!(len % 4, 0);
+            assert_eq!(read_u32(&capture, at + len - 4) as usize, len);
+            blocks.push((read_u32(&capture, at), at));
+            at += len;
+        }
+        assert_eq!(at, capture.len());
+        
+        let types: Vec<u32> = blocks.iter().map(|(t, _)| *t).collect();
+        assert_eq!(types, vec![SECTION_HEADER_BLOCK, INTERFACE_DESCRIPTION_BLOCK, ENHANCED_PACKET_BLOCK, ENHANCED_PACKET_BLOCK]);
+        assert_eq!(read_u32(&capture, 8), 0x1A2B_3C4D);
+        assert_eq!(u16::from_le_bytes([capture[blocks[1].1 + 8], capture[blocks[1].1 + 9]]), PCAP_LINKTYPE);
+        
+        // The first packet carries the sent frame, flagged outbound
+        let sent = blocks[2].1;
+        assert_eq!(read_u32(&capture, sent + 20), 5);
+        assert_eq!(&capture[sent + 28..sent + 33], &[0x05, 0x03, 0x07, 0x01, 0x08]);
+        assert_eq!(u16::from_le_bytes([capture[sent + 36], capture[sent + 37]]), EPB_FLAGS);
+        assert_eq!(read_u32(&capture, sent + 40), EPB_OUTBOUND);
+        assert!(capture[sent..blocks[3].1].windows(14).any(|w| w == b"127.0.0.1:6363"));
+        
+        let received = blocks[3].1;
+        assert_eq!(read_u32(&capture, received + 20), 2);
+        assert_eq!(read_u32(&capture, received + 36), EPB_INBOUND);
+    }
+}
@@ -10,7 +10,7 @@ use std::process::Command;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use tokio::task::JoinHandle;
 use tokio::time::{Duration, interval};
 
@@ -45,6 +45,10 @@ pub struct XdpConfig {
     
     /// Metrics collection interval in seconds
     pub metrics_interval: u64,
+    
+    /// Access the eBPF maps by shelling out to bpftool instead of through
+    /// libbpf (always the case without the `libbpf` feature)
+    pub use_bpftool: bool,
 }
 
 impl Default for XdpConfig {
@@ -58,6 +62,7 @@ impl Default for XdpConfig {
             map_pin_path: "/sys/fs/bpf/ndn".to_string(),
             enable_metrics: true,
             metrics_interval: 10,
+            use_bpftool: !cfg!(feature = "libbpf"),
         }
     }
 }
@@ -104,6 +109,28 @@ pub struct XdpMetrics {
     
     /// Average processing time in nanoseconds
     pub avg_processing_time_ns: u64,
+    
+    /// Bytes of NDN packets processed
+    pub bytes_processed: u64,
+}
+
+impl XdpMetrics {
+    /// Set the field stored under the given metrics map key
+    fn set(&mut self, key: u32, value: u64) {
+        match key {
+            0 => self.packets_processed = value,
+            1 => self.interests = value,
+            2 => self.data_packets = value,
+            3 => self.cache_hits = value,
+            4 => self.cache_misses = value,
+            5 => self.cache_size = value,
+            6 => self.cache_evictions = value,
+            7 => self.errors = value,
+            8 => self.avg_processing_time_ns = value,
+            9 => self.bytes_processed = value,
+            _ => {} // Unknown metric
+        }
+    }
 }
 
 /// Manager for XDP integration
@@ -114,8 +141,8 @@ pub struct XdpManager {
     /// Current status
     status: Arc<RwLock<XdpStatus>>,
     
-    /// Metrics
-    metrics: Arc<RwLock<XdpMetrics>>,
+    /// Latest metrics, published to every metrics stream
+    metrics: Arc<watch::Sender<XdpMetrics>>,
     
     /// Metrics collection task
     metrics_task: RwLock<Option<JoinHandle<()>>>,
@@ -130,14 +157,37 @@ impl XdpManager {
         Self {
             config,
             status: Arc::new(RwLock::new(XdpStatus::NotLoaded)),
-            metrics: Arc::new(RwLock::new(XdpMetrics::default())),
+            metrics: Arc::new(watch::channel(XdpMetrics::default()).0),
             metrics_task: RwLock::new(None),
             prefixes: Arc::new(RwLock::new(HashMap::new())),
         }
     }
     
+    /// Arguments to `ip` that attach the XDP program in the configured mode
+    fn load_args(&self) -> Result<Vec<String>> {
+        // ip selects the attach mode through the xdp keyword itself
+        let mode_keyword = match self.config.xdp_mode.to_ascii_lowercase().as_str() {
+            "skb" => "xdpgeneric",
+            "drv" => "xdpdrv",
+            "hw" => "xdpoffload",
+            "auto" => "xdp",
+            other => {
+                return Err(Error::InvalidArgument(format!(
+                    "Invalid XDP mode '{}', expected skb, drv, hw or auto", other
+                )));
+            }
+        };
+        
+        Ok([
+            "link", "set", "dev", &self.config.interface,
+            mode_keyword, "obj", &self.config.xdp_obj_path,
+        ].iter().map(|arg| arg.to_string()).collect())
+    }
+    
     /// Load and attach the XDP program
     pub async fn load(&self) -> Result<()> {
+        let args = self.load_args()?;
+        
         // Check if XDP object file exists
         let obj_path = Path::new(&self.config.xdp_obj_path);
         if !obj_path.exists() {
@@ -148,11 +198,7 @@ impl XdpManager {
         
         // Build command to load XDP program
         let mut cmd = Command::new("ip");
-        cmd.args([
-            "link", "set", "dev", &self.config.interface,
-            "xdp", &format!("obj {}", self.config.xdp_obj_path),
-            &format!("mode {}", self.config.xdp_mode),
-        ]);
+        cmd.args(&args);
         
         // Execute command
         let output = cmd.output().map_err(|e| {
@@ -194,6 +240,14 @@ impl XdpManager {
             })?;
         }
         
+        #[cfg(feature = "libbpf")]
+        if !self.config.use_bpftool {
+            if let Err(e) = maps::configure_content_store(&self.config) {
+                log::warn!("Warning: could not configure content store: {}", e);
+            }
+            return Ok(());
+        }
+        
         // Set content store configuration using bpftool
         let output = Command::new("bpftool")
             .args([
@@ -233,10 +287,9 @@ impl XdpManager {
                 interval.tick().await;
                 
                 // Read metrics from eBPF maps
-                match Self::read_xdp_metrics(&config.map_pin_path).await {
+                match Self::read_xdp_metrics(&config).await {
                     Ok(new_metrics) => {
-                        // Use the Arc-wrapped metrics field
-                        *metrics.write().await = new_metrics;
+                        metrics.send_replace(new_metrics);
                     },
                     Err(e) => {
                         log::error!("Failed to read XDP metrics: {}", e);
@@ -249,7 +302,13 @@ impl XdpManager {
     }
     
     /// Read metrics from eBPF maps
-    async fn read_xdp_metrics(map_pin_path: &str) -> Result<XdpMetrics> {
+    async fn read_xdp_metrics(config: &XdpConfig) -> Result<XdpMetrics> {
+        #[cfg(feature = "libbpf")]
+        if !config.use_bpftool {
+            return maps::read_metrics(&config.map_pin_path);
+        }
+        
+        let map_pin_path = &config.map_pin_path;
         let mut metrics = XdpMetrics::default();
         
         // Read metrics using bpftool
@@ -279,23 +338,9 @@ impl XdpManager {
                     
                     // Try to extract key and metric type
                     if let Some(key_part) = parts[0].split("key:").nth(1) {
-                        let key = key_part.trim();
-                        
-                        // Parse the value as a u64
-                        if let Ok(value) = value_str.parse::<u64>() {
-                            // Match on metric key and update appropriate field
-                            match key {
-                                "0" => metrics.packets_processed = value,
-                                "1" => metrics.interests = value,
-                                "2" => metrics.data_packets = value,
-                                "3" => metrics.cache_hits = value,
-                                "4" => metrics.cache_misses = value,
-                                "5" => metrics.cache_size = value,
-                                "6" => metrics.cache_evictions = value,
-                                "7" => metrics.errors = value,
-                                "8" => metrics.avg_processing_time_ns = value,
-                                _ => {} // Unknown metric
-                            }
+                        // Parse the key and value, updating the matching field
+                        if let (Ok(key), Ok(value)) = (key_part.trim().parse::<u32>(), value_str.parse::<u64>()) {
+                            metrics.set(key, value);
                         }
                     }
                 }
@@ -345,12 +390,17 @@ impl XdpManager {
     
     /// Get current XDP metrics
     pub async fn metrics(&self) -> XdpMetrics {
-        self.metrics.read().await.clone()
+        self.metrics.borrow().clone()
+    }
+    
+    /// Subscribe to the metrics, updated each time the collection task reads them
+    pub fn metrics_stream(&self) -> watch::Receiver<XdpMetrics> {
+        self.metrics.subscribe()
     }
     
     /// Get metrics as a HashMap for integration with the rest of the transport layer
     pub async fn get_metrics(&self) -> HashMap<String, MetricValue> {
-        let metrics = self.metrics.read().await.clone();
+        let metrics = self.metrics.borrow().clone();
         let mut result = HashMap::new();
         
         result.insert("xdp.packets_processed".to_string(), 
@@ -371,6 +421,8 @@ impl XdpManager {
                       MetricValue::Counter(metrics.errors));
         result.insert("xdp.avg_processing_time_ns".to_string(), 
                       MetricValue::Gauge(metrics.avg_processing_time_ns as f64));
+        result.insert("xdp.bytes_processed".to_string(), 
+                      MetricValue::Counter(metrics.bytes_processed));
         
         result
     }
@@ -389,6 +441,11 @@ impl XdpManager {
     
     /// Add a Data packet to the content store
     pub async fn add_to_content_store(&self, data: &Data) -> Result<()> {
+        #[cfg(feature = "libbpf")]
+        if !self.config.use_bpftool {
+            return maps::add_to_content_store(&self.config, data);
+        }
+        
         let name = data.name().to_string();
         let data_bytes = data.to_bytes();
         
@@ -416,6 +473,11 @@ impl XdpManager {
     
     /// Clear the content store
     pub async fn clear_content_store(&self) -> Result<()> {
+        #[cfg(feature = "libbpf")]
+        if !self.config.use_bpftool {
+            return maps::clear_content_store(&self.config.map_pin_path);
+        }
+        
         let output = Command::new("bpftool")
             .args([
                 "map", "flush", "pinned", 
@@ -435,3 +497,209 @@ impl XdpManager {
         Ok(())
     }
 }
+
+/// Direct access to the pinned eBPF maps through libbpf
+#[cfg(feature = "libbpf")]
+mod maps {
+    use std::path::Path;
+    
+    use libbpf_rs::{MapFlags, MapHandle};
+    
+    use super::{XdpConfig, XdpMetrics};
+    use crate::name::Name;
+    use crate::ndn::Data;
+    use crate::{Error, Result};
+    
+    /// Number of entries read from the metrics map
+    const METRICS_ENTRIES: u32 = 10;
+    
+    /// Value of the single `cs_config` entry
+    struct CsConfig {
+        /// Maximum number of cached entries
+        cs_size: u32,
+        
+        /// Lifetime of cached entries in seconds
+        cs_ttl: u32,
+    }
+    
+    impl CsConfig {
+        /// Encode as laid out in the map
+        fn to_bytes(&self) -> Vec<u8> {
+            let mut bytes = Vec::with_capacity(8);
+            bytes.extend_from_slice(&self.cs_size.to_ne_bytes());
+            bytes.extend_from_slice(&self.cs_ttl.to_ne_bytes());
+            bytes
+        }
+    }
+    
+    /// Header of a `content_store` value, matching the packed `struct cs_entry`
+    struct CsEntry {
+        /// Insertion time in seconds of monotonic clock
+        timestamp: u64,
+        
+        /// Expiry time in seconds of monotonic clock
+        expiry: u64,
+        
+        /// Length of the content following the header
+        content_len: u16,
+        
+        /// Length of the signature following the content
+        signature_len: u16,
+    }
+    
+    impl CsEntry {
+        /// Size of the packed header
+        const SIZE: usize = 20;
+        
+        /// Encode the header followed by the content, zero-padded to `value_size`
+        fn to_bytes(&self, content: &[u8], value_size: usize) -> Vec<u8> {
+            let mut bytes = Vec::with_capacity(value_size);
+            bytes.extend_from_slice(&self.timestamp.to_ne_bytes());
+            bytes.extend_from_slice(&self.expiry.to_ne_bytes());
+            bytes.extend_from_slice(&self.content_len.to_ne_bytes());
+            bytes.extend_from_slice(&self.signature_len.to_ne_bytes());
+            bytes.extend_from_slice(content);
+            bytes.resize(value_size, 0);
+            bytes
+        }
+    }
+    
+    /// Open a map pinned under the pin directory
+    fn open(map_pin_path: &str, map: &str) -> Result<MapHandle> {
+        let path = Path::new(map_pin_path).join(map);
+        MapHandle::from_pinned_path(&path)
+            .map_err(|e| Error::XdpError(format!("Failed to open pinned map {}: {}", path.display(), e)))
+    }
+    
+    /// Key under which the data plane caches a name
+    ///
+    /// The XDP program hashes the value of the Name TLV with Jenkins'
+    /// one-at-a-time hash, looking at no more than its first 64 bytes.
+    fn name_key(name: &Name) -> u64 {
+        let value: Vec<u8> = name.components().iter()
+            .flat_map(|component| component.to_tlv())
+            .take(64)
+            .collect();
+        
+        let mut hash: u64 = 0;
+        for byte in value {
+            hash = hash.wrapping_add(byte as u64);
+            hash = hash.wrapping_add(hash << 10);
+            hash ^= hash >> 6;
+        }
+        hash = hash.wrapping_add(hash << 3);
+        hash ^= hash >> 11;
+        hash.wrapping_add(hash << 15)
+    }
+    
+    /// Seconds on the clock behind `bpf_ktime_get_ns`
+    fn monotonic_secs() -> u64 {
+        let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+        // SAFETY: `ts` is a valid timespec for clock_gettime to fill in
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+        ts.tv_sec as u64
+    }
+    
+    /// Read the per-CPU metrics map, summing each counter over all CPUs
+    pub(super) fn read_metrics(map_pin_path: &str) -> Result<XdpMetrics> {
+        let map = open(map_pin_path, "metrics")?;
+        let mut metrics = XdpMetrics::default();
+        
+        for key in 0..METRICS_ENTRIES {
+            let values = map.lookup_percpu(&key.to_ne_bytes(), MapFlags::ANY)
+                .map_err(|e| Error::XdpError(format!("Failed to read metric {}: {}", key, e)))?;
+            
+            let total = values.into_iter().flatten()
+                .filter_map(|value| value.get(..8).map(|b| u64::from_ne_bytes(b.try_into().unwrap())))
+                .fold(0u64, u64::wrapping_add);
+            metrics.set(key, total);
+        }
+        
+        Ok(metrics)
+    }
+    
+    /// Write the content store size and TTL into the `cs_config` map
+    pub(super) fn configure_content_store(config: &XdpConfig) -> Result<()> {
+        let map = open(&config.map_pin_path, "cs_config")?;
+        let value = CsConfig {
+            cs_size: config.cs_size.min(u32::MAX as usize) as u32,
+            cs_ttl: config.cs_ttl,
+        };
+        
+        map.update(&0u32.to_ne_bytes(), &value.to_bytes(), MapFlags::ANY)
+            .map_err(|e| Error::XdpError(format!("Failed to update cs_config: {}", e)))
+    }
+    
+    /// Cache the content of a Data packet in the `content_store` map
+    pub(super) fn add_to_content_store(config: &XdpConfig, data: &Data) -> Result<()> {
+        let map = open(&config.map_pin_path, "content_store")?;
+        let value_size = map.value_size() as usize;
+        
+        let content = data.content();
+        if CsEntry::SIZE + content.len() > value_size {
+            return Err(Error::XdpError(format!(
+                "Content of {} is {} bytes, too large for the XDP content store",
+                data.name(), content.len()
+            )));
+        }
+        
+        let now = monotonic_secs();
+        let entry = CsEntry {
+            timestamp: now,
+            expiry: now + config.cs_ttl as u64,
+            content_len: content.len() as u16,
+            signature_len: 0,
+        };
+        
+        map.update(&name_key(data.name()).to_ne_bytes(), &entry.to_bytes(content, value_size), MapFlags::ANY)
+            .map_err(|e| Error::XdpError(format!("Failed to update content store: {}", e)))
+    }
+    
+    /// Delete every entry of the `content_store` map
+    pub(super) fn clear_content_store(map_pin_path: &str) -> Result<()> {
+        let map = open(map_pin_path, "content_store")?;
+        
+        // Collect the keys first so deletions don't disturb the iteration
+        let keys: Vec<Vec<u8>> = map.keys().collect();
+        for key in keys {
+            map.delete(&key)
+                .map_err(|e| Error::XdpError(format!("Failed to flush content store: {}", e)))?;
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_load_args() {
+        let manager = XdpManager::new(XdpConfig {
+            interface: "veth0".to_string(),
+            xdp_obj_path: "/tmp/ndn_parser.o".to_string(),
+            xdp_mode: "drv".to_string(),
+            ..XdpConfig::default()
+        });
+        assert_eq!(
+            manager.load_args().unwrap(),
+            ["link", "set", "dev", "veth0", "xdpdrv", "obj", "/tmp/ndn_parser.o"],
+        );
+        
+        // Each mode picks its own keyword, case-insensitively
+        for (mode, keyword) in [("skb", "xdpgeneric"), ("HW", "xdpoffload"), ("auto", "xdp")] {
+            let manager = XdpManager::new(XdpConfig {
+                xdp_mode: mode.to_string(),
+                ..XdpConfig::default()
+            });
+            assert_eq!(manager.load_args().unwrap()[4], keyword);
+        }
+        
+        let manager = XdpManager::new(XdpConfig {
+            xdp_mode: "native".to_string(),
+            ..XdpConfig::default()
+        });
+        assert!(matches!(manager.load_args(), Err(Error::InvalidArgument(_))));
+    }
+}
@@ -83,11 +83,10 @@ async fn test_enhanced_grpc_quic_integration() -> Result<()> {
     
     // Create a retry policy that's aggressive for testing
     let retry_policy = RetryPolicy {
-        max_attempts: 5,
-        base_delay_ms: 50,
-        max_delay_ms: 1000,
-        backoff_factor: 1.5,
-        with_jitter: true,
+        max_retries: 4,
+        base_interval: Duration::from_millis(50),
+        max_interval: Duration::from_secs(1),
+        jitter: 0.25,
     };
     
     let quic_adapter = Arc::new(EnhancedGrpcQuicAdapter::new_with_retry_policy(
@@ -4,12 +4,21 @@
 // This file contains unit tests for the fragmentation and reassembly module.
 //
 
-use udcn_transport::name::Name;
-use udcn_transport::ndn::Data;
-use udcn_transport::fragmentation::{Fragmenter, Fragment, Reassembler};
+use rust_ndn_transport::name::Name;
+use rust_ndn_transport::ndn::Data;
+use rust_ndn_transport::error::Result;
+use rust_ndn_transport::fragmentation::{Fragmenter, Fragment, Reassembler};
 
-#[test]
-fn test_basic_fragmentation() {
+/// Fragment a data object and decode the fragments the fragmenter encoded
+async fn fragment(fragmenter: &Fragmenter, data: &Data) -> Result<Vec<Fragment>> {
+    fragmenter.fragment(data).await?
+        .iter()
+        .map(|wire| Fragment::from_wire(wire))
+        .collect()
+}
+
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_basic_fragmentation() {
     // Create a large data packet
     let name = Name::from("/test/large-data");
     let content = vec![0u8; 5000]; // 5KB of data
@@ -20,7 +29,7 @@ fn test_basic_fragmentation() {
     let fragmenter = Fragmenter::new(mtu);
     
     // Fragment the data
-    let fragments = fragmenter.fragment(&data).expect("Failed to fragment data");
+    let fragments = fragment(&fragmenter, &data).await.expect("Failed to fragment data");
     
     // Check that we have the right number of fragments
     // Each fragment will have some overhead for headers, so we need more than just content.len() / mtu
@@ -43,16 +52,16 @@ fn test_basic_fragmentation() {
     }
 }
 
-#[test]
-fn test_reassembly() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_reassembly() {
     // Create a large data packet
     let name = Name::from("/test/reassembly");
     let content = vec![1u8; 10000]; // 10KB of data with all bytes set to 1
-    let original_data = Data::new(name.clone(), content);
+    let original_data = Data::new(name.clone(), content.clone());
     
     // Fragment the data
     let fragmenter = Fragmenter::new(1400);
-    let fragments = fragmenter.fragment(&original_data).expect("Failed to fragment data");
+    let fragments = fragment(&fragmenter, &original_data).await.expect("Failed to fragment data");
     
     // Ensure we have multiple fragments
     assert!(fragments.len() > 1);
@@ -83,16 +92,16 @@ fn test_reassembly() {
     assert!(reassembly_complete, "Reassembly should have completed");
 }
 
-#[test]
-fn test_out_of_order_reassembly() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_out_of_order_reassembly() {
     // Create a data packet
     let name = Name::from("/test/out-of-order");
     let content = vec![2u8; 8000]; // 8KB of data with all bytes set to 2
-    let original_data = Data::new(name.clone(), content);
+    let original_data = Data::new(name.clone(), content.clone());
     
     // Fragment the data
     let fragmenter = Fragmenter::new(1400);
-    let fragments = fragmenter.fragment(&original_data).expect("Failed to fragment data");
+    let fragments = fragment(&fragmenter, &original_data).await.expect("Failed to fragment data");
     
     // Ensure we have multiple fragments
     assert!(fragments.len() > 1);
@@ -117,16 +126,16 @@ fn test_out_of_order_reassembly() {
     assert_eq!(reassembled_data.content().as_ref(), original_data.content().as_ref());
 }
 
-#[test]
-fn test_duplicate_fragments() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_duplicate_fragments() {
     // Create a data packet
     let name = Name::from("/test/duplicates");
     let content = vec![3u8; 5000]; // 5KB of data with all bytes set to 3
-    let original_data = Data::new(name.clone(), content);
+    let original_data = Data::new(name.clone(), content.clone());
     
     // Fragment the data
     let fragmenter = Fragmenter::new(1400);
-    let fragments = fragmenter.fragment(&original_data).expect("Failed to fragment data");
+    let fragments = fragment(&fragmenter, &original_data).await.expect("Failed to fragment data");
     
     // Create a reassembler
     let mut reassembler = Reassembler::new();
@@ -156,16 +165,16 @@ fn test_duplicate_fragments() {
     }
 }
 
-#[test]
-fn test_partial_reassembly_timeout() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_partial_reassembly_timeout() {
     // Create a data packet
     let name = Name::from("/test/timeout");
     let content = vec![4u8; 5000]; // 5KB of data with all bytes set to 4
-    let original_data = Data::new(name.clone(), content);
+    let original_data = Data::new(name.clone(), content.clone());
     
     // Fragment the data
     let fragmenter = Fragmenter::new(1400);
-    let fragments = fragmenter.fragment(&original_data).expect("Failed to fragment data");
+    let fragments = fragment(&fragmenter, &original_data).await.expect("Failed to fragment data");
     
     // Create a reassembler with a short timeout
     let mut reassembler = Reassembler::with_timeout(std::time::Duration::from_millis(100));
@@ -187,8 +196,8 @@ fn test_partial_reassembly_timeout() {
     assert_eq!(reassembler.pending_reassemblies(), 0);
 }
 
-#[test]
-fn test_multiple_data_reassembly() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_multiple_data_reassembly() {
     // Create two different data packets
     let name1 = Name::from("/test/multiple/1");
     let content1 = vec![5u8; 3000]; // 3KB of data with all bytes set to 5
@@ -200,8 +209,8 @@ fn test_multiple_data_reassembly() {
     
     // Fragment both data packets
     let fragmenter = Fragmenter::new(1400);
-    let fragments1 = fragmenter.fragment(&data1).expect("Failed to fragment data1");
-    let fragments2 = fragmenter.fragment(&data2).expect("Failed to fragment data2");
+    let fragments1 = fragment(&fragmenter, &data1).await.expect("Failed to fragment data1");
+    let fragments2 = fragment(&fragmenter, &data2).await.expect("Failed to fragment data2");
     
     // Create a reassembler
     let mut reassembler = Reassembler::new();
@@ -244,13 +253,13 @@ fn test_multiple_data_reassembly() {
     assert!(reassembled2, "Should have reassembled data2");
 }
 
-#[test]
-fn test_fragment_serialization() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_fragment_serialization() {
     // Create a fragment
     let name = Name::from("/test/serialize");
     let original_data = Data::new(name.clone(), vec![7u8; 100]);
     let fragmenter = Fragmenter::new(1400);
-    let fragments = fragmenter.fragment(&original_data).expect("Failed to fragment data");
+    let fragments = fragment(&fragmenter, &original_data).await.expect("Failed to fragment data");
     let fragment = fragments[0].clone();
     
     // Serialize the fragment
@@ -267,8 +276,8 @@ fn test_fragment_serialization() {
     assert_eq!(deserialized.data(), fragment.data());
 }
 
-#[test]
-fn test_fragment_limits() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_fragment_limits() {
     // Create a very large data packet
     let name = Name::from("/test/limits");
     let content = vec![8u8; 100_000]; // 100KB of data
@@ -276,12 +285,12 @@ fn test_fragment_limits() {
     
     // Try to fragment with a very small MTU (should fail)
     let small_fragmenter = Fragmenter::new(100);
-    let result = small_fragmenter.fragment(&data);
+    let result = fragment(&small_fragmenter, &data).await;
     assert!(result.is_err(), "Fragmentation with tiny MTU should fail");
     
     // Try to fragment with a reasonable MTU
     let normal_fragmenter = Fragmenter::new(1400);
-    let result = normal_fragmenter.fragment(&data);
+    let result = fragment(&normal_fragmenter, &data).await;
     assert!(result.is_ok(), "Fragmentation with normal MTU should succeed");
     
     // Check that we don't create too many fragments
@@ -289,8 +298,8 @@ fn test_fragment_limits() {
     assert!(fragments.len() < 1000, "Should not create an excessive number of fragments");
 }
 
-#[test]
-fn test_reassembler_capacity() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_reassembler_capacity() {
     // Create a reassembler with limited capacity
     let mut reassembler = Reassembler::with_capacity(2);
     
@@ -298,13 +307,13 @@ fn test_reassembler_capacity() {
     let fragmenter = Fragmenter::new(1400);
     
     let data1 = Data::new(Name::from("/test/capacity/1"), vec![1u8; 3000]);
-    let fragments1 = fragmenter.fragment(&data1).expect("Failed to fragment data1");
+    let fragments1 = fragment(&fragmenter, &data1).await.expect("Failed to fragment data1");
     
     let data2 = Data::new(Name::from("/test/capacity/2"), vec![2u8; 3000]);
-    let fragments2 = fragmenter.fragment(&data2).expect("Failed to fragment data2");
+    let fragments2 = fragment(&fragmenter, &data2).await.expect("Failed to fragment data2");
     
     let data3 = Data::new(Name::from("/test/capacity/3"), vec![3u8; 3000]);
-    let fragments3 = fragmenter.fragment(&data3).expect("Failed to fragment data3");
+    let fragments3 = fragment(&fragmenter, &data3).await.expect("Failed to fragment data3");
     
     // Add one fragment from each data packet
     reassembler.add_fragment(fragments1[0].clone());
@@ -336,8 +345,8 @@ fn test_reassembler_capacity() {
     assert!(reassembled, "Reassembly of data3 should succeed");
 }
 
-#[test]
-fn test_fragment_identification() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_fragment_identification() {
     // Create two data packets with the same name but different content
     let name = Name::from("/test/id");
     let data1 = Data::new(name.clone(), vec![1u8; 3000]);
@@ -345,8 +354,8 @@ fn test_fragment_identification() {
     
     // Fragment them
     let fragmenter = Fragmenter::new(1400);
-    let fragments1 = fragmenter.fragment(&data1).expect("Failed to fragment data1");
-    let fragments2 = fragmenter.fragment(&data2).expect("Failed to fragment data2");
+    let fragments1 = fragment(&fragmenter, &data1).await.expect("Failed to fragment data1");
+    let fragments2 = fragment(&fragmenter, &data2).await.expect("Failed to fragment data2");
     
     // Create a reassembler
     let mut reassembler = Reassembler::new();
@@ -370,8 +379,8 @@ fn test_fragment_identification() {
     assert_eq!(reassembled.content().as_ref(), data1.content().as_ref());
 }
 
-#[test]
-fn test_variable_mtu() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_variable_mtu() {
     // Create a large data packet
     let name = Name::from("/test/variable-mtu");
     let content = vec![9u8; 10000]; // 10KB of data
@@ -379,11 +388,11 @@ fn test_variable_mtu() {
     
     // Fragment with one MTU
     let fragmenter1 = Fragmenter::new(1400);
-    let fragments1 = fragmenter1.fragment(&data).expect("Failed to fragment with MTU 1400");
+    let fragments1 = fragment(&fragmenter1, &data).await.expect("Failed to fragment with MTU 1400");
     
     // Fragment with a larger MTU
     let fragmenter2 = Fragmenter::new(4000);
-    let fragments2 = fragmenter2.fragment(&data).expect("Failed to fragment with MTU 4000");
+    let fragments2 = fragment(&fragmenter2, &data).await.expect("Failed to fragment with MTU 4000");
     
     // The larger MTU should result in fewer fragments
     assert!(fragments2.len() < fragments1.len(), 
@@ -420,8 +429,8 @@ fn test_variable_mtu() {
     }
 }
 
-#[test]
-fn test_fragment_name_encoding() {
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_fragment_name_encoding() {
     // Create a data packet with a complex name
     let name = Name::from("/test/fragment/with/many/components");
     let content = vec![10u8; 3000];
@@ -429,7 +438,7 @@ fn test_fragment_name_encoding() {
     
     // Fragment the data
     let fragmenter = Fragmenter::new(1400);
-    let fragments = fragmenter.fragment(&data).expect("Failed to fragment data");
+    let fragments = fragment(&fragmenter, &data).await.expect("Failed to fragment data");
     
     // Check that all fragments correctly encode the original name
     for fragment in &fragments {
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 use tonic::{Request, Response};
-use udcn_transport::UdcnTransport;
-use udcn_transport::grpc::{UdcnControlService, udcn::*};
+use rust_ndn_transport::UdcnTransport;
+use rust_ndn_transport::grpc::{UdcnControlService, udcn::*};
+use rust_ndn_transport::grpc::udcn::udcn_control_server::UdcnControl;
 
 #[cfg_attr(feature = "tokio-test", tokio::test)]
 async fn test_get_transport_state() {
@@ -73,3 +74,99 @@ async fn test_create_quic_connection() {
     // In production code, we would mock the transport's create_quic_connection method
     assert!(result.is_err() || result.is_ok());
 }
+
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_stream_interests_returns_all_data() {
+    use std::collections::HashSet;
+    use tokio_stream::StreamExt;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use rust_ndn_transport::Config;
+    use rust_ndn_transport::name::Name;
+    use rust_ndn_transport::ndn::Data;
+    use rust_ndn_transport::grpc::udcn::udcn_control_client::UdcnControlClient;
+    use rust_ndn_transport::grpc::udcn::udcn_control_server::UdcnControlServer;
+    
+    // Create a transport that produces Data for /test/stream
+    let transport = Arc::new(UdcnTransport::new(Config::default()).await.unwrap());
+    transport.register_prefix(
+        Name::from("/test/stream"),
+        Box::new(|interest| Ok(Data::new(interest.name().clone(), b"streamed".to_vec()))),
+    ).await.unwrap();
+    
+    // Serve the control service on an ephemeral port
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let service = UdcnControlServer::new(UdcnControlService::new(transport));
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    
+    let mut client = UdcnControlClient::connect(format!("http://{}", addr)).await.unwrap();
+    
+    // Send 100 Interests on one stream
+    let requests = (1..=100u32).map(|nonce| InterestPacketRequest {
+        name: format!("/test/stream/{}", nonce),
+        can_be_prefix: false,
+        must_be_fresh: false,
+        lifetime_ms: 1000,
+        nonce,
+        ..Default::default()
+    });
+    let mut responses = client.stream_interests(tokio_stream::iter(requests)).await.unwrap().into_inner();
+    
+    // Every Interest is answered, matched up by nonce
+    let mut nonces = HashSet::new();
+    while let Some(response) = responses.next().await {
+        let response = response.unwrap();
+        assert!(response.success, "{}", response.error_message);
+        assert_eq!(response.name, format!("/test/stream/{}", response.nonce));
+        assert_eq!(response.content, b"streamed");
+        nonces.insert(response.nonce);
+    }
+    assert_eq!(nonces, (1..=100).collect::<HashSet<u32>>());
+}
+
+#[cfg_attr(feature = "tokio-test", tokio::test)]
+async fn test_prefix_registration_and_listing() {
+    let transport = Arc::new(UdcnTransport::new(rust_ndn_transport::Config::default()).await.unwrap());
+    let service = UdcnControlService::new(transport);
+    
+    // Register two forwarding prefixes
+    let mut ids = Vec::new();
+    for (prefix, priority, next_hop) in [("/fib/a", 1, "127.0.0.1:6363"), ("/fib/b", 5, "127.0.0.1:6364")] {
+        let response = service.register_prefix(Request::new(PrefixRegistrationRequest {
+            prefix: prefix.to_string(),
+            r#type: prefix_registration_request::PrefixType::Forwarder as i32,
+            priority,
+            flags: std::collections::HashMap::new(),
+            next_hop: next_hop.to_string(),
+        })).await.unwrap().into_inner();
+        assert!(response.success);
+        ids.push(response.registration_id);
+    }
+    
+    let listing = service.list_prefixes(Request::new(ListPrefixesRequest {})).await.unwrap().into_inner();
+    assert!(listing.success);
+    assert_eq!(listing.prefixes.len(), 2);
+    assert_eq!(listing.prefixes[0].prefix, "/fib/a");
+    assert_eq!(listing.prefixes[1].priority, 5);
+    
+    // Remove the first by ID; only the second is left
+    let response = service.unregister_prefix(Request::new(PrefixUnregistrationRequest {
+        registration_id: ids[0],
+        prefix: String::new(),
+    })).await.unwrap().into_inner();
+    assert!(response.success);
+    
+    let listing = service.list_prefixes(Request::new(ListPrefixesRequest {})).await.unwrap().into_inner();
+    assert_eq!(listing.prefixes, vec![PrefixEntry {
+        prefix: "/fib/b".to_string(),
+        priority: 5,
+        next_hop: "127.0.0.1:6364".to_string(),
+        registration_id: ids[1],
+    }]);
+}
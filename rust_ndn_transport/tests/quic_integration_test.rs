@@ -12,7 +12,8 @@ use rust_ndn_transport::grpc::{run_grpc_server, UdcnControlService};
 use rust_ndn_transport::grpc_quic_integration::GrpcQuicAdapter;
 use rust_ndn_transport::name::Name;
 use rust_ndn_transport::ndn::{Data, Interest};
-use rust_ndn_transport::quic_transport::QuicTransport;
+use rust_ndn_transport::quic::QuicEngine;
+use rust_ndn_transport::quic_transport::{FlowControlWindows, QuicTransport};
 
 // Integration test that demonstrates QUIC transport and gRPC working together
 #[tokio::test(flavor = "multi_thread")]
@@ -158,3 +159,182 @@ async fn test_quic_and_grpc_integration() -> Result<()> {
     
     Ok(())
 }
+
+// Test that the incoming hook rejects a specific peer while admitting others
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quic_incoming_hook() -> Result<()> {
+    let bind_addr = "127.0.0.1";
+    let server_port = 14310;
+    let allowed_port = 14311;
+    let rejected_port = 14312;
+    
+    // Start a QUIC server that refuses one client address
+    let mut server = QuicTransport::new(bind_addr, server_port, 30, 65535).await?;
+    let rejected_addr: SocketAddr = format!("{}:{}", bind_addr, rejected_port).parse()?;
+    server.set_on_incoming(move |remote| remote != rejected_addr);
+    server.start_server().await?;
+    
+    server.register_handler(Name::from_uri("/test/hook")?, |interest| {
+        Ok(Data::new(interest.name().clone(), "admitted"))
+    }).await?;
+    
+    sleep(Duration::from_millis(100)).await;
+    let server_addr: SocketAddr = format!("{}:{}", bind_addr, server_port).parse()?;
+    
+    // The allowed client connects and gets Data
+    let allowed = QuicTransport::new(bind_addr, allowed_port, 30, 65535).await?;
+    allowed.connect(bind_addr, server_port).await?;
+    let data = allowed.send_interest(server_addr, Interest::new(Name::from_uri("/test/hook/1")?)).await?;
+    assert_eq!(data.content().as_ref(), b"admitted");
+    
+    // The rejected client cannot establish a connection
+    let rejected = QuicTransport::new(bind_addr, rejected_port, 30, 65535).await?;
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        rejected.connect(bind_addr, server_port)
+    ).await;
+    assert!(matches!(result, Ok(Err(_))), "Rejected peer was able to connect");
+    assert!(rejected.get_connections().is_empty());
+    
+    server.shutdown().await?;
+    Ok(())
+}
+
+// Test that small Interests travel in datagrams and large ones fall back to streams
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quic_interest_datagrams() -> Result<()> {
+    let bind_addr = "127.0.0.1";
+    let server_port = 14330;
+    
+    let mut server = QuicTransport::new(bind_addr, server_port, 30, 65535).await?;
+    server.start_server().await?;
+    server.register_handler(Name::from_uri("/test/datagram")?, |interest| {
+        Ok(Data::new(interest.name().clone(), "small"))
+    }).await?;
+    server.register_handler(Name::from_uri("/test/bulky")?, |interest| {
+        Ok(Data::new(interest.name().clone(), vec![0x42; 4000]))
+    }).await?;
+    
+    sleep(Duration::from_millis(100)).await;
+    let server_addr: SocketAddr = format!("{}:{}", bind_addr, server_port).parse()?;
+    
+    let client = QuicTransport::new(bind_addr, 14331, 30, 65535).await?;
+    let conn = client.connect(bind_addr, server_port).await?;
+    let sent = |conn: &quinn::Connection| {
+        let frames = conn.stats().frame_tx;
+        (frames.datagram, frames.stream)
+    };
+    
+    // A small Interest and its Data each take a single datagram
+    let (datagrams, streams) = sent(conn.connection());
+    let data = client.send_interest_datagram(server_addr, Interest::new(Name::from_uri("/test/datagram/1")?)).await?;
+    assert_eq!(data.content().as_ref(), b"small");
+    assert_eq!(sent(conn.connection()), (datagrams + 1, streams));
+    
+    // An Interest above max_datagram_size() transparently goes over a stream
+    let max = conn.connection().max_datagram_size().expect("Datagrams not negotiated");
+    let mut large_name = Name::from_uri("/test/datagram")?;
+    large_name.push_str(&"x".repeat(max));
+    let (datagrams, streams) = sent(conn.connection());
+    let data = client.send_interest_datagram(server_addr, Interest::new(large_name.clone())).await?;
+    assert_eq!(data.name(), &large_name);
+    let (datagrams_after, streams_after) = sent(conn.connection());
+    assert_eq!(datagrams_after, datagrams);
+    assert!(streams_after > streams);
+    
+    // Data too large for a datagram is re-requested over a stream
+    let data = client.send_interest_datagram(server_addr, Interest::new(Name::from_uri("/test/bulky/1")?)).await?;
+    assert_eq!(data.content().len(), 4000);
+    
+    server.shutdown().await?;
+    Ok(())
+}
+
+// Fetch a bulk Data from a server built from `config` and return the
+// windows the server applies and how long the transfer took
+async fn bulk_fetch(config: rust_ndn_transport::Config, client_port: u16, data: &Data) -> Result<(FlowControlWindows, Duration)> {
+    let mut server = QuicTransport::from_config(&config).await?;
+    let served = data.clone();
+    server.register_handler(data.name().clone(), move |_interest| Ok(served.clone())).await?;
+    server.start_server().await?;
+    sleep(Duration::from_millis(100)).await;
+    
+    let mut client_config = config.clone();
+    client_config.port = client_port;
+    let client = QuicTransport::from_config(&client_config).await?;
+    client.connect(&config.bind_address, config.port).await?;
+    assert_eq!(client.windows(), server.windows());
+    
+    let server_addr: SocketAddr = format!("{}:{}", config.bind_address, config.port).parse()?;
+    let start = std::time::Instant::now();
+    let fetched = client.send_interest(server_addr, Interest::new(data.name().clone())).await?;
+    let elapsed = start.elapsed();
+    assert_eq!(fetched.content(), data.content());
+    
+    let windows = server.windows();
+    server.shutdown().await?;
+    Ok((windows, elapsed))
+}
+
+// Test that the configured flow-control windows apply to transports and engines
+#[tokio::test(flavor = "multi_thread")]
+async fn test_quic_flow_control_windows() -> Result<()> {
+    let data = Data::new(Name::from_uri("/bulk/object")?, vec![0x5A; 8 * 1024 * 1024]);
+    let base = rust_ndn_transport::Config {
+        bind_address: "127.0.0.1".to_string(),
+        idle_timeout: 30,
+        max_packet_size: 16 * 1024 * 1024,
+        ..Default::default()
+    };
+    
+    let tiny = rust_ndn_transport::Config {
+        port: 14320,
+        stream_receive_window: 4 * 1024,
+        receive_window: 4 * 1024,
+        send_window: 4 * 1024,
+        ..base.clone()
+    };
+    let tuned = rust_ndn_transport::Config {
+        port: 14322,
+        stream_receive_window: 16 * 1024 * 1024,
+        receive_window: 16 * 1024 * 1024,
+        send_window: 16 * 1024 * 1024,
+        ..base.clone()
+    };
+    
+    // A bulk transfer completes under either set of windows, and faster
+    // when they are sized for it
+    let tiny_windows = FlowControlWindows {
+        stream_receive_window: 4 * 1024,
+        receive_window: 4 * 1024,
+        send_window: 4 * 1024,
+    };
+    let tuned_windows = FlowControlWindows {
+        stream_receive_window: 16 * 1024 * 1024,
+        receive_window: 16 * 1024 * 1024,
+        send_window: 16 * 1024 * 1024,
+    };
+    let (windows, tiny_elapsed) = bulk_fetch(tiny, 14321, &data).await?;
+    assert_eq!(windows, tiny_windows);
+    let (windows, tuned_elapsed) = bulk_fetch(tuned.clone(), 14323, &data).await?;
+    assert_eq!(windows, tuned_windows);
+    assert!(tuned_elapsed < tiny_elapsed,
+            "tuned windows took {:?}, tiny windows {:?}", tuned_elapsed, tiny_elapsed);
+    
+    // The engine applies the same windows
+    let engine = QuicEngine::new(&rust_ndn_transport::Config { port: 14325, ..tuned }).await?;
+    assert_eq!(engine.windows(), tuned_windows);
+    
+    // Zero windows are rejected by both
+    let zero = rust_ndn_transport::Config { port: 14324, receive_window: 0, ..base };
+    assert!(matches!(
+        QuicTransport::from_config(&zero).await,
+        Err(rust_ndn_transport::Error::ConfigurationError(_))
+    ));
+    assert!(matches!(
+        QuicEngine::new(&zero).await,
+        Err(rust_ndn_transport::Error::ConfigurationError(_))
+    ));
+    
+    Ok(())
+}